@@ -2,10 +2,11 @@
 //!
 //! 测试配置解析、验证和序列化的性能
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use service_vitals::config::types::WebConfig;
+use criterion::{criterion_group, criterion_main, Criterion};
+use service_vitals::config::types::{RetryBackoff, WebConfig};
 use service_vitals::config::{Config, GlobalConfig, ServiceConfig};
 use std::collections::HashMap;
+use std::hint::black_box;
 
 /// 配置处理基准测试
 fn config_processing_benchmark(c: &mut Criterion) {
@@ -22,7 +23,10 @@ fn config_processing_benchmark(c: &mut Criterion) {
                 max_concurrent_checks: 50,
                 retry_attempts: 3,
                 retry_delay_seconds: 5,
+                retry_backoff: RetryBackoff::Fixed,
                 headers: HashMap::new(),
+                jitter_seconds: None,
+                startup_spread_seconds: None,
                 web: Some(WebConfig {
                     enabled: true,
                     port: 8080,
@@ -30,22 +34,16 @@ fn config_processing_benchmark(c: &mut Criterion) {
                     show_problems_only: false,
                     layout_type: "cards".to_string(),
                     refresh_interval_seconds: 30,
+                    api_token: None,
                 }),
             };
 
             let service_config = ServiceConfig {
                 name: "test-service".to_string(),
                 url: "https://httpbin.org/status/200".to_string(),
-                method: "GET".to_string(),
                 expected_status_codes: vec![200],
-                feishu_webhook_url: None,
-                failure_threshold: 1,
-                check_interval_seconds: None,
-                enabled: true,
                 description: Some("测试服务".to_string()),
-                headers: HashMap::new(),
-                body: None,
-                alert_cooldown_secs: None,
+                ..Default::default()
             };
 
             let config = Config {
@@ -125,7 +123,10 @@ fn create_test_config() -> Config {
         max_concurrent_checks: 50,
         retry_attempts: 3,
         retry_delay_seconds: 5,
+        retry_backoff: RetryBackoff::Fixed,
         headers: HashMap::new(),
+        jitter_seconds: None,
+        startup_spread_seconds: None,
         web: Some(WebConfig {
             enabled: true,
             port: 8080,
@@ -133,22 +134,16 @@ fn create_test_config() -> Config {
             show_problems_only: false,
             layout_type: "cards".to_string(),
             refresh_interval_seconds: 30,
+            api_token: None,
         }),
     };
 
     let service_config = ServiceConfig {
         name: "test-service".to_string(),
         url: "https://httpbin.org/status/200".to_string(),
-        method: "GET".to_string(),
         expected_status_codes: vec![200],
-        feishu_webhook_url: None,
-        failure_threshold: 1,
-        check_interval_seconds: None,
-        enabled: true,
         description: Some("测试服务".to_string()),
-        headers: HashMap::new(),
-        body: None,
-        alert_cooldown_secs: None,
+        ..Default::default()
     };
 
     Config {
@@ -2,9 +2,10 @@
 //!
 //! 测试健康检测器的性能和并发处理能力
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use service_vitals::config::ServiceConfig;
 use service_vitals::health::{HealthResult, HealthStatus};
+use std::hint::black_box;
 use std::time::Duration;
 
 /// 健康检测器基准测试
@@ -28,16 +29,8 @@ fn health_checker_benchmark(c: &mut Criterion) {
             let config = ServiceConfig {
                 name: "test-service".to_string(),
                 url: "https://httpbin.org/status/200".to_string(),
-                method: "GET".to_string(),
                 expected_status_codes: vec![200],
-                feishu_webhook_url: None,
-                failure_threshold: 1,
-                check_interval_seconds: None,
-                enabled: true,
-                description: None,
-                headers: std::collections::HashMap::new(),
-                body: None,
-                alert_cooldown_secs: None,
+                ..Default::default()
             };
             black_box(config)
         });
@@ -2,11 +2,12 @@
 //!
 //! 测试通知模板渲染和消息处理的性能
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use service_vitals::health::{HealthResult, HealthStatus};
 use service_vitals::notification::template::{
     HandlebarsTemplate, MessageTemplate, TemplateContext,
 };
+use std::hint::black_box;
 use std::time::Duration;
 
 /// 通知处理基准测试
@@ -25,6 +26,7 @@ fn notification_benchmark(c: &mut Criterion) {
             response_time: 250,
             timestamp: "2023-01-01T00:00:00Z".to_string(),
             error_message: Some("Connection timeout".to_string()),
+            tags: Vec::new(),
             custom_fields: std::collections::HashMap::new(),
         };
 
@@ -60,6 +62,7 @@ fn notification_benchmark(c: &mut Criterion) {
             response_time: 5000,
             timestamp: "2023-01-01T00:00:00Z".to_string(),
             error_message: Some("Service unavailable".to_string()),
+            tags: Vec::new(),
             custom_fields: std::collections::HashMap::new(),
         };
 
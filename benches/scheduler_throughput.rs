@@ -0,0 +1,120 @@
+//! 调度器吞吐量基准测试
+//!
+//! 在本地模拟服务器上注册大量服务，测量共享定时器和工作池完成一轮检测的耗时
+
+use axum::{routing::get, Router};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use service_vitals::config::types::{GlobalConfig, ServiceConfig};
+use service_vitals::health::{HttpHealthChecker, Scheduler, TaskScheduler};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+/// 工作池大小
+const MAX_CONCURRENT_CHECKS: usize = 256;
+/// 一轮检测的最长等待时间；检测出错时不会触发结果回调，超时后中止而不是一直等待
+const ROUND_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 启动本地模拟服务器，返回健康检测地址
+async fn start_mock_server() -> String {
+    let app = Router::new().route("/health", get(|| async { "ok" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}/health")
+}
+
+fn create_service(index: usize, url: &str) -> ServiceConfig {
+    ServiceConfig {
+        name: format!("service-{index:05}"),
+        url: url.to_string(),
+        expected_status_codes: vec![200],
+        check_interval_seconds: Some(3600),
+        ..Default::default()
+    }
+}
+
+/// 注册指定数量的服务并等待所有服务完成首次检测，返回耗时
+async fn run_round(url: &str, service_count: usize) -> Duration {
+    let checker = Arc::new(
+        HttpHealthChecker::new(Duration::from_secs(10), 0, Duration::from_millis(10)).unwrap(),
+    );
+    let global: GlobalConfig =
+        toml::from_str(&format!("max_concurrent_checks = {MAX_CONCURRENT_CHECKS}")).unwrap();
+    let scheduler = TaskScheduler::new(checker, None, global);
+
+    let completed = Arc::new(AtomicUsize::new(0));
+    let done = Arc::new(Notify::new());
+    {
+        let completed = Arc::clone(&completed);
+        let done = Arc::clone(&done);
+        scheduler
+            .set_health_result_callback(Arc::new(move |_| {
+                if completed.fetch_add(1, Ordering::SeqCst) + 1 == service_count {
+                    done.notify_one();
+                }
+            }))
+            .await;
+    }
+
+    let services: Vec<ServiceConfig> = (0..service_count)
+        .map(|index| create_service(index, url))
+        .collect();
+
+    let start = Instant::now();
+    scheduler.start(services).await.unwrap();
+    if tokio::time::timeout(ROUND_TIMEOUT, done.notified())
+        .await
+        .is_err()
+    {
+        panic!(
+            "{} 秒内只完成了 {}/{service_count} 个服务的检测，部分检测出错",
+            ROUND_TIMEOUT.as_secs(),
+            completed.load(Ordering::SeqCst)
+        );
+    }
+    let elapsed = start.elapsed();
+
+    scheduler.stop().await.unwrap();
+    elapsed
+}
+
+/// 调度器吞吐量基准测试
+fn scheduler_throughput_benchmark(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let url = runtime.block_on(start_mock_server());
+
+    let mut group = c.benchmark_group("scheduler_throughput");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+
+    for service_count in [1_000usize, 10_000] {
+        group.throughput(Throughput::Elements(service_count as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(service_count),
+            &service_count,
+            |b, &service_count| {
+                b.iter_custom(|iters| {
+                    runtime.block_on(async {
+                        let mut total = Duration::ZERO;
+                        for _ in 0..iters {
+                            total += run_round(&url, service_count).await;
+                        }
+                        total
+                    })
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, scheduler_throughput_benchmark);
+criterion_main!(benches);
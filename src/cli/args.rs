@@ -2,6 +2,7 @@
 //!
 //! 使用clap定义应用程序的命令行接口
 
+use crate::config::{ConfigFormat, FileConfigLoader};
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 use tracing::error;
@@ -25,6 +26,28 @@ pub struct Args {
     )]
     pub config: Option<PathBuf>,
 
+    /// 配置文件格式，不指定时按扩展名判断
+    #[arg(
+        long,
+        value_enum,
+        global = true,
+        value_name = "FORMAT",
+        help = "配置文件格式，不指定时按扩展名判断",
+        env = "SERVICE_VITALS_CONFIG_FORMAT"
+    )]
+    pub config_format: Option<ConfigFileFormat>,
+
+    /// 运行中服务管理接口的访问令牌
+    #[arg(
+        long,
+        global = true,
+        value_name = "TOKEN",
+        help = "运行中服务管理接口的访问令牌，默认使用配置中的 web.api_token",
+        env = "SERVICE_VITALS_API_TOKEN",
+        hide_env_values = true
+    )]
+    pub api_token: Option<String>,
+
     /// 日志级别
     #[arg(
         short,
@@ -102,6 +125,27 @@ impl std::fmt::Display for LogLevel {
     }
 }
 
+/// 配置文件格式
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ConfigFileFormat {
+    /// TOML
+    Toml,
+    /// YAML
+    Yaml,
+    /// JSON
+    Json,
+}
+
+impl From<ConfigFileFormat> for ConfigFormat {
+    fn from(format: ConfigFileFormat) -> Self {
+        match format {
+            ConfigFileFormat::Toml => ConfigFormat::Toml,
+            ConfigFileFormat::Yaml => ConfigFormat::Yaml,
+            ConfigFileFormat::Json => ConfigFormat::Json,
+        }
+    }
+}
+
 /// 子命令定义
 #[derive(Subcommand, Debug, Clone)]
 pub enum Commands {
@@ -178,10 +222,59 @@ pub enum Commands {
 
     /// 执行一次性健康检测
     Check {
-        /// 服务名称（可选，不指定则检测所有服务）
-        #[arg(value_name = "SERVICE", help = "服务名称")]
+        /// 服务名称或通配符模式（可选，不指定则检测所有服务）
+        #[arg(
+            value_name = "SERVICE",
+            help = "服务名称，支持 * 和 ? 通配符（如 'api-*'）"
+        )]
         service: Option<String>,
 
+        /// 服务标签，可重复指定，带有任一标签的服务会被检测，支持通配符
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "只检测带有指定标签的服务，可重复指定，支持通配符（如 'team:*'）"
+        )]
+        tags: Vec<String>,
+
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value = "text", help = "输出格式")]
+        format: CheckOutputFormat,
+
+        /// 超时时间（秒），指定后覆盖配置文件中的超时设置
+        #[arg(
+            short,
+            long,
+            value_name = "SECONDS",
+            help = "超时时间（秒），默认使用配置文件中的设置"
+        )]
+        timeout: Option<u64>,
+    },
+
+    /// 立即触发运行中服务的健康检测
+    Trigger {
+        /// 服务名称
+        #[arg(
+            value_name = "SERVICE",
+            help = "服务名称",
+            required_unless_present = "tag",
+            conflicts_with = "tag"
+        )]
+        service: Option<String>,
+
+        /// 服务标签
+        #[arg(long, value_name = "TAG", help = "按标签触发一组服务的检测")]
+        tag: Option<String>,
+
+        /// 运行中服务的API地址
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "运行中服务的API地址（默认根据配置文件的Web配置推断）",
+            env = "SERVICE_VITALS_API_URL"
+        )]
+        api_url: Option<String>,
+
         /// 输出格式
         #[arg(short, long, value_enum, default_value = "text", help = "输出格式")]
         format: OutputFormat,
@@ -191,21 +284,113 @@ pub enum Commands {
             short,
             long,
             value_name = "SECONDS",
-            default_value = "10",
+            default_value = "60",
             help = "超时时间（秒）"
         )]
         timeout: u64,
     },
 
+    /// 暂停运行中服务的定时检测
+    Pause {
+        /// 服务名称
+        #[arg(value_name = "SERVICE", help = "服务名称")]
+        service: String,
+
+        /// 暂停原因
+        #[arg(short, long, help = "暂停原因（显示在状态面板）")]
+        reason: Option<String>,
+
+        /// 暂停时长
+        #[arg(
+            long = "for",
+            value_name = "DURATION",
+            value_parser = parse_duration,
+            conflicts_with = "until",
+            help = "暂停时长，到期自动恢复（如 90s、30m、2h、1d）"
+        )]
+        duration: Option<std::time::Duration>,
+
+        /// 自动恢复时间
+        #[arg(
+            long,
+            value_name = "TIME",
+            help = "自动恢复时间（RFC3339 格式，如 2024-01-01T08:00:00Z）"
+        )]
+        until: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// 运行中服务的API地址
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "运行中服务的API地址（默认根据配置文件的Web配置推断）",
+            env = "SERVICE_VITALS_API_URL"
+        )]
+        api_url: Option<String>,
+    },
+
+    /// 恢复运行中服务的定时检测
+    Resume {
+        /// 服务名称
+        #[arg(value_name = "SERVICE", help = "服务名称")]
+        service: String,
+
+        /// 运行中服务的API地址
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "运行中服务的API地址（默认根据配置文件的Web配置推断）",
+            env = "SERVICE_VITALS_API_URL"
+        )]
+        api_url: Option<String>,
+    },
+
+    /// 终端实时状态面板
+    Top {
+        /// 刷新间隔（秒）
+        #[arg(
+            short,
+            long,
+            value_name = "SECONDS",
+            default_value = "2",
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "刷新间隔（秒）"
+        )]
+        interval: u64,
+
+        /// 初始排序方式
+        #[arg(long, value_enum, default_value = "status", help = "初始排序方式")]
+        sort: TopSort,
+
+        /// 初始过滤条件
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            help = "初始过滤条件（匹配服务名称，支持 * 和 ? 通配符）"
+        )]
+        filter: Option<String>,
+
+        /// 运行中服务的API地址
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "运行中服务的API地址（默认根据配置文件的Web配置推断）",
+            env = "SERVICE_VITALS_API_URL"
+        )]
+        api_url: Option<String>,
+
+        /// 不连接运行中的服务
+        #[arg(long, help = "不连接运行中的服务，直接在本地执行检测")]
+        local: bool,
+    },
+
     /// 初始化配置文件
     Init {
-        /// 配置文件路径
+        /// 配置文件路径，不指定时为 config.toml（或 --config-format 对应的扩展名）
         #[arg(
             value_name = "FILE",
-            help = "配置文件路径",
-            default_value = "config.toml"
+            help = "配置文件路径，默认 config.toml（或 --config-format 对应的扩展名）"
         )]
-        config_path: PathBuf,
+        config_path: Option<PathBuf>,
 
         /// 是否覆盖现有文件
         #[arg(short, long, help = "覆盖现有文件")]
@@ -233,6 +418,49 @@ pub enum Commands {
         verbose: bool,
     },
 
+    /// 查看配置
+    Config {
+        /// 配置操作
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// 从其他监控工具导入服务配置
+    Import {
+        /// 来源类型
+        #[arg(value_enum, help = "来源类型")]
+        source: ImportSource,
+
+        /// 来源文件：Uptime Kuma 备份JSON，或包含 scrape_configs 的 prometheus.yml
+        #[arg(
+            value_name = "FILE",
+            help = "来源文件（Uptime Kuma 备份JSON 或 prometheus.yml）"
+        )]
+        file: PathBuf,
+
+        /// blackbox_exporter 配置文件，来源为 blackbox 时必填
+        #[arg(
+            long,
+            value_name = "FILE",
+            required_if_eq("source", "blackbox"),
+            help = "blackbox_exporter 配置文件（blackbox.yml）"
+        )]
+        modules: Option<PathBuf>,
+
+        /// 输出文件，不指定时输出到标准输出
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "输出文件，默认输出到标准输出"
+        )]
+        output: Option<PathBuf>,
+
+        /// 是否覆盖现有文件
+        #[arg(short, long, help = "覆盖现有文件")]
+        force: bool,
+    },
+
     /// 显示版本信息
     Version {
         /// 输出格式
@@ -316,6 +544,27 @@ pub enum Commands {
     },
 }
 
+/// 配置子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// 显示配置内容
+    Show {
+        /// 显示服务合并全局配置、展开模板和矩阵后的有效配置，支持通配符，不指定服务时显示全部
+        #[arg(
+            long,
+            value_name = "SERVICE",
+            num_args = 0..=1,
+            default_missing_value = "*",
+            help = "显示服务的有效配置（展开模板和矩阵），支持通配符，不指定服务时显示全部"
+        )]
+        effective: Option<String>,
+
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value = "text", help = "输出格式")]
+        format: OutputFormat,
+    },
+}
+
 /// 输出格式枚举
 #[derive(ValueEnum, Clone, Debug, PartialEq)]
 pub enum OutputFormat {
@@ -329,6 +578,43 @@ pub enum OutputFormat {
     Table,
 }
 
+/// 检测命令的输出格式
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum CheckOutputFormat {
+    /// 文本格式
+    Text,
+    /// JSON格式
+    Json,
+    /// 表格格式
+    Table,
+    /// Nagios插件格式：单行状态信息及性能数据
+    Nagios,
+    /// JUnit XML格式，供CI系统展示
+    Junit,
+}
+
+/// 状态面板的排序方式
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum TopSort {
+    /// 按服务名称
+    Name,
+    /// 按状态，异常的服务排在前面
+    Status,
+    /// 按响应时间，慢的服务排在前面
+    Latency,
+    /// 按连续失败次数，失败多的服务排在前面
+    Failures,
+}
+
+/// 导入来源类型
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ImportSource {
+    /// Uptime Kuma 备份（JSON）
+    UptimeKuma,
+    /// blackbox_exporter 模块加 Prometheus 抓取任务
+    Blackbox,
+}
+
 /// 配置模板类型
 #[derive(ValueEnum, Clone, Debug, PartialEq)]
 pub enum ConfigTemplate {
@@ -351,6 +637,32 @@ pub enum NotificationType {
     Webhook,
 }
 
+/// 解析时长参数
+///
+/// 支持 `s`、`m`、`h`、`d` 单位，不带单位时按秒计算
+pub fn parse_duration(value: &str) -> Result<std::time::Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+
+    let number: u64 = number.parse().map_err(|_| format!("无效的时长: {value}"))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("无效的时长单位: {unit}，支持 s、m、h、d")),
+    };
+
+    if number == 0 {
+        return Err("时长必须大于0".to_string());
+    }
+
+    Ok(std::time::Duration::from_secs(number * multiplier))
+}
+
 impl Args {
     /// 解析命令行参数
     pub fn parse_args() -> Self {
@@ -372,8 +684,244 @@ impl Args {
         }
     }
 
+    /// 按 `--config-format` 创建配置加载器（启用环境变量替换）
+    pub fn config_loader(&self) -> FileConfigLoader {
+        FileConfigLoader::new(true).with_format(self.config_format.map(Into::into))
+    }
+
     /// 是否启用详细输出
     pub fn is_verbose(&self) -> bool {
         self.verbose || matches!(self.log_level, LogLevel::Debug)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1800));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("5w").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn test_check_command_args() {
+        let args = Args::try_parse_from([
+            "service-vitals",
+            "check",
+            "api-*",
+            "--tag",
+            "core",
+            "--tag",
+            "db",
+            "--format",
+            "nagios",
+        ])
+        .unwrap();
+
+        match args.command {
+            Commands::Check {
+                service,
+                tags,
+                format,
+                timeout,
+            } => {
+                assert_eq!(service.as_deref(), Some("api-*"));
+                assert_eq!(tags, vec!["core", "db"]);
+                assert_eq!(format, CheckOutputFormat::Nagios);
+                assert_eq!(timeout, None);
+            }
+            other => panic!("解析结果错误: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_top_command_args() {
+        let args = Args::try_parse_from([
+            "service-vitals",
+            "top",
+            "--sort",
+            "latency",
+            "--filter",
+            "api-*",
+            "--local",
+        ])
+        .unwrap();
+
+        match args.command {
+            Commands::Top {
+                interval,
+                sort,
+                filter,
+                local,
+                ..
+            } => {
+                assert_eq!(interval, 2);
+                assert_eq!(sort, TopSort::Latency);
+                assert_eq!(filter.as_deref(), Some("api-*"));
+                assert!(local);
+            }
+            other => panic!("解析结果错误: {other:?}"),
+        }
+
+        assert!(Args::try_parse_from(["service-vitals", "top", "--interval", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_import_command_args() {
+        let args = Args::try_parse_from([
+            "service-vitals",
+            "import",
+            "blackbox",
+            "prometheus.yml",
+            "--modules",
+            "blackbox.yml",
+            "-o",
+            "services.toml",
+        ])
+        .unwrap();
+
+        match args.command {
+            Commands::Import {
+                source,
+                file,
+                modules,
+                output,
+                force,
+            } => {
+                assert_eq!(source, ImportSource::Blackbox);
+                assert_eq!(file, PathBuf::from("prometheus.yml"));
+                assert_eq!(modules, Some(PathBuf::from("blackbox.yml")));
+                assert_eq!(output, Some(PathBuf::from("services.toml")));
+                assert!(!force);
+            }
+            other => panic!("解析结果错误: {other:?}"),
+        }
+
+        // blackbox 来源必须提供模块配置
+        assert!(
+            Args::try_parse_from(["service-vitals", "import", "blackbox", "prometheus.yml"])
+                .is_err()
+        );
+        assert!(
+            Args::try_parse_from(["service-vitals", "import", "uptime-kuma", "backup.json"])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_config_show_args() {
+        let args = Args::try_parse_from([
+            "service-vitals",
+            "config",
+            "show",
+            "--effective",
+            "api",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+
+        match args.command {
+            Commands::Config {
+                action: ConfigAction::Show { effective, format },
+            } => {
+                assert_eq!(effective.as_deref(), Some("api"));
+                assert_eq!(format, OutputFormat::Json);
+            }
+            other => panic!("解析结果错误: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_show_effective_without_service() {
+        let args =
+            Args::try_parse_from(["service-vitals", "config", "show", "--effective"]).unwrap();
+
+        match args.command {
+            Commands::Config {
+                action: ConfigAction::Show { effective, .. },
+            } => assert_eq!(effective.as_deref(), Some("*")),
+            other => panic!("解析结果错误: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_config_format_args() {
+        let args = Args::try_parse_from([
+            "service-vitals",
+            "init",
+            "--config-format",
+            "yaml",
+            "--template",
+            "basic",
+        ])
+        .unwrap();
+
+        assert_eq!(args.config_format, Some(ConfigFileFormat::Yaml));
+        match &args.command {
+            Commands::Init { config_path, .. } => assert_eq!(config_path, &None),
+            other => panic!("解析结果错误: {other:?}"),
+        }
+        assert_eq!(
+            args.config_loader()
+                .format_for(std::path::Path::new("config.toml")),
+            ConfigFormat::Yaml
+        );
+
+        let args = Args::try_parse_from(["service-vitals", "status"]).unwrap();
+        assert_eq!(args.config_format, None);
+    }
+
+    #[test]
+    fn test_pause_command_args() {
+        let args = Args::try_parse_from([
+            "service-vitals",
+            "pause",
+            "api",
+            "--reason",
+            "维护",
+            "--for",
+            "30m",
+        ])
+        .unwrap();
+
+        match args.command {
+            Commands::Pause {
+                service,
+                reason,
+                duration,
+                until,
+                ..
+            } => {
+                assert_eq!(service, "api");
+                assert_eq!(reason.as_deref(), Some("维护"));
+                assert_eq!(duration, Some(Duration::from_secs(1800)));
+                assert!(until.is_none());
+            }
+            _ => panic!("应解析为 pause 命令"),
+        }
+
+        // --for 与 --until 不能同时使用
+        assert!(Args::try_parse_from([
+            "service-vitals",
+            "pause",
+            "api",
+            "--for",
+            "30m",
+            "--until",
+            "2030-01-01T00:00:00Z",
+        ])
+        .is_err());
+    }
+}
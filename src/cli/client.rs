@@ -0,0 +1,344 @@
+//! 运行中服务的API客户端
+//!
+//! 通过Web服务器提供的REST接口与正在运行的检测服务交互
+
+use crate::common::error::{Result, ServiceVitalsError};
+use crate::config::types::{Config, WebConfig};
+use crate::web::handlers::{
+    ApiCheckResponse, ApiCheckResult, ApiErrorResponse, ApiPauseRequest, ApiPauseResponse,
+    ApiSilenceResponse, ApiStatusResponse,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+
+/// 运行中服务的API客户端
+pub struct DaemonClient {
+    /// API基础地址，例如 `http://127.0.0.1:8080`
+    base_url: String,
+    /// HTTP客户端
+    client: reqwest::Client,
+    /// 管理接口的访问令牌
+    api_token: Option<String>,
+}
+
+impl DaemonClient {
+    /// 创建新的客户端
+    ///
+    /// # 参数
+    /// * `base_url` - API基础地址
+    /// * `timeout` - 请求超时时间
+    pub fn new(base_url: impl Into<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| ServiceVitalsError::DaemonError(format!("创建HTTP客户端失败: {e}")))?;
+
+        Ok(Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client,
+            api_token: None,
+        })
+    }
+
+    /// 设置管理接口（触发检测、暂停、静默）的访问令牌
+    pub fn with_api_token(mut self, api_token: Option<String>) -> Self {
+        self.api_token = api_token;
+        self
+    }
+
+    /// 根据配置文件中的Web服务器配置创建客户端
+    ///
+    /// # 参数
+    /// * `config` - 应用配置
+    /// * `timeout` - 请求超时时间
+    pub fn from_config(config: &Config, timeout: Duration) -> Result<Self> {
+        match &config.global.web {
+            Some(web_config) if web_config.enabled => {
+                Ok(Self::new(Self::base_url_for(web_config), timeout)?
+                    .with_api_token(web_config.api_token.clone()))
+            }
+            _ => Err(ServiceVitalsError::DaemonError(
+                "配置中未启用Web服务器，无法连接运行中的服务，请使用 --api-url 指定地址"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// 根据Web配置计算本机可访问的API地址
+    ///
+    /// 监听在通配地址时使用回环地址访问
+    pub fn base_url_for(web_config: &WebConfig) -> String {
+        let host = match web_config.bind_address.as_str() {
+            "0.0.0.0" | "" => "127.0.0.1".to_string(),
+            "::" => "[::1]".to_string(),
+            addr if addr.contains(':') => format!("[{addr}]"),
+            addr => addr.to_string(),
+        };
+        format!("http://{}:{}", host, web_config.port)
+    }
+
+    /// API基础地址
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 获取所有服务的当前状态
+    pub async fn status(&self) -> Result<ApiStatusResponse> {
+        let url = self.endpoint(&["api", "v1", "status"])?;
+        self.send_json(self.client.get(url.clone()), &url).await
+    }
+
+    /// 立即触发指定服务的健康检测
+    pub async fn trigger_service_check(&self, service_name: &str) -> Result<Vec<ApiCheckResult>> {
+        let url = self.endpoint(&["api", "v1", "services", service_name, "check"])?;
+        let response: ApiCheckResponse = self.post_json(url, None::<&()>).await?;
+        Ok(response.results)
+    }
+
+    /// 立即触发带有指定标签的所有服务的健康检测
+    pub async fn trigger_tag_check(&self, tag: &str) -> Result<Vec<ApiCheckResult>> {
+        let url = self.endpoint(&["api", "v1", "tags", tag, "check"])?;
+        let response: ApiCheckResponse = self.post_json(url, None::<&()>).await?;
+        Ok(response.results)
+    }
+
+    /// 暂停指定服务的定时检测
+    pub async fn pause_service(
+        &self,
+        service_name: &str,
+        request: &ApiPauseRequest,
+    ) -> Result<ApiPauseResponse> {
+        let url = self.endpoint(&["api", "v1", "services", service_name, "pause"])?;
+        self.post_json(url, Some(request)).await
+    }
+
+    /// 恢复指定服务的定时检测
+    pub async fn resume_service(&self, service_name: &str) -> Result<ApiPauseResponse> {
+        let url = self.endpoint(&["api", "v1", "services", service_name, "resume"])?;
+        self.post_json(url, None::<&()>).await
+    }
+
+    /// 静默指定服务的告警和恢复通知
+    pub async fn silence_service(
+        &self,
+        service_name: &str,
+        request: &ApiPauseRequest,
+    ) -> Result<ApiSilenceResponse> {
+        let url = self.endpoint(&["api", "v1", "services", service_name, "silence"])?;
+        self.post_json(url, Some(request)).await
+    }
+
+    /// 取消指定服务的通知静默
+    pub async fn unsilence_service(&self, service_name: &str) -> Result<ApiSilenceResponse> {
+        let url = self.endpoint(&["api", "v1", "services", service_name, "unsilence"])?;
+        self.post_json(url, None::<&()>).await
+    }
+
+    /// 拼接API地址，路径段会被正确转义
+    fn endpoint(&self, segments: &[&str]) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&self.base_url).map_err(|e| {
+            ServiceVitalsError::DaemonError(format!("无效的API地址 {}: {e}", self.base_url))
+        })?;
+        url.path_segments_mut()
+            .map_err(|_| {
+                ServiceVitalsError::DaemonError(format!("无效的API地址: {}", self.base_url))
+            })?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    /// 发送POST请求并解析JSON响应
+    async fn post_json<T: DeserializeOwned>(
+        &self,
+        url: reqwest::Url,
+        body: Option<&impl Serialize>,
+    ) -> Result<T> {
+        let mut request = self.client.post(url.clone());
+        if let Some(token) = &self.api_token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        self.send_json(request, &url).await
+    }
+
+    /// 发送请求并解析JSON响应，非成功状态码时返回服务端的错误信息
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        url: &reqwest::Url,
+    ) -> Result<T> {
+        let response = request.send().await.map_err(|e| {
+            ServiceVitalsError::DaemonError(format!("连接运行中的服务失败 ({url}): {e}"))
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = match response.json::<ApiErrorResponse>().await {
+                Ok(body) => body.error,
+                Err(_) => status.to_string(),
+            };
+            return Err(ServiceVitalsError::DaemonError(format!(
+                "请求失败 (HTTP {}): {}",
+                status.as_u16(),
+                message
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| ServiceVitalsError::DaemonError(format!("解析响应失败: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_for_wildcard_address() {
+        let web_config = WebConfig {
+            bind_address: "0.0.0.0".to_string(),
+            port: 9090,
+            ..Default::default()
+        };
+        assert_eq!(
+            DaemonClient::base_url_for(&web_config),
+            "http://127.0.0.1:9090"
+        );
+
+        let web_config = WebConfig {
+            bind_address: "::".to_string(),
+            ..web_config
+        };
+        assert_eq!(DaemonClient::base_url_for(&web_config), "http://[::1]:9090");
+    }
+
+    #[tokio::test]
+    async fn test_trigger_service_check() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/v1/services/api/check")
+            .match_header("authorization", "Bearer s3cret")
+            .with_status(200)
+            .with_body(
+                r#"{"results":[{"name":"api","status":"up","status_code":200,
+                "response_time_ms":12,"error_message":null,
+                "checked_at":"2024-01-01T00:00:00+00:00"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = DaemonClient::new(server.url(), Duration::from_secs(5))
+            .unwrap()
+            .with_api_token(Some("s3cret".to_string()));
+        let results = client.trigger_service_check("api").await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "api");
+        assert_eq!(results[0].status_code, Some(200));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_trigger_check_error_response() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/v1/services/missing/check")
+            .with_status(404)
+            .with_body(r#"{"error":"服务不存在或未启用: missing"}"#)
+            .create_async()
+            .await;
+
+        let client = DaemonClient::new(server.url(), Duration::from_secs(5)).unwrap();
+        let err = client.trigger_service_check("missing").await.unwrap_err();
+
+        assert!(err.to_string().contains("404"));
+        assert!(err.to_string().contains("服务不存在"));
+    }
+
+    #[tokio::test]
+    async fn test_status_and_silence_service() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/v1/status")
+            .with_status(200)
+            .with_body(
+                r#"{"services":[{"name":"api","url":"http://api","status":"Offline",
+                "response_time_ms":12,"last_check":null,"error_message":"timeout",
+                "consecutive_failures":2,"paused":null,"silenced":null}],
+                "last_updated":"2024-01-01T00:00:00+00:00","total_services":1,
+                "online_services":0,"offline_services":1,"unknown_services":0}"#,
+            )
+            .create_async()
+            .await;
+        let silence_mock = server
+            .mock("POST", "/api/v1/services/api/silence")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "duration_seconds": 3600
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"name":"api","silenced":true,"silence":{"silenced_at":"2024-01-01T00:00:00Z",
+                "until":"2024-01-01T01:00:00Z","reason":null}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = DaemonClient::new(server.url(), Duration::from_secs(5)).unwrap();
+        let status = client.status().await.unwrap();
+        assert_eq!(status.services[0].consecutive_failures, 2);
+        assert_eq!(status.offline_services, 1);
+
+        let request = ApiPauseRequest {
+            duration_seconds: Some(3600),
+            ..Default::default()
+        };
+        let response = client.silence_service("api", &request).await.unwrap();
+        assert!(response.silenced);
+        assert!(response.silence.unwrap().until.is_some());
+        silence_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_service() {
+        let mut server = mockito::Server::new_async().await;
+        let pause_mock = server
+            .mock("POST", "/api/v1/services/api/pause")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "reason": "维护",
+                "duration_seconds": 1800
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"name":"api","paused":true,"pause":{"paused_at":"2024-01-01T00:00:00Z",
+                "until":"2024-01-01T00:30:00Z","reason":"维护"}}"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/api/v1/services/api/resume")
+            .with_status(200)
+            .with_body(r#"{"name":"api","paused":false,"pause":null}"#)
+            .create_async()
+            .await;
+
+        let client = DaemonClient::new(server.url(), Duration::from_secs(5)).unwrap();
+        let request = ApiPauseRequest {
+            reason: Some("维护".to_string()),
+            until: None,
+            duration_seconds: Some(1800),
+        };
+        let response = client.pause_service("api", &request).await.unwrap();
+        assert!(response.paused);
+        assert_eq!(response.pause.unwrap().reason.as_deref(), Some("维护"));
+        pause_mock.assert_async().await;
+
+        let response = client.resume_service("api").await.unwrap();
+        assert!(!response.paused);
+    }
+}
@@ -2,10 +2,23 @@
 //!
 //! 实现各种CLI命令的处理逻辑
 
-use crate::cli::args::{Args, Commands, ConfigTemplate, NotificationType, OutputFormat};
+use crate::cli::args::{
+    Args, CheckOutputFormat, Commands, ConfigAction, ConfigTemplate, ImportSource,
+    NotificationType, OutputFormat,
+};
+use crate::cli::check_report::{CheckEntry, CheckReport, NagiosState};
+use crate::cli::client::DaemonClient;
+use crate::cli::top::{self, DataSource, LocalMonitor, TopState};
+use crate::common::error::ConfigError;
 use crate::common::error::Result;
+use crate::common::glob::{glob_match, glob_match_any, is_glob};
 use crate::common::status::{OverallStatus, StatusManager};
-use crate::config::{ConfigLoader, TomlConfigLoader};
+use crate::config::import::{self, ImportOutcome};
+use crate::config::secret;
+use crate::config::{
+    validate_config, Config, ConfigFormat, ConfigLoader, EffectiveServiceConfig, FileConfigLoader,
+    GlobalConfig, ServiceConfig,
+};
 use crate::daemon::{
     service_manager::{ServiceInfo, ServiceManager},
     DaemonConfig,
@@ -13,10 +26,11 @@ use crate::daemon::{
 use crate::health::{HealthChecker, HttpHealthChecker};
 use crate::notification::sender::{MessageType, NotificationMessage};
 use crate::notification::{FeishuSender, NotificationSender};
+use crate::web::handlers::{ApiCheckResult, ApiPauseRequest};
 use async_trait::async_trait;
 use chrono;
 use serde::Serialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// 命令处理器trait
@@ -80,7 +94,10 @@ impl Command for InitCommand {
             template,
         } = &args.command
         {
-            self.create_config_file(config_path, *force, template).await
+            let explicit = args.config_format.map(ConfigFormat::from);
+            let (config_path, format) = Self::resolve_target(config_path.as_deref(), explicit)?;
+            self.create_config_file(&config_path, format, *force, template)
+                .await
         } else {
             Ok(())
         }
@@ -88,10 +105,39 @@ impl Command for InitCommand {
 }
 
 impl InitCommand {
+    /// 确定输出路径和格式：--config-format 优先，其次按扩展名，默认TOML
+    fn resolve_target(
+        config_path: Option<&Path>,
+        explicit: Option<ConfigFormat>,
+    ) -> Result<(PathBuf, ConfigFormat)> {
+        let Some(path) = config_path else {
+            let format = explicit.unwrap_or_default();
+            return Ok((
+                PathBuf::from(format!("config.{}", format.extension())),
+                format,
+            ));
+        };
+
+        let detected = ConfigFormat::from_path(path);
+        let format = match (explicit, detected) {
+            (Some(explicit), Some(detected)) if explicit != detected => {
+                return Err(ConfigError::ValidationError(format!(
+                    "--config-format 指定为 {explicit}，但文件扩展名对应 {detected}: {}",
+                    path.display()
+                ))
+                .into());
+            }
+            (Some(format), _) | (None, Some(format)) => format,
+            (None, None) => ConfigFormat::default(),
+        };
+        Ok((path.to_path_buf(), format))
+    }
+
     /// 创建配置文件
     async fn create_config_file(
         &self,
         config_path: &Path,
+        format: ConfigFormat,
         force: bool,
         template: &ConfigTemplate,
     ) -> Result<()> {
@@ -107,35 +153,49 @@ impl InitCommand {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        // 根据模板类型生成配置内容
-        let config_content = match template {
-            ConfigTemplate::Minimal => self.get_minimal_config(),
-            ConfigTemplate::Basic => self.get_basic_config(),
-            ConfigTemplate::Full => self.get_full_config(),
-        };
+        // 根据模板类型和格式生成配置内容
+        let config_content = Self::template_content(template, format);
 
         // 写入配置文件
         tokio::fs::write(config_path, config_content).await?;
 
-        println!("配置文件已创建: {}", config_path.display());
+        println!("配置文件已创建: {} ({format})", config_path.display());
         println!("请编辑配置文件以添加您的服务配置");
 
         Ok(())
     }
 
-    /// 获取最小配置模板
-    fn get_minimal_config(&self) -> &'static str {
-        include_str!("../../examples/minimal_config.toml")
-    }
-
-    /// 获取基础配置模板
-    fn get_basic_config(&self) -> &'static str {
-        include_str!("../../examples/basic_config.toml")
-    }
-
-    /// 获取完整配置模板
-    fn get_full_config(&self) -> &'static str {
-        include_str!("../../examples/full_config.toml")
+    /// 获取指定格式的配置模板
+    fn template_content(template: &ConfigTemplate, format: ConfigFormat) -> &'static str {
+        match (template, format) {
+            (ConfigTemplate::Minimal, ConfigFormat::Toml) => {
+                include_str!("../../examples/minimal_config.toml")
+            }
+            (ConfigTemplate::Minimal, ConfigFormat::Yaml) => {
+                include_str!("../../examples/minimal_config.yaml")
+            }
+            (ConfigTemplate::Minimal, ConfigFormat::Json) => {
+                include_str!("../../examples/minimal_config.json")
+            }
+            (ConfigTemplate::Basic, ConfigFormat::Toml) => {
+                include_str!("../../examples/basic_config.toml")
+            }
+            (ConfigTemplate::Basic, ConfigFormat::Yaml) => {
+                include_str!("../../examples/basic_config.yaml")
+            }
+            (ConfigTemplate::Basic, ConfigFormat::Json) => {
+                include_str!("../../examples/basic_config.json")
+            }
+            (ConfigTemplate::Full, ConfigFormat::Toml) => {
+                include_str!("../../examples/full_config.toml")
+            }
+            (ConfigTemplate::Full, ConfigFormat::Yaml) => {
+                include_str!("../../examples/full_config.yaml")
+            }
+            (ConfigTemplate::Full, ConfigFormat::Json) => {
+                include_str!("../../examples/full_config.json")
+            }
+        }
     }
 }
 
@@ -154,7 +214,8 @@ impl Command for ValidateCommand {
                 .clone()
                 .unwrap_or_else(|| args.get_config_path());
 
-            self.validate_config_file(&config_file, *verbose).await
+            self.validate_config_file(&args.config_loader(), &config_file, *verbose)
+                .await
         } else {
             Ok(())
         }
@@ -163,11 +224,15 @@ impl Command for ValidateCommand {
 
 impl ValidateCommand {
     /// 验证配置文件
-    async fn validate_config_file(&self, config_path: &Path, verbose: bool) -> Result<()> {
+    async fn validate_config_file(
+        &self,
+        loader: &FileConfigLoader,
+        config_path: &Path,
+        verbose: bool,
+    ) -> Result<()> {
         println!("验证配置文件: {}", config_path.display());
 
         // 加载配置
-        let loader = TomlConfigLoader::new(true);
         let config = loader.load_from_file(config_path).await?;
 
         if verbose {
@@ -180,7 +245,12 @@ impl ValidateCommand {
 
             println!("服务配置:");
             for (i, service) in config.services.iter().enumerate() {
-                println!("  {}. {} ({})", i + 1, service.name, service.url);
+                println!(
+                    "  {}. {} ({})",
+                    i + 1,
+                    service.name,
+                    secret::redact_url(&service.url)
+                );
                 println!("     方法: {}", service.method);
                 println!("     期望状态码: {:?}", service.expected_status_codes);
                 println!("     失败阈值: {}", service.failure_threshold);
@@ -198,7 +268,288 @@ impl ValidateCommand {
     }
 }
 
+/// 配置查看命令
+pub struct ConfigCommand;
+
+#[async_trait]
+impl Command for ConfigCommand {
+    async fn execute(&self, args: &Args) -> Result<()> {
+        if let Commands::Config {
+            action: ConfigAction::Show { effective, format },
+        } = &args.command
+        {
+            self.show_config(args, effective.as_deref(), format).await
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ConfigCommand {
+    /// 显示配置内容
+    ///
+    /// 指定服务（可用通配符）时显示合并全局配置、展开模板和矩阵后的有效配置，否则显示完整配置。
+    /// 密码、令牌和Webhook等敏感值会被隐藏
+    async fn show_config(
+        &self,
+        args: &Args,
+        service_name: Option<&str>,
+        format: &OutputFormat,
+    ) -> Result<()> {
+        let loader = args.config_loader();
+        let config = loader.load_from_file(args.get_config_path()).await?;
+
+        let Some(name) = service_name else {
+            let config = secret::redacted(&config);
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&config)?),
+                _ => print!(
+                    "{}",
+                    toml::to_string(&without_nulls(config))
+                        .map_err(|e| ConfigError::ParseError(format!("序列化配置失败: {e}")))?
+                ),
+            }
+            return Ok(());
+        };
+
+        let views: Vec<EffectiveServiceView> = config
+            .services
+            .iter()
+            .filter(|service| {
+                if is_glob(name) {
+                    glob_match(name, &service.name)
+                } else {
+                    service.name == name
+                }
+            })
+            .map(|service| EffectiveServiceView::new(&config.global, service))
+            .collect();
+        if views.is_empty() {
+            return Err(ConfigError::ValidationError(format!("未找到名为 '{name}' 的服务")).into());
+        }
+
+        match format {
+            // 指定单个服务时输出对象，使用通配符时输出数组
+            OutputFormat::Json if is_glob(name) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&secret::redacted(&views))?
+                )
+            }
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&secret::redacted(&views[0]))?
+            ),
+            _ => {
+                for (index, view) in views.iter().enumerate() {
+                    if index > 0 {
+                        println!();
+                    }
+                    self.print_effective(view);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 以文本格式打印服务的有效配置
+    fn print_effective(&self, view: &EffectiveServiceView) {
+        let effective = &view.effective;
+        println!("服务: {}", effective.name);
+        println!("  URL: {}", secret::redact_url(&effective.url));
+        println!("  方法: {}", effective.method);
+        println!("  启用: {}", if view.enabled { "是" } else { "否" });
+        if !view.expected_status_codes.is_empty() {
+            println!("  期望状态码: {:?}", view.expected_status_codes);
+        }
+        println!("  失败阈值: {}", view.failure_threshold);
+        if !view.tags.is_empty() {
+            println!("  标签: {}", view.tags.join(", "));
+        }
+        println!(
+            "  飞书Webhook: {}",
+            view.feishu_webhook_url
+                .map_or("未配置", |_| secret::REDACTED)
+        );
+        println!("  检测间隔: {}秒", effective.check_interval_seconds);
+        println!("  请求超时: {}秒", effective.timeout.as_secs_f64());
+        println!("  重试次数: {}", effective.retry_attempts);
+        println!("  重试间隔: {}秒", effective.retry_delay.as_secs_f64());
+        println!("  重试退避: {}", effective.retry_backoff);
+        if effective.headers.is_empty() {
+            println!("  请求头: 无");
+        } else {
+            println!("  请求头:");
+            for (key, value) in &effective.headers {
+                let value = if secret::is_secret_key(key) {
+                    secret::REDACTED
+                } else {
+                    value
+                };
+                println!("    {key}: {value}");
+            }
+        }
+    }
+}
+
+/// 移除未设置的字段，TOML 不支持空值
+fn without_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(fields) => fields
+            .into_iter()
+            .filter(|(_, field)| !field.is_null())
+            .map(|(key, field)| (key, without_nulls(field)))
+            .collect(),
+        serde_json::Value::Array(items) => items.into_iter().map(without_nulls).collect(),
+        other => other,
+    }
+}
+
+/// 服务的有效配置，附带模板和矩阵展开后的检测与告警设置
+#[derive(Serialize)]
+struct EffectiveServiceView<'a> {
+    #[serde(flatten)]
+    effective: EffectiveServiceConfig,
+    enabled: bool,
+    expected_status_codes: &'a [u16],
+    failure_threshold: u32,
+    tags: &'a [String],
+    /// 服务未配置时使用全局默认的Webhook
+    feishu_webhook_url: Option<&'a str>,
+}
+
+impl<'a> EffectiveServiceView<'a> {
+    fn new(global: &'a GlobalConfig, service: &'a ServiceConfig) -> Self {
+        Self {
+            effective: EffectiveServiceConfig::from_global(global, service),
+            enabled: service.enabled,
+            expected_status_codes: &service.expected_status_codes,
+            failure_threshold: service.failure_threshold,
+            tags: &service.tags,
+            feishu_webhook_url: service
+                .feishu_webhook_url
+                .as_deref()
+                .or(global.default_feishu_webhook_url.as_deref()),
+        }
+    }
+}
+
+/// 导入命令
+///
+/// 生成的配置写入文件或标准输出，未能转换的字段输出到标准错误
+pub struct ImportCommand;
+
+#[async_trait]
+impl Command for ImportCommand {
+    async fn execute(&self, args: &Args) -> Result<()> {
+        if let Commands::Import {
+            source,
+            file,
+            modules,
+            output,
+            force,
+        } = &args.command
+        {
+            self.import(*source, file, modules.as_deref(), output.as_deref(), *force)
+                .await
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ImportCommand {
+    /// 转换来源配置并输出
+    async fn import(
+        &self,
+        source: ImportSource,
+        file: &Path,
+        modules: Option<&Path>,
+        output: Option<&Path>,
+        force: bool,
+    ) -> Result<()> {
+        if let Some(output) = output {
+            if output.exists() && !force {
+                eprintln!("配置文件已存在: {}", output.display());
+                eprintln!("使用 --force 参数覆盖现有文件");
+                return Ok(());
+            }
+        }
+
+        let content = read_source(file).await?;
+        let (outcome, origin) = match source {
+            ImportSource::UptimeKuma => (
+                import::from_uptime_kuma(&content),
+                format!("Uptime Kuma 备份 {}", file.display()),
+            ),
+            ImportSource::Blackbox => {
+                let modules = modules.ok_or_else(|| {
+                    ConfigError::ValidationError(
+                        "blackbox 来源需要通过 --modules 指定 blackbox.yml".to_string(),
+                    )
+                })?;
+                (
+                    import::from_blackbox(&read_source(modules).await?, &content),
+                    format!("{} 和 {}", modules.display(), file.display()),
+                )
+            }
+        };
+        let outcome = outcome.map_err(ConfigError::ParseError)?;
+        self.print_report(&outcome);
+        if outcome.services.is_empty() {
+            return Err(ConfigError::ValidationError("没有可以导入的服务".to_string()).into());
+        }
+
+        let content = outcome.to_toml(&origin).map_err(ConfigError::ParseError)?;
+        // 生成的配置按正常加载流程再校验一次
+        let config: Config = toml::from_str(&content)
+            .map_err(|e| ConfigError::ParseError(format!("生成的配置无法解析: {e}")))?;
+        validate_config(&config).map_err(ConfigError::ValidationError)?;
+
+        match output {
+            Some(output) => {
+                if let Some(parent) = output.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                tokio::fs::write(output, content).await?;
+                eprintln!("配置文件已创建: {}", output.display());
+            }
+            None => print!("{content}"),
+        }
+        Ok(())
+    }
+
+    /// 在标准错误输出导入报告
+    fn print_report(&self, outcome: &ImportOutcome) {
+        eprintln!(
+            "已转换 {} 个服务，跳过 {} 个监控项",
+            outcome.services.len(),
+            outcome.skipped_count()
+        );
+        if !outcome.issues.is_empty() {
+            eprintln!("未能转换的字段:");
+            for issue in &outcome.issues {
+                eprintln!("  - {issue}");
+            }
+        }
+    }
+}
+
+/// 读取导入来源文件
+async fn read_source(path: &Path) -> Result<String> {
+    if !path.is_file() {
+        return Err(ConfigError::FileNotFound {
+            path: path.display().to_string(),
+        }
+        .into());
+    }
+    Ok(tokio::fs::read_to_string(path).await?)
+}
+
 /// 检测命令
+///
+/// 进程退出码遵循 Nagios 插件约定：0正常、1降级、2异常、3未知（含无法完成检测）
 pub struct CheckCommand;
 
 #[async_trait]
@@ -206,84 +557,137 @@ impl Command for CheckCommand {
     async fn execute(&self, args: &Args) -> Result<()> {
         if let Commands::Check {
             service,
+            tags,
             format,
             timeout,
         } = &args.command
         {
-            self.perform_health_check(args, service.as_deref(), format, *timeout)
+            let exit_code = match self
+                .perform_health_check(args, service.as_deref(), tags, format, *timeout)
                 .await
-        } else {
-            Ok(())
+            {
+                Ok(report) => report.exit_code(),
+                Err(e) => {
+                    if *format == CheckOutputFormat::Nagios {
+                        println!(
+                            "SERVICE-VITALS {} - {}",
+                            NagiosState::Unknown.label(),
+                            e.to_string().replace(['\r', '\n'], " ")
+                        );
+                    }
+                    eprintln!("健康检测失败: {e}");
+                    NagiosState::Unknown.exit_code()
+                }
+            };
+            if exit_code != 0 {
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+                std::process::exit(exit_code);
+            }
         }
+        Ok(())
     }
 }
 
 impl CheckCommand {
-    /// 执行健康检测
+    /// 执行健康检测并输出结果
+    ///
+    /// # 参数
+    /// * `service_pattern` - 服务名称或通配符模式
+    /// * `tags` - 服务标签，服务带有任一标签即被选中
     async fn perform_health_check(
         &self,
         args: &Args,
-        service_name: Option<&str>,
-        format: &OutputFormat,
-        timeout: u64,
-    ) -> Result<()> {
+        service_pattern: Option<&str>,
+        tags: &[String],
+        format: &CheckOutputFormat,
+        timeout: Option<u64>,
+    ) -> Result<CheckReport> {
         // 加载配置
-        let loader = TomlConfigLoader::new(true);
+        let loader = args.config_loader();
         let config = loader.load_from_file(args.get_config_path()).await?;
 
-        // 创建健康检测器
-        let checker = HttpHealthChecker::new(
-            Duration::from_secs(timeout),
-            config.global.retry_attempts,
-            Duration::from_secs(config.global.retry_delay_seconds),
-        )?;
+        // 创建健康检测器，全局请求头、超时和重试策略作为默认值
+        let checker = HttpHealthChecker::from_global_config(&config.global)?;
 
         // 过滤要检测的服务
-        let services_to_check: Vec<_> = if let Some(name) = service_name {
-            config
-                .services
-                .into_iter()
-                .filter(|s| s.name == name && s.enabled)
-                .collect()
-        } else {
-            config.services.into_iter().filter(|s| s.enabled).collect()
-        };
+        let services_to_check: Vec<_> = config
+            .services
+            .into_iter()
+            .filter(|s| s.enabled && Self::is_selected(s, service_pattern, tags))
+            .collect();
 
+        let human_readable = matches!(format, CheckOutputFormat::Text | CheckOutputFormat::Table);
         if services_to_check.is_empty() {
-            if let Some(name) = service_name {
-                eprintln!("未找到名为 '{name}' 的启用服务");
-            } else {
-                eprintln!("未找到任何启用的服务");
+            match (service_pattern, tags.is_empty()) {
+                (Some(pattern), _) => eprintln!("未找到匹配 '{pattern}' 的启用服务"),
+                (None, false) => eprintln!("未找到带有标签 {} 的启用服务", tags.join(", ")),
+                (None, true) => eprintln!("未找到任何启用的服务"),
             }
-            return Ok(());
+        } else if human_readable {
+            println!("开始健康检测...");
         }
 
-        println!("开始健康检测...");
-
-        // 执行检测
-        let results = checker.check_batch(&services_to_check).await;
+        // 执行检测，命令行指定的超时时间优先于配置文件
+        let results = match timeout {
+            Some(seconds) => {
+                let timeout = Duration::from_secs(seconds);
+                futures::future::join_all(
+                    services_to_check
+                        .iter()
+                        .map(|service| checker.check_with_timeout(service, timeout)),
+                )
+                .await
+            }
+            None => checker.check_batch(&services_to_check).await,
+        };
+        let report = CheckReport::new(
+            services_to_check
+                .iter()
+                .zip(results)
+                .map(|(service, result)| CheckEntry {
+                    name: service.name.clone(),
+                    result: result.map_err(|e| e.to_string()),
+                })
+                .collect(),
+        );
 
         // 输出结果
         match format {
-            OutputFormat::Json => {
-                let json_results: Vec<_> = results.into_iter().filter_map(|r| r.ok()).collect();
-                println!("{}", serde_json::to_string_pretty(&json_results)?);
-            }
-            OutputFormat::Table => {
-                self.print_table_results(&results);
-            }
-            _ => {
-                self.print_text_results(&results);
+            CheckOutputFormat::Json => {
+                let json_results: Vec<_> = report
+                    .entries()
+                    .iter()
+                    .filter_map(|entry| entry.result.as_ref().ok())
+                    .collect();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&secret::redacted(&json_results))?
+                );
             }
+            CheckOutputFormat::Table => self.print_table_results(report.entries()),
+            CheckOutputFormat::Text => self.print_text_results(report.entries()),
+            CheckOutputFormat::Nagios => println!("{}", report.nagios_line()),
+            CheckOutputFormat::Junit => print!("{}", report.junit_xml()),
         }
 
-        Ok(())
+        Ok(report)
+    }
+
+    /// 判断服务是否被选中：名称匹配模式且带有任一指定标签（均支持通配符）
+    fn is_selected(
+        service: &crate::config::ServiceConfig,
+        pattern: Option<&str>,
+        tags: &[String],
+    ) -> bool {
+        let name_matches = pattern.is_none_or(|pattern| glob_match(pattern, &service.name));
+        let tag_matches = tags.is_empty() || glob_match_any(tags, &service.tags);
+        name_matches && tag_matches
     }
 
     /// 打印文本格式结果
-    fn print_text_results(&self, results: &[Result<crate::health::HealthResult>]) {
-        for result in results {
-            match result {
+    fn print_text_results(&self, entries: &[CheckEntry]) {
+        for entry in entries {
+            match &entry.result {
                 Ok(health_result) => {
                     let status_icon = if health_result.status.is_healthy() {
                         "✓"
@@ -294,7 +698,7 @@ impl CheckCommand {
                         "{} {} ({}) - {} - {}ms",
                         status_icon,
                         health_result.service_name,
-                        health_result.service_url,
+                        secret::redact_url(&health_result.service_url),
                         health_result.status,
                         health_result.response_time_ms()
                     );
@@ -304,28 +708,23 @@ impl CheckCommand {
                     }
                 }
                 Err(e) => {
-                    println!("✗ 检测失败: {e}");
+                    println!("✗ {} - 检测失败: {e}", entry.name);
                 }
             }
         }
     }
 
     /// 打印表格格式结果
-    fn print_table_results(&self, results: &[Result<crate::health::HealthResult>]) {
+    fn print_table_results(&self, entries: &[CheckEntry]) {
         println!(
             "{:<20} {:<10} {:<15} {:<10} {:<30}",
             "服务名称", "状态", "状态码", "响应时间", "错误信息"
         );
         println!("{}", "-".repeat(85));
 
-        for result in results {
-            match result {
+        for entry in entries {
+            match &entry.result {
                 Ok(health_result) => {
-                    let status = if health_result.status.is_healthy() {
-                        "正常"
-                    } else {
-                        "异常"
-                    };
                     let status_code = health_result
                         .status_code
                         .map(|c| c.to_string())
@@ -335,7 +734,7 @@ impl CheckCommand {
                     println!(
                         "{:<20} {:<10} {:<15} {:<10} {:<30}",
                         health_result.service_name,
-                        status,
+                        health_result.status.to_string(),
                         status_code,
                         format!("{}ms", health_result.response_time_ms()),
                         error_msg
@@ -344,18 +743,229 @@ impl CheckCommand {
                 Err(e) => {
                     println!(
                         "{:<20} {:<10} {:<15} {:<10} {:<30}",
-                        "未知",
-                        "错误",
-                        "N/A",
-                        "N/A",
-                        e.to_string()
+                        entry.name, "错误", "N/A", "N/A", e
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 触发检测命令
+///
+/// 通过REST接口让运行中的服务立即执行检测，结果会经过正常的通知和状态流程
+pub struct TriggerCommand;
+
+#[async_trait]
+impl Command for TriggerCommand {
+    async fn execute(&self, args: &Args) -> Result<()> {
+        if let Commands::Trigger {
+            service,
+            tag,
+            api_url,
+            format,
+            timeout,
+        } = &args.command
+        {
+            let client =
+                daemon_client(args, api_url.as_deref(), Duration::from_secs(*timeout)).await?;
+
+            let results = match (service, tag) {
+                (Some(name), _) => client.trigger_service_check(name).await?,
+                (None, Some(tag)) => client.trigger_tag_check(tag).await?,
+                (None, None) => return Ok(()),
+            };
+
+            self.print_results(&results, format)?;
+        }
+        Ok(())
+    }
+}
+
+impl TriggerCommand {
+    /// 输出触发检测结果
+    fn print_results(&self, results: &[ApiCheckResult], format: &OutputFormat) -> Result<()> {
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(results)?);
+            }
+            OutputFormat::Table => {
+                println!(
+                    "{:<20} {:<10} {:<15} {:<10} {:<30}",
+                    "服务名称", "状态", "状态码", "响应时间", "错误信息"
+                );
+                println!("{}", "-".repeat(85));
+                for result in results {
+                    println!(
+                        "{:<20} {:<10} {:<15} {:<10} {:<30}",
+                        result.name,
+                        result.status.to_string(),
+                        result
+                            .status_code
+                            .map(|c| c.to_string())
+                            .unwrap_or_else(|| "N/A".to_string()),
+                        result
+                            .response_time_ms
+                            .map(|ms| format!("{ms}ms"))
+                            .unwrap_or_else(|| "N/A".to_string()),
+                        result.error_message.as_deref().unwrap_or("")
                     );
                 }
             }
+            _ => {
+                for result in results {
+                    let status_icon = if result.status.is_healthy() {
+                        "✓"
+                    } else {
+                        "✗"
+                    };
+                    println!(
+                        "{} {} - {} - {}ms",
+                        status_icon,
+                        result.name,
+                        result.status,
+                        result.response_time_ms.unwrap_or(0)
+                    );
+                    if let Some(error) = &result.error_message {
+                        println!("  错误: {error}");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 暂停命令
+pub struct PauseCommand;
+
+#[async_trait]
+impl Command for PauseCommand {
+    async fn execute(&self, args: &Args) -> Result<()> {
+        if let Commands::Pause {
+            service,
+            reason,
+            duration,
+            until,
+            api_url,
+        } = &args.command
+        {
+            let client = daemon_client(args, api_url.as_deref(), Duration::from_secs(30)).await?;
+            let request = ApiPauseRequest {
+                reason: reason.clone(),
+                until: *until,
+                duration_seconds: duration.map(|d| d.as_secs()),
+            };
+
+            let response = client.pause_service(service, &request).await?;
+            println!("⏸ 服务 '{}' 已暂停", response.name);
+            if let Some(pause) = &response.pause {
+                if let Some(reason) = &pause.reason {
+                    println!("  原因: {reason}");
+                }
+                match pause.until {
+                    Some(until) => println!("  自动恢复时间: {}", until.to_rfc3339()),
+                    None => println!("  需要手动恢复: service-vitals resume {}", response.name),
+                }
+            }
         }
+        Ok(())
     }
 }
 
+/// 恢复命令
+pub struct ResumeCommand;
+
+#[async_trait]
+impl Command for ResumeCommand {
+    async fn execute(&self, args: &Args) -> Result<()> {
+        if let Commands::Resume { service, api_url } = &args.command {
+            let client = daemon_client(args, api_url.as_deref(), Duration::from_secs(30)).await?;
+            let response = client.resume_service(service).await?;
+            if response.pause.is_some() {
+                println!("▶ 服务 '{}' 已恢复检测", response.name);
+            } else {
+                println!("服务 '{}' 未处于暂停状态", response.name);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 终端状态面板命令
+pub struct TopCommand;
+
+#[async_trait]
+impl Command for TopCommand {
+    async fn execute(&self, args: &Args) -> Result<()> {
+        if let Commands::Top {
+            interval,
+            sort,
+            filter,
+            api_url,
+            local,
+        } = &args.command
+        {
+            let interval = Duration::from_secs(*interval);
+            let mut state = TopState::new(*sort, filter.clone());
+
+            // 优先连接运行中的服务，连接失败时回退到本地检测
+            let daemon = if *local {
+                None
+            } else {
+                match daemon_client(args, api_url.as_deref(), Duration::from_secs(5)).await {
+                    Ok(client) => match client.status().await {
+                        Ok(_) => Some(client),
+                        Err(e) => {
+                            state.set_message(format!("未连接到运行中的服务，改为本地检测: {e}"));
+                            None
+                        }
+                    },
+                    Err(e) => {
+                        state.set_message(format!("未连接到运行中的服务，改为本地检测: {e}"));
+                        None
+                    }
+                }
+            };
+
+            let source = match daemon {
+                Some(client) => DataSource::Daemon(client),
+                None => {
+                    let loader = args.config_loader();
+                    let config = loader.load_from_file(args.get_config_path()).await?;
+                    DataSource::Local(LocalMonitor::start(&config, interval)?)
+                }
+            };
+
+            top::run(source, state, interval).await?;
+        }
+        Ok(())
+    }
+}
+
+/// 创建连接运行中服务的客户端
+///
+/// 优先使用显式指定的API地址，否则根据配置文件中的Web配置推断；
+/// 命令行指定的API令牌优先于配置中的令牌
+async fn daemon_client(
+    args: &Args,
+    api_url: Option<&str>,
+    timeout: Duration,
+) -> Result<DaemonClient> {
+    let client = match api_url {
+        Some(url) => DaemonClient::new(url, timeout)?,
+        None => {
+            let loader = args.config_loader();
+            let config = loader.load_from_file(args.get_config_path()).await?;
+            DaemonClient::from_config(&config, timeout)?
+        }
+    };
+    Ok(match &args.api_token {
+        Some(token) => client.with_api_token(Some(token.clone())),
+        None => client,
+    })
+}
+
 /// 启动命令
 pub struct StartCommand;
 
@@ -448,7 +1058,10 @@ impl StatusCommand {
         match format {
             OutputFormat::Json => {
                 if verbose {
-                    println!("{}", serde_json::to_string_pretty(status)?);
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&secret::redacted(status))?
+                    );
                 } else {
                     let summary = serde_json::json!({
                         "total_services": status.total_services,
@@ -476,7 +1089,7 @@ impl StatusCommand {
                     println!("services:");
                     for service in &status.services {
                         println!("  - name: {}", service.name);
-                        println!("    url: {}", service.url);
+                        println!("    url: {}", secret::redact_url(&service.url));
                         println!("    status: {:?}", service.status);
                         println!("    enabled: {}", service.enabled);
                         if let Some(last_check) = service.last_check {
@@ -571,11 +1184,8 @@ impl StatusCommand {
                     last_check_str
                 );
 
-                if verbose && service.error_message.is_some() {
-                    println!(
-                        "│   错误: {:<71} │",
-                        truncate_string(service.error_message.as_ref().unwrap(), 71)
-                    );
+                if let (true, Some(error_message)) = (verbose, service.error_message.as_ref()) {
+                    println!("│   错误: {:<71} │", truncate_string(error_message, 71));
                 }
             }
 
@@ -794,7 +1404,7 @@ impl ServiceStatusCommand {
                     "is_installed": service_info.is_installed,
                     "platform": service_info.platform
                 },
-                "application_status": app_status,
+                "application_status": secret::redacted(app_status),
                 "metrics_update_check": self.check_metrics_updates(service_info, app_status).await
             });
             println!("{}", serde_json::to_string_pretty(&combined_status)?);
@@ -842,7 +1452,7 @@ impl ServiceStatusCommand {
                 println!("  services:");
                 for service in &status.services {
                     println!("    - name: {}", service.name);
-                    println!("      url: {}", service.url);
+                    println!("      url: {}", secret::redact_url(&service.url));
                     println!("      status: {:?}", service.status);
                     println!("      enabled: {}", service.enabled);
                     if let Some(last_check) = service.last_check {
@@ -1257,7 +1867,7 @@ impl TestNotificationCommand {
     /// 测试飞书通知
     async fn test_feishu_notification(&self, args: &Args, message: &str) -> Result<()> {
         // 加载配置
-        let loader = TomlConfigLoader::new(true);
+        let loader = args.config_loader();
         let config = loader.load_from_file(args.get_config_path()).await?;
 
         // 检查是否配置了飞书webhook
@@ -3,7 +3,10 @@
 //! 提供CLI参数解析和命令处理功能
 
 pub mod args;
+pub mod check_report;
+pub mod client;
 pub mod commands;
+pub mod top;
 
 // 重新导出主要类型
 pub use args::Args;
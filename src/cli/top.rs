@@ -0,0 +1,995 @@
+//! 终端实时状态面板
+//!
+//! `service-vitals top` 的实现：优先从运行中服务的API获取状态，
+//! 找不到运行中的服务时在本地执行检测。界面使用ANSI转义序列绘制，
+//! Unix平台下通过termios进入原始模式逐键读取输入。
+
+use crate::cli::args::TopSort;
+use crate::cli::client::DaemonClient;
+use crate::common::error::{Result, ServiceVitalsError};
+use crate::common::glob::{glob_match, is_glob};
+use crate::config::types::{Config, ServiceConfig};
+use crate::health::{HealthChecker, HealthStatus, HttpHealthChecker};
+use crate::web::handlers::{ApiPauseRequest, ApiServiceStatus};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// 每个服务保留的响应时间样本数，也是趋势图的宽度
+const HISTORY_LEN: usize = 16;
+
+/// 趋势图使用的字符，从低到高
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// 某一时刻的服务状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceSnapshot {
+    /// 服务名称
+    pub name: String,
+    /// 当前状态
+    pub status: HealthStatus,
+    /// 响应时间（毫秒）
+    pub response_time_ms: Option<u64>,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 最后的错误信息
+    pub last_error: Option<String>,
+    /// 最后检测时间
+    pub last_check: Option<DateTime<Utc>>,
+    /// 是否已暂停
+    pub paused: bool,
+    /// 通知是否已静默
+    pub silenced: bool,
+}
+
+impl From<ApiServiceStatus> for ServiceSnapshot {
+    fn from(service: ApiServiceStatus) -> Self {
+        let status = match service.status.as_str() {
+            "Online" => HealthStatus::Up,
+            "Offline" => HealthStatus::Down,
+            _ => HealthStatus::Unknown,
+        };
+        Self {
+            name: service.name,
+            status,
+            response_time_ms: service.response_time_ms,
+            consecutive_failures: service.consecutive_failures,
+            last_error: service.error_message,
+            last_check: service
+                .last_check
+                .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())
+                .map(|time| time.with_timezone(&Utc)),
+            paused: service.paused.is_some(),
+            silenced: service.silenced.is_some(),
+        }
+    }
+}
+
+/// 面板中的一行：服务状态及其响应时间历史
+#[derive(Debug, Clone)]
+struct TopRow {
+    snapshot: ServiceSnapshot,
+    history: VecDeque<u64>,
+}
+
+/// 按键
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Enter,
+    Esc,
+    Backspace,
+    Interrupt,
+    Char(char),
+}
+
+/// 按键对应的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// 无需额外操作，仅重绘
+    None,
+    /// 退出面板
+    Quit,
+    /// 立即刷新数据
+    Refresh,
+    /// 立即检测服务
+    Trigger(String),
+    /// 暂停或恢复服务，参数为服务当前是否已暂停
+    TogglePause(String, bool),
+    /// 静默或取消静默服务通知，参数为服务当前是否已静默
+    ToggleSilence(String, bool),
+}
+
+/// 面板状态：服务列表、排序、过滤和当前选中的服务
+pub struct TopState {
+    rows: Vec<TopRow>,
+    sort: TopSort,
+    filter: String,
+    editing_filter: bool,
+    selected: usize,
+    message: Option<String>,
+}
+
+impl TopState {
+    /// 创建面板状态
+    pub fn new(sort: TopSort, filter: Option<String>) -> Self {
+        Self {
+            rows: Vec::new(),
+            sort,
+            filter: filter.unwrap_or_default(),
+            editing_filter: false,
+            selected: 0,
+            message: None,
+        }
+    }
+
+    /// 设置底部提示信息
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+    }
+
+    /// 合并最新的服务状态，检测时间变化时记录响应时间样本
+    pub fn apply(&mut self, snapshots: Vec<ServiceSnapshot>) {
+        let mut previous: HashMap<String, TopRow> = self
+            .rows
+            .drain(..)
+            .map(|row| (row.snapshot.name.clone(), row))
+            .collect();
+
+        for snapshot in snapshots {
+            let mut history = match previous.remove(&snapshot.name) {
+                Some(row) if row.snapshot.last_check == snapshot.last_check => row.history,
+                Some(row) => {
+                    let mut history = row.history;
+                    if let Some(ms) = snapshot.response_time_ms {
+                        history.push_back(ms);
+                    }
+                    history
+                }
+                None => snapshot.response_time_ms.into_iter().collect(),
+            };
+            while history.len() > HISTORY_LEN {
+                history.pop_front();
+            }
+            self.rows.push(TopRow { snapshot, history });
+        }
+        self.clamp_selection();
+    }
+
+    /// 按当前过滤条件和排序方式得到要显示的服务
+    fn visible_rows(&self) -> Vec<&TopRow> {
+        let filter = self.filter.to_lowercase();
+        let mut rows: Vec<&TopRow> = self
+            .rows
+            .iter()
+            .filter(|row| {
+                let name = row.snapshot.name.to_lowercase();
+                match filter.as_str() {
+                    "" => true,
+                    pattern if is_glob(pattern) => glob_match(pattern, &name),
+                    pattern => name.contains(pattern),
+                }
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let (a, b) = (&a.snapshot, &b.snapshot);
+            let order = match self.sort {
+                TopSort::Name => std::cmp::Ordering::Equal,
+                TopSort::Status => status_rank(b.status).cmp(&status_rank(a.status)),
+                TopSort::Latency => b.response_time_ms.cmp(&a.response_time_ms),
+                TopSort::Failures => b.consecutive_failures.cmp(&a.consecutive_failures),
+            };
+            order.then_with(|| a.name.cmp(&b.name))
+        });
+        rows
+    }
+
+    /// 当前选中的服务
+    fn selected_row(&self) -> Option<&TopRow> {
+        self.visible_rows().get(self.selected).copied()
+    }
+
+    fn clamp_selection(&mut self) {
+        let count = self.visible_rows().len();
+        self.selected = self.selected.min(count.saturating_sub(1));
+    }
+
+    /// 处理按键，返回需要执行的操作
+    pub fn handle_key(&mut self, key: Key) -> Action {
+        if self.editing_filter {
+            match key {
+                Key::Enter => self.editing_filter = false,
+                Key::Esc => {
+                    self.editing_filter = false;
+                    self.filter.clear();
+                }
+                Key::Backspace => {
+                    self.filter.pop();
+                }
+                Key::Interrupt => return Action::Quit,
+                Key::Char(c) if !c.is_control() => self.filter.push(c),
+                _ => {}
+            }
+            self.selected = 0;
+            self.clamp_selection();
+            return Action::None;
+        }
+
+        let selected = self.selected_row().map(|row| {
+            (
+                row.snapshot.name.clone(),
+                row.snapshot.paused,
+                row.snapshot.silenced,
+            )
+        });
+        match key {
+            Key::Interrupt | Key::Char('q') => return Action::Quit,
+            Key::Up | Key::Char('k') => self.selected = self.selected.saturating_sub(1),
+            Key::Down | Key::Char('j') => {
+                self.selected += 1;
+                self.clamp_selection();
+            }
+            Key::Char('s') => {
+                self.sort = match self.sort {
+                    TopSort::Name => TopSort::Status,
+                    TopSort::Status => TopSort::Latency,
+                    TopSort::Latency => TopSort::Failures,
+                    TopSort::Failures => TopSort::Name,
+                };
+            }
+            Key::Char('/') => self.editing_filter = true,
+            Key::Esc => {
+                self.filter.clear();
+                self.clamp_selection();
+            }
+            Key::Char('r') => return Action::Refresh,
+            Key::Char('c') => {
+                if let Some((name, _, _)) = selected {
+                    return Action::Trigger(name);
+                }
+            }
+            Key::Char('p') => {
+                if let Some((name, paused, _)) = selected {
+                    return Action::TogglePause(name, paused);
+                }
+            }
+            Key::Char('m') => {
+                if let Some((name, _, silenced)) = selected {
+                    return Action::ToggleSilence(name, silenced);
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// 绘制整个屏幕
+    ///
+    /// # 参数
+    /// * `source` - 数据来源说明
+    /// * `width` - 终端宽度
+    /// * `height` - 终端高度
+    pub fn render(&self, source: &str, width: usize, height: usize) -> String {
+        let rows = self.visible_rows();
+        let count = |status: HealthStatus| {
+            self.rows
+                .iter()
+                .filter(|row| row.snapshot.status == status)
+                .count()
+        };
+
+        let mut lines = vec![
+            fit(
+                &format!(
+                    "service-vitals top - {source} | 排序: {} | 过滤: {}",
+                    sort_label(self.sort),
+                    if self.filter.is_empty() && !self.editing_filter {
+                        "无"
+                    } else {
+                        &self.filter
+                    }
+                ),
+                width,
+            ),
+            fit(
+                &format!(
+                    "共 {} 个服务  正常 {}  降级 {}  异常 {}  未知 {}  显示 {}    {}",
+                    self.rows.len(),
+                    count(HealthStatus::Up),
+                    count(HealthStatus::Degraded),
+                    count(HealthStatus::Down),
+                    count(HealthStatus::Unknown),
+                    rows.len(),
+                    Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+                ),
+                width,
+            ),
+            String::new(),
+            format!(
+                "\x1b[1m{}\x1b[0m",
+                fit(
+                    &format!(
+                        "  {}{}{}{}{}{}",
+                        fit("状态", 8),
+                        fit("服务名称", 20),
+                        fit("响应时间", 10),
+                        fit("延迟趋势", HISTORY_LEN + 2),
+                        fit("连续失败", 10),
+                        "最后错误"
+                    ),
+                    width
+                )
+            ),
+        ];
+
+        // 预留底部的提示信息和按键说明
+        let capacity = height.saturating_sub(lines.len() + 2).max(1);
+        let offset = self.selected.saturating_sub(capacity - 1);
+        for (index, row) in rows.iter().enumerate().skip(offset).take(capacity) {
+            lines.push(render_row(row, index == self.selected, width));
+        }
+        if rows.is_empty() {
+            lines.push(fit("  没有匹配的服务", width));
+        }
+        while lines.len() < height.saturating_sub(2) {
+            lines.push(String::new());
+        }
+
+        lines.push(fit(self.message.as_deref().unwrap_or(""), width));
+        lines.push(if self.editing_filter {
+            fit(
+                &format!("过滤: {}_  （回车确认，Esc清除）", self.filter),
+                width,
+            )
+        } else {
+            format!(
+                "\x1b[7m{}\x1b[0m",
+                fit(
+                    "↑/↓ 选择  c 立即检测  p 暂停/恢复  m 静默/取消静默  s 排序  / 过滤  r 刷新  q 退出",
+                    width
+                )
+            )
+        });
+
+        format!("\x1b[H{}\x1b[J", lines.join("\x1b[K\r\n"))
+    }
+}
+
+/// 绘制一行服务状态，选中的行反色显示
+fn render_row(row: &TopRow, selected: bool, width: usize) -> String {
+    let snapshot = &row.snapshot;
+    let mut flags = String::new();
+    if snapshot.paused {
+        flags.push_str("[暂停] ");
+    }
+    if snapshot.silenced {
+        flags.push_str("[静默] ");
+    }
+    let samples: Vec<u64> = row.history.iter().copied().collect();
+    let status = fit(&format!("● {}", snapshot.status), 8);
+    let rest = format!(
+        "{}{}{}{}{}{}",
+        fit(&snapshot.name, 20),
+        fit(
+            &snapshot
+                .response_time_ms
+                .map(|ms| format!("{ms}ms"))
+                .unwrap_or_else(|| "-".to_string()),
+            10
+        ),
+        fit(&sparkline(&samples, HISTORY_LEN), HISTORY_LEN + 2),
+        fit(&snapshot.consecutive_failures.to_string(), 10),
+        flags,
+        snapshot
+            .last_error
+            .as_deref()
+            .unwrap_or("")
+            .replace(['\r', '\n'], " ")
+    );
+    let rest = fit(&rest, width.saturating_sub(2 + 8));
+
+    if selected {
+        format!("\x1b[7m> {status}{rest}\x1b[0m")
+    } else {
+        let color = match snapshot.status {
+            HealthStatus::Up => "32",
+            HealthStatus::Degraded => "33",
+            HealthStatus::Down => "31",
+            HealthStatus::Unknown => "90",
+        };
+        format!("  \x1b[{color}m{status}\x1b[0m{rest}")
+    }
+}
+
+/// 状态的严重程度，用于排序
+fn status_rank(status: HealthStatus) -> u8 {
+    match status {
+        HealthStatus::Up => 0,
+        HealthStatus::Unknown => 1,
+        HealthStatus::Degraded => 2,
+        HealthStatus::Down => 3,
+    }
+}
+
+fn sort_label(sort: TopSort) -> &'static str {
+    match sort {
+        TopSort::Name => "名称",
+        TopSort::Status => "状态",
+        TopSort::Latency => "响应时间",
+        TopSort::Failures => "连续失败",
+    }
+}
+
+/// 把响应时间样本绘制为趋势图，只保留最近 `width` 个样本
+pub fn sparkline(samples: &[u64], width: usize) -> String {
+    let samples = &samples[samples.len().saturating_sub(width)..];
+    let max = samples.iter().copied().max().unwrap_or(0);
+    samples
+        .iter()
+        .map(|&value| {
+            let level = (value * (SPARK_CHARS.len() as u64 - 1) + max / 2)
+                .checked_div(max)
+                .unwrap_or(0);
+            SPARK_CHARS[level as usize]
+        })
+        .collect()
+}
+
+/// 字符在终端中占用的列数，中日韩字符和全角符号占两列
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD => 2,
+        _ if c.is_control() => 0,
+        _ => 1,
+    }
+}
+
+/// 按显示宽度截断或补齐文本
+fn fit(text: &str, width: usize) -> String {
+    let mut result = String::new();
+    let mut used = 0;
+    let total: usize = text.chars().map(char_width).sum();
+    for c in text.chars() {
+        let w = char_width(c);
+        if total > width && used + w + 1 > width {
+            if used < width {
+                result.push('…');
+                used += 1;
+            }
+            break;
+        }
+        result.push(c);
+        used += w;
+    }
+    result.push_str(&" ".repeat(width.saturating_sub(used)));
+    result
+}
+
+/// 把输入的字节解析为按键
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut chars = text.chars().peekable();
+    let mut keys = Vec::new();
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' if chars.peek() == Some(&'[') => {
+                chars.next();
+                match chars.next() {
+                    Some('A') => Key::Up,
+                    Some('B') => Key::Down,
+                    _ => continue,
+                }
+            }
+            '\x1b' => Key::Esc,
+            '\x03' => Key::Interrupt,
+            '\r' | '\n' => Key::Enter,
+            '\x7f' | '\x08' => Key::Backspace,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+/// 本地检测的共享状态
+#[derive(Default)]
+struct LocalState {
+    snapshots: HashMap<String, ServiceSnapshot>,
+    paused: HashSet<String>,
+}
+
+/// 找不到运行中的服务时，在本地按刷新间隔执行检测
+pub struct LocalMonitor {
+    checker: Arc<HttpHealthChecker>,
+    services: Arc<Vec<ServiceConfig>>,
+    state: Arc<Mutex<LocalState>>,
+    task: JoinHandle<()>,
+}
+
+impl LocalMonitor {
+    /// 根据配置启动本地检测
+    ///
+    /// # 参数
+    /// * `config` - 应用配置，只检测启用的服务
+    /// * `interval` - 检测间隔
+    pub fn start(config: &Config, interval: Duration) -> Result<Self> {
+        let checker = Arc::new(HttpHealthChecker::from_global_config(&config.global)?);
+        let services: Arc<Vec<ServiceConfig>> = Arc::new(
+            config
+                .services
+                .iter()
+                .filter(|service| service.enabled)
+                .cloned()
+                .collect(),
+        );
+        let state = Arc::new(Mutex::new(LocalState {
+            snapshots: services
+                .iter()
+                .map(|service| {
+                    (
+                        service.name.clone(),
+                        ServiceSnapshot {
+                            name: service.name.clone(),
+                            status: HealthStatus::Unknown,
+                            response_time_ms: None,
+                            consecutive_failures: 0,
+                            last_error: None,
+                            last_check: None,
+                            paused: false,
+                            silenced: false,
+                        },
+                    )
+                })
+                .collect(),
+            paused: HashSet::new(),
+        }));
+
+        let task = {
+            let (checker, services, state) = (
+                Arc::clone(&checker),
+                Arc::clone(&services),
+                Arc::clone(&state),
+            );
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticker.tick().await;
+                    let paused = lock(&state).paused.clone();
+                    let checks = services
+                        .iter()
+                        .filter(|service| !paused.contains(&service.name))
+                        .map(|service| Self::check_one(&checker, service, &state));
+                    futures::future::join_all(checks).await;
+                }
+            })
+        };
+
+        Ok(Self {
+            checker,
+            services,
+            state,
+            task,
+        })
+    }
+
+    /// 检测单个服务并更新状态
+    async fn check_one(
+        checker: &HttpHealthChecker,
+        service: &ServiceConfig,
+        state: &Mutex<LocalState>,
+    ) {
+        let (status, response_time_ms, error) = match checker.check(service).await {
+            Ok(result) => (
+                result.status,
+                Some(result.response_time_ms()),
+                result.error_message,
+            ),
+            Err(e) => (HealthStatus::Unknown, None, Some(e.to_string())),
+        };
+
+        let mut state = lock(state);
+        let paused = state.paused.contains(&service.name);
+        let failures = state
+            .snapshots
+            .get(&service.name)
+            .map_or(0, |snapshot| snapshot.consecutive_failures);
+        state.snapshots.insert(
+            service.name.clone(),
+            ServiceSnapshot {
+                name: service.name.clone(),
+                status,
+                response_time_ms,
+                consecutive_failures: if status.is_healthy() { 0 } else { failures + 1 },
+                last_error: error,
+                last_check: Some(Utc::now()),
+                paused,
+                silenced: false,
+            },
+        );
+    }
+}
+
+impl Drop for LocalMonitor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 面板的数据来源
+pub enum DataSource {
+    /// 运行中的服务
+    Daemon(DaemonClient),
+    /// 本地检测
+    Local(LocalMonitor),
+}
+
+impl DataSource {
+    /// 数据来源说明
+    fn label(&self) -> String {
+        match self {
+            DataSource::Daemon(client) => format!("运行中的服务 {}", client.base_url()),
+            DataSource::Local(_) => "本地检测".to_string(),
+        }
+    }
+
+    /// 获取所有服务的当前状态
+    async fn snapshots(&self) -> Result<Vec<ServiceSnapshot>> {
+        match self {
+            DataSource::Daemon(client) => Ok(client
+                .status()
+                .await?
+                .services
+                .into_iter()
+                .map(ServiceSnapshot::from)
+                .collect()),
+            DataSource::Local(monitor) => {
+                Ok(lock(&monitor.state).snapshots.values().cloned().collect())
+            }
+        }
+    }
+
+    /// 执行按键对应的操作，返回提示信息
+    async fn perform(&self, action: Action) -> Result<String> {
+        match (self, action) {
+            (DataSource::Daemon(client), Action::Trigger(name)) => {
+                let results = client.trigger_service_check(&name).await?;
+                Ok(match results.first() {
+                    Some(result) => format!("{name} 检测完成: {}", result.status),
+                    None => format!("{name} 检测完成"),
+                })
+            }
+            (DataSource::Daemon(client), Action::TogglePause(name, true)) => {
+                client.resume_service(&name).await?;
+                Ok(format!("已恢复 {name} 的定时检测"))
+            }
+            (DataSource::Daemon(client), Action::TogglePause(name, false)) => {
+                let request = ApiPauseRequest {
+                    reason: Some("通过 top 面板暂停".to_string()),
+                    ..Default::default()
+                };
+                client.pause_service(&name, &request).await?;
+                Ok(format!("已暂停 {name} 的定时检测"))
+            }
+            (DataSource::Daemon(client), Action::ToggleSilence(name, true)) => {
+                client.unsilence_service(&name).await?;
+                Ok(format!("已取消 {name} 的通知静默"))
+            }
+            (DataSource::Daemon(client), Action::ToggleSilence(name, false)) => {
+                let request = ApiPauseRequest {
+                    reason: Some("通过 top 面板静默".to_string()),
+                    ..Default::default()
+                };
+                client.silence_service(&name, &request).await?;
+                Ok(format!("已静默 {name} 的通知"))
+            }
+            (DataSource::Local(monitor), Action::Trigger(name)) => {
+                let Some(service) = monitor.services.iter().find(|s| s.name == name) else {
+                    return Ok(format!("服务不存在: {name}"));
+                };
+                LocalMonitor::check_one(&monitor.checker, service, &monitor.state).await;
+                Ok(format!("{name} 检测完成"))
+            }
+            (DataSource::Local(monitor), Action::TogglePause(name, paused)) => {
+                let mut state = lock(&monitor.state);
+                if paused {
+                    state.paused.remove(&name);
+                } else {
+                    state.paused.insert(name.clone());
+                }
+                if let Some(snapshot) = state.snapshots.get_mut(&name) {
+                    snapshot.paused = !paused;
+                }
+                Ok(if paused {
+                    format!("已恢复 {name} 的本地检测")
+                } else {
+                    format!("已暂停 {name} 的本地检测")
+                })
+            }
+            (DataSource::Local(_), Action::ToggleSilence(_, _)) => {
+                Ok("本地检测不发送通知，无需静默".to_string())
+            }
+            _ => Ok(String::new()),
+        }
+    }
+}
+
+/// 运行状态面板，直到用户退出
+///
+/// # 参数
+/// * `source` - 数据来源
+/// * `state` - 初始面板状态
+/// * `interval` - 刷新间隔
+pub async fn run(source: DataSource, mut state: TopState, interval: Duration) -> Result<()> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Err(ServiceVitalsError::Other(anyhow::anyhow!(
+            "top 命令需要在交互式终端中运行"
+        )));
+    }
+
+    let _terminal = terminal::RawTerminal::enter()?;
+    let (key_tx, mut key_rx) = mpsc::unbounded_channel();
+    // 阻塞读取标准输入，进程退出时随之结束
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buffer = [0u8; 64];
+        loop {
+            match std::io::Read::read(&mut stdin, &mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if key_tx.send(parse_keys(&buffer[..n])).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    let label = source.label();
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => refresh(&source, &mut state).await,
+            keys = key_rx.recv() => {
+                let Some(keys) = keys else { break };
+                for key in keys {
+                    match state.handle_key(key) {
+                        Action::None => {}
+                        Action::Quit => return Ok(()),
+                        Action::Refresh => refresh(&source, &mut state).await,
+                        action => {
+                            match source.perform(action).await {
+                                Ok(message) => state.set_message(message),
+                                Err(e) => state.set_message(format!("操作失败: {e}")),
+                            }
+                            refresh(&source, &mut state).await;
+                        }
+                    }
+                }
+            }
+        }
+
+        let (width, height) = terminal::size();
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(state.render(&label, width, height).as_bytes())?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// 刷新服务状态，失败时保留上一次的数据并显示错误
+async fn refresh(source: &DataSource, state: &mut TopState) {
+    match source.snapshots().await {
+        Ok(snapshots) => state.apply(snapshots),
+        Err(e) => state.set_message(format!("获取状态失败: {e}")),
+    }
+}
+
+/// 获取同步互斥锁，锁被污染时继续使用内部数据
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(unix)]
+mod terminal {
+    use crate::common::error::Result;
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+
+    /// 原始模式终端，进入时切换到备用屏幕，释放时恢复原始设置
+    pub struct RawTerminal {
+        original: libc::termios,
+    }
+
+    impl RawTerminal {
+        pub fn enter() -> Result<Self> {
+            let fd = std::io::stdin().as_raw_fd();
+            // SAFETY: termios 是普通的C结构体，由 tcgetattr 完整填充
+            let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+            if unsafe { libc::tcgetattr(fd, &mut termios) } != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            let original = termios;
+            termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+            termios.c_iflag &= !(libc::IXON | libc::ICRNL);
+            termios.c_cc[libc::VMIN] = 1;
+            termios.c_cc[libc::VTIME] = 0;
+            if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &termios) } != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+
+            let mut stdout = std::io::stdout();
+            stdout.write_all(b"\x1b[?1049h\x1b[?25l\x1b[2J")?;
+            stdout.flush()?;
+            Ok(Self { original })
+        }
+    }
+
+    impl Drop for RawTerminal {
+        fn drop(&mut self) {
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l");
+            let _ = stdout.flush();
+            unsafe {
+                libc::tcsetattr(std::io::stdin().as_raw_fd(), libc::TCSANOW, &self.original);
+            }
+        }
+    }
+
+    /// 终端的列数和行数，获取失败时使用 80x24
+    pub fn size() -> (usize, usize) {
+        // SAFETY: winsize 是普通的C结构体，由 ioctl 填充
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        let ok =
+            unsafe { libc::ioctl(std::io::stdout().as_raw_fd(), libc::TIOCGWINSZ, &mut size) } == 0;
+        if ok && size.ws_col > 0 && size.ws_row > 0 {
+            (size.ws_col as usize, size.ws_row as usize)
+        } else {
+            (80, 24)
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod terminal {
+    use crate::common::error::{Result, ServiceVitalsError};
+
+    pub struct RawTerminal;
+
+    impl RawTerminal {
+        pub fn enter() -> Result<Self> {
+            Err(ServiceVitalsError::Other(anyhow::anyhow!(
+                "top 命令目前只支持Unix平台"
+            )))
+        }
+    }
+
+    pub fn size() -> (usize, usize) {
+        (80, 24)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(name: &str, status: HealthStatus, ms: u64, failures: u32) -> ServiceSnapshot {
+        ServiceSnapshot {
+            name: name.to_string(),
+            status,
+            response_time_ms: Some(ms),
+            consecutive_failures: failures,
+            last_error: None,
+            last_check: Some(Utc::now()),
+            paused: false,
+            silenced: false,
+        }
+    }
+
+    fn names(state: &TopState) -> Vec<&str> {
+        state
+            .visible_rows()
+            .iter()
+            .map(|row| row.snapshot.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline(&[], 10), "");
+        assert_eq!(sparkline(&[0, 0], 10), "▁▁");
+        assert_eq!(sparkline(&[10, 40, 70, 35], 10), "▂▅█▅");
+        assert_eq!(sparkline(&[100, 1, 2], 2), "▅█");
+    }
+
+    #[test]
+    fn test_fit_uses_display_width() {
+        assert_eq!(fit("api", 5), "api  ");
+        assert_eq!(fit("服务名称", 6), "服务… ");
+        assert_eq!(fit("主站API", 7), "主站API");
+    }
+
+    #[test]
+    fn test_sort_filter_and_keys() {
+        let mut state = TopState::new(TopSort::Status, None);
+        state.apply(vec![
+            snapshot("api-orders", HealthStatus::Up, 30, 0),
+            snapshot("db-main", HealthStatus::Down, 5, 3),
+            snapshot("api-users", HealthStatus::Degraded, 80, 1),
+        ]);
+        assert_eq!(names(&state), ["db-main", "api-users", "api-orders"]);
+
+        assert_eq!(state.handle_key(Key::Char('s')), Action::None);
+        assert_eq!(names(&state), ["api-users", "api-orders", "db-main"]);
+
+        // 选中第二行后检测、暂停和静默都作用于该服务
+        state.handle_key(Key::Down);
+        assert_eq!(
+            state.handle_key(Key::Char('c')),
+            Action::Trigger("api-orders".to_string())
+        );
+        assert_eq!(
+            state.handle_key(Key::Char('p')),
+            Action::TogglePause("api-orders".to_string(), false)
+        );
+        assert_eq!(
+            state.handle_key(Key::Char('m')),
+            Action::ToggleSilence("api-orders".to_string(), false)
+        );
+
+        for key in parse_keys(b"/api-*\r") {
+            state.handle_key(key);
+        }
+        assert_eq!(names(&state), ["api-users", "api-orders"]);
+        state.handle_key(Key::Esc);
+        assert_eq!(names(&state).len(), 3);
+        assert_eq!(state.handle_key(Key::Interrupt), Action::Quit);
+    }
+
+    #[test]
+    fn test_history_tracks_new_checks_only() {
+        let mut state = TopState::new(TopSort::Name, None);
+        let first = snapshot("api", HealthStatus::Up, 10, 0);
+        state.apply(vec![first.clone()]);
+        state.apply(vec![first]);
+        state.apply(vec![ServiceSnapshot {
+            last_check: Some(Utc::now() + chrono::Duration::seconds(1)),
+            ..snapshot("api", HealthStatus::Up, 20, 0)
+        }]);
+        assert_eq!(state.rows[0].history, [10, 20]);
+
+        let screen = state.render("本地检测", 100, 10);
+        assert!(screen.contains("共 1 个服务"));
+        assert!(screen.contains("api"));
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            parse_keys(b"\x1b[A\x1b[Bq\x1b\x7f\x03"),
+            [
+                Key::Up,
+                Key::Down,
+                Key::Char('q'),
+                Key::Esc,
+                Key::Backspace,
+                Key::Interrupt
+            ]
+        );
+    }
+}
@@ -58,6 +58,14 @@ pub enum ConfigError {
     /// 环境变量替换错误
     #[error("环境变量替换失败: {var}")]
     EnvVarError { var: String },
+
+    /// 使用 `${VAR:?message}` 声明的必需环境变量未设置
+    #[error("环境变量 {var} 未设置: {message}")]
+    EnvVarRequired { var: String, message: String },
+
+    /// `${file:/path}` 引用的密钥文件无法读取
+    #[error("读取密钥文件 {path} 失败: {reason}")]
+    SecretFileError { path: String, reason: String },
 }
 
 /// 健康检测错误类型
@@ -78,6 +86,14 @@ pub enum HealthCheckError {
     /// 连接错误
     #[error("连接失败: {url}")]
     ConnectionError { url: String },
+
+    /// TLS配置错误（证书文件无法读取或解析等）
+    #[error("TLS配置错误: {reason}")]
+    TlsConfig { reason: String },
+
+    /// 认证令牌获取失败（令牌端点异常，而非被检测服务本身异常）
+    #[error("认证令牌获取失败 ({token_url}): {reason}")]
+    TokenEndpoint { token_url: String, reason: String },
 }
 
 /// 通知错误类型
@@ -179,6 +195,8 @@ impl ServiceVitalsError {
                 HealthCheckError::ConnectionError { .. } => ErrorSeverity::Medium,
                 HealthCheckError::StatusCodeMismatch { .. } => ErrorSeverity::Low,
                 HealthCheckError::RequestError(_) => ErrorSeverity::Medium,
+                HealthCheckError::TokenEndpoint { .. } => ErrorSeverity::High,
+                HealthCheckError::TlsConfig { .. } => ErrorSeverity::High,
             },
             ServiceVitalsError::Notification(_) => ErrorSeverity::Low,
             ServiceVitalsError::DaemonError(_) => ErrorSeverity::High,
@@ -198,6 +216,8 @@ impl ServiceVitalsError {
                 HealthCheckError::ConnectionError { .. } => RecoveryStrategy::Retry,
                 HealthCheckError::StatusCodeMismatch { .. } => RecoveryStrategy::Skip,
                 HealthCheckError::RequestError(_) => RecoveryStrategy::Retry,
+                HealthCheckError::TokenEndpoint { .. } => RecoveryStrategy::Retry,
+                HealthCheckError::TlsConfig { .. } => RecoveryStrategy::None,
             },
             ServiceVitalsError::Notification(_) => RecoveryStrategy::Retry,
             ServiceVitalsError::DaemonError(_) => RecoveryStrategy::StopService,
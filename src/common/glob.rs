@@ -0,0 +1,88 @@
+//! 通配符匹配
+//!
+//! 支持 `*`（任意个字符）和 `?`（单个字符），用于按名称或标签模式选择服务
+
+/// 判断模式是否包含通配符
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// 按通配符模式匹配整个文本，按字符（而非字节）匹配
+///
+/// # 参数
+/// * `pattern` - 通配符模式
+/// * `text` - 待匹配文本
+///
+/// # 返回
+/// * `bool` - 是否匹配
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // 最近一个 `*` 的位置，以及它当前匹配到的文本位置，用于回溯
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// 判断是否有任一模式匹配任一文本，例如服务带有任一指定标签
+///
+/// # 参数
+/// * `patterns` - 通配符模式列表
+/// * `texts` - 待匹配文本列表
+pub fn glob_match_any<P: AsRef<str>, T: AsRef<str>>(patterns: &[P], texts: &[T]) -> bool {
+    patterns.iter().any(|pattern| {
+        texts
+            .iter()
+            .any(|text| glob_match(pattern.as_ref(), text.as_ref()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("api-*", "api-orders"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*订单*", "主站订单服务"));
+        assert!(glob_match("db-?", "db-1"));
+        assert!(!glob_match("db-?", "db-10"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        assert!(glob_match("主站API", "主站API"));
+        assert!(!glob_match("主站", "主站API"));
+
+        assert!(is_glob("api-*"));
+        assert!(!is_glob("主站API"));
+    }
+
+    #[test]
+    fn test_glob_match_any() {
+        let tags = ["core", "team:orders"];
+        assert!(glob_match_any(&["db", "core"], &tags));
+        assert!(glob_match_any(&["team:*"], &tags));
+        assert!(!glob_match_any(&["team:billing"], &tags));
+        assert!(!glob_match_any::<&str, &str>(&[], &tags));
+    }
+}
@@ -3,10 +3,13 @@
 //! 包含错误处理、日志系统等通用功能
 
 pub mod error;
+pub mod glob;
 pub mod logging;
+pub mod sharded;
 pub mod status;
 
 // 重新导出主要类型
 pub use error::{ErrorContext, ErrorSeverity, RecoveryStrategy, Result, ServiceVitalsError};
 pub use logging::{LogConfig, LoggingSystem};
+pub use sharded::ShardedMap;
 pub use status::{ServiceStatus, StatusManager};
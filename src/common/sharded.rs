@@ -0,0 +1,211 @@
+//! 分片并发映射
+//!
+//! 按键的哈希值将数据分散到多个独立加锁的分片中，
+//! 大量服务并发读写各自的状态时不会争用同一把锁。
+//! 分片锁只在同步代码中短暂持有，不能跨越 `.await`。
+
+use std::collections::hash_map::{DefaultHasher, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// 默认分片数量
+pub const DEFAULT_SHARD_COUNT: usize = 32;
+
+/// 以服务名称为键的分片并发映射
+#[derive(Debug)]
+pub struct ShardedMap<V> {
+    shards: Vec<RwLock<HashMap<String, V>>>,
+}
+
+impl<V> Default for ShardedMap<V> {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHARD_COUNT)
+    }
+}
+
+impl<V> ShardedMap<V> {
+    /// 创建指定分片数量的映射
+    ///
+    /// # 参数
+    /// * `shard_count` - 分片数量，至少为1
+    pub fn new(shard_count: usize) -> Self {
+        let shards = (0..shard_count.max(1))
+            .map(|_| RwLock::new(HashMap::new()))
+            .collect();
+        Self { shards }
+    }
+
+    /// 分片数量
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// 插入键值对，返回旧值
+    pub fn insert(&self, key: String, value: V) -> Option<V> {
+        self.write_shard(&key).insert(key, value)
+    }
+
+    /// 移除指定键，返回旧值
+    pub fn remove(&self, key: &str) -> Option<V> {
+        self.write_shard(key).remove(key)
+    }
+
+    /// 满足条件时移除指定键，返回被移除的值
+    pub fn remove_if(&self, key: &str, predicate: impl FnOnce(&V) -> bool) -> Option<V> {
+        let mut shard = self.write_shard(key);
+        if shard.get(key).is_some_and(predicate) {
+            shard.remove(key)
+        } else {
+            None
+        }
+    }
+
+    /// 是否包含指定键
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.read_shard(key).contains_key(key)
+    }
+
+    /// 读取指定键的值并返回闭包的计算结果
+    pub fn with<R>(&self, key: &str, f: impl FnOnce(&V) -> R) -> Option<R> {
+        self.read_shard(key).get(key).map(f)
+    }
+
+    /// 修改指定键的值并返回闭包的计算结果
+    pub fn update<R>(&self, key: &str, f: impl FnOnce(&mut V) -> R) -> Option<R> {
+        self.write_shard(key).get_mut(key).map(f)
+    }
+
+    /// 元素总数
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| read(shard).len()).sum()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| read(shard).is_empty())
+    }
+
+    /// 所有键
+    pub fn keys(&self) -> Vec<String> {
+        self.shards
+            .iter()
+            .flat_map(|shard| read(shard).keys().cloned().collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// 保留满足条件的元素
+    pub fn retain(&self, mut predicate: impl FnMut(&str, &mut V) -> bool) {
+        for shard in &self.shards {
+            write(shard).retain(|key, value| predicate(key, value));
+        }
+    }
+
+    /// 清空所有元素
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            write(shard).clear();
+        }
+    }
+
+    fn shard_for(&self, key: &str) -> &RwLock<HashMap<String, V>> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[(hasher.finish() as usize) % self.shards.len()]
+    }
+
+    fn read_shard(&self, key: &str) -> RwLockReadGuard<'_, HashMap<String, V>> {
+        read(self.shard_for(key))
+    }
+
+    fn write_shard(&self, key: &str) -> RwLockWriteGuard<'_, HashMap<String, V>> {
+        write(self.shard_for(key))
+    }
+}
+
+impl<V: Clone> ShardedMap<V> {
+    /// 获取指定键的值副本
+    pub fn get(&self, key: &str) -> Option<V> {
+        self.read_shard(key).get(key).cloned()
+    }
+
+    /// 所有键值对的副本
+    pub fn snapshot(&self) -> Vec<(String, V)> {
+        self.shards
+            .iter()
+            .flat_map(|shard| {
+                read(shard)
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// 获取读锁，锁被污染时继续使用内部数据
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 获取写锁，锁被污染时继续使用内部数据
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_basic_operations() {
+        let map = ShardedMap::new(4);
+        assert!(map.is_empty());
+
+        assert_eq!(map.insert("a".to_string(), 1), None);
+        assert_eq!(map.insert("b".to_string(), 2), None);
+        assert_eq!(map.insert("a".to_string(), 3), Some(1));
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("a"), Some(3));
+        assert!(map.contains_key("b"));
+        assert_eq!(
+            map.update("b", |value| {
+                *value += 10;
+                *value
+            }),
+            Some(12)
+        );
+
+        assert_eq!(map.remove_if("b", |value| *value < 10), None);
+        assert_eq!(map.remove_if("b", |value| *value > 10), Some(12));
+        assert_eq!(map.remove("missing"), None);
+
+        let mut keys = map.keys();
+        keys.sort();
+        assert_eq!(keys, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_concurrent_inserts_across_shards() {
+        let map = Arc::new(ShardedMap::default());
+        let handles: Vec<_> = (0..8)
+            .map(|thread| {
+                let map = Arc::clone(&map);
+                std::thread::spawn(move || {
+                    for i in 0..500 {
+                        map.insert(format!("service-{thread}-{i}"), i);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(map.len(), 4000);
+        map.retain(|_, value| *value % 2 == 0);
+        assert_eq!(map.snapshot().len(), 2000);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! 提供服务运行状态的查询和管理功能
 
-use crate::health::{HealthResult, HealthStatus};
+use crate::health::{HealthResult, HealthStatus, RequestTimings};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -27,6 +27,9 @@ pub struct ServiceStatus {
     pub status_code: Option<u16>,
     /// 响应时间（毫秒）
     pub response_time_ms: Option<u64>,
+    /// 请求各阶段耗时
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<RequestTimings>,
     /// 连续失败次数
     pub consecutive_failures: u32,
     /// 错误信息
@@ -35,6 +38,23 @@ pub struct ServiceStatus {
     pub enabled: bool,
 }
 
+impl From<&HealthResult> for ServiceStatus {
+    fn from(result: &HealthResult) -> Self {
+        Self {
+            name: result.service_name.clone(),
+            url: result.service_url.clone(),
+            status: result.status,
+            last_check: Some(result.timestamp),
+            status_code: result.status_code,
+            response_time_ms: Some(result.response_time.as_millis() as u64),
+            timings: result.timings,
+            consecutive_failures: result.consecutive_failures,
+            error_message: result.error_message.clone(),
+            enabled: true, // 假设运行中的服务都是启用的
+        }
+    }
+}
+
 /// 整体服务状态
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OverallStatus {
@@ -83,20 +103,7 @@ impl StatusManager {
     /// 更新服务状态
     pub async fn update_service_status(&self, result: &HealthResult) {
         let mut status_map = self.service_status.write().await;
-
-        let service_status = ServiceStatus {
-            name: result.service_name.clone(),
-            url: result.service_url.clone(),
-            status: result.status,
-            last_check: Some(result.timestamp),
-            status_code: result.status_code,
-            response_time_ms: Some(result.response_time.as_millis() as u64),
-            consecutive_failures: result.consecutive_failures,
-            error_message: result.error_message.clone(),
-            enabled: true, // 假设运行中的服务都是启用的
-        };
-
-        status_map.insert(result.service_name.clone(), service_status);
+        status_map.insert(result.service_name.clone(), ServiceStatus::from(result));
     }
 
     /// 添加服务（初始状态）
@@ -110,6 +117,7 @@ impl StatusManager {
             last_check: None,
             status_code: None,
             response_time_ms: None,
+            timings: None,
             consecutive_failures: 0,
             error_message: None,
             enabled,
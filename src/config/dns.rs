@@ -0,0 +1,268 @@
+//! DNS检测配置
+//!
+//! URL 为 `dns://域名` 的服务按 `[services.dns]` 配置解析指定类型的记录，
+//! 可以指定解析使用的DNS服务器、期望的应答、解析耗时阈值，以及应答变化时是否告警。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+/// DNS检测URL的前缀
+pub const DNS_SCHEME: &str = "dns://";
+
+/// DNS检测配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DnsCheckConfig {
+    /// 查询的记录类型，默认 A
+    #[serde(default)]
+    pub record_type: DnsRecordType,
+    /// DNS服务器地址（`IP` 或 `IP:端口`），未配置时使用系统DNS配置
+    pub nameserver: Option<String>,
+    /// 期望的应答，未配置时只要求解析成功
+    #[serde(default)]
+    pub expected: Vec<String>,
+    /// 为 true 时应答必须与 `expected` 完全一致，否则只要求包含 `expected` 中的每一项
+    #[serde(default)]
+    pub exact: bool,
+    /// 应答与上次检测不同时将服务标记为降级并告警
+    #[serde(default)]
+    pub alert_on_change: bool,
+    /// 解析耗时阈值（毫秒），超过时将服务标记为降级
+    pub max_latency_ms: Option<u64>,
+}
+
+/// 支持的DNS记录类型
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordType {
+    /// IPv4地址
+    #[default]
+    A,
+    /// IPv6地址
+    Aaaa,
+    /// 别名
+    Cname,
+    /// 文本记录
+    Txt,
+    /// 服务记录，应答格式为 `优先级 权重 端口 目标`
+    Srv,
+}
+
+impl fmt::Display for DnsRecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DnsRecordType::A => "A",
+            DnsRecordType::Aaaa => "AAAA",
+            DnsRecordType::Cname => "CNAME",
+            DnsRecordType::Txt => "TXT",
+            DnsRecordType::Srv => "SRV",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl DnsRecordType {
+    /// 规范化应答文本，便于比较配置的期望应答与实际应答
+    ///
+    /// IP地址按标准格式输出，域名转为小写并去掉末尾的点，TXT记录保持原样
+    ///
+    /// # 参数
+    /// * `answer` - 应答文本
+    ///
+    /// # 返回
+    /// * `Result<String, String>` - 规范化后的应答，格式不符合记录类型时返回错误
+    pub fn normalize(&self, answer: &str) -> Result<String, String> {
+        let answer = answer.trim();
+        match self {
+            DnsRecordType::A => answer
+                .parse::<std::net::Ipv4Addr>()
+                .map(|ip| ip.to_string())
+                .map_err(|_| format!("{answer} 不是有效的IPv4地址")),
+            DnsRecordType::Aaaa => answer
+                .parse::<std::net::Ipv6Addr>()
+                .map(|ip| ip.to_string())
+                .map_err(|_| format!("{answer} 不是有效的IPv6地址")),
+            DnsRecordType::Cname => Ok(normalize_name(answer)),
+            DnsRecordType::Txt => Ok(answer.to_string()),
+            DnsRecordType::Srv => {
+                let parts: Vec<&str> = answer.split_whitespace().collect();
+                let [priority, weight, port, target] = parts.as_slice() else {
+                    return Err(format!(
+                        "{answer} 不是有效的SRV应答，格式为 `优先级 权重 端口 目标`"
+                    ));
+                };
+                for number in [priority, weight, port] {
+                    number
+                        .parse::<u16>()
+                        .map_err(|_| format!("{answer} 不是有效的SRV应答，{number} 不是数字"))?;
+                }
+                Ok(format!(
+                    "{priority} {weight} {port} {}",
+                    normalize_name(target)
+                ))
+            }
+        }
+    }
+}
+
+/// 域名转为小写并去掉末尾的点
+fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+impl DnsCheckConfig {
+    /// 校验DNS检测配置
+    pub fn validate(&self) -> Result<(), String> {
+        self.nameserver_addr()?;
+        for answer in &self.expected {
+            self.record_type.normalize(answer)?;
+        }
+        if self.exact && self.expected.is_empty() {
+            return Err("exact 为 true 时必须配置 expected".to_string());
+        }
+        if self.max_latency_ms == Some(0) {
+            return Err("解析耗时阈值不能为0".to_string());
+        }
+        Ok(())
+    }
+
+    /// 解析DNS服务器地址，未指定端口时使用53
+    ///
+    /// # 返回
+    /// * `Result<Option<SocketAddr>, String>` - DNS服务器地址，未配置时返回 None
+    pub fn nameserver_addr(&self) -> Result<Option<SocketAddr>, String> {
+        let Some(nameserver) = self.nameserver.as_deref() else {
+            return Ok(None);
+        };
+        if let Ok(addr) = nameserver.parse::<SocketAddr>() {
+            return Ok(Some(addr));
+        }
+        nameserver
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .map(|ip| Some(SocketAddr::new(ip, 53)))
+            .map_err(|_| format!("DNS服务器地址 {nameserver} 无效，格式为 IP 或 IP:端口"))
+    }
+
+    /// 规范化后的期望应答
+    pub fn normalized_expected(&self) -> Vec<String> {
+        self.expected
+            .iter()
+            .filter_map(|answer| self.record_type.normalize(answer).ok())
+            .collect()
+    }
+}
+
+/// 从 `dns://域名` 形式的URL中取出查询的域名
+///
+/// # 参数
+/// * `url` - 服务URL
+///
+/// # 返回
+/// * `Result<String, String>` - 查询的域名，URL格式无效时返回错误
+pub fn dns_query_name(url: &str) -> Result<String, String> {
+    let name = url
+        .strip_prefix(DNS_SCHEME)
+        .ok_or_else(|| format!("{url} 不是 {DNS_SCHEME} URL"))?
+        .trim_end_matches('/');
+    if name.is_empty() {
+        return Err(format!("{url} 缺少查询的域名"));
+    }
+    if name.contains(['/', '?', '#', ':', '@']) || name.chars().any(char::is_whitespace) {
+        return Err(format!("{url} 格式无效，应为 {DNS_SCHEME}域名"));
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_query_name() {
+        assert_eq!(dns_query_name("dns://example.com").unwrap(), "example.com");
+        assert_eq!(
+            dns_query_name("dns://_sip._tcp.example.com/").unwrap(),
+            "_sip._tcp.example.com"
+        );
+        assert!(dns_query_name("dns://").is_err());
+        assert!(dns_query_name("dns://example.com:53").is_err());
+        assert!(dns_query_name("https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_normalize_answers() {
+        assert_eq!(
+            DnsRecordType::A.normalize(" 10.0.0.1 ").unwrap(),
+            "10.0.0.1"
+        );
+        assert_eq!(
+            DnsRecordType::Aaaa.normalize("2001:db8:0:0::1").unwrap(),
+            "2001:db8::1"
+        );
+        assert_eq!(
+            DnsRecordType::Cname.normalize("Edge.Example.NET.").unwrap(),
+            "edge.example.net"
+        );
+        assert_eq!(
+            DnsRecordType::Srv
+                .normalize("10 5 5060 SIP.example.com.")
+                .unwrap(),
+            "10 5 5060 sip.example.com"
+        );
+        assert_eq!(
+            DnsRecordType::Txt.normalize("v=spf1 -all").unwrap(),
+            "v=spf1 -all"
+        );
+        assert!(DnsRecordType::A.normalize("example.com").is_err());
+        assert!(DnsRecordType::Srv
+            .normalize("10 5 sip.example.com")
+            .is_err());
+    }
+
+    #[test]
+    fn test_dns_config_validation() {
+        let config: DnsCheckConfig = toml::from_str(
+            r#"
+            record_type = "AAAA"
+            nameserver = "::1"
+            expected = ["2001:db8::1"]
+            exact = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.nameserver_addr().unwrap(),
+            Some("[::1]:53".parse().unwrap())
+        );
+
+        let with_port = DnsCheckConfig {
+            nameserver: Some("127.0.0.1:5353".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            with_port.nameserver_addr().unwrap(),
+            Some("127.0.0.1:5353".parse().unwrap())
+        );
+
+        let invalid = DnsCheckConfig {
+            nameserver: Some("dns.example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        let wrong_answer = DnsCheckConfig {
+            expected: vec!["not-an-ip".to_string()],
+            ..Default::default()
+        };
+        assert!(wrong_answer.validate().is_err());
+
+        let exact_without_expected = DnsCheckConfig {
+            exact: true,
+            ..Default::default()
+        };
+        assert!(exact_without_expected.validate().is_err());
+    }
+}
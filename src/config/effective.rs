@@ -0,0 +1,214 @@
+//! 服务有效配置解析
+//!
+//! 按“内置默认值 → 全局配置 → 服务配置”的顺序逐层合并，
+//! 得到每个服务实际生效的请求头、超时和重试策略。
+//! 检测器和调度器都通过这里解析配置，保证两者行为一致。
+
+use crate::config::types::{GlobalConfig, RetryBackoff, ServiceConfig};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// 全局层的请求默认值
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestDefaults {
+    /// 全局请求头
+    pub headers: HashMap<String, String>,
+    /// 请求超时时间
+    pub timeout: Duration,
+    /// 失败重试次数
+    pub retry_attempts: u32,
+    /// 重试间隔
+    pub retry_delay: Duration,
+    /// 重试间隔退避策略
+    pub retry_backoff: RetryBackoff,
+    /// 检测间隔（秒）
+    pub check_interval_seconds: u64,
+}
+
+impl From<&GlobalConfig> for RequestDefaults {
+    fn from(global: &GlobalConfig) -> Self {
+        Self {
+            headers: global.headers.clone(),
+            timeout: Duration::from_secs(global.request_timeout_seconds),
+            retry_attempts: global.retry_attempts,
+            retry_delay: Duration::from_secs(global.retry_delay_seconds),
+            retry_backoff: global.retry_backoff,
+            check_interval_seconds: global.check_interval_seconds,
+        }
+    }
+}
+
+/// 服务实际生效的配置
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveServiceConfig {
+    /// 服务名称
+    pub name: String,
+    /// 服务URL
+    pub url: String,
+    /// HTTP方法
+    pub method: String,
+    /// 合并后的请求头（服务配置覆盖同名的全局请求头，名称不区分大小写）
+    pub headers: BTreeMap<String, String>,
+    /// 请求超时时间
+    #[serde(rename = "timeout_seconds", serialize_with = "serialize_seconds")]
+    pub timeout: Duration,
+    /// 失败重试次数
+    pub retry_attempts: u32,
+    /// 基础重试间隔
+    #[serde(rename = "retry_delay_seconds", serialize_with = "serialize_seconds")]
+    pub retry_delay: Duration,
+    /// 重试间隔退避策略
+    pub retry_backoff: RetryBackoff,
+    /// 检测间隔（秒）
+    pub check_interval_seconds: u64,
+}
+
+impl EffectiveServiceConfig {
+    /// 解析服务的有效配置
+    ///
+    /// # 参数
+    /// * `defaults` - 全局层的默认值
+    /// * `service` - 服务配置
+    pub fn resolve(defaults: &RequestDefaults, service: &ServiceConfig) -> Self {
+        let mut headers: BTreeMap<String, String> = defaults
+            .headers
+            .iter()
+            .filter(|(key, _)| {
+                !service
+                    .headers
+                    .keys()
+                    .any(|override_key| override_key.eq_ignore_ascii_case(key))
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        headers.extend(
+            service
+                .headers
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+
+        Self {
+            name: service.name.clone(),
+            url: service.url.clone(),
+            method: service.method.clone(),
+            headers,
+            timeout: service
+                .request_timeout_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+            retry_attempts: service.retry_attempts.unwrap_or(defaults.retry_attempts),
+            retry_delay: service
+                .retry_delay_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.retry_delay),
+            retry_backoff: service.retry_backoff.unwrap_or(defaults.retry_backoff),
+            check_interval_seconds: service
+                .check_interval_seconds
+                .unwrap_or(defaults.check_interval_seconds),
+        }
+    }
+
+    /// 根据全局配置解析服务的有效配置
+    pub fn from_global(global: &GlobalConfig, service: &ServiceConfig) -> Self {
+        Self::resolve(&RequestDefaults::from(global), service)
+    }
+
+    /// 第 `retry` 次重试（从1开始）前的等待时间
+    pub fn retry_delay_for(&self, retry: u32) -> Duration {
+        self.retry_backoff.delay_for(self.retry_delay, retry)
+    }
+}
+
+/// 以秒为单位序列化时间间隔
+fn serialize_seconds<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    if duration.subsec_nanos() == 0 {
+        serializer.serialize_u64(duration.as_secs())
+    } else {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn global_config() -> GlobalConfig {
+        toml::from_str(
+            r#"
+            request_timeout_seconds = 10
+            retry_attempts = 3
+            retry_delay_seconds = 5
+
+            [headers]
+            "User-Agent" = "ServiceVitals/1.0"
+            "Accept" = "application/json"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn service_config(extra: &str) -> ServiceConfig {
+        toml::from_str(&format!(
+            r#"
+            name = "api"
+            url = "https://api.example.com/health"
+            expected_status_codes = [200]
+            {extra}
+            "#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_inherits_global_values() {
+        let effective = EffectiveServiceConfig::from_global(&global_config(), &service_config(""));
+
+        assert_eq!(effective.timeout, Duration::from_secs(10));
+        assert_eq!(effective.retry_attempts, 3);
+        assert_eq!(effective.retry_delay, Duration::from_secs(5));
+        assert_eq!(effective.retry_backoff, RetryBackoff::Fixed);
+        assert_eq!(effective.headers.len(), 2);
+        assert_eq!(effective.check_interval_seconds, 60);
+    }
+
+    #[test]
+    fn test_service_overrides() {
+        let service = service_config(
+            r#"
+            request_timeout_seconds = 3
+            retry_attempts = 1
+            retry_delay_seconds = 2
+            retry_backoff = "exponential"
+            check_interval_seconds = 15
+
+            [headers]
+            "user-agent" = "custom"
+            "X-Token" = "abc"
+            "#,
+        );
+        let effective = EffectiveServiceConfig::from_global(&global_config(), &service);
+
+        assert_eq!(effective.timeout, Duration::from_secs(3));
+        assert_eq!(effective.retry_attempts, 1);
+        assert_eq!(effective.retry_delay_for(2), Duration::from_secs(4));
+        assert_eq!(effective.check_interval_seconds, 15);
+
+        // 同名请求头（不区分大小写）以服务配置为准
+        assert!(!effective.headers.contains_key("User-Agent"));
+        assert_eq!(effective.headers.get("user-agent").unwrap(), "custom");
+        assert_eq!(effective.headers.get("Accept").unwrap(), "application/json");
+        assert_eq!(effective.headers.get("X-Token").unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_serialize_in_seconds() {
+        let effective = EffectiveServiceConfig::from_global(&global_config(), &service_config(""));
+        let json = serde_json::to_value(&effective).unwrap();
+
+        assert_eq!(json["timeout_seconds"], 10);
+        assert_eq!(json["retry_delay_seconds"], 5);
+        assert_eq!(json["retry_backoff"], "fixed");
+    }
+}
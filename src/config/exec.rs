@@ -0,0 +1,80 @@
+//! 命令检测配置
+//!
+//! URL 为 `exec://名称` 的服务执行 `[services.exec]` 中配置的命令，
+//! 按 Nagios 插件约定解析退出码和输出。URL中的名称只用于展示。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// 命令检测URL的前缀
+pub const EXEC_SCHEME: &str = "exec://";
+
+/// 命令检测配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecCheckConfig {
+    /// 命令及参数，第一项为可执行文件；不经过shell
+    pub command: Vec<String>,
+    /// 工作目录，未配置时使用 service-vitals 的当前目录
+    pub working_dir: Option<PathBuf>,
+    /// 传给命令的环境变量
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// 是否继承 service-vitals 的环境变量，默认只保留 `PATH`
+    #[serde(default)]
+    pub inherit_env: bool,
+}
+
+impl ExecCheckConfig {
+    /// 验证命令检测配置
+    pub fn validate(&self) -> Result<(), String> {
+        match self.command.first() {
+            None => return Err("命令不能为空".to_string()),
+            Some(program) if program.trim().is_empty() => {
+                return Err("可执行文件不能为空".to_string())
+            }
+            Some(_) => {}
+        }
+        if let Some(dir) = &self.working_dir {
+            if !dir.is_dir() {
+                return Err(format!("工作目录 {} 不存在", dir.display()));
+            }
+        }
+        if let Some(key) = self
+            .env
+            .keys()
+            .find(|key| key.is_empty() || key.contains('=') || key.contains('\0'))
+        {
+            return Err(format!("环境变量名 {key:?} 无效"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_config_validation() {
+        let config: ExecCheckConfig = toml::from_str(
+            r#"
+            command = ["/usr/lib/nagios/plugins/check_disk", "-w", "20%"]
+            env = { LC_ALL = "C" }
+            "#,
+        )
+        .unwrap();
+        assert!(!config.inherit_env);
+        assert!(config.validate().is_ok());
+
+        assert!(ExecCheckConfig::default().validate().is_err());
+
+        let mut missing_dir = config.clone();
+        missing_dir.working_dir = Some(PathBuf::from("/nonexistent/service-vitals"));
+        assert!(missing_dir.validate().unwrap_err().contains("工作目录"));
+
+        let mut bad_env = config;
+        bad_env.env.insert("A=B".to_string(), "c".to_string());
+        assert!(bad_env.validate().is_err());
+    }
+}
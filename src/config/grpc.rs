@@ -0,0 +1,110 @@
+//! gRPC健康检测配置
+//!
+//! URL 为 `grpc://主机:端口`（h2c明文）或 `grpcs://主机:端口`（TLS）的服务
+//! 调用 gRPC Health Checking Protocol 的 `grpc.health.v1.Health/Check`。
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// h2c明文gRPC检测URL的前缀
+pub const GRPC_SCHEME: &str = "grpc://";
+/// TLS gRPC检测URL的前缀
+pub const GRPCS_SCHEME: &str = "grpcs://";
+
+/// gRPC健康检测配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GrpcCheckConfig {
+    /// 检测的服务名（HealthCheckRequest.service），为空时检测服务端整体状态
+    #[serde(default)]
+    pub service: String,
+}
+
+/// gRPC检测的连接目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrpcTarget {
+    /// 主机名或IP地址（IPv6不带方括号）
+    pub host: String,
+    /// 端口，未指定时明文为80、TLS为443
+    pub port: u16,
+    /// 是否使用TLS
+    pub tls: bool,
+}
+
+impl GrpcTarget {
+    /// tonic 连接使用的URI
+    pub fn uri(&self) -> String {
+        let scheme = if self.tls { "https" } else { "http" };
+        if self.host.contains(':') {
+            format!("{scheme}://[{}]:{}", self.host, self.port)
+        } else {
+            format!("{scheme}://{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// 判断URL是否为gRPC检测URL
+pub fn is_grpc_url(url: &str) -> bool {
+    url.starts_with(GRPC_SCHEME) || url.starts_with(GRPCS_SCHEME)
+}
+
+/// 解析 `grpc://主机:端口` 或 `grpcs://主机:端口` 形式的URL
+///
+/// # 参数
+/// * `url` - 服务URL
+///
+/// # 返回
+/// * `Result<GrpcTarget, String>` - 连接目标，URL格式无效时返回错误
+pub fn grpc_target(url: &str) -> Result<GrpcTarget, String> {
+    if !is_grpc_url(url) {
+        return Err(format!("{url} 不是 {GRPC_SCHEME} 或 {GRPCS_SCHEME} URL"));
+    }
+    let parsed = Url::parse(url).map_err(|e| format!("{url} 格式无效: {e}"))?;
+    let host = parsed
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| format!("{url} 缺少主机"))?;
+    if !matches!(parsed.path(), "" | "/") || parsed.query().is_some() {
+        return Err(format!("{url} 格式无效，应为 {GRPC_SCHEME}主机:端口"));
+    }
+    let tls = url.starts_with(GRPCS_SCHEME);
+    Ok(GrpcTarget {
+        host: host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+        port: parsed.port().unwrap_or(if tls { 443 } else { 80 }),
+        tls,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grpc_target() {
+        let target = grpc_target("grpc://127.0.0.1:50051").unwrap();
+        assert_eq!(
+            target,
+            GrpcTarget {
+                host: "127.0.0.1".to_string(),
+                port: 50051,
+                tls: false,
+            }
+        );
+        assert_eq!(target.uri(), "http://127.0.0.1:50051");
+
+        let target = grpc_target("grpcs://api.example.com/").unwrap();
+        assert!(target.tls);
+        assert_eq!(target.port, 443);
+        assert_eq!(target.uri(), "https://api.example.com:443");
+
+        let target = grpc_target("grpc://[::1]:9000").unwrap();
+        assert_eq!(target.host, "::1");
+        assert_eq!(target.uri(), "http://[::1]:9000");
+
+        assert!(grpc_target("grpc://").is_err());
+        assert!(grpc_target("grpc://host:1/pkg.Service").is_err());
+        assert!(grpc_target("https://host:1").is_err());
+    }
+}
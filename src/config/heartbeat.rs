@@ -0,0 +1,87 @@
+//! 心跳检测配置
+//!
+//! URL 为 `heartbeat://名称` 的服务不主动发起检测，而是等待任务调用
+//! `POST /api/v1/heartbeat/{token}` 上报心跳；超过周期加宽限时间仍未收到心跳时服务状态为异常。
+//! URL中的名称只用于展示。
+
+use serde::{Deserialize, Serialize};
+
+/// 心跳检测URL的前缀
+pub const HEARTBEAT_SCHEME: &str = "heartbeat://";
+
+/// 心跳令牌的最小长度
+const MIN_TOKEN_LEN: usize = 16;
+
+/// 心跳检测配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HeartbeatCheckConfig {
+    /// 上报心跳使用的令牌，出现在上报URL中
+    pub token: String,
+    /// 期望的心跳周期（秒）
+    pub period_seconds: u64,
+    /// 宽限时间（秒），超过周期加宽限时间未收到心跳时服务标记为异常
+    #[serde(default)]
+    pub grace_seconds: u64,
+    /// 任务最长运行时间（秒），上报开始后超过该时间仍未上报结束时服务标记为异常
+    pub max_duration_seconds: Option<u64>,
+}
+
+impl HeartbeatCheckConfig {
+    /// 验证心跳检测配置
+    pub fn validate(&self) -> Result<(), String> {
+        if self.token.len() < MIN_TOKEN_LEN {
+            return Err(format!("令牌长度不能少于 {MIN_TOKEN_LEN} 个字符"));
+        }
+        if !self
+            .token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err("令牌只能包含字母、数字、'-' 和 '_'".to_string());
+        }
+        if self.period_seconds == 0 {
+            return Err("心跳周期不能为0".to_string());
+        }
+        if self.max_duration_seconds == Some(0) {
+            return Err("最长运行时间不能为0".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heartbeat_config_validation() {
+        let config: HeartbeatCheckConfig = toml::from_str(
+            r#"
+            token = "nightly-backup-7f3a9c"
+            period_seconds = 86400
+            grace_seconds = 1800
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.max_duration_seconds, None);
+        assert!(config.validate().is_ok());
+
+        let short = HeartbeatCheckConfig {
+            token: "abc".to_string(),
+            ..config.clone()
+        };
+        assert!(short.validate().unwrap_err().contains("令牌长度"));
+
+        let invalid = HeartbeatCheckConfig {
+            token: "nightly/backup/7f3a9c".to_string(),
+            ..config.clone()
+        };
+        assert!(invalid.validate().is_err());
+
+        let no_period = HeartbeatCheckConfig {
+            period_seconds: 0,
+            ..config
+        };
+        assert!(no_period.validate().is_err());
+    }
+}
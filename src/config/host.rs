@@ -0,0 +1,282 @@
+//! 本机资源检测配置
+//!
+//! URL 为 `host://名称` 的服务按 `[services.host]` 中的 `check` 检测运行 service-vitals 的主机：
+//! 磁盘使用率、内存与交换分区、负载、进程存活以及文件更新时间。URL中的名称只用于展示。
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 本机资源检测URL的前缀
+pub const HOST_SCHEME: &str = "host://";
+
+/// 本机资源检测配置，`check` 字段决定检测项
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "check", rename_all = "lowercase")]
+pub enum HostCheckConfig {
+    /// 挂载点的磁盘空间和inode使用率
+    Disk(DiskCheckConfig),
+    /// 内存和交换分区使用率
+    Memory(MemoryCheckConfig),
+    /// 系统负载
+    Load(LoadCheckConfig),
+    /// 进程存活
+    Process(ProcessCheckConfig),
+    /// 文件更新时间
+    File(FileCheckConfig),
+}
+
+/// 磁盘检测配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DiskCheckConfig {
+    /// 挂载点或其中的任意路径
+    pub path: PathBuf,
+    /// 使用率达到该百分比时标记为降级
+    #[serde(default = "default_disk_warning_percent")]
+    pub warning_percent: f64,
+    /// 使用率达到该百分比时标记为异常
+    #[serde(default = "default_disk_critical_percent")]
+    pub critical_percent: f64,
+    /// inode使用率达到该百分比时标记为降级
+    pub inode_warning_percent: Option<f64>,
+    /// inode使用率达到该百分比时标记为异常
+    pub inode_critical_percent: Option<f64>,
+}
+
+/// 内存检测配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MemoryCheckConfig {
+    /// 内存使用率（按 `MemAvailable` 计算）达到该百分比时标记为降级
+    #[serde(default = "default_memory_warning_percent")]
+    pub warning_percent: f64,
+    /// 内存使用率达到该百分比时标记为异常
+    #[serde(default = "default_memory_critical_percent")]
+    pub critical_percent: f64,
+    /// 交换分区使用率达到该百分比时标记为降级
+    pub swap_warning_percent: Option<f64>,
+    /// 交换分区使用率达到该百分比时标记为异常
+    pub swap_critical_percent: Option<f64>,
+}
+
+/// 负载检测配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoadCheckConfig {
+    /// 负载达到该值时标记为降级
+    pub warning: f64,
+    /// 负载达到该值时标记为异常
+    pub critical: f64,
+    /// 使用1、5或15分钟平均负载，默认5分钟
+    #[serde(default = "default_load_minutes")]
+    pub minutes: u8,
+    /// 是否按CPU核数折算负载，默认开启
+    #[serde(default = "default_per_cpu")]
+    pub per_cpu: bool,
+}
+
+/// 进程检测配置，`name` 和 `pidfile` 二选一
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProcessCheckConfig {
+    /// 进程名，匹配 `/proc/<pid>/comm` 或命令行第一项的文件名
+    pub name: Option<String>,
+    /// PID文件，文件中的进程存在即为正常
+    pub pidfile: Option<PathBuf>,
+    /// 按进程名检测时的最少进程数，少于该值标记为异常
+    #[serde(default = "default_min_count")]
+    pub min_count: usize,
+    /// 按进程名检测时的最多进程数，超过该值标记为降级
+    pub max_count: Option<usize>,
+}
+
+/// 文件更新时间检测配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileCheckConfig {
+    /// 文件路径，不存在时标记为异常
+    pub path: PathBuf,
+    /// 距离最后修改时间超过该秒数时标记为降级
+    pub warning_age_seconds: Option<u64>,
+    /// 距离最后修改时间超过该秒数时标记为异常
+    pub critical_age_seconds: u64,
+}
+
+fn default_disk_warning_percent() -> f64 {
+    80.0
+}
+
+fn default_disk_critical_percent() -> f64 {
+    90.0
+}
+
+fn default_memory_warning_percent() -> f64 {
+    90.0
+}
+
+fn default_memory_critical_percent() -> f64 {
+    95.0
+}
+
+fn default_load_minutes() -> u8 {
+    5
+}
+
+fn default_per_cpu() -> bool {
+    true
+}
+
+fn default_min_count() -> usize {
+    1
+}
+
+impl HostCheckConfig {
+    /// 检测项名称
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HostCheckConfig::Disk(_) => "disk",
+            HostCheckConfig::Memory(_) => "memory",
+            HostCheckConfig::Load(_) => "load",
+            HostCheckConfig::Process(_) => "process",
+            HostCheckConfig::File(_) => "file",
+        }
+    }
+
+    /// 验证本机资源检测配置
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            HostCheckConfig::Disk(disk) => {
+                if disk.path.as_os_str().is_empty() {
+                    return Err("磁盘检测的 path 不能为空".to_string());
+                }
+                validate_percent("", disk.warning_percent, disk.critical_percent)?;
+                if let (Some(warning), Some(critical)) =
+                    (disk.inode_warning_percent, disk.inode_critical_percent)
+                {
+                    validate_percent("inode_", warning, critical)?;
+                }
+                validate_optional_percent("inode_warning_percent", disk.inode_warning_percent)?;
+                validate_optional_percent("inode_critical_percent", disk.inode_critical_percent)
+            }
+            HostCheckConfig::Memory(memory) => {
+                validate_percent("", memory.warning_percent, memory.critical_percent)?;
+                if let (Some(warning), Some(critical)) =
+                    (memory.swap_warning_percent, memory.swap_critical_percent)
+                {
+                    validate_percent("swap_", warning, critical)?;
+                }
+                validate_optional_percent("swap_warning_percent", memory.swap_warning_percent)?;
+                validate_optional_percent("swap_critical_percent", memory.swap_critical_percent)
+            }
+            HostCheckConfig::Load(load) => {
+                if ![1, 5, 15].contains(&load.minutes) {
+                    return Err("负载检测的 minutes 只能为 1、5 或 15".to_string());
+                }
+                if !(load.warning > 0.0 && load.warning <= load.critical) {
+                    return Err("负载检测的 warning 必须大于0且不大于 critical".to_string());
+                }
+                Ok(())
+            }
+            HostCheckConfig::Process(process) => {
+                match (&process.name, &process.pidfile) {
+                    (Some(_), Some(_)) | (None, None) => {
+                        return Err("进程检测必须且只能配置 name 或 pidfile 之一".to_string())
+                    }
+                    (Some(name), None) if name.trim().is_empty() => {
+                        return Err("进程名不能为空".to_string())
+                    }
+                    _ => {}
+                }
+                if process.max_count.is_some_and(|max| max < process.min_count) {
+                    return Err("进程检测的 max_count 不能小于 min_count".to_string());
+                }
+                Ok(())
+            }
+            HostCheckConfig::File(file) => {
+                if file.path.as_os_str().is_empty() {
+                    return Err("文件检测的 path 不能为空".to_string());
+                }
+                if file.critical_age_seconds == 0 {
+                    return Err("文件检测的 critical_age_seconds 不能为0".to_string());
+                }
+                if file
+                    .warning_age_seconds
+                    .is_some_and(|warning| warning == 0 || warning > file.critical_age_seconds)
+                {
+                    return Err(
+                        "文件检测的 warning_age_seconds 必须大于0且不大于 critical_age_seconds"
+                            .to_string(),
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 验证成对的百分比阈值
+fn validate_percent(prefix: &str, warning: f64, critical: f64) -> Result<(), String> {
+    validate_optional_percent(&format!("{prefix}warning_percent"), Some(warning))?;
+    validate_optional_percent(&format!("{prefix}critical_percent"), Some(critical))?;
+    if warning > critical {
+        return Err(format!(
+            "{prefix}warning_percent 不能大于 {prefix}critical_percent"
+        ));
+    }
+    Ok(())
+}
+
+/// 验证百分比阈值在 (0, 100] 范围内
+fn validate_optional_percent(name: &str, value: Option<f64>) -> Result<(), String> {
+    match value {
+        Some(value) if !(value > 0.0 && value <= 100.0) => {
+            Err(format!("{name} 必须在 0 到 100 之间"))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_config_validation() {
+        let disk: HostCheckConfig = toml::from_str(
+            r#"
+            check = "disk"
+            path = "/"
+            inode_critical_percent = 95
+            "#,
+        )
+        .unwrap();
+        assert_eq!(disk.kind(), "disk");
+        assert!(disk.validate().is_ok());
+        let HostCheckConfig::Disk(mut disk) = disk else {
+            panic!("应解析为磁盘检测");
+        };
+        assert_eq!(disk.warning_percent, 80.0);
+        disk.warning_percent = 95.0;
+        assert!(HostCheckConfig::Disk(disk.clone()).validate().is_err());
+        disk.warning_percent = 80.0;
+        disk.critical_percent = 120.0;
+        assert!(HostCheckConfig::Disk(disk).validate().is_err());
+
+        let load: HostCheckConfig =
+            toml::from_str("check = \"load\"\nwarning = 2.0\ncritical = 4.0\nminutes = 10")
+                .unwrap();
+        assert!(load.validate().unwrap_err().contains("minutes"));
+
+        let process: HostCheckConfig =
+            toml::from_str("check = \"process\"\nname = \"nginx\"\npidfile = \"/run/nginx.pid\"")
+                .unwrap();
+        assert!(process.validate().unwrap_err().contains("name 或 pidfile"));
+        let process: HostCheckConfig =
+            toml::from_str("check = \"process\"\nname = \"nginx\"\nmin_count = 2\nmax_count = 1")
+                .unwrap();
+        assert!(process.validate().unwrap_err().contains("max_count"));
+
+        let file: HostCheckConfig = toml::from_str(
+            "check = \"file\"\npath = \"/var/backups/db.tar\"\nwarning_age_seconds = 7200\ncritical_age_seconds = 3600",
+        )
+        .unwrap();
+        assert!(file.validate().is_err());
+
+        assert!(toml::from_str::<HostCheckConfig>("check = \"cpu\"").is_err());
+    }
+}
@@ -0,0 +1,617 @@
+//! blackbox_exporter 配置导入
+//!
+//! blackbox_exporter 的探测方式定义在 `blackbox.yml` 的 `modules` 中，探测目标则写在
+//! Prometheus `scrape_configs` 里：任务通过 `params.module` 选择模块，`static_configs`
+//! 列出目标，目标的 `labels` 转换为服务标签。`http`、`dns` 和 `grpc` 探测可以转换，
+//! `tcp` 和 `icmp` 探测没有对应的检测方式，会被跳过。
+
+use super::{expand_status_codes, new_service, slug, ImportOutcome, Notes};
+use crate::config::dns::{DnsCheckConfig, DnsRecordType};
+use crate::config::grpc::GrpcCheckConfig;
+use crate::config::steps::StepConfig;
+use crate::config::types::{AuthConfig, ServiceConfig, TlsConfig, TlsVersion};
+use crate::config::yaml;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Prometheus 默认的抓取间隔（秒）
+const DEFAULT_SCRAPE_INTERVAL: u64 = 60;
+
+/// blackbox_exporter 标准配置中用于传递探测目标的重写目标标签
+const PROBE_RELABEL_TARGETS: [&str; 3] = ["__param_target", "instance", "__address__"];
+
+/// 转换 blackbox_exporter 模块和 Prometheus 抓取任务
+///
+/// # 参数
+/// * `modules` - blackbox.yml 内容
+/// * `prometheus` - prometheus.yml 内容
+///
+/// # 返回
+/// * `Result<ImportOutcome, String>` - 转换结果，配置格式无效时返回错误
+pub fn from_blackbox(modules: &str, prometheus: &str) -> Result<ImportOutcome, String> {
+    let modules = yaml::parse(modules).map_err(|e| format!("无法解析blackbox配置: {e}"))?;
+    let modules = modules
+        .get("modules")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "blackbox配置中没有 modules".to_string())?;
+    let prometheus = yaml::parse(prometheus).map_err(|e| format!("无法解析Prometheus配置: {e}"))?;
+    let jobs = prometheus
+        .get("scrape_configs")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "Prometheus配置中没有 scrape_configs".to_string())?;
+    let global_interval = prometheus
+        .get("global")
+        .and_then(|global| duration_field(global, "scrape_interval"))
+        .unwrap_or(DEFAULT_SCRAPE_INTERVAL);
+
+    let mut outcome = ImportOutcome::default();
+    for job in jobs {
+        let job_name = str_field(job, "job_name").unwrap_or("job");
+        let module_names: Vec<&str> = job
+            .get("params")
+            .and_then(|params| params.get("module"))
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let Some(module_name) = module_names.first() else {
+            outcome.skip(job_name, "params.module", "不是blackbox探测任务");
+            continue;
+        };
+        let Some(module) = modules.get(*module_name) else {
+            outcome.skip(
+                job_name,
+                "params.module",
+                &format!("blackbox配置中没有模块 {module_name}"),
+            );
+            continue;
+        };
+
+        let targets = static_targets(job);
+        if targets.is_empty() {
+            outcome.skip(job_name, "static_configs", "只支持 static_configs 中的目标");
+            continue;
+        }
+
+        let interval = duration_field(job, "scrape_interval").unwrap_or(global_interval);
+        let timeout =
+            duration_field(module, "timeout").or_else(|| duration_field(job, "scrape_timeout"));
+        for (target, labels) in &targets {
+            let name = if targets.len() == 1 {
+                job_name.to_string()
+            } else {
+                format!("{job_name}-{}", slug(target))
+            };
+            let mut notes = Notes::default();
+            if module_names.len() > 1 {
+                notes.push("params.module", format!("只使用第一个模块 {module_name}"));
+            }
+            for key in [
+                "file_sd_configs",
+                "dns_sd_configs",
+                "kubernetes_sd_configs",
+                "http_sd_configs",
+            ] {
+                if job.get(key).is_some() {
+                    notes.push(key, "只支持 static_configs 中的目标");
+                }
+            }
+            if has_custom_relabeling(job) {
+                notes.push("relabel_configs", "不支持标签重写");
+            }
+
+            match convert_module(module, &name, target, &mut notes) {
+                Ok(mut service) => {
+                    service.check_interval_seconds = Some(interval);
+                    service.request_timeout_seconds = timeout;
+                    service.tags = labels
+                        .iter()
+                        .map(|(key, value)| format!("{key}:{value}"))
+                        .collect();
+                    outcome.add(&name, service, notes);
+                }
+                Err(reason) => outcome.skip(&name, "prober", &reason),
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+/// 收集任务 `static_configs` 中的目标及其标签
+fn static_targets(job: &Value) -> Vec<(String, BTreeMap<String, String>)> {
+    let mut targets = Vec::new();
+    let Some(configs) = job.get("static_configs").and_then(Value::as_array) else {
+        return targets;
+    };
+    for config in configs {
+        let labels: BTreeMap<String, String> = config
+            .get("labels")
+            .and_then(Value::as_object)
+            .map(|labels| {
+                labels
+                    .iter()
+                    .map(|(key, value)| (key.clone(), scalar_string(value)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for target in config
+            .get("targets")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            targets.push((scalar_string(target), labels.clone()));
+        }
+    }
+    targets
+}
+
+/// 是否有blackbox标准写法之外的标签重写规则
+fn has_custom_relabeling(job: &Value) -> bool {
+    job.get("relabel_configs")
+        .and_then(Value::as_array)
+        .is_some_and(|rules| {
+            rules.iter().any(|rule| {
+                str_field(rule, "target_label")
+                    .is_none_or(|label| !PROBE_RELABEL_TARGETS.contains(&label))
+            })
+        })
+}
+
+/// 按模块的探测方式转换单个目标
+fn convert_module(
+    module: &Value,
+    name: &str,
+    target: &str,
+    notes: &mut Notes,
+) -> Result<ServiceConfig, String> {
+    let prober = str_field(module, "prober").unwrap_or("http");
+    let options = module
+        .get(prober)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    for key in module.as_object().into_iter().flat_map(Map::keys) {
+        if !["prober", "timeout", prober].contains(&key.as_str()) {
+            notes.push(key, "与探测方式无关");
+        }
+    }
+
+    let (service, handled): (ServiceConfig, &[&str]) = match prober {
+        "http" => (
+            convert_http(&options, name, target, notes)?,
+            &[
+                "valid_status_codes",
+                "method",
+                "headers",
+                "body",
+                "basic_auth",
+                "bearer_token",
+                "tls_config",
+                "fail_if_body_not_matches_regexp",
+            ],
+        ),
+        "dns" => (
+            convert_dns(&options, name, target, notes)?,
+            &["query_name", "query_type"],
+        ),
+        "grpc" => (
+            convert_grpc(&options, name, target, notes)?,
+            &["service", "tls", "tls_config"],
+        ),
+        other => return Err(format!("不支持 {other} 探测")),
+    };
+    for key in options.keys() {
+        if !handled.contains(&key.as_str()) {
+            notes.push(&format!("{prober}.{key}"), "不支持");
+        }
+    }
+    Ok(service)
+}
+
+/// 转换HTTP探测
+fn convert_http(
+    options: &Map<String, Value>,
+    name: &str,
+    target: &str,
+    notes: &mut Notes,
+) -> Result<ServiceConfig, String> {
+    // 与blackbox一致，没有协议前缀的目标按 http 处理
+    let url = if target.contains("://") {
+        target.to_string()
+    } else {
+        format!("http://{target}")
+    };
+    let mut service = new_service(name, &url);
+    service.method = options
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET")
+        .to_ascii_uppercase();
+
+    let codes: Vec<String> = options
+        .get("valid_status_codes")
+        .and_then(Value::as_array)
+        .map(|codes| codes.iter().map(scalar_string).collect())
+        .unwrap_or_default();
+    // 未配置时与blackbox一致，接受所有 2xx
+    service.expected_status_codes = if codes.is_empty() {
+        expand_status_codes(&["200-299".to_string()])?
+    } else {
+        expand_status_codes(&codes)?
+    };
+
+    if let Some(headers) = options.get("headers").and_then(Value::as_object) {
+        service.headers = headers
+            .iter()
+            .map(|(key, value)| (key.clone(), scalar_string(value)))
+            .collect();
+    }
+    if let Some(body) = options.get("body").and_then(Value::as_str) {
+        match serde_json::from_str::<Value>(body) {
+            Ok(body) if !body.is_null() => service.body = Some(body),
+            _ => notes.push("http.body", "只支持JSON请求体"),
+        }
+    }
+
+    if let Some(basic) = options.get("basic_auth") {
+        if let Some(username) = str_field(basic, "username") {
+            service.auth = Some(AuthConfig::Basic {
+                username: username.to_string(),
+                password: str_field(basic, "password").map(str::to_string),
+            });
+        }
+        if basic.get("password_file").is_some() {
+            notes.push("http.basic_auth.password_file", "不支持从文件读取密码");
+        }
+    }
+    if let Some(token) = options.get("bearer_token").and_then(Value::as_str) {
+        service.auth = Some(AuthConfig::Bearer {
+            token: token.to_string(),
+        });
+    }
+    if service.auth.is_some() {
+        service
+            .headers
+            .retain(|key, _| !key.eq_ignore_ascii_case("authorization"));
+    }
+
+    if let Some(tls) = options.get("tls_config") {
+        if url.starts_with("https://") {
+            service.tls = convert_tls(tls, notes);
+        } else {
+            notes.push("http.tls_config", "目标不是https");
+        }
+    }
+
+    if let Some(patterns) = options
+        .get("fail_if_body_not_matches_regexp")
+        .and_then(Value::as_array)
+    {
+        match patterns.as_slice() {
+            [Value::String(pattern)] if !pattern.contains(is_regex_meta) => {
+                service.steps = vec![StepConfig {
+                    name: "body".to_string(),
+                    url: url.clone(),
+                    method: service.method.clone(),
+                    headers: HashMap::new(),
+                    body: service.body.take(),
+                    expected_status_codes: Vec::new(),
+                    body_contains: Some(pattern.clone()),
+                    extract: BTreeMap::new(),
+                }];
+            }
+            _ => notes.push(
+                "http.fail_if_body_not_matches_regexp",
+                "只支持单个不含正则语法的关键字",
+            ),
+        }
+    }
+    Ok(service)
+}
+
+/// 转换DNS探测，目标是DNS服务器地址
+fn convert_dns(
+    options: &Map<String, Value>,
+    name: &str,
+    target: &str,
+    notes: &mut Notes,
+) -> Result<ServiceConfig, String> {
+    let query_name = options
+        .get("query_name")
+        .and_then(Value::as_str)
+        .map(|name| name.trim_end_matches('.'))
+        .ok_or_else(|| "DNS探测缺少 query_name".to_string())?;
+    let record_type = options
+        .get("query_type")
+        .and_then(Value::as_str)
+        .unwrap_or("ANY");
+    let record_type: DnsRecordType =
+        serde_json::from_value(Value::String(record_type.to_ascii_uppercase()))
+            .map_err(|_| format!("不支持 {record_type} 记录"))?;
+
+    let nameserver = target
+        .trim_start_matches("tcp://")
+        .trim_start_matches("udp://");
+    let probe = DnsCheckConfig {
+        nameserver: Some(nameserver.to_string()),
+        ..Default::default()
+    };
+    let nameserver = match probe.nameserver_addr() {
+        Ok(Some(addr)) if addr.port() == 53 => Some(addr.ip().to_string()),
+        Ok(Some(addr)) => Some(addr.to_string()),
+        _ => {
+            notes.push("target", format!("{target} 不是IP地址，使用系统DNS"));
+            None
+        }
+    };
+
+    let mut service = new_service(name, &format!("dns://{query_name}"));
+    service.dns = Some(DnsCheckConfig {
+        record_type,
+        nameserver,
+        ..Default::default()
+    });
+    Ok(service)
+}
+
+/// 转换gRPC探测
+fn convert_grpc(
+    options: &Map<String, Value>,
+    name: &str,
+    target: &str,
+    notes: &mut Notes,
+) -> Result<ServiceConfig, String> {
+    let tls = options.get("tls").and_then(Value::as_bool).unwrap_or(false);
+    let scheme = if tls { "grpcs" } else { "grpc" };
+    let address = target
+        .split_once("://")
+        .map_or(target, |(_, address)| address);
+    let mut service = new_service(name, &format!("{scheme}://{address}"));
+    let health_service = options
+        .get("service")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if !health_service.is_empty() {
+        service.grpc = Some(GrpcCheckConfig {
+            service: health_service.to_string(),
+        });
+    }
+    if let Some(tls_config) = options.get("tls_config") {
+        if tls {
+            service.tls = convert_tls(tls_config, notes);
+        } else {
+            notes.push("grpc.tls_config", "未启用 tls");
+        }
+    }
+    Ok(service)
+}
+
+/// 转换 `tls_config`，不存在的证书文件不导入
+fn convert_tls(tls: &Value, notes: &mut Notes) -> Option<TlsConfig> {
+    let mut file = |key: &str| {
+        let path = str_field(tls, key)?;
+        if Path::new(path).is_file() {
+            Some(path.to_string())
+        } else {
+            notes.push(
+                &format!("tls_config.{key}"),
+                format!("证书文件 {path} 不存在"),
+            );
+            None
+        }
+    };
+    let mut config = TlsConfig {
+        ca_certs: file("ca_file").into_iter().collect(),
+        client_cert: file("cert_file"),
+        client_key: file("key_file"),
+        insecure_skip_verify: tls
+            .get("insecure_skip_verify")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        server_name: str_field(tls, "server_name").map(str::to_string),
+        min_version: match str_field(tls, "min_version") {
+            Some("TLS12") => Some(TlsVersion::Tls12),
+            Some("TLS13") => Some(TlsVersion::Tls13),
+            Some(other) => {
+                notes.push("tls_config.min_version", format!("不支持 {other}"));
+                None
+            }
+            None => None,
+        },
+    };
+    if config.client_cert.is_none() || config.client_key.is_none() {
+        config.client_cert = None;
+        config.client_key = None;
+    }
+    Some(config).filter(|config| config != &TlsConfig::default())
+}
+
+/// 解析 Prometheus 时长（如 `30s`、`1m30s`、`1h`），返回向上取整的秒数
+fn parse_duration(text: &str) -> Option<u64> {
+    let mut total_ms: u64 = 0;
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let value: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit_ms = match &rest[..unit_len] {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            "w" => 604_800_000,
+            "y" => 31_536_000_000,
+            _ => return None,
+        };
+        total_ms = total_ms.checked_add(value.checked_mul(unit_ms)?)?;
+        rest = &rest[unit_len..];
+    }
+    Some(total_ms.div_ceil(1_000)).filter(|seconds| *seconds > 0)
+}
+
+fn duration_field(value: &Value, key: &str) -> Option<u64> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .and_then(parse_duration)
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value
+        .get(key)?
+        .as_str()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+/// YAML标量转换为字符串（数字和布尔值按字面输出）
+fn scalar_string(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn is_regex_meta(c: char) -> bool {
+    "\\.+*?()|[]{}^$".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::{validate_config, Config};
+
+    const MODULES: &str = r#"
+modules:
+  http_2xx:
+    prober: http
+    timeout: 5s
+    http:
+      method: GET
+      valid_status_codes: [200, 204]
+      fail_if_body_not_matches_regexp: ["healthy"]
+      preferred_ip_protocol: ip4
+      tls_config:
+        insecure_skip_verify: true
+      bearer_token: s3cr3t
+  dns_example:
+    prober: dns
+    dns:
+      query_name: "example.com"
+      query_type: "A"
+  grpc_health:
+    prober: grpc
+    grpc:
+      tls: true
+      service: "orders"
+  tcp_connect:
+    prober: tcp
+"#;
+
+    const PROMETHEUS: &str = r#"
+global:
+  scrape_interval: 30s
+scrape_configs:
+  - job_name: web
+    metrics_path: /probe
+    scrape_interval: 1m
+    params:
+      module: [http_2xx]
+    static_configs:
+      - targets:
+          - https://example.com/health
+          - example.org
+        labels:
+          env: prod
+    relabel_configs:
+      - source_labels: [__address__]
+        target_label: __param_target
+      - target_label: __address__
+        replacement: blackbox:9115
+  - job_name: dns
+    params:
+      module: [dns_example]
+    static_configs:
+      - targets: ["8.8.8.8"]
+  - job_name: orders
+    params:
+      module: [grpc_health]
+    static_configs:
+      - targets: ["orders.internal:50051"]
+  - job_name: ssh
+    params:
+      module: [tcp_connect]
+    static_configs:
+      - targets: ["10.0.0.1:22"]
+  - job_name: node
+    static_configs:
+      - targets: ["localhost:9100"]
+"#;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s"), Some(30));
+        assert_eq!(parse_duration("1m30s"), Some(90));
+        assert_eq!(parse_duration("500ms"), Some(1));
+        assert_eq!(parse_duration("2h"), Some(7200));
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration("10"), None);
+    }
+
+    #[test]
+    fn test_convert_blackbox_targets() {
+        let outcome = from_blackbox(MODULES, PROMETHEUS).unwrap();
+        let names: Vec<&str> = outcome.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "web-https-example.com-health",
+                "web-example.org",
+                "dns",
+                "orders"
+            ]
+        );
+
+        let web = &outcome.services[0];
+        assert_eq!(web.expected_status_codes, vec![200, 204]);
+        assert_eq!(web.check_interval_seconds, Some(60));
+        assert_eq!(web.request_timeout_seconds, Some(5));
+        assert_eq!(web.tags, vec!["env:prod"]);
+        assert_eq!(web.steps[0].body_contains.as_deref(), Some("healthy"));
+        assert!(web.tls.as_ref().unwrap().insecure_skip_verify);
+        assert!(matches!(web.auth, Some(AuthConfig::Bearer { .. })));
+        assert_eq!(outcome.services[1].url, "http://example.org");
+        assert!(outcome.services[1].tls.is_none());
+
+        let dns = &outcome.services[2];
+        assert_eq!(dns.url, "dns://example.com");
+        assert_eq!(dns.check_interval_seconds, Some(30));
+        assert_eq!(
+            dns.dns.as_ref().unwrap().nameserver.as_deref(),
+            Some("8.8.8.8")
+        );
+
+        let orders = &outcome.services[3];
+        assert_eq!(orders.url, "grpcs://orders.internal:50051");
+        assert_eq!(orders.grpc.as_ref().unwrap().service, "orders");
+
+        let fields: Vec<&str> = outcome.issues.iter().map(|i| i.field.as_str()).collect();
+        assert!(fields.contains(&"http.preferred_ip_protocol"));
+        assert!(fields.contains(&"http.tls_config"));
+        assert!(!fields.contains(&"relabel_configs"));
+        assert_eq!(outcome.skipped_count(), 2);
+
+        let toml = outcome.to_toml("blackbox_exporter").unwrap();
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert!(validate_config(&config).is_ok());
+        assert_eq!(config.services, outcome.services);
+    }
+}
@@ -0,0 +1,370 @@
+//! 监控配置导入
+//!
+//! 把其他监控工具的配置转换为 service-vitals 的 `[[services]]`：
+//! - Uptime Kuma 的 JSON 备份（`monitorList`）
+//! - blackbox_exporter 的 `modules` 配置加上 Prometheus `scrape_configs` 中的探测目标
+//!
+//! 无法转换的字段和监控项记录在 [`ImportIssue`] 中，生成的每个服务都单独通过
+//! [`validate_config`] 校验，校验失败的服务不会写入结果。
+
+pub mod blackbox;
+pub mod uptime_kuma;
+
+use crate::config::types::{validate_config, Config, GlobalConfig, ServiceConfig};
+use std::collections::HashSet;
+use std::fmt;
+
+pub use blackbox::from_blackbox;
+pub use uptime_kuma::from_uptime_kuma;
+
+/// 一个未能转换的字段或监控项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportIssue {
+    /// 来源中的监控项名称
+    pub source: String,
+    /// 生成的服务名称，整个监控项被跳过时为 `None`
+    pub service: Option<String>,
+    /// 未能转换的字段
+    pub field: String,
+    /// 原因
+    pub reason: String,
+}
+
+impl ImportIssue {
+    /// 整个监控项是否被跳过
+    pub fn is_skipped(&self) -> bool {
+        self.service.is_none()
+    }
+}
+
+impl fmt::Display for ImportIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = if self.is_skipped() {
+            "已跳过"
+        } else {
+            "已忽略"
+        };
+        write!(
+            f,
+            "{}: {} {}（{}）",
+            self.source, self.field, action, self.reason
+        )
+    }
+}
+
+/// 导入结果
+#[derive(Debug, Default)]
+pub struct ImportOutcome {
+    /// 转换得到的服务
+    pub services: Vec<ServiceConfig>,
+    /// 未能转换的字段和被跳过的监控项
+    pub issues: Vec<ImportIssue>,
+    names: HashSet<String>,
+}
+
+impl ImportOutcome {
+    /// 添加转换后的服务
+    ///
+    /// 名称重复时追加序号；服务未通过校验时跳过并记录原因。
+    /// `notes` 为该监控项未能转换的字段。
+    pub(crate) fn add(&mut self, source: &str, mut service: ServiceConfig, notes: Notes) {
+        let mut notes = notes.0;
+        if self.names.contains(&service.name) {
+            let base = service.name.clone();
+            let mut index = 2;
+            while self.names.contains(&format!("{base}-{index}")) {
+                index += 1;
+            }
+            service.name = format!("{base}-{index}");
+            notes.push(Note {
+                field: "name".to_string(),
+                reason: format!("名称 {base} 重复，已重命名为 {}", service.name),
+            });
+        }
+
+        if let Err(e) = validate_service(&service) {
+            self.skip(source, "*", &e);
+            return;
+        }
+
+        for note in notes {
+            self.issues.push(ImportIssue {
+                source: source.to_string(),
+                service: Some(service.name.clone()),
+                field: note.field,
+                reason: note.reason,
+            });
+        }
+        self.names.insert(service.name.clone());
+        self.services.push(service);
+    }
+
+    /// 记录被跳过的监控项
+    pub(crate) fn skip(&mut self, source: &str, field: &str, reason: &str) {
+        self.issues.push(ImportIssue {
+            source: source.to_string(),
+            service: None,
+            field: field.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /// 被跳过的监控项数量
+    pub fn skipped_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.is_skipped())
+            .count()
+    }
+
+    /// 生成TOML配置
+    ///
+    /// 只输出与默认值不同的字段；未能转换的字段以注释形式写在对应服务之前
+    ///
+    /// # 参数
+    /// * `origin` - 写在文件开头的来源说明
+    pub fn to_toml(&self, origin: &str) -> Result<String, String> {
+        let mut output = format!("# 由 service-vitals import 从 {origin} 生成\n");
+        let skipped: Vec<&ImportIssue> = self.issues.iter().filter(|i| i.is_skipped()).collect();
+        if !skipped.is_empty() {
+            output.push_str("#\n# 以下监控项未能导入：\n");
+            for issue in skipped {
+                output.push_str(&format!("#   {issue}\n"));
+            }
+        }
+        // 来源工具没有检测内重试，失败次数由各服务的 failure_threshold 控制
+        output.push_str("\n[global]\nretry_attempts = 0\n");
+
+        for service in &self.services {
+            output.push('\n');
+            let notes: Vec<&ImportIssue> = self
+                .issues
+                .iter()
+                .filter(|issue| issue.service.as_ref() == Some(&service.name))
+                .collect();
+            for issue in notes {
+                output.push_str(&format!("# 未导入 {}: {}\n", issue.field, issue.reason));
+            }
+            output.push_str(&render_service(service)?);
+        }
+        Ok(output)
+    }
+}
+
+/// 转换过程中记录的未能转换字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Note {
+    field: String,
+    reason: String,
+}
+
+/// 收集某个监控项的未能转换字段
+#[derive(Debug, Default)]
+pub(crate) struct Notes(Vec<Note>);
+
+impl Notes {
+    /// 记录未能转换的字段及原因
+    pub fn push(&mut self, field: &str, reason: impl Into<String>) {
+        self.0.push(Note {
+            field: field.to_string(),
+            reason: reason.into(),
+        });
+    }
+}
+
+/// 创建只有名称和URL、其余字段为默认值的服务
+pub(crate) fn new_service(name: &str, url: &str) -> ServiceConfig {
+    serde_json::from_value(serde_json::json!({ "name": name, "url": url }))
+        .expect("服务默认配置可以反序列化")
+}
+
+/// 校验单个服务
+fn validate_service(service: &ServiceConfig) -> Result<(), String> {
+    let global: GlobalConfig =
+        serde_json::from_value(serde_json::json!({})).map_err(|e| e.to_string())?;
+    validate_config(&Config {
+        global,
+        services: vec![service.clone()],
+    })
+}
+
+/// 渲染服务时排在最前面的字段，其余字段按字母顺序输出
+const LEADING_KEYS: [&str; 5] = ["name", "url", "method", "description", "enabled"];
+
+/// 把服务渲染为 `[[services]]` 表，省略默认值
+fn render_service(service: &ServiceConfig) -> Result<String, String> {
+    let toml::Value::Table(mut table) =
+        toml::Value::try_from(service).map_err(|e| e.to_string())?
+    else {
+        return Err("服务配置无法转换为TOML表".to_string());
+    };
+    table.retain(|key, value| match (key, &*value) {
+        ("method", toml::Value::String(method)) => method != "GET",
+        ("enabled", toml::Value::Boolean(enabled)) => !enabled,
+        ("failure_threshold", toml::Value::Integer(threshold)) => *threshold != 1,
+        _ => true,
+    });
+    prune(&mut table);
+
+    let (sections, mut values): (Vec<_>, Vec<_>) =
+        table.into_iter().partition(|(_, value)| is_section(value));
+    values.sort_by_key(|(key, _)| {
+        LEADING_KEYS
+            .iter()
+            .position(|leading| leading == key)
+            .unwrap_or(LEADING_KEYS.len())
+    });
+
+    let mut output = String::from("[[services]]\n");
+    for (key, value) in values {
+        output.push_str(&format!("{key} = {value}\n"));
+    }
+    if !sections.is_empty() {
+        // 子表交给toml输出，只保留 [[services]] 之后的部分
+        let mut root = toml::Table::new();
+        root.insert(
+            "services".to_string(),
+            toml::Value::Array(vec![toml::Value::Table(sections.into_iter().collect())]),
+        );
+        let rendered = toml::to_string(&root).map_err(|e| e.to_string())?;
+        output.push_str(rendered.trim_start_matches("[[services]]\n"));
+    }
+    Ok(output)
+}
+
+/// 是否需要以 `[services.x]` 或 `[[services.x]]` 子表形式输出
+fn is_section(value: &toml::Value) -> bool {
+    match value {
+        toml::Value::Table(_) => true,
+        toml::Value::Array(items) => items.iter().all(toml::Value::is_table),
+        _ => false,
+    }
+}
+
+/// 去掉空数组、空表以及嵌套表中为 false 的开关
+fn prune(table: &mut toml::Table) {
+    for (_, value) in table.iter_mut() {
+        if let toml::Value::Table(nested) = value {
+            prune(nested);
+            nested.retain(|_, value| !matches!(value, toml::Value::Boolean(false)));
+        }
+        if let toml::Value::Array(items) = value {
+            for item in items {
+                if let toml::Value::Table(nested) = item {
+                    prune(nested);
+                }
+            }
+        }
+    }
+    table.retain(|_, value| match value {
+        toml::Value::Array(items) => !items.is_empty(),
+        toml::Value::Table(nested) => !nested.is_empty(),
+        _ => true,
+    });
+}
+
+/// 把任意文本转换为可用作服务名称的片段
+pub(crate) fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// 已注册的HTTP状态码，状态码范围只展开为其中的状态码
+const REGISTERED_STATUS_CODES: [u16; 62] = [
+    100, 101, 102, 103, 200, 201, 202, 203, 204, 205, 206, 207, 208, 226, 300, 301, 302, 303, 304,
+    305, 307, 308, 400, 401, 402, 403, 404, 405, 406, 407, 408, 409, 410, 411, 412, 413, 414, 415,
+    416, 417, 418, 421, 422, 423, 424, 425, 426, 428, 429, 431, 451, 500, 501, 502, 503, 504, 505,
+    506, 507, 508, 510, 511,
+];
+
+/// 把 "200-299"、"301" 形式的状态码展开
+///
+/// 范围只展开为其中已注册的状态码，避免生成上百个无意义的状态码；单个状态码原样保留
+pub(crate) fn expand_status_codes(ranges: &[String]) -> Result<Vec<u16>, String> {
+    let mut codes = Vec::new();
+    for range in ranges {
+        let range = range.trim();
+        let parse = |code: &str| {
+            code.trim()
+                .parse::<u16>()
+                .ok()
+                .filter(|code| (100..=599).contains(code))
+                .ok_or_else(|| format!("无效的状态码 {range}"))
+        };
+        let expanded: Vec<u16> = match range.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(format!("无效的状态码范围 {range}"));
+                }
+                REGISTERED_STATUS_CODES
+                    .into_iter()
+                    .filter(|code| (start..=end).contains(code))
+                    .collect()
+            }
+            None => vec![parse(range)?],
+        };
+        for code in expanded {
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+    }
+    Ok(codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_status_codes() {
+        assert_eq!(
+            expand_status_codes(&["200-202".to_string(), "299".to_string()]).unwrap(),
+            vec![200, 201, 202, 299]
+        );
+        assert_eq!(
+            expand_status_codes(&["200-299".to_string()]).unwrap(),
+            vec![200, 201, 202, 203, 204, 205, 206, 207, 208, 226]
+        );
+        assert!(expand_status_codes(&["299-200".to_string()]).is_err());
+        assert!(expand_status_codes(&["abc".to_string()]).is_err());
+        assert_eq!(
+            slug("https://example.com:8443/health"),
+            "https-example.com-8443-health"
+        );
+    }
+
+    #[test]
+    fn test_outcome_renames_duplicates_and_skips_invalid() {
+        let mut outcome = ImportOutcome::default();
+        let mut service = new_service("api", "https://example.com");
+        service.expected_status_codes = vec![200];
+        outcome.add("api", service.clone(), Notes::default());
+        outcome.add("api", service.clone(), Notes::default());
+        outcome.add(
+            "broken",
+            new_service("broken", "https://example.com"),
+            Notes::default(),
+        );
+
+        let names: Vec<&str> = outcome.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "api-2"]);
+        assert_eq!(outcome.skipped_count(), 1);
+
+        let toml = outcome.to_toml("测试").unwrap();
+        assert!(toml.contains("[[services]]\nname = \"api\"\nurl = \"https://example.com\"\n"));
+        assert!(toml.contains("# 未导入 name: 名称 api 重复，已重命名为 api-2"));
+        assert!(!toml.contains("enabled"));
+        assert!(!toml.contains("headers"));
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert!(validate_config(&config).is_ok());
+    }
+}
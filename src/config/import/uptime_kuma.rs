@@ -0,0 +1,524 @@
+//! Uptime Kuma 备份导入
+//!
+//! 读取 Uptime Kuma「设置 → 备份」导出的JSON文件中的 `monitorList`，按监控类型转换：
+//! `http` 转为HTTP检测，`keyword` 转为带 `body_contains` 的单步骤检测，`dns` 转为 `dns://`，
+//! `push` 转为 `heartbeat://`，`grpc-keyword` 转为 `grpc://`，`postgres`/`mysql`/`redis` 转为数据库检测。
+//! 其余类型（`port`、`ping`、`docker` 等）没有对应的检测方式，整个监控项会被跳过。
+
+use super::{expand_status_codes, new_service, slug, ImportOutcome, Notes};
+use crate::config::dns::{DnsCheckConfig, DnsRecordType};
+use crate::config::heartbeat::HeartbeatCheckConfig;
+use crate::config::steps::StepConfig;
+use crate::config::types::{AuthConfig, OAuth2ClientAuth, OAuth2Config, ServiceConfig, TlsConfig};
+use rand::distr::{Alphanumeric, SampleString};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+/// Uptime Kuma 默认的最大重定向次数
+const DEFAULT_MAX_REDIRECTS: u64 = 10;
+
+/// 转换 Uptime Kuma 备份
+///
+/// # 参数
+/// * `json` - 备份文件内容
+///
+/// # 返回
+/// * `Result<ImportOutcome, String>` - 转换结果，备份格式无效时返回错误
+pub fn from_uptime_kuma(json: &str) -> Result<ImportOutcome, String> {
+    let backup: Value =
+        serde_json::from_str(json).map_err(|e| format!("无法解析Uptime Kuma备份: {e}"))?;
+    let monitors = backup
+        .get("monitorList")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "备份中没有 monitorList".to_string())?;
+
+    // 分组监控项只用于界面展示，其名称作为子监控项的标签
+    let groups: HashMap<u64, &str> = monitors
+        .iter()
+        .filter(|monitor| str_field(monitor, "type") == Some("group"))
+        .filter_map(|monitor| Some((monitor.get("id")?.as_u64()?, str_field(monitor, "name")?)))
+        .collect();
+
+    let mut outcome = ImportOutcome::default();
+    for monitor in monitors {
+        let kind = str_field(monitor, "type").unwrap_or("http");
+        if kind == "group" {
+            continue;
+        }
+        let source = match (str_field(monitor, "name"), monitor.get("id")) {
+            (Some(name), _) => name.to_string(),
+            (None, Some(id)) => format!("monitor-{id}"),
+            (None, None) => "monitor".to_string(),
+        };
+
+        let mut notes = Notes::default();
+        let service = match kind {
+            "http" => convert_http(monitor, &source, None, &mut notes),
+            "keyword" => match str_field(monitor, "keyword") {
+                Some(keyword) => convert_http(monitor, &source, Some(keyword), &mut notes),
+                None => Err("关键字监控缺少 keyword".to_string()),
+            },
+            "dns" => convert_dns(monitor, &source, &mut notes),
+            "push" => convert_push(monitor, &source, &mut notes),
+            "grpc-keyword" => convert_grpc(monitor, &source, &mut notes),
+            "postgres" | "mysql" | "redis" => convert_database(monitor, &source, &mut notes),
+            other => Err(format!("不支持的监控类型 {other}")),
+        };
+
+        match service {
+            Ok(mut service) => {
+                apply_common(monitor, kind, &groups, &mut service, &mut notes);
+                outcome.add(&source, service, notes);
+            }
+            Err(reason) => outcome.skip(&source, "type", &reason),
+        }
+    }
+    Ok(outcome)
+}
+
+/// 转换所有监控类型共有的字段：启用状态、间隔、重试次数、描述和标签
+fn apply_common(
+    monitor: &Value,
+    kind: &str,
+    groups: &HashMap<u64, &str>,
+    service: &mut ServiceConfig,
+    notes: &mut Notes,
+) {
+    service.enabled = bool_field(monitor, "active").unwrap_or(true);
+    service.description = str_field(monitor, "description").map(str::to_string);
+
+    let interval = u64_field(monitor, "interval");
+    // 推送监控的间隔就是心跳周期
+    if kind != "push" {
+        service.check_interval_seconds = interval;
+        if let Some(retry_interval) = u64_field(monitor, "retryInterval") {
+            if Some(retry_interval) != interval {
+                service.failure_check_interval_seconds = Some(retry_interval);
+            }
+        }
+    }
+    if let Some(retries) = u64_field(monitor, "maxretries") {
+        service.failure_threshold = u32::try_from(retries + 1).unwrap_or(u32::MAX);
+    }
+    if let (Some(resend), Some(interval)) = (u64_field(monitor, "resendInterval"), interval) {
+        // Uptime Kuma 按检测次数重发告警，换算为冷却时间
+        service.alert_cooldown_secs = Some(resend * interval);
+    }
+
+    if let Some(tags) = monitor.get("tags").and_then(Value::as_array) {
+        for tag in tags {
+            let Some(name) = str_field(tag, "name") else {
+                continue;
+            };
+            service.tags.push(match str_field(tag, "value") {
+                Some(value) => format!("{name}:{value}"),
+                None => name.to_string(),
+            });
+        }
+    }
+    if let Some(group) = monitor
+        .get("parent")
+        .and_then(Value::as_u64)
+        .and_then(|parent| groups.get(&parent))
+    {
+        service.tags.push(group.to_string());
+    }
+
+    if bool_field(monitor, "upsideDown") == Some(true) {
+        notes.push("upsideDown", "不支持反转检测结果");
+    }
+    let has_notifications = monitor
+        .get("notificationIDList")
+        .and_then(Value::as_object)
+        .is_some_and(|list| list.values().any(|enabled| enabled == &Value::Bool(true)));
+    if has_notifications {
+        notes.push(
+            "notificationIDList",
+            "通知渠道需要在 [global] 或 feishu_webhook_url 中重新配置",
+        );
+    }
+}
+
+/// 转换HTTP和关键字监控
+fn convert_http(
+    monitor: &Value,
+    source: &str,
+    keyword: Option<&str>,
+    notes: &mut Notes,
+) -> Result<ServiceConfig, String> {
+    let url = str_field(monitor, "url").ok_or_else(|| "缺少 url".to_string())?;
+    let mut service = new_service(source, url);
+    service.method = str_field(monitor, "method")
+        .unwrap_or("GET")
+        .to_ascii_uppercase();
+
+    let accepted: Vec<String> = match monitor
+        .get("accepted_statuscodes")
+        .and_then(Value::as_array)
+    {
+        Some(codes) => codes
+            .iter()
+            .filter_map(|code| code.as_str().map(str::to_string))
+            .collect(),
+        None => vec!["200-299".to_string()],
+    };
+    service.expected_status_codes = expand_status_codes(&accepted)?;
+
+    if let Some(headers) = str_field(monitor, "headers") {
+        match serde_json::from_str::<serde_json::Map<String, Value>>(headers) {
+            Ok(headers) => {
+                service.headers = headers
+                    .into_iter()
+                    .map(|(key, value)| match value {
+                        Value::String(value) => (key, value),
+                        other => (key, other.to_string()),
+                    })
+                    .collect();
+            }
+            Err(_) => notes.push("headers", "请求头不是JSON对象"),
+        }
+    }
+
+    let body = match str_field(monitor, "body") {
+        Some(body) => {
+            let encoding = str_field(monitor, "httpBodyEncoding").unwrap_or("json");
+            match serde_json::from_str::<Value>(body) {
+                Ok(body) if encoding == "json" && !contains_null(&body) => Some(body),
+                Ok(_) if encoding == "json" => {
+                    notes.push("body", "请求体包含 null，TOML无法表示");
+                    None
+                }
+                _ => {
+                    notes.push("body", format!("只支持JSON请求体，当前编码为 {encoding}"));
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    if let Some(timeout) = u64_field(monitor, "timeout") {
+        service.request_timeout_seconds = Some(timeout);
+    }
+    if u64_field(monitor, "maxredirects").is_some_and(|max| max != DEFAULT_MAX_REDIRECTS) {
+        notes.push("maxredirects", "不支持限制重定向次数");
+    }
+    if monitor
+        .get("proxy_id")
+        .is_some_and(|proxy| !proxy.is_null())
+    {
+        notes.push("proxy_id", "不支持代理");
+    }
+    if bool_field(monitor, "expiryNotification") == Some(true) {
+        notes.push("expiryNotification", "不支持证书过期提醒");
+    }
+    if bool_field(monitor, "ignoreTls") == Some(true) && url.starts_with("https://") {
+        service.tls = Some(TlsConfig {
+            insecure_skip_verify: true,
+            ..Default::default()
+        });
+    }
+
+    match str_field(monitor, "authMethod") {
+        Some("basic") | None => {
+            if let Some(username) = str_field(monitor, "basic_auth_user") {
+                service.auth = Some(AuthConfig::Basic {
+                    username: username.to_string(),
+                    password: str_field(monitor, "basic_auth_pass").map(str::to_string),
+                });
+            }
+        }
+        Some("oauth2-cc") => {
+            service.auth = Some(AuthConfig::OAuth2(OAuth2Config {
+                token_url: str_field(monitor, "oauth_token_url")
+                    .unwrap_or_default()
+                    .to_string(),
+                client_id: str_field(monitor, "oauth_client_id")
+                    .unwrap_or_default()
+                    .to_string(),
+                client_secret: str_field(monitor, "oauth_client_secret")
+                    .unwrap_or_default()
+                    .to_string(),
+                scopes: str_field(monitor, "oauth_scopes")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect(),
+                audience: None,
+                client_auth: match str_field(monitor, "oauth_auth_method") {
+                    Some("client_secret_post") => OAuth2ClientAuth::Body,
+                    _ => OAuth2ClientAuth::Basic,
+                },
+                refresh_before_seconds: 60,
+            }));
+        }
+        Some("mtls") => notes.push(
+            "authMethod",
+            "客户端证书需要保存为文件后配置 tls.client_cert 和 tls.client_key",
+        ),
+        Some(other) => notes.push("authMethod", format!("不支持 {other} 认证")),
+    }
+    if service.auth.is_some() {
+        service
+            .headers
+            .retain(|key, _| !key.eq_ignore_ascii_case("authorization"));
+    }
+
+    match keyword {
+        Some(keyword) => {
+            if bool_field(monitor, "invertKeyword") == Some(true) {
+                notes.push("invertKeyword", "不支持关键字不存在时判定为正常");
+            }
+            service.steps = vec![StepConfig {
+                name: "keyword".to_string(),
+                url: url.to_string(),
+                method: service.method.clone(),
+                headers: HashMap::new(),
+                body,
+                expected_status_codes: Vec::new(),
+                body_contains: Some(keyword.to_string()),
+                extract: BTreeMap::new(),
+            }];
+        }
+        None => service.body = body,
+    }
+    Ok(service)
+}
+
+/// 转换DNS监控
+fn convert_dns(monitor: &Value, source: &str, notes: &mut Notes) -> Result<ServiceConfig, String> {
+    let hostname = str_field(monitor, "hostname").ok_or_else(|| "缺少 hostname".to_string())?;
+    let record_type = str_field(monitor, "dns_resolve_type").unwrap_or("A");
+    let record_type: DnsRecordType =
+        serde_json::from_value(Value::String(record_type.to_ascii_uppercase()))
+            .map_err(|_| format!("不支持 {record_type} 记录"))?;
+
+    let nameserver = match str_field(monitor, "dns_resolve_server") {
+        Some(server) => match server.parse::<IpAddr>() {
+            Ok(ip) => match u64_field(monitor, "port").filter(|port| *port != 53) {
+                Some(port) if ip.is_ipv6() => Some(format!("[{ip}]:{port}")),
+                Some(port) => Some(format!("{ip}:{port}")),
+                None => Some(ip.to_string()),
+            },
+            Err(_) => {
+                notes.push(
+                    "dns_resolve_server",
+                    format!("{server} 不是IP地址，使用系统DNS"),
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut service = new_service(source, &format!("dns://{hostname}"));
+    service.dns = Some(DnsCheckConfig {
+        record_type,
+        nameserver,
+        ..Default::default()
+    });
+    Ok(service)
+}
+
+/// 转换推送监控为心跳检测
+fn convert_push(monitor: &Value, source: &str, notes: &mut Notes) -> Result<ServiceConfig, String> {
+    let token = match str_field(monitor, "pushToken") {
+        Some(token)
+            if token.len() >= 16
+                && token
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            token.to_string()
+        }
+        _ => {
+            notes.push(
+                "pushToken",
+                "令牌不符合要求，已生成新令牌，需要同步修改任务中的上报地址",
+            );
+            Alphanumeric.sample_string(&mut rand::rng(), 32)
+        }
+    };
+
+    let mut service = new_service(source, &format!("heartbeat://{}", slug(source)));
+    service.heartbeat = Some(HeartbeatCheckConfig {
+        token,
+        period_seconds: u64_field(monitor, "interval").unwrap_or(60),
+        grace_seconds: 0,
+        max_duration_seconds: None,
+    });
+    Ok(service)
+}
+
+/// 转换gRPC监控为标准健康检查
+fn convert_grpc(monitor: &Value, source: &str, notes: &mut Notes) -> Result<ServiceConfig, String> {
+    let address = str_field(monitor, "grpcUrl").ok_or_else(|| "缺少 grpcUrl".to_string())?;
+    let address = address
+        .trim_start_matches("grpc://")
+        .trim_start_matches("grpcs://")
+        .trim_end_matches('/');
+    let scheme = if bool_field(monitor, "grpcEnableTls") == Some(true) {
+        "grpcs"
+    } else {
+        "grpc"
+    };
+    for field in ["grpcServiceName", "grpcMethod", "grpcBody", "keyword"] {
+        if str_field(monitor, field).is_some() {
+            notes.push(field, "只支持 grpc.health.v1 标准健康检查");
+        }
+    }
+    Ok(new_service(source, &format!("{scheme}://{address}")))
+}
+
+/// 转换数据库监控
+fn convert_database(
+    monitor: &Value,
+    source: &str,
+    notes: &mut Notes,
+) -> Result<ServiceConfig, String> {
+    let connection = str_field(monitor, "databaseConnectionString")
+        .ok_or_else(|| "缺少 databaseConnectionString".to_string())?;
+    let url = match connection.split_once('?') {
+        Some((url, _)) => {
+            notes.push("databaseConnectionString", "不支持连接参数");
+            url
+        }
+        None => connection,
+    };
+    if str_field(monitor, "databaseQuery").is_some() {
+        notes.push("databaseQuery", "不支持自定义查询");
+    }
+    Ok(new_service(source, url))
+}
+
+/// 读取非空字符串字段
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value
+        .get(key)?
+        .as_str()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+/// 读取正整数字段，小数向上取整
+fn u64_field(value: &Value, key: &str) -> Option<u64> {
+    let field = value.get(key)?;
+    field
+        .as_u64()
+        .or_else(|| field.as_f64().map(|number| number.ceil() as u64))
+        .or_else(|| field.as_str()?.trim().parse().ok())
+        .filter(|number| *number > 0)
+}
+
+/// 读取布尔字段，兼容 SQLite 导出的 0/1
+fn bool_field(value: &Value, key: &str) -> Option<bool> {
+    match value.get(key)? {
+        Value::Bool(flag) => Some(*flag),
+        Value::Number(number) => Some(number.as_f64() != Some(0.0)),
+        _ => None,
+    }
+}
+
+/// JSON中是否包含 null
+fn contains_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.iter().any(contains_null),
+        Value::Object(map) => map.values().any(contains_null),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::{validate_config, Config};
+
+    const BACKUP: &str = r#"{
+        "version": "1.23.16",
+        "notificationList": [],
+        "monitorList": [
+            {"id": 1, "name": "Production", "type": "group", "active": 1},
+            {
+                "id": 2, "name": "API", "type": "http", "url": "https://api.example.com/health",
+                "method": "post", "interval": 60, "retryInterval": 20, "maxretries": 2,
+                "resendInterval": 0, "timeout": 48, "active": 1, "upsideDown": false,
+                "maxredirects": 10, "accepted_statuscodes": ["200-201", "204"],
+                "ignoreTls": true, "headers": "{\"X-Env\": \"prod\"}", "body": "{\"ping\": true}",
+                "authMethod": "basic", "basic_auth_user": "monitor", "basic_auth_pass": "secret",
+                "description": "核心API", "parent": 1,
+                "tags": [{"name": "team", "value": "core"}, {"name": "critical", "value": ""}],
+                "notificationIDList": {"1": true}
+            },
+            {
+                "id": 3, "name": "API", "type": "keyword", "url": "http://api.example.com/",
+                "method": "GET", "interval": 30, "maxretries": 0, "keyword": "ok",
+                "invertKeyword": true, "active": false, "accepted_statuscodes": ["200-299"]
+            },
+            {"id": 4, "name": "DNS", "type": "dns", "hostname": "example.com",
+             "dns_resolve_type": "AAAA", "dns_resolve_server": "1.1.1.1", "port": 5353,
+             "interval": 60},
+            {"id": 5, "name": "Backup job", "type": "push", "pushToken": "short", "interval": 3600},
+            {"id": 6, "name": "Cache", "type": "redis",
+             "databaseConnectionString": "redis://:pass@cache:6379/0", "interval": 60},
+            {"id": 7, "name": "SSH", "type": "port", "hostname": "10.0.0.1", "port": 22},
+            {"id": 8, "name": "MX", "type": "dns", "hostname": "example.com", "dns_resolve_type": "MX"}
+        ]
+    }"#;
+
+    #[test]
+    fn test_convert_uptime_kuma_backup() {
+        let outcome = from_uptime_kuma(BACKUP).unwrap();
+        let names: Vec<&str> = outcome.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["API", "API-2", "DNS", "Backup job", "Cache"]);
+
+        let api = &outcome.services[0];
+        assert_eq!(api.method, "POST");
+        assert_eq!(api.expected_status_codes, vec![200, 201, 204]);
+        assert_eq!(api.failure_threshold, 3);
+        assert_eq!(api.check_interval_seconds, Some(60));
+        assert_eq!(api.failure_check_interval_seconds, Some(20));
+        assert_eq!(api.request_timeout_seconds, Some(48));
+        assert_eq!(api.headers.get("X-Env").map(String::as_str), Some("prod"));
+        assert_eq!(api.body, Some(serde_json::json!({"ping": true})));
+        assert!(api.tls.as_ref().unwrap().insecure_skip_verify);
+        assert!(matches!(api.auth, Some(AuthConfig::Basic { .. })));
+        assert_eq!(api.tags, vec!["team:core", "critical", "Production"]);
+
+        let keyword = &outcome.services[1];
+        assert!(!keyword.enabled);
+        assert_eq!(keyword.steps[0].body_contains.as_deref(), Some("ok"));
+
+        let dns = outcome.services[2].dns.as_ref().unwrap();
+        assert_eq!(dns.record_type, DnsRecordType::Aaaa);
+        assert_eq!(dns.nameserver.as_deref(), Some("1.1.1.1:5353"));
+
+        let heartbeat = outcome.services[3].heartbeat.as_ref().unwrap();
+        assert_eq!(outcome.services[3].url, "heartbeat://Backup-job");
+        assert_eq!(heartbeat.token.len(), 32);
+        assert_eq!(heartbeat.period_seconds, 3600);
+
+        let fields: Vec<(&str, &str)> = outcome
+            .issues
+            .iter()
+            .map(|issue| (issue.source.as_str(), issue.field.as_str()))
+            .collect();
+        assert!(fields.contains(&("API", "notificationIDList")));
+        assert!(fields.contains(&("API", "invertKeyword")));
+        assert!(fields.contains(&("API", "name")));
+        assert!(fields.contains(&("Backup job", "pushToken")));
+        assert_eq!(outcome.skipped_count(), 2);
+
+        let toml = outcome.to_toml("Uptime Kuma").unwrap();
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert!(validate_config(&config).is_ok());
+        assert_eq!(config.services, outcome.services);
+    }
+
+    #[test]
+    fn test_invalid_backup() {
+        assert!(from_uptime_kuma("{}").is_err());
+        assert!(from_uptime_kuma("not json").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! 配置文件包含
+//!
+//! 主配置文件通过顶层 `include = ["services.d/*.toml"]` 引入其他文件中的 `services`，
+//! 相对路径基于主配置文件所在目录，通配符只能出现在文件名中
+
+use crate::common::glob::{glob_match, is_glob};
+use crate::config::types::ServiceConfig;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// 主配置文件中声明包含文件的键
+pub const INCLUDE_KEY: &str = "include";
+
+/// 被包含的配置文件，只能包含服务列表
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IncludedFile {
+    /// 服务配置列表
+    #[serde(default)]
+    pub services: Vec<ServiceConfig>,
+}
+
+/// 一条包含规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludePattern {
+    /// 文件所在目录
+    pub dir: PathBuf,
+    /// 文件名（可包含 `*`、`?` 通配符）
+    pub file_pattern: String,
+}
+
+impl IncludePattern {
+    /// 解析包含规则
+    ///
+    /// # 参数
+    /// * `base_dir` - 相对路径的基准目录（主配置文件所在目录）
+    /// * `pattern` - 包含规则，如 `services.d/*.toml`
+    pub fn new(base_dir: &Path, pattern: &str) -> Result<Self, String> {
+        let path = base_dir.join(pattern);
+        let file_pattern = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| format!("包含规则缺少文件名: {pattern}"))?
+            .to_string();
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        if is_glob(&dir.to_string_lossy()) {
+            return Err(format!("包含规则只支持在文件名中使用通配符: {pattern}"));
+        }
+
+        Ok(Self { dir, file_pattern })
+    }
+
+    /// 是否使用通配符
+    pub fn is_glob(&self) -> bool {
+        is_glob(&self.file_pattern)
+    }
+
+    /// 判断文件是否被该规则包含
+    pub fn matches(&self, path: &Path) -> bool {
+        path.parent() == Some(self.dir.as_path())
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| glob_match(&self.file_pattern, name))
+    }
+
+    /// 列出该规则包含的文件，按文件名排序
+    ///
+    /// 不含通配符的规则要求文件存在；通配符规则匹配不到文件时返回空列表
+    pub fn resolve(&self) -> Result<Vec<PathBuf>, String> {
+        if !self.is_glob() {
+            let path = self.dir.join(&self.file_pattern);
+            return if path.is_file() {
+                Ok(vec![path])
+            } else {
+                Err(format!("包含的配置文件不存在: {}", path.display()))
+            };
+        }
+
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("读取目录 {} 失败: {e}", self.dir.display())),
+        };
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && self.matches(path))
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// 从主配置中读取包含规则
+///
+/// # 参数
+/// * `value` - 主配置文件解析后的内容
+/// * `base_dir` - 主配置文件所在目录
+pub fn include_patterns(
+    value: &serde_json::Value,
+    base_dir: &Path,
+) -> Result<Vec<IncludePattern>, String> {
+    let Some(include) = value.get(INCLUDE_KEY) else {
+        return Ok(Vec::new());
+    };
+
+    let patterns: Vec<&str> = match include {
+        serde_json::Value::String(pattern) => vec![pattern.as_str()],
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .ok_or_else(|| format!("{INCLUDE_KEY} 只能包含字符串: {item}"))
+            })
+            .collect::<Result<_, _>>()?,
+        other => return Err(format!("{INCLUDE_KEY} 必须是字符串数组: {other}")),
+    };
+
+    patterns
+        .into_iter()
+        .map(|pattern| IncludePattern::new(base_dir, pattern))
+        .collect()
+}
+
+/// 检查服务名称在所有配置文件中是否唯一
+///
+/// # 参数
+/// * `services` - 服务名称及其所在文件
+pub fn check_unique_service_names<'a>(
+    services: impl IntoIterator<Item = (&'a str, &'a Path)>,
+) -> Result<(), String> {
+    let mut seen: Vec<(&str, &Path)> = Vec::new();
+    for (name, file) in services {
+        if let Some((_, first)) = seen.iter().find(|(seen_name, _)| *seen_name == name) {
+            return Err(if *first == file {
+                format!("服务名称 '{name}' 在 {} 中重复定义", file.display())
+            } else {
+                format!(
+                    "服务名称 '{name}' 重复: 同时定义在 {} 和 {}",
+                    first.display(),
+                    file.display()
+                )
+            });
+        }
+        seen.push((name, file));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_include_pattern_resolution() {
+        let dir = tempfile::tempdir().unwrap();
+        let services_dir = dir.path().join("services.d");
+        std::fs::create_dir(&services_dir).unwrap();
+        for name in ["b.toml", "a.toml", "notes.md"] {
+            std::fs::write(services_dir.join(name), "").unwrap();
+        }
+
+        let value = json!({ "include": ["services.d/*.toml", "missing.d/*.toml"] });
+        let patterns = include_patterns(&value, dir.path()).unwrap();
+        assert_eq!(patterns.len(), 2);
+        assert_eq!(
+            patterns[0].resolve().unwrap(),
+            vec![services_dir.join("a.toml"), services_dir.join("b.toml")]
+        );
+        assert!(patterns[1].resolve().unwrap().is_empty());
+        assert!(patterns[0].matches(&services_dir.join("new.toml")));
+        assert!(!patterns[0].matches(&dir.path().join("new.toml")));
+
+        let literal = IncludePattern::new(dir.path(), "extra.toml").unwrap();
+        assert!(literal.resolve().unwrap_err().contains("extra.toml"));
+        assert!(IncludePattern::new(dir.path(), "*/services.toml").is_err());
+        assert!(include_patterns(&json!({ "include": 1 }), dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_check_unique_service_names() {
+        let main = Path::new("config.toml");
+        let extra = Path::new("services.d/api.toml");
+
+        assert!(check_unique_service_names([("api", main), ("web", extra)]).is_ok());
+
+        let error = check_unique_service_names([("api", main), ("api", extra)]).unwrap_err();
+        assert!(error.contains("config.toml") && error.contains("services.d/api.toml"));
+
+        let error = check_unique_service_names([("api", extra), ("api", extra)]).unwrap_err();
+        assert!(error.contains("重复定义"));
+    }
+}
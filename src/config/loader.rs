@@ -1,14 +1,19 @@
 //! 配置加载器实现
 //!
-//! 提供TOML配置文件解析、环境变量替换和错误处理功能
+//! 提供TOML、YAML和JSON配置文件解析、环境变量替换和错误处理功能
 
 use crate::common::error::{ConfigError, Result};
+use crate::config::include::{self, IncludePattern, IncludedFile};
+use crate::config::secret;
+use crate::config::template::ServiceTemplates;
 use crate::config::types::{validate_config, Config};
+use crate::config::yaml;
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 
-use regex::Regex;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 /// 配置加载器trait，定义配置加载接口
 #[async_trait]
@@ -41,18 +46,121 @@ pub trait ConfigLoader: Send + Sync {
     fn validate(&self, config: &Config) -> Result<()>;
 }
 
-/// TOML配置加载器实现
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    /// TOML
+    #[default]
+    Toml,
+    /// YAML
+    Yaml,
+    /// JSON
+    Json,
+}
+
+impl ConfigFormat {
+    /// 根据文件扩展名判断格式，无法识别时返回 `None`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "toml" => Some(ConfigFormat::Toml),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "json" => Some(ConfigFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// 该格式配置文件的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "toml",
+            ConfigFormat::Yaml => "yaml",
+            ConfigFormat::Json => "json",
+        }
+    }
+
+    /// 解析为通用的JSON值
+    fn parse_value(&self, content: &str) -> std::result::Result<serde_json::Value, String> {
+        match self {
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => yaml::parse(content),
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// 反序列化为指定类型
+    fn deserialize<T: DeserializeOwned>(&self, content: &str) -> std::result::Result<T, String> {
+        match self {
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => yaml::parse(content)
+                .and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string())),
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// 解析为通用的JSON值，失败时返回解析错误
+    fn parse_value_or_error(&self, content: &str) -> Result<serde_json::Value> {
+        self.parse_value(content)
+            .map_err(|e| ConfigError::ParseError(format!("{self}解析失败: {e}")).into())
+    }
+
+    /// 展开服务模板和矩阵后反序列化
+    ///
+    /// 没有服务需要展开且没有值被替换时直接解析原始内容，以保留原始格式的错误位置
+    ///
+    /// # 参数
+    /// * `content` - 原始配置内容，有值被替换时为 `None`
+    /// * `value` - 配置内容解析后的值
+    /// * `templates` - 服务模板
+    /// * `origin` - 被包含的文件路径，用于错误提示
+    fn deserialize_expanded<T: DeserializeOwned>(
+        &self,
+        content: Option<&str>,
+        mut value: serde_json::Value,
+        templates: &ServiceTemplates,
+        origin: Option<&Path>,
+    ) -> Result<T> {
+        let prefix = origin
+            .map(|path| format!("包含的配置文件 {}: ", path.display()))
+            .unwrap_or_default();
+        let expanded = templates
+            .expand_services(&mut value)
+            .map_err(|e| ConfigError::ValidationError(format!("{prefix}{e}")))?;
+        let parsed = match content {
+            Some(content) if !expanded => self.deserialize(content),
+            _ => serde_json::from_value(value).map_err(|e| e.to_string()),
+        };
+        parsed.map_err(|e| ConfigError::ParseError(format!("{prefix}{self}解析失败: {e}")).into())
+    }
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFormat::Toml => write!(f, "TOML"),
+            ConfigFormat::Yaml => write!(f, "YAML"),
+            ConfigFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+/// 文件配置加载器实现
+///
+/// 支持TOML、YAML和JSON格式：未指定格式时按文件扩展名选择，
+/// 扩展名无法识别或从字符串加载时按TOML解析
 #[derive(Debug, Clone)]
-pub struct TomlConfigLoader {
+pub struct FileConfigLoader {
     /// 是否启用环境变量替换
     enable_env_substitution: bool,
+    /// 指定的配置格式，覆盖扩展名判断
+    format: Option<ConfigFormat>,
     /// 环境变量缓存
     #[allow(dead_code)]
     env_cache: HashMap<String, String>,
 }
 
-impl TomlConfigLoader {
-    /// 创建新的TOML配置加载器
+impl FileConfigLoader {
+    /// 创建新的配置加载器
     ///
     /// # 参数
     /// * `enable_env_substitution` - 是否启用环境变量替换
@@ -62,69 +170,159 @@ impl TomlConfigLoader {
     pub fn new(enable_env_substitution: bool) -> Self {
         Self {
             enable_env_substitution,
+            format: None,
             env_cache: HashMap::new(),
         }
     }
 
-    /// 替换字符串中的环境变量
+    /// 指定配置格式，为 `None` 时按文件扩展名选择
+    pub fn with_format(mut self, format: Option<ConfigFormat>) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 文件使用的配置格式
+    pub fn format_for(&self, path: &Path) -> ConfigFormat {
+        self.format
+            .or_else(|| ConfigFormat::from_path(path))
+            .unwrap_or_default()
+    }
+
+    /// 替换配置字符串值中的环境变量和密钥文件引用
+    ///
+    /// 支持 `${VAR}`、`${VAR:-默认值}`、`${VAR:?错误信息}` 和 `${file:/路径}`，`$${...}` 保留为字面量。
+    /// 多步骤检测中提取的变量在检测时才替换，同名的引用不视为环境变量
     ///
     /// # 参数
-    /// * `content` - 要处理的字符串
+    /// * `value` - 解析后的配置
+    /// * `source` - 配置文件路径，相对路径的密钥文件基于其所在目录
     ///
     /// # 返回
-    /// * `Result<String>` - 替换后的字符串或错误
-    fn substitute_env_vars(&self, content: &str) -> Result<String> {
+    /// * `Result<bool>` - 是否有值被替换
+    fn substitute_env_vars(&self, value: &mut serde_json::Value, source: &Path) -> Result<bool> {
         if !self.enable_env_substitution {
-            return Ok(content.to_string());
+            return Ok(false);
         }
 
-        // 匹配 ${VAR_NAME} 格式的环境变量
-        let env_var_regex = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}")
-            .map_err(|e| ConfigError::ParseError(format!("正则表达式错误: {e}")))?;
-
-        let mut result = content.to_string();
-
-        for captures in env_var_regex.captures_iter(content) {
-            let full_match = &captures[0];
-            let var_name = &captures[1];
-
-            match std::env::var(var_name) {
-                Ok(value) => {
-                    result = result.replace(full_match, &value);
-                }
-                Err(_) => {
-                    return Err(ConfigError::EnvVarError {
-                        var: var_name.to_string(),
-                    }
-                    .into());
-                }
-            }
-        }
+        let step_variables = step_variable_names(value);
+        let base_dir = source.parent().unwrap_or(Path::new(""));
+        Ok(secret::substitute_value(value, base_dir, &step_variables)?)
+    }
 
-        Ok(result)
+    /// 被包含文件使用的配置格式：优先按扩展名判断，其次使用指定格式
+    fn include_format_for(&self, path: &Path) -> ConfigFormat {
+        ConfigFormat::from_path(path)
+            .or(self.format)
+            .unwrap_or_default()
     }
 
-    /// 解析TOML内容
+    /// 解析配置内容，并合并 `include` 引入的服务
     ///
     /// # 参数
-    /// * `content` - TOML内容
+    /// * `content` - 配置内容
+    /// * `format` - 配置格式
+    /// * `source` - 配置文件路径，用于定位相对路径的包含文件和错误提示
     ///
     /// # 返回
     /// * `Result<Config>` - 解析的配置或错误
-    fn parse_toml(&self, content: &str) -> Result<Config> {
+    fn parse(&self, content: &str, format: ConfigFormat, source: &Path) -> Result<Config> {
+        let mut value = format.parse_value_or_error(content)?;
         // 替换环境变量
-        let processed_content = self.substitute_env_vars(content)?;
+        let substituted = self.substitute_env_vars(&mut value, source)?;
+        let templates =
+            ServiceTemplates::from_config(&value).map_err(ConfigError::ValidationError)?;
+        let include_patterns = Self::include_patterns_of(&value, source)?;
+
+        let mut config: Config = format.deserialize_expanded(
+            (!substituted).then_some(content),
+            value,
+            &templates,
+            None,
+        )?;
+        let mut sources = vec![source.to_path_buf(); config.services.len()];
+
+        for pattern in include_patterns {
+            let files = pattern.resolve().map_err(ConfigError::ValidationError)?;
+            if files.is_empty() {
+                tracing::debug!(
+                    "包含规则未匹配到文件: {}",
+                    pattern.dir.join(&pattern.file_pattern).display()
+                );
+            }
+            for file in files {
+                let included = self.parse_included_file(&file, &templates)?;
+                sources.extend(std::iter::repeat_n(file, included.services.len()));
+                config.services.extend(included.services);
+            }
+        }
 
-        // 解析TOML
-        let config: Config = toml::from_str(&processed_content)
-            .map_err(|e| ConfigError::ParseError(format!("TOML解析失败: {e}")))?;
+        include::check_unique_service_names(
+            config
+                .services
+                .iter()
+                .map(|service| service.name.as_str())
+                .zip(sources.iter().map(PathBuf::as_path)),
+        )
+        .map_err(ConfigError::ValidationError)?;
 
         Ok(config)
     }
+
+    /// 读取配置内容中的包含规则
+    fn include_patterns_of(
+        value: &serde_json::Value,
+        source: &Path,
+    ) -> Result<Vec<IncludePattern>> {
+        let base_dir = source.parent().unwrap_or(Path::new(""));
+        include::include_patterns(value, base_dir)
+            .map_err(|e| ConfigError::ValidationError(e).into())
+    }
+
+    /// 解析被包含的配置文件，文件中的服务可以引用主配置文件定义的模板
+    fn parse_included_file(
+        &self,
+        path: &Path,
+        templates: &ServiceTemplates,
+    ) -> Result<IncludedFile> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ConfigError::ParseError(format!("读取包含的配置文件 {} 失败: {e}", path.display()))
+        })?;
+        let format = self.include_format_for(path);
+        let mut value = format.parse_value(&content).map_err(|e| {
+            ConfigError::ParseError(format!(
+                "包含的配置文件 {} {format}解析失败: {e}",
+                path.display()
+            ))
+        })?;
+        let substituted = self.substitute_env_vars(&mut value, path)?;
+
+        format.deserialize_expanded(
+            (!substituted).then_some(content.as_str()),
+            value,
+            templates,
+            Some(path),
+        )
+    }
+
+    /// 列出配置文件的包含规则，供热重载监控新增和变更的文件
+    ///
+    /// # 参数
+    /// * `path` - 主配置文件路径
+    ///
+    /// # 返回
+    /// * `Result<Vec<IncludePattern>>` - 包含规则列表
+    pub fn include_patterns(&self, path: &Path) -> Result<Vec<IncludePattern>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::ParseError(format!("读取文件失败: {e}")))?;
+        let format = self.format_for(path);
+        let mut value = format.parse_value_or_error(&content)?;
+        self.substitute_env_vars(&mut value, path)?;
+        Self::include_patterns_of(&value, path)
+    }
 }
 
 #[async_trait]
-impl ConfigLoader for TomlConfigLoader {
+impl ConfigLoader for FileConfigLoader {
     async fn load_from_file<P: AsRef<Path> + Send>(&self, path: P) -> Result<Config> {
         let path = path.as_ref();
 
@@ -142,20 +340,25 @@ impl ConfigLoader for TomlConfigLoader {
             .map_err(|e| ConfigError::ParseError(format!("读取文件失败: {e}")))?;
 
         // 解析配置
-        let config = self.parse_toml(&content)?;
+        let config = self.parse(&content, self.format_for(path), path)?;
 
         // 验证配置
         self.validate(&config)?;
 
         tracing::info!("成功加载配置文件: {}", path.display());
-        tracing::debug!("配置内容: {:?}", config);
+        tracing::debug!("配置内容: {}", secret::redacted(&config));
 
         Ok(config)
     }
 
     async fn load_from_string(&self, content: &str) -> Result<Config> {
         // 解析配置
-        let config = self.parse_toml(content)?;
+        // 包含规则的相对路径基于当前目录
+        let config = self.parse(
+            content,
+            self.format.unwrap_or_default(),
+            Path::new("<配置字符串>"),
+        )?;
 
         // 验证配置
         self.validate(&config)?;
@@ -170,27 +373,48 @@ impl ConfigLoader for TomlConfigLoader {
     }
 }
 
+/// 收集配置中多步骤检测提取的变量名
+fn step_variable_names(value: &serde_json::Value) -> HashSet<String> {
+    value
+        .get("services")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|service| service.get("steps")?.as_array())
+        .flatten()
+        .filter_map(|step| step.get("extract")?.as_object())
+        .flat_map(|extract| extract.keys().cloned())
+        .collect()
+}
+
+/// 默认配置文件名，按顺序查找
+const DEFAULT_CONFIG_FILES: [&str; 4] = ["config.toml", "config.yaml", "config.yml", "config.json"];
+
 /// 获取默认配置文件路径
 pub fn get_default_config_path() -> Result<std::path::PathBuf> {
-    // 先检测当前目录是否存在config.toml，不存在则检测~/.config/service-vitals/config.toml
-    let mut path = std::path::PathBuf::from("config.toml");
-    if path.exists() {
-        // 保证返回绝对路径
-        return std::env::current_dir()
-            .map(|dir| dir.join(path))
-            .map_err(|e| ConfigError::ParseError(format!("获取当前目录失败: {e}")))
-            .map_err(Into::into);
-    } else {
-        if let Some(config_dir) = dirs::config_dir() {
-            path = config_dir.join("service-vitals").join("config.toml");
-        }
+    // 先检测当前目录是否存在config.toml（或 .yaml/.yml/.json），不存在则检测~/.config/service-vitals/下的同名文件
+    for name in DEFAULT_CONFIG_FILES {
+        let path = std::path::PathBuf::from(name);
         if path.exists() {
             // 保证返回绝对路径
-            return Ok(path.canonicalize().unwrap_or(path));
+            return std::env::current_dir()
+                .map(|dir| dir.join(path))
+                .map_err(|e| ConfigError::ParseError(format!("获取当前目录失败: {e}")))
+                .map_err(Into::into);
+        }
+    }
+    if let Some(config_dir) = dirs::config_dir() {
+        for name in DEFAULT_CONFIG_FILES {
+            let path = config_dir.join("service-vitals").join(name);
+            if path.exists() {
+                // 保证返回绝对路径
+                return Ok(path.canonicalize().unwrap_or(path));
+            }
         }
     }
     Err(ConfigError::FileNotFound {
-        path: "./config.toml 或 ~/.config/service-vitals/config.toml".to_string(),
+        path: "./config.toml 或 ~/.config/service-vitals/config.toml（也支持 .yaml、.yml、.json）"
+            .to_string(),
     }
     .into())
 }
@@ -234,7 +458,7 @@ enabled = true
 
     #[tokio::test]
     async fn test_toml_parsing() {
-        let loader = TomlConfigLoader::new(false);
+        let loader = FileConfigLoader::new(false);
         let config = loader.load_from_string(TEST_CONFIG_TOML).await.unwrap();
 
         assert_eq!(config.global.check_interval_seconds, 30);
@@ -250,7 +474,7 @@ enabled = true
         env::set_var("WEBHOOK_URL", "https://test.webhook.url");
         env::set_var("API_TOKEN", "test-token-123");
 
-        let loader = TomlConfigLoader::new(true);
+        let loader = FileConfigLoader::new(true);
         let config = loader
             .load_from_string(TEST_CONFIG_WITH_ENV_VARS)
             .await
@@ -282,7 +506,7 @@ url = "https://example.com"
 expected_status_codes = [200]
 "#;
 
-        let loader = TomlConfigLoader::new(true);
+        let loader = FileConfigLoader::new(true);
         let result = loader.load_from_string(config_with_missing_var).await;
 
         assert!(result.is_err());
@@ -291,9 +515,47 @@ expected_status_codes = [200]
         }
     }
 
+    #[tokio::test]
+    async fn test_step_variables_not_substituted_from_env() {
+        env::set_var("STEP_TEST_PASSWORD", "secret");
+
+        let config_with_steps = r#"
+[global]
+
+[[services]]
+name = "Login flow"
+url = "https://example.com"
+expected_status_codes = [200]
+
+[[services.steps]]
+name = "login"
+url = "/login"
+method = "POST"
+body = { password = "${STEP_TEST_PASSWORD}" }
+extract = { token = { json = "$.token" } }
+
+[[services.steps]]
+name = "profile"
+url = "/me"
+headers = { Authorization = "Bearer ${token}" }
+"#;
+
+        let loader = FileConfigLoader::new(true);
+        let config = loader.load_from_string(config_with_steps).await.unwrap();
+        let steps = &config.services[0].steps;
+
+        assert_eq!(
+            steps[0].body,
+            Some(serde_json::json!({ "password": "secret" }))
+        );
+        assert_eq!(steps[1].headers["Authorization"], "Bearer ${token}");
+
+        env::remove_var("STEP_TEST_PASSWORD");
+    }
+
     #[tokio::test]
     async fn test_load_sample_config() {
-        let loader = TomlConfigLoader::new(false);
+        let loader = FileConfigLoader::new(false);
 
         // 测试加载示例配置文件
         let config_path = "examples/minimal_config.toml";
@@ -307,12 +569,303 @@ expected_status_codes = [200]
         }
     }
 
+    #[tokio::test]
+    async fn test_example_templates_match_across_formats() {
+        let loader = FileConfigLoader::new(false);
+
+        for template in ["minimal", "basic", "full"] {
+            let toml_config = loader
+                .load_from_file(format!("examples/{template}_config.toml"))
+                .await
+                .unwrap();
+            let expected = serde_json::to_value(&toml_config).unwrap();
+
+            for extension in ["yaml", "json"] {
+                let config = loader
+                    .load_from_file(format!("examples/{template}_config.{extension}"))
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    serde_json::to_value(&config).unwrap(),
+                    expected,
+                    "{template}_config.{extension} 与TOML模板不一致"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_yaml_env_var_substitution_with_format_override() {
+        env::set_var("YAML_TEST_API_TOKEN", "yaml-token");
+
+        let config_yaml = r#"
+global:
+  check_interval_seconds: 30
+services:
+  - name: "Login flow"
+    url: "https://example.com"
+    expected_status_codes: [200]
+    headers:
+      Authorization: "Bearer ${YAML_TEST_API_TOKEN}"
+    steps:
+      - name: login
+        url: /login
+        extract: { token: { json: "$.token" } }
+      - name: profile
+        url: /me
+        headers: { Authorization: "Bearer ${token}" }
+"#;
+
+        let loader = FileConfigLoader::new(true).with_format(Some(ConfigFormat::Yaml));
+        let config = loader.load_from_string(config_yaml).await.unwrap();
+
+        assert_eq!(config.global.check_interval_seconds, 30);
+        assert_eq!(
+            config.services[0].headers["Authorization"],
+            "Bearer yaml-token"
+        );
+        assert_eq!(
+            config.services[0].steps[1].headers["Authorization"],
+            "Bearer ${token}"
+        );
+
+        env::remove_var("YAML_TEST_API_TOKEN");
+    }
+
+    #[tokio::test]
+    async fn test_include_merges_services_from_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let services_dir = dir.path().join("services.d");
+        std::fs::create_dir(&services_dir).unwrap();
+
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+include = ["services.d/*"]
+
+[global]
+
+[[services]]
+name = "main"
+url = "https://example.com"
+expected_status_codes = [200]
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            services_dir.join("b.yaml"),
+            "services:\n  - name: yaml\n    url: https://example.com/b\n    expected_status_codes: [200]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            services_dir.join("a.toml"),
+            "[[services]]\nname = \"toml\"\nurl = \"https://example.com/a\"\nexpected_status_codes = [200]\n",
+        )
+        .unwrap();
+
+        let loader = FileConfigLoader::new(false);
+        let config = loader.load_from_file(&config_path).await.unwrap();
+        let names: Vec<&str> = config.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["main", "toml", "yaml"]);
+        assert_eq!(loader.include_patterns(&config_path).unwrap().len(), 1);
+
+        // 跨文件的重复服务名称
+        std::fs::write(
+            services_dir.join("c.json"),
+            r#"{"services": [{"name": "main", "url": "https://example.com/c", "expected_status_codes": [200]}]}"#,
+        )
+        .unwrap();
+        let error = loader.load_from_file(&config_path).await.unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("'main'"), "{message}");
+        assert!(message.contains("c.json"), "{message}");
+
+        // 被包含的文件不能包含全局配置
+        std::fs::write(services_dir.join("c.json"), r#"{"global": {}}"#).unwrap();
+        let error = loader.load_from_file(&config_path).await.unwrap_err();
+        assert!(error.to_string().contains("c.json"));
+    }
+
+    #[tokio::test]
+    async fn test_templates_and_matrix_expand_across_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+include = ["team.yaml"]
+
+[global]
+
+[templates.internal]
+method = "HEAD"
+expected_status_codes = [204]
+failure_threshold = 3
+headers = { "X-Probe" = "service-vitals" }
+
+[[services]]
+name = "orders"
+url = "https://orders.internal/health"
+template = "internal"
+failure_threshold = 1
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("team.yaml"),
+            r#"
+services:
+  - name: "billing-${matrix.env}"
+    url: "https://billing.${matrix.env}.internal/health"
+    template: internal
+    matrix:
+      env: [staging, prod]
+"#,
+        )
+        .unwrap();
+
+        let loader = FileConfigLoader::new(false);
+        let config = loader.load_from_file(&config_path).await.unwrap();
+        let names: Vec<&str> = config.services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["orders", "billing-staging", "billing-prod"]);
+
+        let orders = &config.services[0];
+        assert_eq!(orders.method, "HEAD");
+        assert_eq!(orders.failure_threshold, 1);
+        assert_eq!(orders.headers["X-Probe"], "service-vitals");
+
+        let billing = &config.services[2];
+        assert_eq!(billing.url, "https://billing.prod.internal/health");
+        assert_eq!(billing.expected_status_codes, vec![204]);
+        assert_eq!(billing.failure_threshold, 3);
+    }
+
+    #[test]
+    fn test_config_format_selection() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.yml")),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("/etc/sv/config.JSON")),
+            Some(ConfigFormat::Json)
+        );
+        assert_eq!(ConfigFormat::from_path(Path::new("config.conf")), None);
+
+        let loader = FileConfigLoader::new(false);
+        assert_eq!(
+            loader.format_for(Path::new("config.conf")),
+            ConfigFormat::Toml
+        );
+        let loader = loader.with_format(Some(ConfigFormat::Json));
+        assert_eq!(
+            loader.format_for(Path::new("config.yaml")),
+            ConfigFormat::Json
+        );
+    }
+
+    #[tokio::test]
+    async fn test_secret_file_and_default_substitution() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("secrets")).unwrap();
+        std::fs::write(dir.path().join("secrets/api_token"), "file-token\n").unwrap();
+        env::remove_var("LOADER_TEST_UNSET_HOST");
+
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[global]
+
+[[services]]
+name = "api"
+url = "https://${LOADER_TEST_UNSET_HOST:-api.internal}/health"
+expected_status_codes = [200]
+headers = { Authorization = "Bearer ${file:secrets/api_token}", X-Template = "$${literal}" }
+"#,
+        )
+        .unwrap();
+
+        let config = FileConfigLoader::new(true)
+            .load_from_file(&path)
+            .await
+            .unwrap();
+        let service = &config.services[0];
+        assert_eq!(service.url, "https://api.internal/health");
+        assert_eq!(service.headers["Authorization"], "Bearer file-token");
+        assert_eq!(service.headers["X-Template"], "${literal}");
+
+        std::fs::write(
+            &path,
+            "[global]\n[[services]]\nname = \"api\"\nurl = \"${LOADER_TEST_UNSET_HOST:?请设置API地址}\"\n",
+        )
+        .unwrap();
+        let error = FileConfigLoader::new(true)
+            .load_from_file(&path)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("请设置API地址"));
+    }
+
+    #[tokio::test]
+    async fn test_secret_with_quote_and_newline_is_kept_verbatim() {
+        let secret = "p\"a\nb\"\n[[services]]\nname = \"injected\"";
+        env::set_var("LOADER_TEST_QUOTED_SECRET", secret);
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("token"), format!("{secret}\n")).unwrap();
+
+        let toml_path = dir.path().join("config.toml");
+        std::fs::write(
+            &toml_path,
+            r#"
+[global]
+
+[[services]]
+name = "api"
+url = "https://api.internal/health"
+expected_status_codes = [200]
+headers = { X-Env = "${LOADER_TEST_QUOTED_SECRET}", X-File = "${file:token}" }
+"#,
+        )
+        .unwrap();
+        let yaml_path = dir.path().join("config.yaml");
+        std::fs::write(
+            &yaml_path,
+            r#"
+global: {}
+services:
+  - name: api
+    url: https://api.internal/health
+    expected_status_codes: [200]
+    headers:
+      X-Env: "${LOADER_TEST_QUOTED_SECRET}"
+      X-File: ${file:token}
+"#,
+        )
+        .unwrap();
+
+        for path in [toml_path, yaml_path] {
+            let config = FileConfigLoader::new(true)
+                .load_from_file(&path)
+                .await
+                .unwrap();
+            assert_eq!(config.services.len(), 1, "{}", path.display());
+            let service = &config.services[0];
+            assert_eq!(service.headers["X-Env"], secret);
+            assert_eq!(service.headers["X-File"], secret);
+        }
+    }
+
     #[test]
     fn test_substitute_env_vars_disabled() {
-        let loader = TomlConfigLoader::new(false);
-        let content = "test ${VAR} content";
-        let result = loader.substitute_env_vars(content).unwrap();
-        assert_eq!(result, content);
+        let loader = FileConfigLoader::new(false);
+        let mut value = serde_json::json!({ "url": "test ${VAR} content" });
+        let substituted = loader
+            .substitute_env_vars(&mut value, Path::new("config.toml"))
+            .unwrap();
+        assert!(!substituted);
+        assert_eq!(value["url"], "test ${VAR} content");
     }
 
     #[test]
@@ -2,10 +2,14 @@
 //!
 //! 提供线程安全的配置管理和热重载功能
 
+use crate::config::loader::ConfigFormat;
+use crate::config::secret;
 use crate::config::types::{Config, ServiceConfig};
 use crate::config::watcher::{ConfigChangeEvent, ConfigWatcher};
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -13,7 +17,9 @@ use tokio::sync::{broadcast, RwLock};
 use tracing::{debug, error, info, warn};
 
 /// 配置差异类型
-#[derive(Debug, Clone)]
+///
+/// `Debug` 和 `Display` 输出中的密码、令牌等敏感值会被隐藏
+#[derive(Clone)]
 pub enum ConfigDiff {
     /// 服务添加
     ServiceAdded(Box<ServiceConfig>),
@@ -28,6 +34,82 @@ pub enum ConfigDiff {
     GlobalConfigModified,
 }
 
+impl ConfigDiff {
+    /// 服务修改中变更的字段及新旧值，敏感值已隐藏
+    ///
+    /// # 返回
+    /// * `Vec<(String, Value, Value)>` - 字段名、旧值和新值，按字段名排序
+    pub fn changed_fields(&self) -> Vec<(String, Value, Value)> {
+        let ConfigDiff::ServiceModified { old, new } = self else {
+            return Vec::new();
+        };
+        let (Ok(Value::Object(old_fields)), Ok(Value::Object(new_fields))) =
+            (serde_json::to_value(old), serde_json::to_value(new))
+        else {
+            return Vec::new();
+        };
+        let (Value::Object(old_redacted), Value::Object(new_redacted)) =
+            (secret::redacted(old), secret::redacted(new))
+        else {
+            return Vec::new();
+        };
+
+        let keys: BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+        keys.into_iter()
+            .filter(|key| old_fields.get(*key) != new_fields.get(*key))
+            .map(|key| {
+                (
+                    key.clone(),
+                    old_redacted.get(key).cloned().unwrap_or(Value::Null),
+                    new_redacted.get(key).cloned().unwrap_or(Value::Null),
+                )
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigDiff::ServiceAdded(service) => f
+                .debug_tuple("ServiceAdded")
+                .field(&secret::redacted(service))
+                .finish(),
+            ConfigDiff::ServiceRemoved(name) => {
+                f.debug_tuple("ServiceRemoved").field(name).finish()
+            }
+            ConfigDiff::ServiceModified { old, new } => f
+                .debug_struct("ServiceModified")
+                .field("old", &secret::redacted(old))
+                .field("new", &secret::redacted(new))
+                .finish(),
+            ConfigDiff::GlobalConfigModified => f.write_str("GlobalConfigModified"),
+        }
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigDiff::ServiceAdded(service) => write!(f, "添加服务: {}", service.name),
+            ConfigDiff::ServiceRemoved(name) => write!(f, "移除服务: {name}"),
+            ConfigDiff::ServiceModified { new, .. } => {
+                write!(f, "修改服务: {}", new.name)?;
+                let changes: Vec<String> = self
+                    .changed_fields()
+                    .iter()
+                    .map(|(key, old, new)| format!("{key}: {old} -> {new}"))
+                    .collect();
+                if !changes.is_empty() {
+                    write!(f, " ({})", changes.join(", "))?;
+                }
+                Ok(())
+            }
+            ConfigDiff::GlobalConfigModified => f.write_str("全局配置已修改"),
+        }
+    }
+}
+
 /// 配置变更通知
 #[derive(Debug, Clone)]
 pub struct ConfigUpdateNotification {
@@ -84,6 +166,7 @@ impl ConfigManager {
     ///
     /// # 参数
     /// * `config_path` - 配置文件路径
+    /// * `format` - 显式指定的配置格式（`--config-format`），`None` 时按扩展名判断
     /// * `debounce_delay` - 防抖动延迟
     ///
     /// # 返回
@@ -91,12 +174,14 @@ impl ConfigManager {
     pub async fn enable_hot_reload<P: AsRef<Path>>(
         &mut self,
         config_path: P,
+        format: Option<ConfigFormat>,
         debounce_delay: Duration,
     ) -> Result<()> {
         info!("启用配置热重载功能");
 
         let (mut watcher, change_receiver) =
-            ConfigWatcher::new(config_path, debounce_delay).context("创建配置监控器失败")?;
+            ConfigWatcher::new(config_path, format, debounce_delay)
+                .context("创建配置监控器失败")?;
 
         watcher.start().context("启动配置监控失败")?;
 
@@ -154,6 +239,7 @@ impl ConfigManager {
             debug!("配置无实质性变更，跳过更新");
             return Ok(());
         }
+        debug!("配置差异: {:?}", diffs);
 
         // 检查是否需要重启服务
         let requires_restart = Self::requires_service_restart(&diffs);
@@ -338,7 +424,7 @@ impl ConfigManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::types::GlobalConfig;
+    use crate::config::types::{GlobalConfig, RetryBackoff};
     use std::collections::HashMap;
 
     fn create_test_config() -> Config {
@@ -352,7 +438,10 @@ mod tests {
                 max_concurrent_checks: 50,
                 retry_attempts: 3,
                 retry_delay_seconds: 5,
+                retry_backoff: RetryBackoff::Fixed,
                 headers: HashMap::new(),
+                jitter_seconds: None,
+                startup_spread_seconds: None,
                 web: None,
             },
             services: vec![],
@@ -382,4 +471,37 @@ mod tests {
         let updated_config = manager.get_config().await;
         assert_eq!(updated_config.global.log_level, "debug");
     }
+
+    #[test]
+    fn test_config_diff_redacts_secrets() {
+        let old: ServiceConfig = serde_json::from_value(serde_json::json!({
+            "name": "api",
+            "url": "https://example.com/health",
+            "headers": { "Authorization": "Bearer old-token" }
+        }))
+        .unwrap();
+        let mut new = old.clone();
+        new.url = "https://example.com/ready".to_string();
+        new.headers
+            .insert("Authorization".to_string(), "Bearer new-token".to_string());
+
+        let diff = ConfigDiff::ServiceModified {
+            old: Box::new(old),
+            new: Box::new(new),
+        };
+        let changes = diff.changed_fields();
+        assert_eq!(
+            changes
+                .iter()
+                .map(|(key, _, _)| key.as_str())
+                .collect::<Vec<_>>(),
+            ["headers", "url"]
+        );
+
+        for output in [diff.to_string(), format!("{diff:?}")] {
+            assert!(!output.contains("old-token") && !output.contains("new-token"));
+            assert!(output.contains(secret::REDACTED));
+        }
+        assert!(diff.to_string().contains("https://example.com/ready"));
+    }
 }
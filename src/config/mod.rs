@@ -2,13 +2,28 @@
 //!
 //! 提供配置文件解析、验证和热重载功能
 
+pub mod database;
+pub mod dns;
+pub mod effective;
+pub mod exec;
+pub mod grpc;
+pub mod heartbeat;
+pub mod host;
+pub mod import;
+pub mod include;
 pub mod loader;
 pub mod manager;
+pub mod secret;
+pub mod steps;
+pub mod template;
 pub mod types;
 pub mod watcher;
+pub mod websocket;
+pub mod yaml;
 
 // 重新导出主要类型
-pub use loader::{ConfigLoader, TomlConfigLoader};
+pub use effective::{EffectiveServiceConfig, RequestDefaults};
+pub use loader::{ConfigFormat, ConfigLoader, FileConfigLoader};
 pub use manager::{ConfigDiff, ConfigManager, ConfigUpdateNotification};
-pub use types::{validate_config, Config, GlobalConfig, ServiceConfig};
+pub use types::{validate_config, CheckProtocol, Config, GlobalConfig, ServiceConfig};
 pub use watcher::{ConfigChangeEvent, ConfigWatcher};
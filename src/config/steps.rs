@@ -0,0 +1,448 @@
+//! 多步骤检测配置
+//!
+//! 服务配置 `steps` 后，检测时按顺序执行每个请求步骤，整个流程计为一次检测结果。
+//! 每个步骤可以从响应中提取变量（JSON路径、响应头或正则表达式），
+//! 后续步骤的URL、请求头和请求体中以 `${变量名}` 引用。
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// 多步骤检测中的单个请求步骤
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepConfig {
+    /// 步骤名称
+    pub name: String,
+    /// 请求URL，以 `/` 开头时相对于服务URL
+    pub url: String,
+    /// HTTP方法
+    #[serde(default = "default_step_method")]
+    pub method: String,
+    /// 步骤请求头，覆盖服务和全局的同名请求头
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 请求体（JSON）
+    pub body: Option<serde_json::Value>,
+    /// 期望的状态码，未配置时使用服务的 `expected_status_codes`
+    #[serde(default)]
+    pub expected_status_codes: Vec<u16>,
+    /// 响应体必须包含的文本
+    pub body_contains: Option<String>,
+    /// 从响应中提取的变量，键为变量名
+    #[serde(default)]
+    pub extract: BTreeMap<String, Extractor>,
+}
+
+fn default_step_method() -> String {
+    "GET".to_string()
+}
+
+/// 变量提取方式
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Extractor {
+    /// 按JSON路径从响应体中提取，例如 `$.data.token`
+    Json(String),
+    /// 提取响应头的值
+    Header(String),
+    /// 按正则表达式从响应体中提取，有捕获组时取第一个捕获组
+    Regex(String),
+}
+
+impl Extractor {
+    /// 校验提取表达式
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Extractor::Json(path) => JsonPath::from_str(path).map(|_| ()),
+            Extractor::Header(name) => {
+                if name.trim().is_empty() {
+                    Err("响应头名称不能为空".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            Extractor::Regex(pattern) => Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|e| format!("无效的正则表达式 {pattern}: {e}")),
+        }
+    }
+}
+
+impl fmt::Display for Extractor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Extractor::Json(path) => write!(f, "JSON路径 {path}"),
+            Extractor::Header(name) => write!(f, "响应头 {name}"),
+            Extractor::Regex(pattern) => write!(f, "正则表达式 {pattern}"),
+        }
+    }
+}
+
+impl StepConfig {
+    /// 校验步骤本身的配置（不含变量引用）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("步骤名称不能为空".to_string());
+        }
+
+        if !self.url.starts_with("http://")
+            && !self.url.starts_with("https://")
+            && !self.url.starts_with('/')
+            && !self.url.starts_with("${")
+        {
+            return Err(format!("步骤 {} 的URL格式无效", self.name));
+        }
+
+        let valid_methods = ["GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS", "PATCH"];
+        if !valid_methods.contains(&self.method.as_str()) {
+            return Err(format!(
+                "步骤 {} 的HTTP方法 {} 无效，支持的方法: {:?}",
+                self.name, self.method, valid_methods
+            ));
+        }
+
+        for &code in &self.expected_status_codes {
+            if !(100..=599).contains(&code) {
+                return Err(format!("步骤 {} 的状态码 {} 无效", self.name, code));
+            }
+        }
+
+        for (variable, extractor) in &self.extract {
+            if !is_variable_name(variable) {
+                return Err(format!("步骤 {} 的变量名 {} 无效", self.name, variable));
+            }
+            extractor.validate().map_err(|e| {
+                format!("步骤 {} 的变量 {} 提取方式无效: {}", self.name, variable, e)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// 步骤URL、请求头和请求体中引用的变量
+    pub fn referenced_variables(&self) -> BTreeSet<String> {
+        let mut variables = BTreeSet::new();
+        let mut collect = |text: &str| {
+            variables.extend(variable_references(text).map(str::to_string));
+        };
+
+        collect(&self.url);
+        for (key, value) in &self.headers {
+            collect(key);
+            collect(value);
+        }
+        if let Some(body) = &self.body {
+            visit_json_strings(body, &mut collect);
+        }
+
+        variables
+    }
+}
+
+/// 校验服务的所有步骤
+///
+/// 步骤名称不能重复，引用的变量必须由前面的步骤提取
+pub fn validate_steps(steps: &[StepConfig]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    let mut defined = HashSet::new();
+
+    for step in steps {
+        step.validate()?;
+        if !names.insert(step.name.as_str()) {
+            return Err(format!("步骤名称 {} 重复", step.name));
+        }
+
+        if let Some(undefined) = step
+            .referenced_variables()
+            .into_iter()
+            .find(|variable| !defined.contains(variable.as_str()))
+        {
+            return Err(format!(
+                "步骤 {} 引用的变量 {} 未由前面的步骤提取",
+                step.name, undefined
+            ));
+        }
+
+        defined.extend(step.extract.keys().map(String::as_str));
+    }
+
+    Ok(())
+}
+
+fn variable_regex() -> &'static Regex {
+    static VARIABLE_REGEX: OnceLock<Regex> = OnceLock::new();
+    VARIABLE_REGEX
+        .get_or_init(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("变量正则表达式无效"))
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 文本中以 `${变量名}` 引用的变量
+pub fn variable_references(text: &str) -> impl Iterator<Item = &str> {
+    variable_regex()
+        .captures_iter(text)
+        .filter_map(|captures| captures.get(1))
+        .map(|name| name.as_str())
+}
+
+/// 替换文本中已定义的变量，未定义的引用保持原样
+pub fn substitute_variables(text: &str, variables: &HashMap<String, String>) -> String {
+    variable_regex()
+        .replace_all(text, |captures: &regex::Captures| {
+            variables
+                .get(&captures[1])
+                .cloned()
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+/// 替换JSON值中所有字符串里的变量
+pub fn substitute_json_variables(
+    value: &serde_json::Value,
+    variables: &HashMap<String, String>,
+) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::String(text) => Value::String(substitute_variables(text, variables)),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| substitute_json_variables(item, variables))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| {
+                    (
+                        substitute_variables(key, variables),
+                        substitute_json_variables(item, variables),
+                    )
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn visit_json_strings(value: &serde_json::Value, visit: &mut impl FnMut(&str)) {
+    use serde_json::Value;
+
+    match value {
+        Value::String(text) => visit(text),
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| visit_json_strings(item, visit)),
+        Value::Object(map) => {
+            for (key, item) in map {
+                visit(key);
+                visit_json_strings(item, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// JSON路径中的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// 简单的JSON路径
+///
+/// 支持 `$.a.b`、`$.items[0].id` 和 `$['key with.dot']`，开头的 `$` 可以省略
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    segments: Vec<JsonPathSegment>,
+}
+
+impl JsonPath {
+    /// 按路径选取JSON值
+    pub fn select<'a>(&self, value: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                JsonPathSegment::Key(key) => current.get(key),
+                JsonPathSegment::Index(index) => current.get(index),
+            })
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = String;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| format!("无效的JSON路径 {path}: {reason}");
+        let rest = path.trim();
+        let rest = rest.strip_prefix('$').unwrap_or(rest);
+        // 省略 `$` 时允许直接以键名开头
+        let mut rest = if rest.starts_with(['.', '[']) || rest.is_empty() {
+            rest.to_string()
+        } else {
+            format!(".{rest}")
+        };
+
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after_dot) = rest.strip_prefix('.') {
+                let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+                let key = &after_dot[..end];
+                if key.is_empty() {
+                    return Err(invalid("键名不能为空"));
+                }
+                segments.push(JsonPathSegment::Key(key.to_string()));
+                rest = after_dot[end..].to_string();
+            } else if let Some(after_bracket) = rest.strip_prefix('[') {
+                let end = after_bracket.find(']').ok_or_else(|| invalid("缺少 ]"))?;
+                let inner = after_bracket[..end].trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                let segment = match quoted {
+                    Some(key) => JsonPathSegment::Key(key.to_string()),
+                    None => JsonPathSegment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| invalid("下标必须是非负整数或带引号的键名"))?,
+                    ),
+                };
+                segments.push(segment);
+                rest = after_bracket[end + 1..].to_string();
+            } else {
+                return Err(invalid("路径各段必须以 . 或 [ 开头"));
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(invalid("路径不能为空"));
+        }
+        Ok(Self { segments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn step(toml_str: &str) -> StepConfig {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    #[test]
+    fn test_json_path() {
+        let value = json!({
+            "data": {"token": "abc", "items": [{"id": 7}, {"id": 8}]},
+            "key.with.dot": true
+        });
+
+        let select = |path: &str| JsonPath::from_str(path).unwrap().select(&value).cloned();
+        assert_eq!(select("$.data.token"), Some(json!("abc")));
+        assert_eq!(select("data.items[1].id"), Some(json!(8)));
+        assert_eq!(select("$['key.with.dot']"), Some(json!(true)));
+        assert_eq!(select("$.data.missing"), None);
+        assert_eq!(select("$.data.items[5]"), None);
+
+        assert!(JsonPath::from_str("$").is_err());
+        assert!(JsonPath::from_str("$.a..b").is_err());
+        assert!(JsonPath::from_str("$.items[x]").is_err());
+        assert!(JsonPath::from_str("$.items[0").is_err());
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let variables = HashMap::from([("token".to_string(), "abc".to_string())]);
+
+        assert_eq!(
+            substitute_variables("Bearer ${token} ${other}", &variables),
+            "Bearer abc ${other}"
+        );
+        assert_eq!(
+            substitute_json_variables(&json!({"auth": ["${token}", 1]}), &variables),
+            json!({"auth": ["abc", 1]})
+        );
+    }
+
+    #[test]
+    fn test_step_deserialization() {
+        let step = step(
+            r#"
+            name = "login"
+            url = "/login"
+            method = "POST"
+            body = { user = "${user_id}" }
+            [extract]
+            token = { json = "$.access_token" }
+            session = { header = "x-session" }
+            csrf = { regex = "csrf=([a-z0-9]+)" }
+            "#,
+        );
+
+        assert_eq!(step.method, "POST");
+        assert_eq!(
+            step.extract["token"],
+            Extractor::Json("$.access_token".to_string())
+        );
+        assert_eq!(
+            step.extract["session"],
+            Extractor::Header("x-session".to_string())
+        );
+        assert_eq!(
+            step.referenced_variables().into_iter().collect::<Vec<_>>(),
+            vec!["user_id"]
+        );
+        assert!(step.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_steps() {
+        let login = step(
+            r#"
+            name = "login"
+            url = "https://api.example.com/login"
+            [extract]
+            token = { json = "$.token" }
+            "#,
+        );
+        let profile = step(
+            r#"
+            name = "profile"
+            url = "https://api.example.com/me"
+            headers = { Authorization = "Bearer ${token}" }
+            "#,
+        );
+
+        assert!(validate_steps(&[login.clone(), profile.clone()]).is_ok());
+
+        // 变量必须由前面的步骤提取
+        let error = validate_steps(&[profile.clone(), login.clone()]).unwrap_err();
+        assert!(error.contains("token"));
+
+        let error = validate_steps(&[login.clone(), login.clone()]).unwrap_err();
+        assert!(error.contains("重复"));
+
+        let mut bad_regex = login.clone();
+        bad_regex.extract.insert(
+            "broken".to_string(),
+            Extractor::Regex("(unclosed".to_string()),
+        );
+        assert!(validate_steps(&[bad_regex]).is_err());
+
+        let mut bad_url = login;
+        bad_url.url = "ftp://example.com".to_string();
+        assert!(validate_steps(&[bad_url]).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! 服务模板与矩阵展开
+//!
+//! 顶层 `[templates.<名称>]` 定义服务的公共字段，服务通过 `template = "<名称>"` 引用，
+//! 服务中的字段覆盖模板中的同名字段，表（如 headers）按键合并。
+//! 服务的 `matrix` 按各变量取值的笛卡尔积展开为多个服务，
+//! 字符串中的 `${matrix.<变量>}` 替换为对应的取值。
+
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+/// 主配置文件中定义模板的键
+pub const TEMPLATES_KEY: &str = "templates";
+
+/// 服务引用模板的键
+const TEMPLATE_KEY: &str = "template";
+
+/// 服务矩阵的键
+const MATRIX_KEY: &str = "matrix";
+
+/// 匹配 `${matrix.<变量>}` 占位符
+fn matrix_placeholder() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| {
+        Regex::new(r"\$\{matrix\.([A-Za-z_][A-Za-z0-9_]*)\}").expect("矩阵占位符正则表达式无效")
+    })
+}
+
+/// 配置中定义的服务模板
+#[derive(Debug, Clone, Default)]
+pub struct ServiceTemplates {
+    templates: Map<String, Value>,
+}
+
+impl ServiceTemplates {
+    /// 从主配置中读取模板定义
+    ///
+    /// # 参数
+    /// * `value` - 主配置文件解析后的内容
+    pub fn from_config(value: &Value) -> Result<Self, String> {
+        let templates = match value.get(TEMPLATES_KEY) {
+            None => Map::new(),
+            Some(Value::Object(templates)) => templates.clone(),
+            Some(other) => return Err(format!("{TEMPLATES_KEY} 必须是表: {other}")),
+        };
+
+        for (name, template) in &templates {
+            let fields = template
+                .as_object()
+                .ok_or_else(|| format!("模板 '{name}' 必须是表"))?;
+            if let Some(key) = ["name", TEMPLATE_KEY, MATRIX_KEY]
+                .into_iter()
+                .find(|key| fields.contains_key(*key))
+            {
+                return Err(format!("模板 '{name}' 不能包含 {key} 字段"));
+            }
+        }
+
+        Ok(Self { templates })
+    }
+
+    /// 展开配置文件中引用模板或声明矩阵的服务
+    ///
+    /// # 参数
+    /// * `value` - 配置文件解析后的内容，`services` 会被原地替换
+    ///
+    /// # 返回
+    /// * `Result<bool, String>` - 是否有服务被展开
+    pub fn expand_services(&self, value: &mut Value) -> Result<bool, String> {
+        let Some(Value::Array(services)) = value.get_mut("services") else {
+            return Ok(false);
+        };
+        let needs_expansion = services.iter().any(|service| {
+            service.get(TEMPLATE_KEY).is_some() || service.get(MATRIX_KEY).is_some()
+        });
+        if !needs_expansion {
+            return Ok(false);
+        }
+
+        let mut expanded = Vec::with_capacity(services.len());
+        for service in services.drain(..) {
+            expanded.extend(self.expand_service(service)?);
+        }
+        *services = expanded;
+        Ok(true)
+    }
+
+    /// 展开单个服务
+    fn expand_service(&self, service: Value) -> Result<Vec<Value>, String> {
+        let Value::Object(mut fields) = service else {
+            return Ok(vec![service]);
+        };
+        let name = fields
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("<未命名>")
+            .to_string();
+
+        if let Some(template) = fields.remove(TEMPLATE_KEY) {
+            let template_name = template
+                .as_str()
+                .ok_or_else(|| format!("服务 '{name}' 的 {TEMPLATE_KEY} 必须是字符串"))?;
+            let mut merged = self
+                .templates
+                .get(template_name)
+                .ok_or_else(|| format!("服务 '{name}' 引用的模板 '{template_name}' 不存在"))?
+                .clone();
+            merge(&mut merged, Value::Object(fields));
+            let Value::Object(merged) = merged else {
+                unreachable!("模板已验证为表");
+            };
+            fields = merged;
+        }
+
+        let Some(matrix) = fields.remove(MATRIX_KEY) else {
+            return Ok(vec![Value::Object(fields)]);
+        };
+        let combinations = matrix_combinations(&name, &matrix)?;
+        if !matrix_placeholder().is_match(&name) {
+            return Err(format!(
+                "矩阵服务 '{name}' 的名称必须引用矩阵变量，例如 ${{matrix.env}}"
+            ));
+        }
+
+        let service = Value::Object(fields);
+        combinations
+            .iter()
+            .map(|variables| substitute(&service, variables, &name))
+            .collect()
+    }
+}
+
+/// 合并字段，`overlay` 中的值覆盖 `base` 中的同名值，两者都是表时按键递归合并
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// 计算矩阵变量取值的笛卡尔积
+fn matrix_combinations(name: &str, matrix: &Value) -> Result<Vec<Vec<(String, String)>>, String> {
+    let variables = matrix
+        .as_object()
+        .filter(|variables| !variables.is_empty())
+        .ok_or_else(|| format!("服务 '{name}' 的 {MATRIX_KEY} 必须是非空的表"))?;
+
+    let mut combinations: Vec<Vec<(String, String)>> = vec![Vec::new()];
+    for (variable, values) in variables {
+        let values = values
+            .as_array()
+            .filter(|values| !values.is_empty())
+            .ok_or_else(|| format!("服务 '{name}' 的矩阵变量 {variable} 必须是非空数组"))?
+            .iter()
+            .map(|value| match value {
+                Value::String(value) => Ok(value.clone()),
+                Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+                other => Err(format!(
+                    "服务 '{name}' 的矩阵变量 {variable} 只能包含字符串、数字或布尔值: {other}"
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination| {
+                values.iter().map(move |value| {
+                    let mut combination = combination.clone();
+                    combination.push((variable.clone(), value.clone()));
+                    combination
+                })
+            })
+            .collect();
+    }
+    Ok(combinations)
+}
+
+/// 替换服务中所有字符串的矩阵占位符
+fn substitute(value: &Value, variables: &[(String, String)], name: &str) -> Result<Value, String> {
+    Ok(match value {
+        Value::String(text) => {
+            let mut missing = None;
+            let replaced = matrix_placeholder().replace_all(text, |captures: &Captures| {
+                let variable = &captures[1];
+                match variables.iter().find(|(key, _)| key == variable) {
+                    Some((_, value)) => value.clone(),
+                    None => {
+                        missing = Some(variable.to_string());
+                        captures[0].to_string()
+                    }
+                }
+            });
+            if let Some(variable) = missing {
+                return Err(format!("服务 '{name}' 引用了未定义的矩阵变量 {variable}"));
+            }
+            Value::String(replaced.into_owned())
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| substitute(item, variables, name))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), substitute(value, variables, name)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_template_fields_are_merged() {
+        let mut value = json!({
+            "templates": {
+                "internal-api": {
+                    "method": "HEAD",
+                    "expected_status_codes": [200, 204],
+                    "failure_threshold": 3,
+                    "headers": { "Accept": "application/json", "X-Team": "core" }
+                }
+            },
+            "services": [
+                {
+                    "name": "orders",
+                    "url": "https://orders.internal/health",
+                    "template": "internal-api",
+                    "failure_threshold": 1,
+                    "headers": { "X-Team": "orders" }
+                },
+                { "name": "plain", "url": "https://example.com" }
+            ]
+        });
+
+        let templates = ServiceTemplates::from_config(&value).unwrap();
+        assert!(templates.expand_services(&mut value).unwrap());
+        assert_eq!(
+            value["services"],
+            json!([
+                {
+                    "name": "orders",
+                    "url": "https://orders.internal/health",
+                    "method": "HEAD",
+                    "expected_status_codes": [200, 204],
+                    "failure_threshold": 1,
+                    "headers": { "Accept": "application/json", "X-Team": "orders" }
+                },
+                { "name": "plain", "url": "https://example.com" }
+            ])
+        );
+
+        let mut unknown = json!({ "services": [{ "name": "a", "url": "x", "template": "nope" }] });
+        let error = templates.expand_services(&mut unknown).unwrap_err();
+        assert!(error.contains("'nope'"));
+
+        let invalid = json!({ "templates": { "t": { "name": "fixed" } } });
+        assert!(ServiceTemplates::from_config(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_matrix_expansion() {
+        let mut value = json!({
+            "services": [{
+                "name": "api-${matrix.env}-${matrix.region}",
+                "url": "https://${matrix.region}.${matrix.env}.example.com/health",
+                "matrix": { "env": ["staging", "prod"], "region": ["eu", "us"] },
+                "tags": ["env:${matrix.env}"]
+            }]
+        });
+
+        let templates = ServiceTemplates::default();
+        assert!(templates.expand_services(&mut value).unwrap());
+        let services = value["services"].as_array().unwrap();
+        let names: Vec<&str> = services
+            .iter()
+            .map(|service| service["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "api-staging-eu",
+                "api-staging-us",
+                "api-prod-eu",
+                "api-prod-us"
+            ]
+        );
+        assert_eq!(services[3]["url"], "https://us.prod.example.com/health");
+        assert_eq!(services[3]["tags"], json!(["env:prod"]));
+        assert!(services[0].get("matrix").is_none());
+
+        let mut unnamed = json!({
+            "services": [{ "name": "api", "url": "x", "matrix": { "env": ["a", "b"] } }]
+        });
+        assert!(templates.expand_services(&mut unnamed).is_err());
+
+        let mut undefined = json!({
+            "services": [{ "name": "${matrix.env}", "url": "${matrix.host}", "matrix": { "env": ["a"] } }]
+        });
+        let error = templates.expand_services(&mut undefined).unwrap_err();
+        assert!(error.contains("host"));
+    }
+}
@@ -1,13 +1,15 @@
 //! 配置文件监控模块
 //!
-//! 提供配置文件的实时监控和热重载功能
+//! 提供配置文件的实时监控和热重载功能，`include` 引入的文件和目录一并监控
 
-use crate::config::loader::{ConfigLoader, TomlConfigLoader};
+use crate::config::include::IncludePattern;
+use crate::config::loader::{ConfigFormat, ConfigLoader, FileConfigLoader};
 use crate::config::types::Config;
 use anyhow::{Context, Result};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc, Mutex, Weak};
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, warn};
@@ -25,14 +27,85 @@ pub struct ConfigChangeEvent {
     pub version: u64,
 }
 
+/// 事件处理任务的监控状态
+struct WatchContext {
+    /// 主配置文件路径
+    config_path: PathBuf,
+    /// 当前的包含规则
+    include_patterns: Vec<IncludePattern>,
+    /// 已监控的目录
+    watched_dirs: HashSet<PathBuf>,
+    /// 不存在或无法监控的包含目录，每次防抖动间隔重试，只在首次失败时警告
+    pending_dirs: HashSet<PathBuf>,
+    /// 文件系统监控器，监控停止后失效
+    watcher: Weak<Mutex<RecommendedWatcher>>,
+}
+
+impl WatchContext {
+    /// 监控尚未监控的包含目录
+    ///
+    /// # 返回
+    /// * `Vec<PathBuf>` - 之前无法监控、本次监控成功的目录
+    fn watch_include_dirs(&mut self, watcher: &mut RecommendedWatcher) -> Vec<PathBuf> {
+        // 不再被包含规则引用的目录无需重试
+        self.pending_dirs
+            .retain(|dir| self.include_patterns.iter().any(|p| &p.dir == dir));
+
+        let mut recovered = Vec::new();
+        for pattern in &self.include_patterns {
+            if self.watched_dirs.contains(&pattern.dir) {
+                continue;
+            }
+            match watcher.watch(&pattern.dir, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    debug!("监控包含目录: {}", pattern.dir.display());
+                    self.watched_dirs.insert(pattern.dir.clone());
+                    if self.pending_dirs.remove(&pattern.dir) {
+                        info!("包含目录已可监控: {}", pattern.dir.display());
+                        recovered.push(pattern.dir.clone());
+                    }
+                }
+                Err(e) if self.pending_dirs.insert(pattern.dir.clone()) => warn!(
+                    "包含目录不存在或无法监控，将定期重试: {}: {}",
+                    pattern.dir.display(),
+                    e
+                ),
+                Err(e) => debug!("仍无法监控包含目录: {}: {}", pattern.dir.display(), e),
+            }
+        }
+        recovered
+    }
+
+    /// 重试监控之前无法监控的包含目录
+    ///
+    /// # 返回
+    /// * `bool` - 新监控的目录中是否已有匹配的文件，有则需要重新加载配置
+    fn retry_pending_dirs(&mut self) -> bool {
+        if self.pending_dirs.is_empty() {
+            return false;
+        }
+        let Some(watcher) = self.watcher.upgrade() else {
+            return false;
+        };
+        let recovered = match watcher.lock() {
+            Ok(mut watcher) => self.watch_include_dirs(&mut watcher),
+            Err(_) => return false,
+        };
+        self.include_patterns
+            .iter()
+            .filter(|pattern| recovered.contains(&pattern.dir))
+            .any(|pattern| pattern.resolve().is_ok_and(|files| !files.is_empty()))
+    }
+}
+
 /// 配置文件监控器
 pub struct ConfigWatcher {
     /// 配置文件路径
     config_path: PathBuf,
-    /// 文件系统监控器
-    watcher: Option<RecommendedWatcher>,
+    /// 文件系统监控器，事件处理任务持有弱引用以便监控新增的包含目录
+    watcher: Option<Arc<Mutex<RecommendedWatcher>>>,
     /// 配置加载器
-    loader: TomlConfigLoader,
+    loader: FileConfigLoader,
     /// 事件发送器
     event_sender: broadcast::Sender<ConfigChangeEvent>,
     /// 防抖动延迟
@@ -50,20 +123,25 @@ impl ConfigWatcher {
     ///
     /// # 参数
     /// * `config_path` - 配置文件路径
+    /// * `format` - 显式指定的配置格式（`--config-format`），`None` 时按扩展名判断
     /// * `debounce_delay` - 防抖动延迟时间
     ///
     /// # 返回
     /// * `Result<(Self, broadcast::Receiver<ConfigChangeEvent>)>` - 监控器和事件接收器
     pub fn new<P: AsRef<Path>>(
         config_path: P,
+        format: Option<ConfigFormat>,
         debounce_delay: Duration,
     ) -> Result<(Self, broadcast::Receiver<ConfigChangeEvent>)> {
         let config_path = config_path.as_ref().to_path_buf();
 
         // 验证配置文件路径
-        Self::validate_config_path(&config_path)?;
+        Self::validate_config_path(&config_path, format)?;
+        // 文件系统事件使用绝对路径
+        let config_path = config_path.canonicalize().unwrap_or(config_path);
 
-        let loader = TomlConfigLoader::new(true);
+        // 重载时与首次加载使用相同的格式
+        let loader = FileConfigLoader::new(true).with_format(format);
         let (event_sender, event_receiver) = broadcast::channel(32);
 
         let watcher = Self {
@@ -80,7 +158,7 @@ impl ConfigWatcher {
     }
 
     /// 验证配置文件路径
-    fn validate_config_path(path: &Path) -> Result<()> {
+    fn validate_config_path(path: &Path, format: Option<ConfigFormat>) -> Result<()> {
         // 检查文件是否存在
         if !path.exists() {
             return Err(anyhow::anyhow!("配置文件不存在: {}", path.display()));
@@ -92,10 +170,11 @@ impl ConfigWatcher {
         }
 
         // 检查文件扩展名
-        if let Some(extension) = path.extension() {
-            if extension != "toml" {
-                warn!("配置文件扩展名不是.toml: {}", path.display());
-            }
+        if format.is_none() && ConfigFormat::from_path(path).is_none() {
+            warn!(
+                "配置文件扩展名不是 .toml、.yaml、.yml 或 .json，按TOML解析: {}",
+                path.display()
+            );
         }
 
         // 检查文件权限（读取权限）
@@ -132,38 +211,86 @@ impl ConfigWatcher {
             .watch(watch_path, RecursiveMode::NonRecursive)
             .with_context(|| format!("监控目录失败: {}", watch_path.display()))?;
 
+        // 监控包含文件所在目录，以便发现新增的文件
+        let mut context = WatchContext {
+            config_path: self.config_path.clone(),
+            include_patterns: Self::load_include_patterns(&self.loader, &self.config_path),
+            watched_dirs: HashSet::from([watch_path.to_path_buf()]),
+            pending_dirs: HashSet::new(),
+            watcher: Weak::new(),
+        };
+        context.watch_include_dirs(&mut watcher);
+
+        let watcher = Arc::new(Mutex::new(watcher));
+        context.watcher = Arc::downgrade(&watcher);
         self.watcher = Some(watcher);
 
         // 启动事件处理任务
-        let config_path = self.config_path.clone();
         let event_sender = self.event_sender.clone();
         let loader = self.loader.clone();
         let debounce_delay = self.debounce_delay;
 
         tokio::spawn(async move {
-            Self::handle_file_events(rx, config_path, event_sender, loader, debounce_delay).await;
+            Self::handle_file_events(rx, context, event_sender, loader, debounce_delay).await;
         });
 
         info!("配置文件监控已启动");
         Ok(())
     }
 
+    /// 读取包含规则，失败时只监控主配置文件
+    fn load_include_patterns(loader: &FileConfigLoader, config_path: &Path) -> Vec<IncludePattern> {
+        match loader.include_patterns(config_path) {
+            Ok(patterns) => patterns
+                .into_iter()
+                .map(|pattern| IncludePattern {
+                    dir: pattern.dir.canonicalize().unwrap_or(pattern.dir),
+                    ..pattern
+                })
+                .collect(),
+            Err(e) => {
+                warn!("读取包含规则失败，只监控主配置文件: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     /// 处理文件系统事件
+    ///
+    /// 没有事件时每隔防抖动延迟重试监控之前无法监控的包含目录
     async fn handle_file_events(
         rx: mpsc::Receiver<notify::Result<Event>>,
-        config_path: PathBuf,
+        mut context: WatchContext,
         event_sender: broadcast::Sender<ConfigChangeEvent>,
-        loader: TomlConfigLoader,
+        loader: FileConfigLoader,
         debounce_delay: Duration,
     ) {
         let mut last_event_time: Option<Instant> = None;
         let mut version = 1u64;
 
-        for res in rx {
+        loop {
+            let res = match rx.recv_timeout(debounce_delay) {
+                Ok(res) => res,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if context.retry_pending_dirs() {
+                        // 延迟处理，确保文件写入完成
+                        tokio::time::sleep(debounce_delay).await;
+                        Self::reload_and_notify(&mut context, &event_sender, &loader, &mut version)
+                            .await;
+                    }
+                    continue;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+
             match res {
                 Ok(event) => {
                     // 检查是否是我们关心的文件
-                    if !Self::is_target_file_event(&event, &config_path) {
+                    if !Self::is_target_file_event(
+                        &event,
+                        &context.config_path,
+                        &context.include_patterns,
+                    ) {
                         continue;
                     }
 
@@ -182,20 +309,8 @@ impl ConfigWatcher {
                     // 延迟处理，确保文件写入完成
                     tokio::time::sleep(debounce_delay).await;
 
-                    // 重新加载配置
-                    match Self::reload_config(&loader, &config_path, version).await {
-                        Ok(change_event) => {
-                            info!("配置重载成功，版本: {}", version);
-                            version += 1;
-
-                            if let Err(e) = event_sender.send(change_event) {
-                                error!("发送配置变更事件失败: {}", e);
-                            }
-                        }
-                        Err(e) => {
-                            error!("配置重载失败: {}", e);
-                        }
-                    }
+                    Self::reload_and_notify(&mut context, &event_sender, &loader, &mut version)
+                        .await;
                 }
                 Err(e) => {
                     error!("文件监控事件错误: {}", e);
@@ -204,19 +319,60 @@ impl ConfigWatcher {
         }
     }
 
+    /// 重新加载配置，更新包含目录的监控并发送变更事件
+    async fn reload_and_notify(
+        context: &mut WatchContext,
+        event_sender: &broadcast::Sender<ConfigChangeEvent>,
+        loader: &FileConfigLoader,
+        version: &mut u64,
+    ) {
+        match Self::reload_config(loader, &context.config_path, *version).await {
+            Ok(change_event) => {
+                info!("配置重载成功，版本: {}", version);
+                *version += 1;
+
+                // 包含规则可能已变化
+                context.include_patterns =
+                    Self::load_include_patterns(loader, &context.config_path);
+                if let Some(watcher) = context.watcher.upgrade() {
+                    if let Ok(mut watcher) = watcher.lock() {
+                        context.watch_include_dirs(&mut watcher);
+                    }
+                }
+
+                if let Err(e) = event_sender.send(change_event) {
+                    error!("发送配置变更事件失败: {}", e);
+                }
+            }
+            Err(e) => {
+                error!("配置重载失败: {}", e);
+            }
+        }
+    }
+
     /// 检查是否是目标文件的事件
-    fn is_target_file_event(event: &Event, target_path: &Path) -> bool {
+    ///
+    /// 主配置文件的修改和创建，以及包含文件的修改、创建和删除都会触发重载
+    fn is_target_file_event(
+        event: &Event,
+        target_path: &Path,
+        include_patterns: &[IncludePattern],
+    ) -> bool {
+        let is_included =
+            |path: &PathBuf| include_patterns.iter().any(|pattern| pattern.matches(path));
         match &event.kind {
-            EventKind::Modify(_) | EventKind::Create(_) => {
-                event.paths.iter().any(|path| path == target_path)
-            }
+            EventKind::Modify(_) | EventKind::Create(_) => event
+                .paths
+                .iter()
+                .any(|path| path == target_path || is_included(path)),
+            EventKind::Remove(_) => event.paths.iter().any(is_included),
             _ => false,
         }
     }
 
     /// 重新加载配置
     async fn reload_config(
-        loader: &TomlConfigLoader,
+        loader: &FileConfigLoader,
         config_path: &Path,
         version: u64,
     ) -> Result<ConfigChangeEvent> {
@@ -266,19 +422,119 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         fs::write(temp_file.path(), "[global]\nlog_level = \"info\"").unwrap();
 
-        let result = ConfigWatcher::new(temp_file.path(), Duration::from_millis(100));
+        let result = ConfigWatcher::new(temp_file.path(), None, Duration::from_millis(100));
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_reload_uses_explicit_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("service-vitals.conf");
+        fs::write(
+            &path,
+            "global:\n  check_interval_seconds: 30\nservices:\n  - name: api\n    url: https://example.com/health\n    expected_status_codes: [200]\n",
+        )
+        .unwrap();
+
+        let (watcher, _receiver) =
+            ConfigWatcher::new(&path, Some(ConfigFormat::Yaml), Duration::from_millis(100))
+                .unwrap();
+        let event = ConfigWatcher::reload_config(&watcher.loader, &watcher.config_path, 1)
+            .await
+            .unwrap();
+        assert_eq!(event.new_config.services[0].name, "api");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_missing_include_dir_is_watched_once_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "include = [\"services.d/*.toml\"]\n\n[global]\n\n[[services]]\nname = \"main\"\nurl = \"https://example.com\"\nexpected_status_codes = [200]\n",
+        )
+        .unwrap();
+
+        let (mut watcher, mut receiver) =
+            ConfigWatcher::new(&path, None, Duration::from_millis(100)).unwrap();
+        watcher.start().unwrap();
+
+        // 目录在监控启动后才创建，主配置文件没有变化
+        let services_dir = dir.path().join("services.d");
+        fs::create_dir(&services_dir).unwrap();
+        fs::write(
+            services_dir.join("api.toml"),
+            "[[services]]\nname = \"api\"\nurl = \"https://example.com/api\"\nexpected_status_codes = [200]\n",
+        )
+        .unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(10), receiver.recv())
+            .await
+            .expect("创建包含目录后应重新加载配置")
+            .unwrap();
+        let names: Vec<&str> = event
+            .new_config
+            .services
+            .iter()
+            .map(|service| service.name.as_str())
+            .collect();
+        assert_eq!(names, ["main", "api"]);
+    }
+
+    #[test]
+    fn test_include_file_events_are_targets() {
+        use notify::event::{CreateKind, ModifyKind, RemoveKind};
+
+        let config_path = Path::new("/etc/sv/config.toml");
+        let patterns =
+            vec![IncludePattern::new(Path::new("/etc/sv"), "services.d/*.toml").unwrap()];
+        let event = |kind, path: &str| Event::new(kind).add_path(PathBuf::from(path));
+
+        assert!(ConfigWatcher::is_target_file_event(
+            &event(EventKind::Modify(ModifyKind::Any), "/etc/sv/config.toml"),
+            config_path,
+            &patterns
+        ));
+        assert!(ConfigWatcher::is_target_file_event(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/etc/sv/services.d/new.toml"
+            ),
+            config_path,
+            &patterns
+        ));
+        assert!(ConfigWatcher::is_target_file_event(
+            &event(
+                EventKind::Remove(RemoveKind::File),
+                "/etc/sv/services.d/old.toml"
+            ),
+            config_path,
+            &patterns
+        ));
+        assert!(!ConfigWatcher::is_target_file_event(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/etc/sv/services.d/notes.md"
+            ),
+            config_path,
+            &patterns
+        ));
+        assert!(!ConfigWatcher::is_target_file_event(
+            &event(EventKind::Create(CreateKind::File), "/etc/sv/other.toml"),
+            config_path,
+            &patterns
+        ));
+    }
+
     #[tokio::test]
     async fn test_config_file_validation() {
         // 测试不存在的文件
-        let result = ConfigWatcher::validate_config_path(Path::new("/nonexistent/file.toml"));
+        let result = ConfigWatcher::validate_config_path(Path::new("/nonexistent/file.toml"), None);
         assert!(result.is_err());
 
         // 测试有效文件
         let temp_file = NamedTempFile::new().unwrap();
-        let result = ConfigWatcher::validate_config_path(temp_file.path());
+        let result = ConfigWatcher::validate_config_path(temp_file.path(), None);
         assert!(result.is_ok());
     }
 }
@@ -0,0 +1,161 @@
+//! WebSocket检测配置
+//!
+//! URL 为 `ws://` 或 `wss://` 的服务完成 WebSocket 升级握手，
+//! 可选地发送一条消息并在超时时间内等待期望的应答。
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// 明文WebSocket检测URL的前缀
+pub const WS_SCHEME: &str = "ws://";
+/// TLS WebSocket检测URL的前缀
+pub const WSS_SCHEME: &str = "wss://";
+
+/// WebSocket检测配置，未配置时只检测升级握手
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WebSocketCheckConfig {
+    /// 握手成功后发送的文本消息
+    pub send: Option<String>,
+    /// 期望的应答，与收到的文本消息完全相同时通过
+    pub expect: Option<String>,
+    /// 期望应答的正则表达式，与 `expect` 二选一
+    pub expect_regex: Option<String>,
+    /// 握手时请求的子协议（`Sec-WebSocket-Protocol`）
+    #[serde(default)]
+    pub subprotocols: Vec<String>,
+}
+
+impl WebSocketCheckConfig {
+    /// 是否需要等待应答：配置了发送消息或期望应答时等待
+    pub fn awaits_reply(&self) -> bool {
+        self.send.is_some() || self.expect.is_some() || self.expect_regex.is_some()
+    }
+
+    /// 验证WebSocket检测配置
+    pub fn validate(&self) -> Result<(), String> {
+        if self.expect.is_some() && self.expect_regex.is_some() {
+            return Err("expect 和 expect_regex 不能同时配置".to_string());
+        }
+        if let Some(pattern) = &self.expect_regex {
+            regex::Regex::new(pattern).map_err(|e| format!("expect_regex 无效: {e}"))?;
+        }
+        if let Some(protocol) = self
+            .subprotocols
+            .iter()
+            .find(|protocol| protocol.is_empty() || !protocol.chars().all(is_token_char))
+        {
+            return Err(format!("子协议 {protocol:?} 无效"));
+        }
+        Ok(())
+    }
+}
+
+/// HTTP token 允许的字符
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+/// WebSocket检测的连接目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketTarget {
+    /// 主机名或IP地址（IPv6不带方括号）
+    pub host: String,
+    /// 端口，未指定时明文为80、TLS为443
+    pub port: u16,
+    /// 是否使用TLS
+    pub tls: bool,
+    /// 请求路径及查询参数
+    pub resource: String,
+    /// Host请求头
+    pub host_header: String,
+}
+
+/// 判断URL是否为WebSocket检测URL
+pub fn is_websocket_url(url: &str) -> bool {
+    url.starts_with(WS_SCHEME) || url.starts_with(WSS_SCHEME)
+}
+
+/// 解析 `ws://主机:端口/路径` 或 `wss://主机:端口/路径` 形式的URL
+///
+/// # 参数
+/// * `url` - 服务URL
+///
+/// # 返回
+/// * `Result<WebSocketTarget, String>` - 连接目标，URL格式无效时返回错误
+pub fn websocket_target(url: &str) -> Result<WebSocketTarget, String> {
+    if !is_websocket_url(url) {
+        return Err(format!("{url} 不是 {WS_SCHEME} 或 {WSS_SCHEME} URL"));
+    }
+    let parsed = Url::parse(url).map_err(|e| format!("{url} 格式无效: {e}"))?;
+    let host = parsed
+        .host_str()
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| format!("{url} 缺少主机"))?;
+    if parsed.fragment().is_some() {
+        return Err(format!("{url} 不能包含片段标识"));
+    }
+    let tls = url.starts_with(WSS_SCHEME);
+    let port = parsed
+        .port_or_known_default()
+        .unwrap_or(if tls { 443 } else { 80 });
+    let mut resource = parsed.path().to_string();
+    if let Some(query) = parsed.query() {
+        resource.push('?');
+        resource.push_str(query);
+    }
+    let host_header = match parsed.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    };
+    Ok(WebSocketTarget {
+        host: host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string(),
+        port,
+        tls,
+        resource,
+        host_header,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_websocket_target() {
+        let target = websocket_target("wss://gateway.example.com/live?room=1").unwrap();
+        assert_eq!(target.host, "gateway.example.com");
+        assert_eq!(target.port, 443);
+        assert!(target.tls);
+        assert_eq!(target.resource, "/live?room=1");
+        assert_eq!(target.host_header, "gateway.example.com");
+
+        let target = websocket_target("ws://[::1]:8080").unwrap();
+        assert_eq!(target.host, "::1");
+        assert_eq!(target.port, 8080);
+        assert_eq!(target.resource, "/");
+        assert_eq!(target.host_header, "[::1]:8080");
+
+        assert!(websocket_target("https://example.com").is_err());
+        assert!(websocket_target("ws://").is_err());
+
+        let config = WebSocketCheckConfig {
+            expect: Some("pong".to_string()),
+            expect_regex: Some("^pong$".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = WebSocketCheckConfig {
+            expect_regex: Some("(".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("expect_regex"));
+        let config = WebSocketCheckConfig {
+            subprotocols: vec!["graphql ws".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! YAML解析
+//!
+//! 使用 `serde_norway` 解析YAML配置并转换为 `serde_json::Value`，支持锚点、别名和 `<<` 合并键。
+//! 配置文件只能包含一个文档，自定义标签（如 `!include`）无法映射到配置结构，均直接报错。
+
+use serde_json::Value;
+
+/// 解析YAML文本
+///
+/// # 参数
+/// * `input` - YAML文本
+///
+/// # 返回
+/// * `Result<Value, String>` - 解析结果，空文档为 `Value::Null`；出错时返回带行号的错误信息
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut documents = serde_norway::Deserializer::from_str(input);
+    let Some(document) = documents.next() else {
+        return Ok(Value::Null);
+    };
+    let mut value: serde_norway::Value =
+        serde::Deserialize::deserialize(document).map_err(|e| e.to_string())?;
+    if documents.next().is_some() {
+        return Err("不支持多文档YAML，配置文件只能包含一个文档".to_string());
+    }
+
+    value
+        .apply_merge()
+        .map_err(|e| format!("合并键 `<<` 无效: {e}"))?;
+    reject_tags(&value)?;
+    serde_json::to_value(&value).map_err(|e| format!("无法转换为配置结构: {e}"))
+}
+
+/// 拒绝带自定义标签的值，避免标签被当作普通映射键静默接受
+fn reject_tags(value: &serde_norway::Value) -> Result<(), String> {
+    match value {
+        serde_norway::Value::Tagged(tagged) => Err(format!("不支持YAML标签 {}", tagged.tag)),
+        serde_norway::Value::Sequence(items) => items.iter().try_for_each(reject_tags),
+        serde_norway::Value::Mapping(fields) => fields
+            .iter()
+            .try_for_each(|(key, field)| reject_tags(key).and_then(|_| reject_tags(field))),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_nested_blocks() {
+        let value = parse(
+            r#"
+# blackbox 配置
+modules:
+  http_2xx:
+    prober: http
+    timeout: 5s
+    http:
+      valid_status_codes: [200, 204]  # 注释
+      method: GET
+      headers:
+        Host: "example.com"
+      fail_if_not_ssl: true
+scrape_configs:
+- job_name: 'blackbox'
+  params:
+    module: [http_2xx]
+  static_configs:
+    - targets:
+      - https://example.com/health
+      - http://10.0.0.1:8080/#anchor
+      labels: {env: prod, team: "core"}
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value["modules"]["http_2xx"]["http"]["valid_status_codes"],
+            json!([200, 204])
+        );
+        assert_eq!(value["modules"]["http_2xx"]["timeout"], "5s");
+        assert_eq!(
+            value["modules"]["http_2xx"]["http"]["fail_if_not_ssl"],
+            true
+        );
+        let job = &value["scrape_configs"][0];
+        assert_eq!(job["job_name"], "blackbox");
+        assert_eq!(job["params"]["module"], json!(["http_2xx"]));
+        assert_eq!(
+            job["static_configs"][0]["targets"],
+            json!(["https://example.com/health", "http://10.0.0.1:8080/#anchor"])
+        );
+        assert_eq!(job["static_configs"][0]["labels"]["team"], "core");
+    }
+
+    #[test]
+    fn test_parse_scalars_and_block_text() {
+        let value = parse(
+            "---\nempty:\nnull_value: ~\nfloat: 1.5\nversion: 1.2.3\nquoted: 'it''s'\nescaped: \"a\\tb\"\nliteral: |\n  line 1\n\n  line 2\nfolded: >-\n  one\n  two\nlast: end",
+        )
+        .unwrap();
+        assert_eq!(value["empty"], Value::Null);
+        assert_eq!(value["null_value"], Value::Null);
+        assert_eq!(value["float"], 1.5);
+        assert_eq!(value["version"], "1.2.3");
+        assert_eq!(value["quoted"], "it's");
+        assert_eq!(value["escaped"], "a\tb");
+        assert_eq!(value["literal"], "line 1\n\nline 2\n");
+        assert_eq!(value["folded"], "one two");
+        assert_eq!(value["last"], "end");
+    }
+
+    #[test]
+    fn test_parse_sequences() {
+        let value =
+            parse("- - 1\n  - 2\n- name: a\n  tags:\n  - x\n-\n  nested: true\n- plain").unwrap();
+        assert_eq!(
+            value,
+            json!([[1, 2], {"name": "a", "tags": ["x"]}, {"nested": true}, "plain"])
+        );
+        assert_eq!(parse("").unwrap(), Value::Null);
+        assert!(parse("a: 1\na: 2").is_err());
+        assert!(parse("a: [1, 2").is_err());
+    }
+
+    #[test]
+    fn test_parse_anchors_and_unsupported_syntax() {
+        let value = parse(
+            "defaults: &defaults\n  interval: 30\n  tags: &tags [prod]\nservices:\n- <<: *defaults\n  name: api\n  interval: 10\n- name: web\n  tags: *tags",
+        )
+        .unwrap();
+        assert_eq!(
+            value["services"],
+            json!([
+                {"name": "api", "interval": 10, "tags": ["prod"]},
+                {"name": "web", "tags": ["prod"]}
+            ])
+        );
+
+        let error = parse("a: 1\n---\nb: 2").unwrap_err();
+        assert!(error.contains("多文档"), "{error}");
+        assert_eq!(parse("---\na: 1\n...\n").unwrap(), json!({"a": 1}));
+
+        let error = parse("url: !include other.yaml").unwrap_err();
+        assert!(error.contains("!include"), "{error}");
+        assert!(parse("a: *missing").is_err());
+    }
+}
@@ -2,16 +2,18 @@
 //!
 //! 包含主函数、命令执行和应用程序生命周期管理
 
-use crate::cli::args::{Args, Commands};
+use crate::cli::args::{Args, CheckOutputFormat, Commands};
 use crate::cli::commands::{
-    CheckCommand, Command, InitCommand, InstallCommand, RestartServiceCommand,
-    ServiceStatusCommand, StartServiceCommand, StatusCommand, StopCommand, StopServiceCommand,
-    TestNotificationCommand, UninstallCommand, ValidateCommand, VersionCommand,
+    CheckCommand, Command, ConfigCommand, ImportCommand, InitCommand, InstallCommand, PauseCommand,
+    RestartServiceCommand, ResumeCommand, ServiceStatusCommand, StartServiceCommand, StatusCommand,
+    StopCommand, StopServiceCommand, TestNotificationCommand, TopCommand, TriggerCommand,
+    UninstallCommand, ValidateCommand, VersionCommand,
 };
 use crate::common::logging::{LogConfig, LoggingSystem};
 use crate::core::service::ServiceManager;
 use anyhow::{Context, Result};
 use clap::Parser;
+use log::LevelFilter;
 use std::time::Duration;
 use tracing::{error, info};
 
@@ -20,9 +22,21 @@ pub async fn main() -> Result<()> {
     // 解析命令行参数
     let args = Args::parse();
 
-    // 初始化日志系统
+    // 初始化日志系统，机器可读的检测输出、全屏状态面板和输出到标准输出的导入结果不混入控制台日志
+    let quiet_console = matches!(
+        &args.command,
+        Commands::Check {
+            format: CheckOutputFormat::Json | CheckOutputFormat::Nagios | CheckOutputFormat::Junit,
+            ..
+        } | Commands::Top { .. }
+            | Commands::Import { output: None, .. }
+    );
     let log_config = LogConfig {
-        level: args.log_level.clone().into(),
+        level: if quiet_console {
+            LevelFilter::Off
+        } else {
+            args.log_level.clone().into()
+        },
         console: true,
         json_format: false,
         ..Default::default()
@@ -82,17 +96,41 @@ pub async fn execute_command(args: &Args) -> Result<()> {
                 .await
                 .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
         }
-        Commands::Check {
-            service: _,
-            format: _,
-            timeout: _,
-        } => {
+        Commands::Check { .. } => {
             let command = CheckCommand;
             command
                 .execute(args)
                 .await
                 .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
         }
+        Commands::Trigger { .. } => {
+            let command = TriggerCommand;
+            command
+                .execute(args)
+                .await
+                .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
+        }
+        Commands::Pause { .. } => {
+            let command = PauseCommand;
+            command
+                .execute(args)
+                .await
+                .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
+        }
+        Commands::Resume { .. } => {
+            let command = ResumeCommand;
+            command
+                .execute(args)
+                .await
+                .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
+        }
+        Commands::Top { .. } => {
+            let command = TopCommand;
+            command
+                .execute(args)
+                .await
+                .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
+        }
         Commands::Init {
             config_path: _,
             force: _,
@@ -114,6 +152,20 @@ pub async fn execute_command(args: &Args) -> Result<()> {
                 .await
                 .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
         }
+        Commands::Config { .. } => {
+            let command = ConfigCommand;
+            command
+                .execute(args)
+                .await
+                .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
+        }
+        Commands::Import { .. } => {
+            let command = ImportCommand;
+            command
+                .execute(args)
+                .await
+                .map_err(|e: crate::common::error::ServiceVitalsError| anyhow::anyhow!(e))
+        }
         Commands::Version { format: _ } => {
             let command = VersionCommand;
             command
@@ -66,8 +66,7 @@ impl ForegroundService {
 
         // 3. 启动Web服务器（如果启用）
         let web_server_handle =
-            ServiceLauncher::start_web_server_if_enabled(&config, &service_components.scheduler)
-                .await?;
+            ServiceLauncher::start_web_server_if_enabled(&config, &service_components).await?;
 
         // 4. 设置配置热重载
         ServiceLauncher::setup_config_hot_reload(args, &service_components).await?;
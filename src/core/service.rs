@@ -3,16 +3,16 @@
 //! 负责服务的启动、组件初始化和生命周期管理
 
 use crate::cli::args::Args;
-use crate::common::status::StatusManager;
-use crate::config::{self, ConfigLoader, TomlConfigLoader};
-use crate::health::{HttpHealthChecker, Scheduler, TaskScheduler};
+use crate::common::status::{ServiceStatus, StatusManager};
+use crate::config::{self, ConfigLoader};
+use crate::health::{HeartbeatRegistry, HttpHealthChecker, Scheduler, TaskScheduler};
 use crate::notification::sender::NoOpSender;
 use crate::notification::FeishuSender;
 use crate::web::WebServer;
 use anyhow::{Context, Result};
 use std::sync::Arc;
 use tokio::sync::broadcast;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::core::daemon_service::DaemonService;
 use crate::core::foreground_service::ForegroundService;
@@ -61,14 +61,21 @@ pub struct ServiceComponents {
     pub status_manager: Arc<StatusManager>,
     /// 任务调度器，负责执行健康检测任务
     pub scheduler: Arc<TaskScheduler>,
+    /// 心跳记录表，由健康检测器和Web服务器的心跳上报接口共享
+    pub heartbeats: Arc<HeartbeatRegistry>,
 }
 
 impl ServiceComponents {
     /// 创建新的服务组件
-    pub fn new(status_manager: Arc<StatusManager>, scheduler: Arc<TaskScheduler>) -> Self {
+    pub fn new(
+        status_manager: Arc<StatusManager>,
+        scheduler: Arc<TaskScheduler>,
+        heartbeats: Arc<HeartbeatRegistry>,
+    ) -> Self {
         Self {
             status_manager,
             scheduler,
+            heartbeats,
         }
     }
 }
@@ -87,7 +94,7 @@ impl ServiceLauncher {
         info!("加载配置文件: {:?}", config_path);
 
         // 创建配置加载器
-        let config_loader = TomlConfigLoader::new(true);
+        let config_loader = args.config_loader();
         let mut config = config_loader
             .load_from_file(&config_path)
             .await
@@ -127,11 +134,8 @@ impl ServiceLauncher {
             };
 
         // 创建健康检测器
-        let health_checker = Arc::new(HttpHealthChecker::new(
-            std::time::Duration::from_secs(config.global.request_timeout_seconds),
-            config.global.retry_attempts,
-            std::time::Duration::from_secs(config.global.retry_delay_seconds),
-        )?);
+        let health_checker = Arc::new(HttpHealthChecker::from_global_config(&config.global)?);
+        let heartbeats = health_checker.heartbeats();
 
         // 创建任务调度器
         let scheduler = Arc::new(TaskScheduler::new(
@@ -146,19 +150,37 @@ impl ServiceLauncher {
             .await
             .context("启动调度器失败")?;
 
-        Ok(ServiceComponents::new(status_manager, scheduler))
+        Ok(ServiceComponents::new(
+            status_manager,
+            scheduler,
+            heartbeats,
+        ))
     }
 
     /// 启动Web服务器（如果启用）
     pub async fn start_web_server_if_enabled(
         config: &config::Config,
-        _scheduler: &Arc<TaskScheduler>,
+        service_components: &ServiceComponents,
     ) -> Result<Option<tokio::task::JoinHandle<()>>> {
+        let scheduler = &service_components.scheduler;
         if let Some(web_config) = &config.global.web {
             if web_config.enabled {
                 info!("启动Web服务器，监听地址: {}", web_config.bind_address);
 
-                let (web_server, _status_tx) = WebServer::new(web_config.clone());
+                let (web_server, status_tx) = WebServer::new(web_config.clone());
+                let web_server = web_server
+                    .with_scheduler(Arc::clone(scheduler))
+                    .with_heartbeats(Arc::clone(&service_components.heartbeats));
+
+                // 将检测结果（包括手动触发的检测）推送到Web状态面板
+                scheduler
+                    .set_health_result_callback(Arc::new(move |result| {
+                        if let Err(e) = status_tx.try_send(ServiceStatus::from(result)) {
+                            warn!("推送服务状态到Web服务器失败: {}", e);
+                        }
+                    }))
+                    .await;
+
                 let handle = tokio::spawn(async move {
                     if let Err(e) = web_server.start().await {
                         error!("Web服务器运行失败: {}", e);
@@ -5,8 +5,7 @@
 use crate::common::error::Result;
 #[cfg(unix)]
 use crate::daemon::PlatformDaemonManager;
-use crate::daemon::{DaemonConfig, DaemonStatus};
-use crate::ServiceVitalsError;
+use crate::daemon::{DaemonConfig, DaemonManager, DaemonStatus};
 use serde::{Deserialize, Serialize};
 
 /// 服务管理器
@@ -179,8 +178,7 @@ impl ServiceManager {
         for service_name in service_names {
             match self.get_service_status(service_name).await {
                 Ok(info) => services.push(info),
-                Err(e) => {
-                    // error!("获取服务状态失败 {service_name}: {e}"); // Removed unused imports
+                Err(_) => {
                     services.push(ServiceInfo {
                         name: service_name.clone(),
                         status: DaemonStatus::Unknown,
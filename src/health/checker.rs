@@ -1,13 +1,33 @@
 //! HTTP健康检测器实现
 //!
-//! 提供HTTP健康检测功能，支持多种HTTP方法和超时处理
+//! 提供HTTP健康检测功能，支持多种HTTP方法和超时处理；`dns://` 和 `grpc://` 服务分别转交 [`DnsChecker`] 和 [`GrpcChecker`] 检测，
+//! `redis://`、`postgres://`、`mysql://` 服务转交对应的数据库检测器，`exec://` 服务转交 [`ExecChecker`] 执行命令，
+//! `heartbeat://` 服务由 [`HeartbeatChecker`] 根据上报记录判断状态，`host://` 服务由 [`HostChecker`] 检测本机资源，
+//! `ws://`、`wss://` 服务由 [`WebSocketChecker`] 完成升级握手和消息往返
 
 use crate::common::error::{HealthCheckError, Result};
+use crate::config::database::DatabaseKind;
+use crate::config::effective::{EffectiveServiceConfig, RequestDefaults};
+use crate::config::types::{CheckProtocol, GlobalConfig, RetryBackoff};
 use crate::config::ServiceConfig;
+use crate::health::auth::TokenCache;
+use crate::health::database::{MysqlHealthChecker, PostgresHealthChecker, RedisHealthChecker};
+use crate::health::dns::DnsChecker;
+use crate::health::exec::ExecChecker;
+use crate::health::grpc::GrpcChecker;
+use crate::health::heartbeat::{HeartbeatChecker, HeartbeatRegistry};
+use crate::health::host::HostChecker;
 use crate::health::result::{HealthResult, HealthStatus};
+use crate::health::steps::{self, StepOutcome, StepVariables};
+use crate::health::timing::PhaseRecorder;
+use crate::health::tls::{self, ClientPool};
+use crate::health::websocket::WebSocketChecker;
 use async_trait::async_trait;
+use reqwest::header::HeaderMap;
 use reqwest::{Client, Method, Response};
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 
@@ -37,6 +57,30 @@ pub trait HealthChecker: Send + Sync {
         timeout_duration: Duration,
     ) -> Result<HealthResult>;
 
+    /// 按服务的有效配置执行健康检测
+    ///
+    /// 有效配置由全局配置与服务配置逐层合并得到，见 [`EffectiveServiceConfig`]。
+    /// 默认实现只使用其中的超时时间。
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置
+    ///
+    /// # 返回
+    /// * `Result<HealthResult>` - 检测结果
+    async fn check_with_config(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> Result<HealthResult> {
+        self.check_with_timeout(service, effective.timeout).await
+    }
+
+    /// 心跳记录表，支持心跳检测的检测器返回它，调度器据此登记服务的心跳令牌
+    fn heartbeat_registry(&self) -> Option<Arc<HeartbeatRegistry>> {
+        None
+    }
+
     /// 批量健康检测
     ///
     /// # 参数
@@ -49,14 +93,48 @@ pub trait HealthChecker: Send + Sync {
 
 /// HTTP健康检测器实现
 pub struct HttpHealthChecker {
-    /// HTTP客户端
-    client: Client,
-    /// 默认超时时间
-    default_timeout: Duration,
-    /// 重试次数
-    retry_attempts: u32,
-    /// 重试间隔
-    retry_delay: Duration,
+    /// 按TLS配置复用的HTTP客户端
+    clients: ClientPool,
+    /// 服务未单独配置时使用的请求默认值
+    defaults: RequestDefaults,
+    /// OAuth2 访问令牌缓存
+    tokens: TokenCache,
+    /// `dns://` 服务的DNS解析检测器
+    dns: DnsChecker,
+    /// `grpc://`、`grpcs://` 服务的gRPC健康检测器
+    grpc: GrpcChecker,
+    /// `redis://` 服务的Redis检测器
+    redis: RedisHealthChecker,
+    /// `postgres://` 服务的PostgreSQL检测器
+    postgres: PostgresHealthChecker,
+    /// `mysql://` 服务的MySQL检测器
+    mysql: MysqlHealthChecker,
+    /// `exec://` 服务的命令检测器
+    exec: ExecChecker,
+    /// `heartbeat://` 服务的心跳检测器
+    heartbeat: HeartbeatChecker,
+    /// `host://` 服务的本机资源检测器
+    host: HostChecker,
+    /// `ws://`、`wss://` 服务的WebSocket检测器
+    websocket: WebSocketChecker,
+}
+
+/// 完整读取的响应内容，供多步骤检测校验响应和提取变量
+struct ResponseContent {
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+/// 收到响应头时的请求计时信息
+struct ResponseTiming {
+    /// 本次请求的阶段记录器
+    recorder: PhaseRecorder,
+    /// 发出请求的时间
+    request_start: Instant,
+    /// 发出请求到收到响应头的时间
+    response_time: Duration,
+    /// 读取响应体剩余的超时时间
+    body_timeout: Duration,
 }
 
 impl HttpHealthChecker {
@@ -70,28 +148,81 @@ impl HttpHealthChecker {
     /// # 返回
     /// * `Result<Self>` - 检测器实例
     pub fn new(timeout: Duration, retry_attempts: u32, retry_delay: Duration) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(timeout)
-            .user_agent(format!("{}/{}", crate::APP_NAME, crate::VERSION))
-            .build()
-            .map_err(HealthCheckError::RequestError)?;
-
-        Ok(Self {
-            client,
-            default_timeout: timeout,
+        Self::with_defaults(RequestDefaults {
+            headers: HashMap::new(),
+            timeout,
             retry_attempts,
             retry_delay,
+            retry_backoff: RetryBackoff::default(),
+            check_interval_seconds: crate::config::types::default_check_interval(),
         })
     }
 
+    /// 根据全局配置创建HTTP健康检测器
+    ///
+    /// 全局请求头、超时和重试策略作为所有服务的默认值
+    ///
+    /// # 参数
+    /// * `global` - 全局配置
+    ///
+    /// # 返回
+    /// * `Result<Self>` - 检测器实例
+    pub fn from_global_config(global: &GlobalConfig) -> Result<Self> {
+        Self::with_defaults(RequestDefaults::from(global))
+    }
+
+    /// 使用指定的请求默认值创建HTTP健康检测器
+    ///
+    /// # 参数
+    /// * `defaults` - 请求默认值
+    ///
+    /// # 返回
+    /// * `Result<Self>` - 检测器实例
+    pub fn with_defaults(defaults: RequestDefaults) -> Result<Self> {
+        // 超时按服务单独设置，客户端不设置统一超时
+        let clients = ClientPool::new(format!("{}/{}", crate::APP_NAME, crate::VERSION))?;
+        let tokens = TokenCache::new(clients.default_client().clone());
+        Ok(Self {
+            clients,
+            tokens,
+            dns: DnsChecker::new(),
+            grpc: GrpcChecker::new(),
+            redis: RedisHealthChecker::new(defaults.timeout),
+            postgres: PostgresHealthChecker::new(defaults.timeout),
+            mysql: MysqlHealthChecker::new(defaults.timeout),
+            exec: ExecChecker::new(),
+            heartbeat: HeartbeatChecker::new(),
+            host: HostChecker::new(),
+            websocket: WebSocketChecker::new(),
+            defaults,
+        })
+    }
+
+    /// 获取心跳记录表，Web服务器的心跳上报接口通过它记录心跳
+    pub fn heartbeats(&self) -> Arc<HeartbeatRegistry> {
+        self.heartbeat.registry()
+    }
+
+    /// 解析服务的有效配置
+    fn resolve(&self, service: &ServiceConfig) -> EffectiveServiceConfig {
+        EffectiveServiceConfig::resolve(&self.defaults, service)
+    }
+
     /// 构建HTTP请求
     ///
     /// # 参数
+    /// * `client` - 服务使用的HTTP客户端
     /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置
     ///
     /// # 返回
     /// * `Result<reqwest::RequestBuilder>` - 请求构建器
-    fn build_request(&self, service: &ServiceConfig) -> Result<reqwest::RequestBuilder> {
+    fn build_request(
+        &self,
+        client: &Client,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> Result<reqwest::RequestBuilder> {
         // 解析HTTP方法
         let method = Method::from_str(&service.method.to_uppercase()).map_err(|_| {
             HealthCheckError::ConnectionError {
@@ -99,10 +230,20 @@ impl HttpHealthChecker {
             }
         })?;
 
-        let mut request = self.client.request(method, &service.url);
+        // 覆盖SNI时请求地址的主机会被替换，并保留原始Host请求头
+        let mut request = match tls::server_name_target(service)? {
+            Some((url, host)) => client
+                .request(method, url)
+                .header(reqwest::header::HOST, host),
+            None => client.request(method, &service.url),
+        }
+        .timeout(effective.timeout);
 
-        // 添加请求头
-        for (key, value) in &service.headers {
+        // 添加请求头（全局请求头与服务请求头合并后的结果），配置了认证时由认证配置决定Authorization
+        for (key, value) in &effective.headers {
+            if service.auth.is_some() && key.eq_ignore_ascii_case("authorization") {
+                continue;
+            }
             request = request.header(key, value);
         }
 
@@ -130,57 +271,267 @@ impl HttpHealthChecker {
     ///
     /// # 参数
     /// * `service` - 服务配置
-    /// * `timeout_duration` - 超时时间
+    /// * `effective` - 服务的有效配置
     ///
     /// # 返回
     /// * `Result<HealthResult>` - 检测结果
     async fn perform_request(
         &self,
         service: &ServiceConfig,
-        timeout_duration: Duration,
+        effective: &EffectiveServiceConfig,
+    ) -> Result<HealthResult> {
+        let (result, _) = self.execute_request(service, effective).await?;
+        Ok(result)
+    }
+
+    /// 执行多步骤检测
+    ///
+    /// 按顺序执行各步骤，任一步骤失败即停止。整个流程计为一次检测结果，
+    /// 响应时间为所有步骤的总耗时，各步骤的结果记录在元数据 `steps` 中
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置
+    ///
+    /// # 返回
+    /// * `Result<HealthResult>` - 检测结果
+    async fn perform_steps(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
     ) -> Result<HealthResult> {
         let start_time = Instant::now();
+        let mut variables = StepVariables::new();
+        let mut outcomes = Vec::with_capacity(service.steps.len());
+        let mut response_size = 0;
+        let mut last_status_code = None;
+        let mut failure = None;
+
+        for step in &service.steps {
+            let step_start = Instant::now();
+            let (step_service, step_effective) =
+                match steps::step_request(service, effective, step, &variables) {
+                    Ok(request) => request,
+                    Err(reason) => {
+                        outcomes.push(StepOutcome {
+                            name: step.name.clone(),
+                            method: step.method.clone(),
+                            url: step.url.clone(),
+                            status: HealthStatus::Down,
+                            status_code: None,
+                            response_time_ms: step_start.elapsed().as_millis() as u64,
+                            error: Some(reason.clone()),
+                            extracted: Vec::new(),
+                        });
+                        failure = Some((HealthStatus::Down, step, reason, None));
+                        break;
+                    }
+                };
+
+            let (result, content) = self.execute_request(&step_service, &step_effective).await?;
+            last_status_code = result.status_code.or(last_status_code);
+            response_size += result.response_size.unwrap_or(0);
+
+            let evaluation = match content {
+                Some(content) if result.status.is_healthy() => {
+                    steps::evaluate_response(step, &content.headers, &content.body, &mut variables)
+                }
+                _ => Err(result
+                    .error_message
+                    .clone()
+                    .unwrap_or_else(|| result.status.to_string())),
+            };
+            // 认证或TLS配置问题导致步骤未实际执行时保持未知状态
+            let failed_status = match result.status {
+                HealthStatus::Unknown => HealthStatus::Unknown,
+                _ => HealthStatus::Down,
+            };
+
+            let (status, error, extracted) = match evaluation {
+                Ok(extracted) => (HealthStatus::Up, None, extracted),
+                Err(reason) => (failed_status, Some(reason), Vec::new()),
+            };
+            outcomes.push(StepOutcome {
+                name: step.name.clone(),
+                method: step.method.clone(),
+                url: step_service.url.clone(),
+                status,
+                status_code: result.status_code,
+                response_time_ms: step_start.elapsed().as_millis() as u64,
+                error: error.clone(),
+                extracted,
+            });
+
+            if let Some(reason) = error {
+                failure = Some((
+                    status,
+                    step,
+                    reason,
+                    result.metadata.get("failure_kind").cloned(),
+                ));
+                break;
+            }
+        }
+
+        let status = failure
+            .as_ref()
+            .map_or(HealthStatus::Up, |(status, ..)| *status);
+        let mut result = HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            status,
+            service.method.clone(),
+        )
+        .with_response_time(start_time.elapsed())
+        .with_response_size(response_size)
+        .with_metadata(
+            "steps".to_string(),
+            serde_json::to_value(&outcomes).unwrap_or_default(),
+        );
+
+        if let Some(status_code) = last_status_code {
+            result = result.with_status_code(status_code);
+        }
+        if let Some((_, step, reason, failure_kind)) = failure {
+            result = result.with_error(format!("步骤 {} 失败: {}", step.name, reason));
+            if let Some(failure_kind) = failure_kind {
+                result = result.with_metadata("failure_kind".to_string(), failure_kind);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// 执行单次HTTP请求，并在完整读取响应体时一并返回响应内容
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置
+    ///
+    /// # 返回
+    /// * `Result<(HealthResult, Option<ResponseContent>)>` - 检测结果和响应内容
+    async fn execute_request(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> Result<(HealthResult, Option<ResponseContent>)> {
+        let start_time = Instant::now();
+
+        // 获取服务TLS配置对应的客户端，证书无法加载时单独报告
+        let client = match self.clients.client_for(service) {
+            Ok(client) => client,
+            Err(e) => {
+                let result = self.create_unchecked_result(service, start_time.elapsed(), e, "tls");
+                return Ok((result, None));
+            }
+        };
 
         // 构建请求
-        let request = self.build_request(service)?;
+        let mut request = self.build_request(&client, service, effective)?;
+
+        // 附加认证信息，令牌端点异常时单独报告，不视为服务本身的响应
+        if let Some(auth) = &service.auth {
+            request = match self
+                .tokens
+                .authorize(request, auth, effective.timeout)
+                .await
+            {
+                Ok(request) => request,
+                Err(e) => {
+                    let result =
+                        self.create_unchecked_result(service, start_time.elapsed(), e, "auth");
+                    return Ok((result, None));
+                }
+            };
+        }
 
-        // 执行请求（带超时）
-        let response_result = timeout(timeout_duration, request.send()).await;
+        // 执行请求（带超时），响应时间为发出请求到收到响应头的时间
+        let recorder = PhaseRecorder::default();
+        let request_start = Instant::now();
+        let response_result = timeout(effective.timeout, recorder.scope(request.send())).await;
 
-        let response_time = start_time.elapsed();
+        let response_time = request_start.elapsed();
 
         match response_result {
             Ok(Ok(response)) => {
-                self.process_successful_response(service, response, response_time)
+                // 令牌被服务拒绝时丢弃缓存，下一次请求重新获取
+                if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                    if let Some(auth) = &service.auth {
+                        self.tokens.invalidate(auth).await;
+                    }
+                }
+                let timing = ResponseTiming {
+                    recorder,
+                    request_start,
+                    response_time,
+                    body_timeout: effective.timeout.saturating_sub(response_time),
+                };
+                self.process_successful_response(service, response, timing)
                     .await
             }
-            Ok(Err(e)) => {
-                Ok(self.create_error_result(service, response_time, self.format_request_error(&e)))
-            }
-            Err(_) => Ok(self.create_timeout_result(service, response_time)),
+            Ok(Err(e)) => Ok((
+                self.create_error_result(service, response_time, self.format_request_error(&e)),
+                None,
+            )),
+            Err(_) => Ok((self.create_timeout_result(service, response_time), None)),
         }
     }
 
     /// 处理成功的HTTP响应
     ///
+    /// 读取完整的响应体以记录下载耗时，响应体读取失败或超时视为服务异常
+    ///
     /// # 参数
     /// * `service` - 服务配置
     /// * `response` - HTTP响应
-    /// * `response_time` - 响应时间
+    /// * `timing` - 请求计时信息
     ///
     /// # 返回
-    /// * `Result<HealthResult>` - 检测结果
+    /// * `Result<(HealthResult, Option<ResponseContent>)>` - 检测结果，响应体读取完成时附带响应内容
     async fn process_successful_response(
         &self,
         service: &ServiceConfig,
         response: Response,
-        response_time: Duration,
-    ) -> Result<HealthResult> {
+        timing: ResponseTiming,
+    ) -> Result<(HealthResult, Option<ResponseContent>)> {
         let status_code = response.status().as_u16();
-        let is_healthy = self.validate_status_code(status_code, &service.expected_status_codes);
+        let mut is_healthy = self.validate_status_code(status_code, &service.expected_status_codes);
 
-        // 获取响应体大小
-        let response_size = response.content_length().map(|len| len as usize);
+        // 响应体会被消费，先取出需要的响应头
+        let server_header = response
+            .headers()
+            .get("server")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| serde_json::Value::String(s.to_string()));
+
+        let headers = response.headers().clone();
+
+        let headers_at = timing.request_start + timing.response_time;
+        let body = timeout(timing.body_timeout, response.bytes()).await;
+        let body_end = Instant::now();
+
+        let (content, body_error) = match body {
+            Ok(Ok(body)) => (
+                Some(ResponseContent {
+                    headers,
+                    body: body.into(),
+                }),
+                None,
+            ),
+            // 请求级超时同样覆盖响应体的读取
+            Ok(Err(e)) if e.is_timeout() => (None, Some("Response body timeout".to_string())),
+            Ok(Err(e)) => (None, Some(format!("Response body error: {e}"))),
+            Err(_) => (None, Some("Response body timeout".to_string())),
+        };
+        let response_size = content.as_ref().map(|content| content.body.len());
+        let timings = timing.recorder.timings(
+            timing.request_start,
+            headers_at,
+            body_error.is_none().then_some(body_end),
+        );
+        if body_error.is_some() {
+            is_healthy = false;
+        }
 
         let mut result = HealthResult::new(
             service.name.clone(),
@@ -193,13 +544,16 @@ impl HttpHealthChecker {
             service.method.clone(),
         )
         .with_status_code(status_code)
-        .with_response_time(response_time);
+        .with_response_time(timing.response_time)
+        .with_timings(timings);
 
         if let Some(size) = response_size {
             result = result.with_response_size(size);
         }
 
-        if !is_healthy {
+        if let Some(error) = body_error {
+            result = result.with_error(error);
+        } else if !is_healthy {
             result = result.with_error(format!(
                 "HTTP {} {}",
                 status_code,
@@ -210,17 +564,11 @@ impl HttpHealthChecker {
         }
 
         // 添加响应头信息到元数据
-        let server_header = response
-            .headers()
-            .get("server")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| serde_json::Value::String(s.to_string()));
-
         if let Some(server) = server_header {
             result = result.with_metadata("server".to_string(), server);
         }
 
-        Ok(result)
+        Ok((result, content))
     }
 
     /// 创建错误结果
@@ -240,6 +588,31 @@ impl HttpHealthChecker {
         .with_error(error_message)
     }
 
+    /// 创建未实际请求服务的失败结果
+    ///
+    /// 认证令牌获取失败或TLS配置无法加载时服务并未被实际请求，
+    /// 状态记为未知，并在元数据中标记失败类型（`auth` 或 `tls`）
+    fn create_unchecked_result(
+        &self,
+        service: &ServiceConfig,
+        response_time: Duration,
+        error: HealthCheckError,
+        failure_kind: &str,
+    ) -> HealthResult {
+        HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            HealthStatus::Unknown,
+            service.method.clone(),
+        )
+        .with_response_time(response_time)
+        .with_error(error.to_string())
+        .with_metadata(
+            "failure_kind".to_string(),
+            serde_json::Value::String(failure_kind.to_string()),
+        )
+    }
+
     /// 创建超时结果
     fn create_timeout_result(
         &self,
@@ -261,7 +634,11 @@ impl HttpHealthChecker {
         if error.is_timeout() {
             "Request timeout".to_string()
         } else if error.is_connect() {
-            "Connection refused".to_string()
+            // TLS握手失败也属于连接错误，需要从错误链中区分出来
+            match tls_error_detail(error) {
+                Some(detail) => format!("SSL/TLS error: {detail}"),
+                None => "Connection refused".to_string(),
+            }
         } else if error.is_request() {
             "Invalid request".to_string()
         } else if let Some(status) = error.status() {
@@ -291,10 +668,30 @@ impl HttpHealthChecker {
     }
 }
 
+/// 从错误链中提取TLS握手失败的具体原因
+fn tls_error_detail(error: &reqwest::Error) -> Option<String> {
+    let mut source = std::error::Error::source(error);
+    let mut detail = None;
+    while let Some(err) = source {
+        let message = err.to_string();
+        let lower = message.to_lowercase();
+        if lower.contains("certificate")
+            || lower.contains("tls")
+            || lower.contains("handshake")
+            || lower.contains("alert")
+        {
+            detail = Some(message);
+        }
+        source = err.source();
+    }
+    detail
+}
+
 #[async_trait]
 impl HealthChecker for HttpHealthChecker {
     async fn check(&self, service: &ServiceConfig) -> Result<HealthResult> {
-        self.check_with_timeout(service, self.default_timeout).await
+        let effective = self.resolve(service);
+        self.check_with_config(service, &effective).await
     }
 
     async fn check_with_timeout(
@@ -302,19 +699,58 @@ impl HealthChecker for HttpHealthChecker {
         service: &ServiceConfig,
         timeout_duration: Duration,
     ) -> Result<HealthResult> {
+        let mut effective = self.resolve(service);
+        effective.timeout = timeout_duration;
+        self.check_with_config(service, &effective).await
+    }
+
+    async fn check_with_config(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> Result<HealthResult> {
+        let protocol = service.protocol();
+        let retry_attempts = if protocol.retries_failures() {
+            effective.retry_attempts
+        } else {
+            0
+        };
         let mut last_error = None;
 
         // 重试逻辑
-        for attempt in 0..=self.retry_attempts {
-            match self.perform_request(service, timeout_duration).await {
+        for attempt in 0..=retry_attempts {
+            let attempt_result = match protocol {
+                CheckProtocol::Dns => Ok(self.dns.check(service, effective).await),
+                CheckProtocol::Grpc => Ok(self.grpc.check(service, effective).await),
+                CheckProtocol::Database(kind) => {
+                    let checker: &dyn HealthChecker = match kind {
+                        DatabaseKind::Redis => &self.redis,
+                        DatabaseKind::Postgres => &self.postgres,
+                        DatabaseKind::Mysql => &self.mysql,
+                    };
+                    checker.check_with_config(service, effective).await
+                }
+                CheckProtocol::Exec => Ok(self.exec.check(service, effective).await),
+                CheckProtocol::Heartbeat => Ok(self.heartbeat.check(service)),
+                CheckProtocol::Host => Ok(self.host.check(service, effective).await),
+                CheckProtocol::WebSocket => Ok(self.websocket.check(service, effective).await),
+                CheckProtocol::Http if service.steps.is_empty() => {
+                    self.perform_request(service, effective).await
+                }
+                CheckProtocol::Http => self.perform_steps(service, effective).await,
+            };
+            match attempt_result {
                 Ok(result) => {
-                    if result.status.is_healthy() || attempt == self.retry_attempts {
+                    if result.status.is_healthy()
+                        || attempt == retry_attempts
+                        || (result.status == HealthStatus::Degraded && !protocol.retries_degraded())
+                    {
                         return Ok(result);
                     }
                     last_error = result.error_message;
                 }
                 Err(e) => {
-                    if attempt == self.retry_attempts {
+                    if attempt == retry_attempts {
                         return Err(e);
                     }
                     last_error = Some(e.to_string());
@@ -322,8 +758,8 @@ impl HealthChecker for HttpHealthChecker {
             }
 
             // 等待重试间隔
-            if attempt < self.retry_attempts {
-                tokio::time::sleep(self.retry_delay).await;
+            if attempt < retry_attempts {
+                tokio::time::sleep(effective.retry_delay_for(attempt + 1)).await;
             }
         }
 
@@ -339,6 +775,10 @@ impl HealthChecker for HttpHealthChecker {
         let futures = services.iter().map(|service| self.check(service));
         futures::future::join_all(futures).await
     }
+
+    fn heartbeat_registry(&self) -> Option<Arc<HeartbeatRegistry>> {
+        Some(self.heartbeats())
+    }
 }
 
 #[cfg(test)]
@@ -352,16 +792,10 @@ mod tests {
         ServiceConfig {
             name: "Test Service".to_string(),
             url: url.to_string(),
-            method: "GET".to_string(),
             expected_status_codes: expected_codes,
-            feishu_webhook_url: None,
-            failure_threshold: 1,
-            check_interval_seconds: None,
-            enabled: true,
             description: Some("Test service".to_string()),
-            headers: HashMap::new(),
-            body: None,
             alert_cooldown_secs: Some(60),
+            ..Default::default()
         }
     }
 
@@ -529,4 +963,282 @@ mod tests {
                 || error_msg.contains("timeout")
         );
     }
+
+    #[tokio::test]
+    async fn test_global_headers_merged_with_service_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/health")
+            .match_header("x-env", "prod")
+            .match_header("x-token", "service")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let global: GlobalConfig = toml::from_str(
+            r#"
+            [headers]
+            "X-Env" = "prod"
+            "X-Token" = "global"
+            "#,
+        )
+        .unwrap();
+        let checker = HttpHealthChecker::from_global_config(&global).unwrap();
+
+        let mut service = create_test_service(&format!("{}/health", server.url()), vec![200]);
+        service
+            .headers
+            .insert("x-token".to_string(), "service".to_string());
+
+        let result = checker.check(&service).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Up);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_service_timeout_overrides_default() {
+        // 接受连接但从不响应的服务器
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                connections.push(socket);
+            }
+        });
+
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(30), 0, Duration::from_millis(10)).unwrap();
+        let mut service = create_test_service(&format!("http://{addr}/health"), vec![200]);
+        service.request_timeout_seconds = Some(1);
+
+        let start = Instant::now();
+        let result = checker.check(&service).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Down);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_service_retry_policy_overrides_default() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/health")
+            .with_status(500)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_secs(10)).unwrap();
+        let mut service = create_test_service(&format!("{}/health", server.url()), vec![200]);
+        service.retry_attempts = Some(2);
+        service.retry_delay_seconds = Some(0);
+
+        let result = checker.check(&service).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Down);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_auth_applied_to_check() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/token")
+            .with_status(200)
+            .with_body(r#"{"access_token":"abc","expires_in":3600}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let health_mock = server
+            .mock("GET", "/health")
+            .match_header("authorization", "Bearer abc")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap();
+        let mut service = create_test_service(&format!("{}/health", server.url()), vec![200]);
+        service.auth = Some(
+            toml::from_str(&format!(
+                r#"
+                type = "oauth2"
+                token_url = "{}/token"
+                client_id = "client"
+                client_secret = "secret"
+                "#,
+                server.url()
+            ))
+            .unwrap(),
+        );
+
+        for _ in 0..2 {
+            let result = checker.check(&service).await.unwrap();
+            assert_eq!(result.status, HealthStatus::Up);
+        }
+        health_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_token_endpoint_failure_reported_separately() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/token")
+            .with_status(503)
+            .create_async()
+            .await;
+        let health_mock = server
+            .mock("GET", "/health")
+            .with_status(200)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap();
+        let mut service = create_test_service(&format!("{}/health", server.url()), vec![200]);
+        service.auth = Some(crate::config::types::AuthConfig::OAuth2(
+            crate::config::types::OAuth2Config {
+                token_url: format!("{}/token", server.url()),
+                client_id: "client".to_string(),
+                client_secret: "secret".to_string(),
+                scopes: Vec::new(),
+                audience: None,
+                client_auth: Default::default(),
+                refresh_before_seconds: 60,
+            },
+        ));
+
+        let result = checker.check(&service).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Unknown);
+        assert!(result.error_message.unwrap().contains("认证令牌获取失败"));
+        assert_eq!(result.metadata["failure_kind"], "auth");
+        health_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_response_body_and_timings_recorded() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/health")
+            .with_status(200)
+            .with_body("hello world")
+            .create_async()
+            .await;
+
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap();
+        let service = create_test_service(&format!("{}/health", server.url()), vec![200]);
+
+        let first = checker.check(&service).await.unwrap();
+        assert_eq!(first.status, HealthStatus::Up);
+        assert_eq!(first.response_size, Some(11));
+        let timings = first.timings.unwrap();
+        assert!(!timings.connection_reused);
+        assert!(timings.connect_ms.is_some());
+        assert!(timings.download_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_response_body_timeout() {
+        // 返回响应头后不再发送完整响应体的服务器
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let mut connections = Vec::new();
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\nok")
+                    .await;
+                connections.push(socket);
+            }
+        });
+
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(1), 0, Duration::from_millis(10)).unwrap();
+        let service = create_test_service(&format!("http://{addr}/health"), vec![200]);
+
+        let result = checker.check(&service).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Down);
+        assert_eq!(result.status_code, Some(200));
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("Response body timeout")
+        );
+        assert!(result.timings.unwrap().download_ms.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_multi_step_transaction() {
+        let mut server = mockito::Server::new_async().await;
+        let login = server
+            .mock("POST", "/login")
+            .match_body(mockito::Matcher::Json(serde_json::json!({"user": "probe"})))
+            .with_status(200)
+            .with_header("x-request-id", "req-1")
+            .with_body(r#"{"access_token":"tok-123"}"#)
+            .create_async()
+            .await;
+        let profile = server
+            .mock("GET", "/me")
+            .match_header("authorization", "Bearer tok-123")
+            .match_header("x-request-id", "req-1")
+            .with_status(200)
+            .with_body(r#"{"name":"probe"}"#)
+            .create_async()
+            .await;
+
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap();
+        let mut service = create_test_service(&format!("{}/health", server.url()), vec![200]);
+        service.steps = toml::from_str::<HashMap<String, Vec<crate::config::steps::StepConfig>>>(
+            r#"
+            [[steps]]
+            name = "login"
+            url = "/login"
+            method = "POST"
+            body = { user = "probe" }
+            extract = { token = { json = "$.access_token" }, request_id = { header = "x-request-id" } }
+
+            [[steps]]
+            name = "profile"
+            url = "/me"
+            body_contains = "probe"
+            headers = { Authorization = "Bearer ${token}", "X-Request-Id" = "${request_id}" }
+            "#,
+        )
+        .unwrap()
+        .remove("steps")
+        .unwrap();
+
+        let result = checker.check(&service).await.unwrap();
+        assert_eq!(
+            result.status,
+            HealthStatus::Up,
+            "{:?}",
+            result.error_message
+        );
+        assert_eq!(result.status_code, Some(200));
+        let outcomes: Vec<StepOutcome> =
+            serde_json::from_value(result.metadata["steps"].clone()).unwrap();
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0].extracted, vec!["request_id", "token"]);
+        assert!(outcomes[1].url.ends_with("/me"));
+        login.assert_async().await;
+        profile.assert_async().await;
+
+        // 断言失败时整个流程失败，并停止执行后续步骤
+        service.steps[0].body_contains = Some("missing".to_string());
+        let result = checker.check(&service).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Down);
+        assert!(result.error_message.unwrap().starts_with("步骤 login 失败"));
+        let outcomes: Vec<StepOutcome> =
+            serde_json::from_value(result.metadata["steps"].clone()).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].status, HealthStatus::Down);
+    }
 }
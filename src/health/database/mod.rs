@@ -0,0 +1,221 @@
+//! 数据库协议检测
+//!
+//! 每种数据库对应一个 [`HealthChecker`](crate::health::HealthChecker) 实现，
+//! 由 [`HttpHealthChecker`](crate::health::HttpHealthChecker) 按URL前缀选择：
+//!
+//! - `redis://`：登录（AUTH）后执行 `PING`，从 `INFO` 读取版本、角色和复制状态
+//! - `postgres://`：完成启动与认证后执行 `SELECT 1`，查询是否处于恢复模式及回放延迟
+//! - `mysql://`：完成握手与认证后执行 ping，查询复制状态及延迟
+//!
+//! 连接、认证或检测命令失败时服务状态为异常；角色与期望不符为异常，复制延迟超过阈值为降级。
+
+pub mod mysql;
+pub mod postgres;
+pub mod redis;
+
+pub use mysql::MysqlHealthChecker;
+pub use postgres::PostgresHealthChecker;
+pub use redis::RedisHealthChecker;
+
+use crate::config::database::{
+    database_target, DatabaseCheckConfig, DatabaseKind, DatabaseRole, DatabaseTarget,
+};
+use crate::config::ServiceConfig;
+use crate::health::result::{HealthResult, HealthStatus};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// 协议检测得到的服务端信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DatabaseProbe {
+    /// 服务端版本
+    pub server_version: Option<String>,
+    /// 复制角色，无权限查询或服务端不支持时为 None
+    pub role: Option<DatabaseRole>,
+    /// 从库的复制延迟（秒）
+    pub replication_lag_seconds: Option<f64>,
+    /// 其他复制状态信息，原样写入元数据
+    pub details: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 执行一次数据库检测
+///
+/// 解析连接目标后在超时时间内执行 `probe`，并按期望角色和复制延迟阈值判断服务状态
+///
+/// # 参数
+/// * `service` - 服务配置
+/// * `timeout` - 超时时间，包含连接、认证和检测命令
+/// * `method` - 检测结果中显示的检测命令
+/// * `probe` - 按连接目标执行协议检测
+///
+/// # 返回
+/// * `HealthResult` - 检测结果
+pub(crate) async fn run_check<F, Fut>(
+    service: &ServiceConfig,
+    timeout: Duration,
+    method: &str,
+    probe: F,
+) -> HealthResult
+where
+    F: FnOnce(DatabaseTarget) -> Fut,
+    Fut: Future<Output = Result<DatabaseProbe, String>>,
+{
+    let result = HealthResult::new(
+        service.name.clone(),
+        service.url.clone(),
+        HealthStatus::Up,
+        method.to_string(),
+    );
+    let target = match database_target(&service.url, service.database.as_ref()) {
+        Ok(target) => target,
+        Err(e) => {
+            let kind =
+                DatabaseKind::from_url(&service.url).map_or("database", |kind| kind.failure_kind());
+            return failed(result, HealthStatus::Unknown, Duration::ZERO, e, kind);
+        }
+    };
+    let kind = target.kind;
+
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(timeout, probe(target)).await;
+    let latency = start.elapsed();
+
+    let probe = match outcome {
+        Ok(Ok(probe)) => probe,
+        Ok(Err(e)) => {
+            let message = format!("{kind}检测失败: {e}");
+            return failed(
+                result,
+                HealthStatus::Down,
+                latency,
+                message,
+                kind.failure_kind(),
+            );
+        }
+        Err(_) => {
+            let message = format!("{kind}检测超时（{} 秒）", timeout.as_secs_f64());
+            return failed(
+                result,
+                HealthStatus::Down,
+                latency,
+                message,
+                kind.failure_kind(),
+            );
+        }
+    };
+
+    let config = service.database.clone().unwrap_or_default();
+    let (status, error) = evaluate(&config, &probe);
+    let mut result = result
+        .with_response_time(latency)
+        .with_metadata("database".to_string(), metadata(kind, &probe));
+    result.status = status;
+    if let Some(error) = error {
+        result = result.with_error(error);
+    }
+    result
+}
+
+/// 按期望角色和复制延迟阈值判断服务状态
+fn evaluate(config: &DatabaseCheckConfig, probe: &DatabaseProbe) -> (HealthStatus, Option<String>) {
+    if let Some(expected) = config.expected_role {
+        match probe.role {
+            Some(role) if role != expected => {
+                return (
+                    HealthStatus::Down,
+                    Some(format!("复制角色为 {role}，期望 {expected}")),
+                );
+            }
+            None => {
+                return (
+                    HealthStatus::Unknown,
+                    Some(format!("无法获取复制角色，期望 {expected}")),
+                );
+            }
+            Some(_) => {}
+        }
+    }
+
+    if let (Some(max_lag), Some(lag)) = (
+        config.max_replication_lag_seconds,
+        probe.replication_lag_seconds,
+    ) {
+        if lag > max_lag as f64 {
+            return (
+                HealthStatus::Degraded,
+                Some(format!("复制延迟 {lag:.1} 秒，超过阈值 {max_lag} 秒")),
+            );
+        }
+    }
+
+    (HealthStatus::Up, None)
+}
+
+fn metadata(kind: DatabaseKind, probe: &DatabaseProbe) -> serde_json::Value {
+    let mut metadata = probe.details.clone();
+    metadata.insert("kind".to_string(), kind.failure_kind().into());
+    if let Some(version) = &probe.server_version {
+        metadata.insert("server_version".to_string(), version.clone().into());
+    }
+    if let Some(role) = probe.role {
+        metadata.insert("role".to_string(), role.to_string().into());
+    }
+    if let Some(lag) = probe.replication_lag_seconds {
+        metadata.insert("replication_lag_seconds".to_string(), lag.into());
+    }
+    serde_json::Value::Object(metadata)
+}
+
+fn failed(
+    result: HealthResult,
+    status: HealthStatus,
+    latency: Duration,
+    message: String,
+    failure_kind: &str,
+) -> HealthResult {
+    let mut result = result
+        .with_response_time(latency)
+        .with_error(message)
+        .with_metadata(
+            "failure_kind".to_string(),
+            serde_json::Value::String(failure_kind.to_string()),
+        );
+    result.status = status;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_role_and_lag() {
+        let replica = DatabaseProbe {
+            role: Some(DatabaseRole::Replica),
+            replication_lag_seconds: Some(12.0),
+            ..Default::default()
+        };
+
+        let config = DatabaseCheckConfig::default();
+        assert_eq!(evaluate(&config, &replica), (HealthStatus::Up, None));
+
+        let expect_primary = DatabaseCheckConfig {
+            expected_role: Some(DatabaseRole::Primary),
+            ..Default::default()
+        };
+        let (status, error) = evaluate(&expect_primary, &replica);
+        assert_eq!(status, HealthStatus::Down);
+        assert_eq!(error.unwrap(), "复制角色为 replica，期望 primary");
+
+        let (status, _) = evaluate(&expect_primary, &DatabaseProbe::default());
+        assert_eq!(status, HealthStatus::Unknown);
+
+        let max_lag = DatabaseCheckConfig {
+            max_replication_lag_seconds: Some(10),
+            ..Default::default()
+        };
+        let (status, error) = evaluate(&max_lag, &replica);
+        assert_eq!(status, HealthStatus::Degraded);
+        assert_eq!(error.unwrap(), "复制延迟 12.0 秒，超过阈值 10 秒");
+    }
+}
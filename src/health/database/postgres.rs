@@ -0,0 +1,330 @@
+//! PostgreSQL 检测
+//!
+//! 使用 tokio-postgres 完成启动与认证（支持 cleartext、MD5 和 SCRAM-SHA-256），
+//! 执行 `SELECT 1`，然后查询 `pg_is_in_recovery()` 判断主从角色，
+//! 从库的复制延迟取 `now() - pg_last_xact_replay_timestamp()`。
+//! 连接不使用TLS。
+
+use super::{run_check, DatabaseProbe};
+use crate::common::error::Result;
+use crate::config::database::{DatabaseRole, DatabaseTarget};
+use crate::config::ServiceConfig;
+use crate::health::checker::HealthChecker;
+use crate::health::result::HealthResult;
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio_postgres::config::SslMode;
+use tokio_postgres::{NoTls, SimpleQueryMessage};
+
+/// 未配置用户名时使用的默认用户
+const DEFAULT_USER: &str = "postgres";
+
+/// 读取复制角色、回放延迟和版本的查询
+const REPLICATION_QUERY: &str = "SELECT pg_is_in_recovery()::text, \
+     EXTRACT(EPOCH FROM (now() - pg_last_xact_replay_timestamp()))::text, \
+     current_setting('server_version')";
+
+/// PostgreSQL 健康检测器
+#[derive(Debug, Clone)]
+pub struct PostgresHealthChecker {
+    /// 默认超时时间
+    timeout: Duration,
+}
+
+impl PostgresHealthChecker {
+    /// 创建 PostgreSQL 健康检测器
+    ///
+    /// # 参数
+    /// * `timeout` - 默认超时时间
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+#[async_trait]
+impl HealthChecker for PostgresHealthChecker {
+    async fn check(&self, service: &ServiceConfig) -> Result<HealthResult> {
+        self.check_with_timeout(service, self.timeout).await
+    }
+
+    async fn check_with_timeout(
+        &self,
+        service: &ServiceConfig,
+        timeout_duration: Duration,
+    ) -> Result<HealthResult> {
+        Ok(run_check(service, timeout_duration, "SELECT 1", |target| {
+            probe(target, timeout_duration)
+        })
+        .await)
+    }
+
+    async fn check_batch(&self, services: &[ServiceConfig]) -> Vec<Result<HealthResult>> {
+        let futures = services.iter().map(|service| self.check(service));
+        futures::future::join_all(futures).await
+    }
+}
+
+/// 登录并执行 `SELECT 1`，读取版本与复制状态
+async fn probe(
+    target: DatabaseTarget,
+    timeout: Duration,
+) -> std::result::Result<DatabaseProbe, String> {
+    let mut config = tokio_postgres::Config::new();
+    config
+        .host(&target.host)
+        .port(target.port)
+        .user(target.username.as_deref().unwrap_or(DEFAULT_USER))
+        .application_name("service-vitals")
+        .connect_timeout(timeout)
+        .ssl_mode(SslMode::Disable);
+    if let Some(password) = &target.password {
+        config.password(password);
+    }
+    if let Some(database) = &target.database {
+        config.dbname(database);
+    }
+
+    let (client, connection) = config.connect(NoTls).await.map_err(describe_error)?;
+    let connection = tokio::spawn(connection);
+
+    let result = async {
+        client
+            .simple_query("SELECT 1")
+            .await
+            .map_err(describe_error)?;
+
+        let mut probe = DatabaseProbe::default();
+        // 监控账号可能无权调用复制相关函数，此时只报告 SELECT 1 结果
+        if let Ok(messages) = client.simple_query(REPLICATION_QUERY).await {
+            if let Some(row) = messages.iter().find_map(|message| match message {
+                SimpleQueryMessage::Row(row) => Some(row),
+                _ => None,
+            }) {
+                apply_replication_row(&mut probe, row.get(0), row.get(1), row.get(2));
+            }
+        }
+        Ok(probe)
+    }
+    .await;
+
+    drop(client);
+    let _ = connection.await;
+    result
+}
+
+/// 根据复制查询的结果设置角色、延迟和版本
+///
+/// 从库尚未回放过任何事务时 `pg_last_xact_replay_timestamp()` 为 NULL，此时不报告延迟
+fn apply_replication_row(
+    probe: &mut DatabaseProbe,
+    in_recovery: Option<&str>,
+    replay_lag: Option<&str>,
+    server_version: Option<&str>,
+) {
+    probe.role = match in_recovery {
+        Some("true") => Some(DatabaseRole::Replica),
+        Some("false") => Some(DatabaseRole::Primary),
+        _ => None,
+    };
+    if probe.role == Some(DatabaseRole::Replica) {
+        probe.replication_lag_seconds = replay_lag
+            .and_then(|lag| lag.parse::<f64>().ok())
+            .map(|lag| lag.max(0.0));
+    }
+    probe.server_version = server_version.map(str::to_string);
+}
+
+/// 生成可读的错误信息，服务端错误带上 SQLSTATE
+fn describe_error(error: tokio_postgres::Error) -> String {
+    match error.as_db_error() {
+        Some(db_error) => format!("{} ({})", db_error.message(), db_error.code().code()),
+        None => {
+            let mut message = error.to_string();
+            if let Some(source) = std::error::Error::source(&error) {
+                message.push_str(&format!(": {source}"));
+            }
+            message
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::result::HealthStatus;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    fn message(tag: u8, body: &[u8]) -> Vec<u8> {
+        let mut message = vec![tag];
+        message.extend_from_slice(&(body.len() as i32 + 4).to_be_bytes());
+        message.extend_from_slice(body);
+        message
+    }
+
+    fn row_description(columns: &[&str]) -> Vec<u8> {
+        let mut body = (columns.len() as i16).to_be_bytes().to_vec();
+        for column in columns {
+            body.extend_from_slice(column.as_bytes());
+            body.push(0);
+            // table oid, column, type oid (text), size, modifier, format
+            body.extend_from_slice(&0i32.to_be_bytes());
+            body.extend_from_slice(&0i16.to_be_bytes());
+            body.extend_from_slice(&25i32.to_be_bytes());
+            body.extend_from_slice(&(-1i16).to_be_bytes());
+            body.extend_from_slice(&(-1i32).to_be_bytes());
+            body.extend_from_slice(&0i16.to_be_bytes());
+        }
+        message(b'T', &body)
+    }
+
+    fn data_row(values: &[Option<&str>]) -> Vec<u8> {
+        let mut body = (values.len() as i16).to_be_bytes().to_vec();
+        for value in values {
+            match value {
+                Some(value) => {
+                    body.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                    body.extend_from_slice(value.as_bytes());
+                }
+                None => body.extend_from_slice(&(-1i32).to_be_bytes()),
+            }
+        }
+        message(b'D', &body)
+    }
+
+    fn ready() -> Vec<u8> {
+        message(b'Z', b"I")
+    }
+
+    async fn read_message(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)> {
+        let tag = stream.read_u8().await.ok()?;
+        let len = stream.read_i32().await.ok()?;
+        let mut body = vec![0u8; len as usize - 4];
+        stream.read_exact(&mut body).await.ok()?;
+        Some((tag, body))
+    }
+
+    /// 本地 PostgreSQL 桩服务器：cleartext 密码认证（密码 `secret`），
+    /// 复制查询返回 `replication_row`
+    async fn stub_postgres_server(
+        replication_row: [Option<&'static str>; 3],
+    ) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    // 启动消息没有类型字节
+                    let len = stream.read_i32().await.unwrap();
+                    let mut startup = vec![0u8; len as usize - 4];
+                    stream.read_exact(&mut startup).await.unwrap();
+
+                    stream
+                        .write_all(&message(b'R', &3i32.to_be_bytes()))
+                        .await
+                        .unwrap();
+                    let (_, password) = read_message(&mut stream).await.unwrap();
+                    if password != b"secret\0" {
+                        let error =
+                            b"SFATAL\0C28P01\0Mpassword authentication failed for user \"monitor\"\0\0";
+                        let _ = stream.write_all(&message(b'E', error)).await;
+                        return;
+                    }
+                    let mut reply = message(b'R', &0i32.to_be_bytes());
+                    reply.extend(message(b'K', &[0u8; 8]));
+                    reply.extend(ready());
+                    stream.write_all(&reply).await.unwrap();
+
+                    while let Some((tag, body)) = read_message(&mut stream).await {
+                        if tag != b'Q' {
+                            break;
+                        }
+                        let query = String::from_utf8_lossy(&body);
+                        let mut reply = Vec::new();
+                        if query.starts_with("SELECT 1") {
+                            reply.extend(row_description(&["?column?"]));
+                            reply.extend(data_row(&[Some("1")]));
+                        } else {
+                            reply.extend(row_description(&["a", "b", "c"]));
+                            reply.extend(data_row(&replication_row));
+                        }
+                        reply.extend(message(b'C', b"SELECT 1\0"));
+                        reply.extend(ready());
+                        stream.write_all(&reply).await.unwrap();
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    fn service(url: String, database: &str) -> ServiceConfig {
+        toml::from_str(&format!(
+            "name = \"postgres\"\nurl = \"{url}\"\n[database]\n{database}"
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_postgres_select_and_replication() {
+        let addr = stub_postgres_server([Some("true"), Some("42.5"), Some("16.2")]).await;
+        let checker = PostgresHealthChecker::new(Duration::from_secs(2));
+
+        let replica = service(
+            format!("postgres://monitor@{addr}/app"),
+            "password = \"secret\"\nexpected_role = \"standby\"",
+        );
+        let result = checker.check(&replica).await.unwrap();
+        assert_eq!(
+            result.status,
+            HealthStatus::Up,
+            "{:?}",
+            result.error_message
+        );
+        assert_eq!(result.method, "SELECT 1");
+        let metadata = &result.metadata["database"];
+        assert_eq!(metadata["kind"], "postgres");
+        assert_eq!(metadata["server_version"], "16.2");
+        assert_eq!(metadata["role"], "replica");
+        assert_eq!(metadata["replication_lag_seconds"], 42.5);
+
+        let lagging = service(
+            format!("postgresql://monitor:secret@{addr}"),
+            "max_replication_lag_seconds = 30",
+        );
+        let result = checker.check(&lagging).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Degraded);
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("复制延迟 42.5 秒，超过阈值 30 秒")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_postgres_auth_failure() {
+        let addr = stub_postgres_server([Some("false"), None, Some("16.2")]).await;
+        let checker = PostgresHealthChecker::new(Duration::from_secs(2));
+
+        let wrong_password = service(format!("postgres://monitor:wrong@{addr}"), "");
+        let result = checker.check(&wrong_password).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Down);
+        assert_eq!(result.metadata["failure_kind"], "postgres");
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("PostgreSQL检测失败: password authentication failed for user \"monitor\" (28P01)")
+        );
+
+        let primary = service(format!("postgres://monitor:secret@{addr}"), "");
+        let result = checker.check(&primary).await.unwrap();
+        assert_eq!(
+            result.status,
+            HealthStatus::Up,
+            "{:?}",
+            result.error_message
+        );
+        assert_eq!(result.metadata["database"]["role"], "primary");
+        assert!(result.metadata["database"]
+            .get("replication_lag_seconds")
+            .is_none());
+    }
+}
@@ -0,0 +1,369 @@
+//! Redis 检测
+//!
+//! 直接使用 RESP 协议：配置了密码时先 `AUTH`，指定数据库编号时 `SELECT`，
+//! 然后 `PING` 并期望 `PONG`；最后读取 `INFO server` 与 `INFO replication`
+//! 获取版本、角色和复制状态（`INFO` 被禁用时忽略）。
+
+use super::{run_check, DatabaseProbe};
+use crate::common::error::Result;
+use crate::config::database::{DatabaseRole, DatabaseTarget};
+use crate::config::ServiceConfig;
+use crate::health::checker::HealthChecker;
+use crate::health::result::HealthResult;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// Redis 健康检测器
+#[derive(Debug, Clone)]
+pub struct RedisHealthChecker {
+    /// 默认超时时间
+    timeout: Duration,
+}
+
+impl RedisHealthChecker {
+    /// 创建 Redis 健康检测器
+    ///
+    /// # 参数
+    /// * `timeout` - 默认超时时间
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+#[async_trait]
+impl HealthChecker for RedisHealthChecker {
+    async fn check(&self, service: &ServiceConfig) -> Result<HealthResult> {
+        self.check_with_timeout(service, self.timeout).await
+    }
+
+    async fn check_with_timeout(
+        &self,
+        service: &ServiceConfig,
+        timeout_duration: Duration,
+    ) -> Result<HealthResult> {
+        Ok(run_check(service, timeout_duration, "PING", probe).await)
+    }
+
+    async fn check_batch(&self, services: &[ServiceConfig]) -> Vec<Result<HealthResult>> {
+        let futures = services.iter().map(|service| self.check(service));
+        futures::future::join_all(futures).await
+    }
+}
+
+/// RESP 应答
+#[derive(Debug, Clone, PartialEq)]
+enum Reply {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<Reply>),
+}
+
+/// Redis 连接
+struct Connection {
+    stream: BufReader<TcpStream>,
+}
+
+impl Connection {
+    async fn connect(target: &DatabaseTarget) -> std::result::Result<Self, String> {
+        let stream = TcpStream::connect((target.host.as_str(), target.port))
+            .await
+            .map_err(|e| format!("无法连接 {}:{}: {e}", target.host, target.port))?;
+        Ok(Self {
+            stream: BufReader::new(stream),
+        })
+    }
+
+    /// 发送命令并读取应答，错误应答转为 Err
+    async fn command(&mut self, args: &[&str]) -> std::result::Result<Reply, String> {
+        let mut request = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            request.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            request.extend_from_slice(arg.as_bytes());
+            request.extend_from_slice(b"\r\n");
+        }
+        self.stream
+            .get_mut()
+            .write_all(&request)
+            .await
+            .map_err(|e| format!("发送命令失败: {e}"))?;
+
+        match Box::pin(self.read_reply()).await? {
+            Reply::Error(message) => Err(message),
+            reply => Ok(reply),
+        }
+    }
+
+    async fn read_reply(&mut self) -> std::result::Result<Reply, String> {
+        let mut line = String::new();
+        let read = self
+            .stream
+            .read_line(&mut line)
+            .await
+            .map_err(|e| format!("读取应答失败: {e}"))?;
+        if read == 0 {
+            return Err("连接已被服务端关闭".to_string());
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (kind, rest) = line.split_at(line.len().min(1));
+        let invalid = || format!("无效的应答: {line}");
+        match kind {
+            "+" => Ok(Reply::Simple(rest.to_string())),
+            "-" => Ok(Reply::Error(rest.to_string())),
+            ":" => rest.parse().map(Reply::Integer).map_err(|_| invalid()),
+            "$" => {
+                let len: i64 = rest.parse().map_err(|_| invalid())?;
+                if len < 0 {
+                    return Ok(Reply::Bulk(None));
+                }
+                let mut data = vec![0u8; len as usize + 2];
+                self.stream
+                    .read_exact(&mut data)
+                    .await
+                    .map_err(|e| format!("读取应答失败: {e}"))?;
+                data.truncate(len as usize);
+                Ok(Reply::Bulk(Some(data)))
+            }
+            "*" => {
+                let len: i64 = rest.parse().map_err(|_| invalid())?;
+                let mut items = Vec::with_capacity(len.max(0) as usize);
+                for _ in 0..len {
+                    items.push(Box::pin(self.read_reply()).await?);
+                }
+                Ok(Reply::Array(items))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// 登录并执行 PING，读取版本与复制状态
+async fn probe(target: DatabaseTarget) -> std::result::Result<DatabaseProbe, String> {
+    let mut connection = Connection::connect(&target).await?;
+
+    if let Some(password) = &target.password {
+        let mut args = vec!["AUTH"];
+        if let Some(username) = &target.username {
+            args.push(username);
+        }
+        args.push(password);
+        connection
+            .command(&args)
+            .await
+            .map_err(|e| format!("认证失败: {e}"))?;
+    }
+    if let Some(database) = &target.database {
+        connection
+            .command(&["SELECT", database])
+            .await
+            .map_err(|e| format!("无法选择数据库 {database}: {e}"))?;
+    }
+
+    match connection.command(&["PING"]).await? {
+        Reply::Simple(pong) if pong == "PONG" => {}
+        reply => return Err(format!("PING 应答异常: {reply:?}")),
+    }
+
+    let mut probe = DatabaseProbe::default();
+    // INFO 可能被 rename-command 禁用或因 ACL 无权限，此时只报告 PING 结果
+    if let Ok(server) = info(&mut connection, "server").await {
+        probe.server_version = server.get("redis_version").cloned();
+    }
+    if let Ok(replication) = info(&mut connection, "replication").await {
+        apply_replication_info(&mut probe, &replication);
+    }
+    Ok(probe)
+}
+
+/// 执行 `INFO <section>` 并解析为键值对
+async fn info(
+    connection: &mut Connection,
+    section: &str,
+) -> std::result::Result<HashMap<String, String>, String> {
+    let Reply::Bulk(Some(data)) = connection.command(&["INFO", section]).await? else {
+        return Err("INFO 应答异常".to_string());
+    };
+    Ok(String::from_utf8_lossy(&data)
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect())
+}
+
+/// 从 `INFO replication` 中读取角色和复制状态
+///
+/// 从库的复制延迟取 `master_last_io_seconds_ago`（距上次与主库交互的秒数），
+/// 复制链路断开时该值为 -1，此时不报告延迟
+fn apply_replication_info(probe: &mut DatabaseProbe, info: &HashMap<String, String>) {
+    probe.role = match info.get("role").map(String::as_str) {
+        Some("master") => Some(DatabaseRole::Primary),
+        Some("slave") | Some("replica") => Some(DatabaseRole::Replica),
+        _ => None,
+    };
+    match probe.role {
+        Some(DatabaseRole::Primary) => {
+            if let Some(replicas) = info
+                .get("connected_slaves")
+                .and_then(|v| v.parse::<u64>().ok())
+            {
+                probe
+                    .details
+                    .insert("connected_replicas".to_string(), replicas.into());
+            }
+        }
+        Some(DatabaseRole::Replica) => {
+            if let Some(status) = info.get("master_link_status") {
+                probe
+                    .details
+                    .insert("master_link_status".to_string(), status.clone().into());
+            }
+            probe.replication_lag_seconds = info
+                .get("master_last_io_seconds_ago")
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|lag| *lag >= 0.0);
+        }
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::result::HealthStatus;
+    use tokio::net::TcpListener;
+
+    /// 本地 Redis 桩服务器，密码为 `secret`，`INFO replication` 返回 `replication`
+    async fn stub_redis_server(replication: &'static str) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut connection = Connection {
+                        stream: BufReader::new(stream),
+                    };
+                    let mut authenticated = false;
+                    while let Ok(Reply::Array(args)) = connection.read_reply().await {
+                        let args: Vec<String> = args
+                            .into_iter()
+                            .map(|arg| match arg {
+                                Reply::Bulk(Some(data)) => String::from_utf8(data).unwrap(),
+                                other => panic!("unexpected argument {other:?}"),
+                            })
+                            .collect();
+                        let reply = match (args[0].as_str(), authenticated) {
+                            ("AUTH", _) if args.last().unwrap() == "secret" => {
+                                authenticated = true;
+                                "+OK\r\n".to_string()
+                            }
+                            ("AUTH", _) => {
+                                "-WRONGPASS invalid username-password pair\r\n".to_string()
+                            }
+                            (_, false) => "-NOAUTH Authentication required.\r\n".to_string(),
+                            ("SELECT", true) => "+OK\r\n".to_string(),
+                            ("PING", true) => "+PONG\r\n".to_string(),
+                            ("INFO", true) => {
+                                let body = if args[1] == "server" {
+                                    "# Server\r\nredis_version:7.2.4\r\n"
+                                } else {
+                                    replication
+                                };
+                                format!("${}\r\n{body}\r\n", body.len())
+                            }
+                            _ => "-ERR unknown command\r\n".to_string(),
+                        };
+                        let _ = connection
+                            .stream
+                            .get_mut()
+                            .write_all(reply.as_bytes())
+                            .await;
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    fn service(url: String, database: &str) -> ServiceConfig {
+        toml::from_str(&format!(
+            "name = \"redis\"\nurl = \"{url}\"\n[database]\n{database}"
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_redis_ping_with_auth_and_role() {
+        let addr = stub_redis_server(
+            "# Replication\r\nrole:slave\r\nmaster_link_status:up\r\nmaster_last_io_seconds_ago:3\r\n",
+        )
+        .await;
+        let checker = RedisHealthChecker::new(Duration::from_secs(2));
+
+        let ok = service(
+            format!("redis://{addr}/1"),
+            "password = \"secret\"\nexpected_role = \"replica\"",
+        );
+        let result = checker.check(&ok).await.unwrap();
+        assert_eq!(
+            result.status,
+            HealthStatus::Up,
+            "{:?}",
+            result.error_message
+        );
+        assert_eq!(result.method, "PING");
+        let metadata = &result.metadata["database"];
+        assert_eq!(metadata["server_version"], "7.2.4");
+        assert_eq!(metadata["role"], "replica");
+        assert_eq!(metadata["replication_lag_seconds"], 3.0);
+        assert_eq!(metadata["master_link_status"], "up");
+
+        let wrong_role = service(
+            format!("redis://:secret@{addr}"),
+            "expected_role = \"master\"",
+        );
+        let result = checker.check(&wrong_role).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Down);
+        assert_eq!(
+            result.error_message.as_deref(),
+            Some("复制角色为 replica，期望 primary")
+        );
+
+        let lagging = service(
+            format!("redis://{addr}"),
+            "password = \"secret\"\nmax_replication_lag_seconds = 1",
+        );
+        let result = checker.check(&lagging).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Degraded);
+    }
+
+    #[tokio::test]
+    async fn test_redis_auth_failure() {
+        let addr =
+            stub_redis_server("# Replication\r\nrole:master\r\nconnected_slaves:2\r\n").await;
+        let checker = RedisHealthChecker::new(Duration::from_secs(2));
+
+        let wrong_password = service(format!("redis://{addr}"), "password = \"wrong\"");
+        let result = checker.check(&wrong_password).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Down);
+        assert_eq!(result.metadata["failure_kind"], "redis");
+        assert!(result
+            .error_message
+            .unwrap()
+            .contains("认证失败: WRONGPASS"));
+
+        let no_password = service(format!("redis://{addr}"), "");
+        let result = checker.check(&no_password).await.unwrap();
+        assert!(result.error_message.unwrap().contains("NOAUTH"));
+
+        let primary = service(format!("redis://{addr}"), "password = \"secret\"");
+        let result = checker.check(&primary).await.unwrap();
+        assert_eq!(result.status, HealthStatus::Up);
+        assert_eq!(result.metadata["database"]["role"], "primary");
+        assert_eq!(result.metadata["database"]["connected_replicas"], 2);
+    }
+}
@@ -0,0 +1,136 @@
+//! 检测任务分发
+//!
+//! 所有服务共享一个定时器：到期时间保存在最小堆中，
+//! 定时器任务只负责在到期时把检测任务投递给工作池，
+//! 由固定数量的工作任务执行检测并把下一次调度时间送回定时器。
+//! 这样服务数量增长时不再需要为每个服务创建独立的任务和定时器。
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Instant;
+use tracing::debug;
+
+/// 一次待执行的服务检测
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ScheduledCheck {
+    /// 计划执行时间
+    pub due: Instant,
+    /// 服务注册代数，服务重新注册或移除后旧的调度会被丢弃
+    pub generation: u64,
+    /// 服务名称
+    pub service_name: String,
+}
+
+/// 工作池共享的任务队列
+pub type JobQueue = Arc<Mutex<mpsc::UnboundedReceiver<ScheduledCheck>>>;
+
+/// 从共享任务队列中取出下一个检测任务
+///
+/// 队列关闭时返回None
+pub async fn next_job(jobs: &JobQueue) -> Option<ScheduledCheck> {
+    jobs.lock().await.recv().await
+}
+
+/// 运行共享定时器
+///
+/// 从 `schedules` 接收新的调度请求，到期后按时间顺序投递到 `jobs`。
+/// 调度请求通道关闭或任务队列关闭时退出。
+///
+/// # 参数
+/// * `schedules` - 调度请求接收端
+/// * `jobs` - 到期任务发送端
+pub async fn run_timer(
+    mut schedules: mpsc::UnboundedReceiver<ScheduledCheck>,
+    jobs: mpsc::UnboundedSender<ScheduledCheck>,
+) {
+    let mut queue: BinaryHeap<Reverse<ScheduledCheck>> = BinaryHeap::new();
+
+    loop {
+        let next_due = queue.peek().map(|Reverse(check)| check.due);
+
+        tokio::select! {
+            schedule = schedules.recv() => match schedule {
+                Some(check) => {
+                    queue.push(Reverse(check));
+                    // 批量接收已到达的调度请求，减少定时器重建次数
+                    while let Ok(check) = schedules.try_recv() {
+                        queue.push(Reverse(check));
+                    }
+                }
+                None => break,
+            },
+            _ = tokio::time::sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {
+                let now = Instant::now();
+                while queue.peek().is_some_and(|Reverse(check)| check.due <= now) {
+                    let Some(Reverse(check)) = queue.pop() else {
+                        break;
+                    };
+                    if jobs.send(check).is_err() {
+                        debug!("检测任务队列已关闭，定时器退出");
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    debug!("调度请求通道已关闭，定时器退出");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn check(name: &str, delay_ms: u64) -> ScheduledCheck {
+        ScheduledCheck {
+            due: Instant::now() + Duration::from_millis(delay_ms),
+            generation: 1,
+            service_name: name.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timer_delivers_in_due_order() {
+        let (schedule_tx, schedule_rx) = mpsc::unbounded_channel();
+        let (job_tx, job_rx) = mpsc::unbounded_channel();
+        let timer = tokio::spawn(run_timer(schedule_rx, job_tx));
+        let jobs: JobQueue = Arc::new(Mutex::new(job_rx));
+
+        schedule_tx.send(check("late", 80)).unwrap();
+        schedule_tx.send(check("early", 10)).unwrap();
+        schedule_tx.send(check("middle", 40)).unwrap();
+
+        let mut order = Vec::new();
+        for _ in 0..3 {
+            let job = tokio::time::timeout(Duration::from_secs(2), next_job(&jobs))
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(job.due <= Instant::now());
+            order.push(job.service_name);
+        }
+        assert_eq!(order, vec!["early", "middle", "late"]);
+
+        // 关闭调度通道后定时器退出
+        drop(schedule_tx);
+        tokio::time::timeout(Duration::from_secs(2), timer)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_timer_does_not_deliver_before_due() {
+        let (schedule_tx, schedule_rx) = mpsc::unbounded_channel();
+        let (job_tx, job_rx) = mpsc::unbounded_channel();
+        tokio::spawn(run_timer(schedule_rx, job_tx));
+        let jobs: JobQueue = Arc::new(Mutex::new(job_rx));
+
+        schedule_tx.send(check("svc", 500)).unwrap();
+        let early = tokio::time::timeout(Duration::from_millis(100), next_job(&jobs)).await;
+        assert!(early.is_err());
+    }
+}
@@ -0,0 +1,536 @@
+//! DNS解析检测
+//!
+//! 对 `dns://域名` 形式的服务，按服务的DNS检测配置向指定DNS服务器（或系统DNS配置）
+//! 查询记录，校验期望的应答，并在应答变化或解析耗时超过阈值时将服务标记为降级。
+
+use crate::config::dns::{dns_query_name, DnsCheckConfig, DnsRecordType};
+use crate::config::effective::EffectiveServiceConfig;
+use crate::config::ServiceConfig;
+use crate::health::result::{HealthResult, HealthStatus};
+use hickory_resolver::config::{NameServerConfigGroup, ResolveHosts, ResolverConfig};
+use hickory_resolver::name_server::TokioConnectionProvider;
+use hickory_resolver::proto::rr::{RData, RecordType};
+use hickory_resolver::{ResolveError, Resolver, TokioResolver};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// DNS解析检测器
+///
+/// 记录每个服务上次的应答，用于 `alert_on_change` 检测应答变化
+#[derive(Debug, Default)]
+pub struct DnsChecker {
+    /// 上次检测的应答，键为服务名称、域名和记录类型
+    previous: Mutex<HashMap<String, BTreeSet<String>>>,
+}
+
+impl DnsChecker {
+    /// 创建DNS解析检测器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 执行一次DNS解析检测
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置，使用其中的超时时间
+    ///
+    /// # 返回
+    /// * `HealthResult` - 检测结果，解析失败时状态为异常并标记失败类型 `dns`
+    pub async fn check(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> HealthResult {
+        let config = service.dns.clone().unwrap_or_default();
+        let record_type = config.record_type;
+        let result = HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            HealthStatus::Up,
+            record_type.to_string(),
+        );
+
+        let prepared = dns_query_name(&service.url).and_then(|name| {
+            build_resolver(&config, effective.timeout).map(|resolver| (name, resolver))
+        });
+        let (name, resolver) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => return failed(result, HealthStatus::Unknown, Duration::ZERO, e),
+        };
+
+        let start = Instant::now();
+        let lookup = tokio::time::timeout(
+            effective.timeout,
+            resolver.lookup(name.as_str(), to_record_type(record_type)),
+        )
+        .await;
+        let latency = start.elapsed();
+
+        let answers = match lookup {
+            Ok(Ok(lookup)) => lookup
+                .record_iter()
+                .filter_map(|record| format_answer(record.data()))
+                .filter(|(answer_type, _)| *answer_type == record_type)
+                .map(|(_, answer)| answer)
+                .collect::<BTreeSet<_>>(),
+            Ok(Err(e)) => {
+                let message = format!("DNS解析失败: {}", describe_error(&e, record_type));
+                return failed(result, HealthStatus::Down, latency, message);
+            }
+            Err(_) => {
+                let message = format!("DNS解析超时（{} 秒）", effective.timeout.as_secs_f64());
+                return failed(result, HealthStatus::Down, latency, message);
+            }
+        };
+        if answers.is_empty() {
+            let message = format!("DNS解析失败: 没有 {record_type} 记录");
+            return failed(result, HealthStatus::Down, latency, message);
+        }
+
+        let previous = self.remember(service, &name, record_type, &answers);
+        let (status, error) = evaluate(&config, &answers, previous.as_ref(), latency);
+
+        let mut result = result
+            .with_response_time(latency)
+            .with_metadata("dns".to_string(), metadata(&config, &answers));
+        result.status = status;
+        if let Some(error) = error {
+            result = result.with_error(error);
+        }
+        result
+    }
+
+    /// 记录本次应答并返回上次的应答
+    fn remember(
+        &self,
+        service: &ServiceConfig,
+        name: &str,
+        record_type: DnsRecordType,
+        answers: &BTreeSet<String>,
+    ) -> Option<BTreeSet<String>> {
+        let key = format!("{}\u{0}{}\u{0}{}", service.name, name, record_type);
+        self.previous
+            .lock()
+            .expect("DNS应答记录锁已损坏")
+            .insert(key, answers.clone())
+    }
+}
+
+/// 按期望应答、上次应答和解析耗时判断服务状态
+fn evaluate(
+    config: &DnsCheckConfig,
+    answers: &BTreeSet<String>,
+    previous: Option<&BTreeSet<String>>,
+    latency: Duration,
+) -> (HealthStatus, Option<String>) {
+    let expected: BTreeSet<String> = config.normalized_expected().into_iter().collect();
+    let missing: Vec<&String> = expected.difference(answers).collect();
+    if !missing.is_empty() {
+        return (
+            HealthStatus::Down,
+            Some(format!(
+                "DNS应答不符合预期: 缺少 {}，实际应答 {}",
+                join(missing),
+                join(answers)
+            )),
+        );
+    }
+    if config.exact {
+        let unexpected: Vec<&String> = answers.difference(&expected).collect();
+        if !unexpected.is_empty() {
+            return (
+                HealthStatus::Down,
+                Some(format!("DNS应答不符合预期: 多出 {}", join(unexpected))),
+            );
+        }
+    }
+
+    if config.alert_on_change {
+        if let Some(previous) = previous.filter(|previous| *previous != answers) {
+            return (
+                HealthStatus::Degraded,
+                Some(format!(
+                    "DNS应答发生变化: {} → {}",
+                    join(previous),
+                    join(answers)
+                )),
+            );
+        }
+    }
+
+    if let Some(max_latency_ms) = config.max_latency_ms {
+        let latency_ms = latency.as_millis();
+        if latency_ms > u128::from(max_latency_ms) {
+            return (
+                HealthStatus::Degraded,
+                Some(format!(
+                    "DNS解析耗时 {latency_ms} 毫秒，超过阈值 {max_latency_ms} 毫秒"
+                )),
+            );
+        }
+    }
+
+    (HealthStatus::Up, None)
+}
+
+/// 创建解析器
+///
+/// 每次检测使用新的解析器且不启用缓存，确保每次都向DNS服务器查询；
+/// 重试由检测器的重试策略负责，解析器只尝试一次
+fn build_resolver(config: &DnsCheckConfig, timeout: Duration) -> Result<TokioResolver, String> {
+    let provider = TokioConnectionProvider::default();
+    let mut builder = match config.nameserver_addr()? {
+        Some(addr) => {
+            let servers = NameServerConfigGroup::from_ips_clear(&[addr.ip()], addr.port(), true);
+            Resolver::builder_with_config(
+                ResolverConfig::from_parts(None, vec![], servers),
+                provider,
+            )
+        }
+        None => Resolver::builder(provider).map_err(|e| format!("无法读取系统DNS配置: {e}"))?,
+    };
+    let options = builder.options_mut();
+    options.timeout = timeout;
+    options.attempts = 1;
+    options.cache_size = 0;
+    options.use_hosts_file = ResolveHosts::Never;
+    Ok(builder.build())
+}
+
+fn to_record_type(record_type: DnsRecordType) -> RecordType {
+    match record_type {
+        DnsRecordType::A => RecordType::A,
+        DnsRecordType::Aaaa => RecordType::AAAA,
+        DnsRecordType::Cname => RecordType::CNAME,
+        DnsRecordType::Txt => RecordType::TXT,
+        DnsRecordType::Srv => RecordType::SRV,
+    }
+}
+
+/// 将应答记录格式化为与期望应答相同的规范文本
+fn format_answer(data: &RData) -> Option<(DnsRecordType, String)> {
+    let (record_type, text) = match data {
+        RData::A(a) => (DnsRecordType::A, a.to_string()),
+        RData::AAAA(aaaa) => (DnsRecordType::Aaaa, aaaa.to_string()),
+        RData::CNAME(cname) => (DnsRecordType::Cname, cname.to_string()),
+        RData::TXT(txt) => (
+            DnsRecordType::Txt,
+            txt.txt_data()
+                .iter()
+                .map(|part| String::from_utf8_lossy(part))
+                .collect::<String>(),
+        ),
+        RData::SRV(srv) => (
+            DnsRecordType::Srv,
+            format!(
+                "{} {} {} {}",
+                srv.priority(),
+                srv.weight(),
+                srv.port(),
+                srv.target()
+            ),
+        ),
+        _ => return None,
+    };
+    record_type
+        .normalize(&text)
+        .ok()
+        .map(|text| (record_type, text))
+}
+
+/// 生成可读的解析错误
+fn describe_error(error: &ResolveError, record_type: DnsRecordType) -> String {
+    if error.is_nx_domain() {
+        "域名不存在 (NXDOMAIN)".to_string()
+    } else if error.is_no_records_found() {
+        format!("没有 {record_type} 记录")
+    } else {
+        error.to_string()
+    }
+}
+
+fn metadata(config: &DnsCheckConfig, answers: &BTreeSet<String>) -> serde_json::Value {
+    serde_json::json!({
+        "record_type": config.record_type.to_string(),
+        "nameserver": config.nameserver.as_deref().unwrap_or("system"),
+        "answers": answers,
+    })
+}
+
+fn failed(
+    result: HealthResult,
+    status: HealthStatus,
+    latency: Duration,
+    message: String,
+) -> HealthResult {
+    let mut result = result
+        .with_response_time(latency)
+        .with_error(message)
+        .with_metadata(
+            "failure_kind".to_string(),
+            serde_json::Value::String("dns".to_string()),
+        );
+    result.status = status;
+    result
+}
+
+fn join<'a>(answers: impl IntoIterator<Item = &'a String>) -> String {
+    let answers: Vec<&str> = answers.into_iter().map(String::as_str).collect();
+    format!("[{}]", answers.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::effective::RequestDefaults;
+    use hickory_resolver::proto::op::{Message, MessageType, ResponseCode};
+    use hickory_resolver::proto::rr::rdata::{A, SRV, TXT};
+    use hickory_resolver::proto::rr::{Name, Record};
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use tokio::net::UdpSocket;
+
+    type Zone = Arc<Mutex<HashMap<(String, RecordType), Vec<RData>>>>;
+
+    /// 本地DNS桩服务器，按 zone 中的记录应答，不存在的域名返回 NXDOMAIN
+    async fn stub_dns_server(zone: Zone) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 512];
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    return;
+                };
+                let Ok(request) = Message::from_vec(&buf[..len]) else {
+                    continue;
+                };
+                let mut response = Message::new();
+                response
+                    .set_id(request.id())
+                    .set_message_type(MessageType::Response)
+                    .set_recursion_desired(request.recursion_desired())
+                    .set_recursion_available(true)
+                    .set_authoritative(true);
+                let known_names: Vec<String>;
+                let answers = {
+                    let zone = zone.lock().unwrap();
+                    known_names = zone.keys().map(|(name, _)| name.clone()).collect();
+                    request.queries().first().map(|query| {
+                        response.add_query(query.clone());
+                        let name = query.name().to_string().to_ascii_lowercase();
+                        let records = zone
+                            .get(&(name.clone(), query.query_type()))
+                            .cloned()
+                            .unwrap_or_default();
+                        (name, query.name().clone(), records)
+                    })
+                };
+                if let Some((name, query_name, records)) = answers {
+                    if !known_names.contains(&name) {
+                        response.set_response_code(ResponseCode::NXDomain);
+                    }
+                    for data in records {
+                        response.add_answer(Record::from_rdata(query_name.clone(), 60, data));
+                    }
+                }
+                let _ = socket.send_to(&response.to_vec().unwrap(), peer).await;
+            }
+        });
+        addr
+    }
+
+    fn zone(records: Vec<(&str, RecordType, RData)>) -> Zone {
+        let mut zone: HashMap<(String, RecordType), Vec<RData>> = HashMap::new();
+        for (name, record_type, data) in records {
+            zone.entry((format!("{name}."), record_type))
+                .or_default()
+                .push(data);
+        }
+        Arc::new(Mutex::new(zone))
+    }
+
+    fn a(ip: [u8; 4]) -> RData {
+        RData::A(A(Ipv4Addr::from(ip)))
+    }
+
+    fn service(name: &str, dns: &str) -> ServiceConfig {
+        toml::from_str(&format!(
+            "name = \"dns\"\nurl = \"dns://{name}\"\n[dns]\n{dns}"
+        ))
+        .unwrap()
+    }
+
+    fn effective(service: &ServiceConfig) -> EffectiveServiceConfig {
+        let defaults = RequestDefaults {
+            headers: HashMap::new(),
+            timeout: Duration::from_secs(2),
+            retry_attempts: 0,
+            retry_delay: Duration::from_secs(1),
+            retry_backoff: Default::default(),
+            check_interval_seconds: 60,
+        };
+        EffectiveServiceConfig::resolve(&defaults, service)
+    }
+
+    #[tokio::test]
+    async fn test_dns_check_expected_answers() {
+        let zone = zone(vec![
+            ("app.test", RecordType::A, a([10, 0, 0, 1])),
+            ("app.test", RecordType::A, a([10, 0, 0, 2])),
+            (
+                "app.test",
+                RecordType::TXT,
+                RData::TXT(TXT::new(vec!["v=spf1 -all".to_string()])),
+            ),
+            (
+                "_sip._tcp.app.test",
+                RecordType::SRV,
+                RData::SRV(SRV::new(
+                    10,
+                    5,
+                    5060,
+                    Name::from_str("sip.app.test.").unwrap(),
+                )),
+            ),
+        ]);
+        let addr = stub_dns_server(zone).await;
+        let checker = DnsChecker::new();
+
+        let service_a = service(
+            "app.test",
+            &format!("nameserver = \"{addr}\"\nexpected = [\"10.0.0.1\"]"),
+        );
+        let result = checker.check(&service_a, &effective(&service_a)).await;
+        assert_eq!(
+            result.status,
+            HealthStatus::Up,
+            "{:?}",
+            result.error_message
+        );
+        assert_eq!(result.method, "A");
+        assert_eq!(
+            result.metadata["dns"]["answers"],
+            serde_json::json!(["10.0.0.1", "10.0.0.2"])
+        );
+
+        let exact = service(
+            "app.test",
+            &format!("nameserver = \"{addr}\"\nexpected = [\"10.0.0.1\"]\nexact = true"),
+        );
+        let result = checker.check(&exact, &effective(&exact)).await;
+        assert_eq!(result.status, HealthStatus::Down);
+        assert!(result.error_message.unwrap().contains("多出 [10.0.0.2]"));
+
+        let txt = service(
+            "app.test",
+            &format!(
+                "nameserver = \"{addr}\"\nrecord_type = \"TXT\"\nexpected = [\"v=spf1 -all\"]"
+            ),
+        );
+        let result = checker.check(&txt, &effective(&txt)).await;
+        assert_eq!(
+            result.status,
+            HealthStatus::Up,
+            "{:?}",
+            result.error_message
+        );
+
+        let srv = service(
+            "_sip._tcp.app.test",
+            &format!(
+                "nameserver = \"{addr}\"\nrecord_type = \"SRV\"\nexpected = [\"10 5 5060 sip.app.test\"]"
+            ),
+        );
+        let result = checker.check(&srv, &effective(&srv)).await;
+        assert_eq!(
+            result.status,
+            HealthStatus::Up,
+            "{:?}",
+            result.error_message
+        );
+
+        let wrong = service(
+            "app.test",
+            &format!("nameserver = \"{addr}\"\nexpected = [\"10.0.0.9\"]"),
+        );
+        let result = checker.check(&wrong, &effective(&wrong)).await;
+        assert_eq!(result.status, HealthStatus::Down);
+        assert!(result.error_message.unwrap().contains("缺少 [10.0.0.9]"));
+    }
+
+    #[tokio::test]
+    async fn test_dns_check_resolution_failure() {
+        let addr = stub_dns_server(zone(vec![("app.test", RecordType::A, a([10, 0, 0, 1]))])).await;
+        let checker = DnsChecker::new();
+
+        let missing = service("missing.test", &format!("nameserver = \"{addr}\""));
+        let result = checker.check(&missing, &effective(&missing)).await;
+        assert_eq!(result.status, HealthStatus::Down);
+        assert_eq!(result.metadata["failure_kind"], "dns");
+        assert!(result.error_message.unwrap().contains("NXDOMAIN"));
+
+        let no_aaaa = service(
+            "app.test",
+            &format!("nameserver = \"{addr}\"\nrecord_type = \"AAAA\""),
+        );
+        let result = checker.check(&no_aaaa, &effective(&no_aaaa)).await;
+        assert_eq!(result.status, HealthStatus::Down);
+        assert!(result.error_message.unwrap().contains("没有 AAAA 记录"));
+    }
+
+    #[tokio::test]
+    async fn test_dns_check_alerts_on_change() {
+        use crate::health::checker::{HealthChecker, HttpHealthChecker};
+
+        let zone = zone(vec![("app.test", RecordType::A, a([10, 0, 0, 1]))]);
+        let addr = stub_dns_server(zone.clone()).await;
+        // 默认配置会重试非正常结果，应答变化的降级结果不能被重试吞掉
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(2), 3, Duration::from_millis(10)).unwrap();
+        let service = service(
+            "app.test",
+            &format!("nameserver = \"{addr}\"\nalert_on_change = true"),
+        );
+
+        assert_eq!(
+            checker.check(&service).await.unwrap().status,
+            HealthStatus::Up
+        );
+
+        zone.lock().unwrap().insert(
+            ("app.test.".to_string(), RecordType::A),
+            vec![a([10, 0, 0, 3])],
+        );
+        let changed = checker.check(&service).await.unwrap();
+        assert_eq!(changed.status, HealthStatus::Degraded);
+        assert_eq!(
+            changed.error_message.as_deref(),
+            Some("DNS应答发生变化: [10.0.0.1] → [10.0.0.3]")
+        );
+
+        assert_eq!(
+            checker.check(&service).await.unwrap().status,
+            HealthStatus::Up
+        );
+    }
+
+    #[test]
+    fn test_evaluate_latency_threshold() {
+        let config = DnsCheckConfig {
+            max_latency_ms: Some(50),
+            ..Default::default()
+        };
+        let answers = BTreeSet::from(["10.0.0.1".to_string()]);
+
+        let (status, _) = evaluate(&config, &answers, None, Duration::from_millis(10));
+        assert_eq!(status, HealthStatus::Up);
+
+        let (status, error) = evaluate(&config, &answers, None, Duration::from_millis(120));
+        assert_eq!(status, HealthStatus::Degraded);
+        assert!(error.unwrap().contains("超过阈值 50 毫秒"));
+    }
+}
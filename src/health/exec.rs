@@ -0,0 +1,586 @@
+//! 命令检测
+//!
+//! 对 `exec://` 服务执行配置的命令，兼容 Nagios 插件约定：
+//! 退出码 0/1/2/3 分别对应正常/降级/异常/未知；输出第一行 `|` 之前为状态信息，
+//! 之后以及后续行中 `|` 之后的内容为性能数据，解析后写入检测结果元数据。
+
+use crate::config::effective::EffectiveServiceConfig;
+use crate::config::exec::ExecCheckConfig;
+use crate::config::ServiceConfig;
+use crate::health::result::{HealthResult, HealthStatus};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+/// 检测结果中显示的请求方法
+const EXEC_METHOD: &str = "EXEC";
+/// 元数据中保留的多行输出的最大长度
+const MAX_LONG_OUTPUT: usize = 4096;
+/// 标准输出和标准错误各自读取的最大字节数，超出部分丢弃
+const MAX_OUTPUT_BYTES: u64 = 64 * 1024;
+
+/// 命令检测器
+#[derive(Debug, Default)]
+pub struct ExecChecker;
+
+impl ExecChecker {
+    /// 创建命令检测器
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 执行一次命令检测
+    ///
+    /// 命令不经过shell执行，标准输入为空；命令在独立的进程组中运行，超时后终止整个进程组
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置，使用其中的超时时间
+    ///
+    /// # 返回
+    /// * `HealthResult` - 检测结果，命令无法执行、超时或被信号终止时标记失败类型 `exec`
+    pub async fn check(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> HealthResult {
+        let result = HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            HealthStatus::Up,
+            EXEC_METHOD.to_string(),
+        );
+        let Some(config) = service
+            .exec
+            .as_ref()
+            .filter(|exec| !exec.command.is_empty())
+        else {
+            let message = "未配置 exec.command".to_string();
+            return failed(result, HealthStatus::Unknown, Duration::ZERO, message);
+        };
+        let program = &config.command[0];
+
+        let start = Instant::now();
+        let mut child = match command(config).spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                let message = format!("无法执行命令 {program}: {e}");
+                return failed(result, HealthStatus::Unknown, start.elapsed(), message);
+            }
+        };
+        let pid = child.id();
+        let (stdout, stderr) = (child.stdout.take(), child.stderr.take());
+        let outcome = tokio::time::timeout(effective.timeout, async {
+            tokio::try_join!(child.wait(), read_capped(stdout), read_capped(stderr))
+        })
+        .await;
+        let latency = start.elapsed();
+
+        let (status, stdout, stderr) = match outcome {
+            Ok(Ok(output)) => output,
+            Ok(Err(e)) => {
+                let message = format!("读取命令 {program} 的输出失败: {e}");
+                return failed(result, HealthStatus::Unknown, latency, message);
+            }
+            // kill_on_drop 只终止子进程本身，命令启动的后台进程需要随进程组一起终止，
+            // 在丢弃子进程之前终止，此时命令尚未被回收，进程组ID不会被复用；
+            // 与 Nagios 默认行为一致视为 CRITICAL
+            Err(_) => {
+                kill_process_group(pid);
+                drop(child);
+                let message = format!(
+                    "命令 {program} 执行超时（{} 秒）",
+                    effective.timeout.as_secs_f64()
+                );
+                return failed(result, HealthStatus::Down, latency, message);
+            }
+        };
+
+        let Some(exit_code) = status.code() else {
+            let message = format!("命令 {program} 被信号终止: {status}");
+            return failed(result, HealthStatus::Unknown, latency, message);
+        };
+
+        let stdout = String::from_utf8_lossy(&stdout);
+        let mut plugin_output = PluginOutput::parse(&stdout);
+        if plugin_output.text.is_empty() {
+            // 插件没有标准输出时使用标准错误的第一行作为状态信息
+            let stderr = String::from_utf8_lossy(&stderr);
+            plugin_output.text = stderr.lines().next().unwrap_or_default().trim().to_string();
+        }
+        let status = map_exit_code(exit_code);
+
+        let mut exec = serde_json::json!({
+            "command": program,
+            "exit_code": exit_code,
+            "output": plugin_output.text,
+        });
+        if !plugin_output.long_text.is_empty() {
+            exec["long_output"] = truncate(&plugin_output.long_text, MAX_LONG_OUTPUT).into();
+        }
+        let mut result = result
+            .with_response_time(latency)
+            .with_metadata("exec".to_string(), exec);
+        let perfdata = parse_perfdata(&plugin_output.perfdata);
+        if !perfdata.is_empty() {
+            result =
+                result.with_metadata("perfdata".to_string(), serde_json::Value::Object(perfdata));
+        }
+        result.status = status;
+        if status != HealthStatus::Up {
+            let message = if plugin_output.text.is_empty() {
+                format!("命令 {program} 退出码 {exit_code}，没有输出")
+            } else {
+                plugin_output.text
+            };
+            result = result.with_error(message);
+        }
+        result
+    }
+}
+
+/// 按配置构造命令：控制环境变量和工作目录，标准输入为空
+fn command(config: &ExecCheckConfig) -> Command {
+    let mut command = Command::new(&config.command[0]);
+    command
+        .args(&config.command[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // 独立的进程组，超时后可以终止命令派生的所有进程
+    #[cfg(unix)]
+    command.process_group(0);
+    if !config.inherit_env {
+        command.env_clear();
+        if let Some(path) = std::env::var_os("PATH") {
+            command.env("PATH", path);
+        }
+    }
+    command.envs(&config.env);
+    if let Some(dir) = &config.working_dir {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// 读取命令输出，最多保留 [`MAX_OUTPUT_BYTES`] 字节
+///
+/// 超出部分继续读取并丢弃，避免命令因管道写满而阻塞
+async fn read_capped<R: AsyncRead + Unpin>(reader: Option<R>) -> std::io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    if let Some(mut reader) = reader {
+        (&mut reader)
+            .take(MAX_OUTPUT_BYTES)
+            .read_to_end(&mut buffer)
+            .await?;
+        tokio::io::copy(&mut reader, &mut tokio::io::sink()).await?;
+    }
+    Ok(buffer)
+}
+
+/// 终止命令所在的进程组（进程组ID即命令的进程ID）
+///
+/// 需要在子进程被回收之前调用，或进程组中仍有其他进程时调用，进程组ID才不会被复用
+#[cfg(unix)]
+fn kill_process_group(pid: Option<u32>) {
+    if let Some(pgid) = pid.and_then(|pid| i32::try_from(pid).ok()) {
+        unsafe {
+            libc::kill(-pgid, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: Option<u32>) {}
+
+/// 按 Nagios 插件约定将退出码映射为健康状态，超出 0-3 的退出码视为未知
+fn map_exit_code(exit_code: i32) -> HealthStatus {
+    match exit_code {
+        0 => HealthStatus::Up,
+        1 => HealthStatus::Degraded,
+        2 => HealthStatus::Down,
+        _ => HealthStatus::Unknown,
+    }
+}
+
+/// 插件输出
+#[derive(Debug, Default, PartialEq)]
+struct PluginOutput {
+    /// 第一行 `|` 之前的状态信息
+    text: String,
+    /// 后续行中的多行输出
+    long_text: String,
+    /// 所有性能数据，以空格分隔
+    perfdata: String,
+}
+
+impl PluginOutput {
+    /// 解析插件输出
+    ///
+    /// 格式为 `状态信息 | 性能数据`，之后的多行输出中第一个 `|` 之后的内容（含后续各行）
+    /// 也是性能数据
+    fn parse(stdout: &str) -> Self {
+        let mut lines = stdout.lines();
+        let first = lines.next().unwrap_or_default();
+        let (text, mut perfdata) = match first.split_once('|') {
+            Some((text, perfdata)) => (text, perfdata.trim().to_string()),
+            None => (first, String::new()),
+        };
+
+        let mut long_lines = Vec::new();
+        let mut in_perfdata = false;
+        for line in lines {
+            let data = if in_perfdata {
+                line
+            } else if let Some((text, data)) = line.split_once('|') {
+                long_lines.push(text);
+                in_perfdata = true;
+                data
+            } else {
+                long_lines.push(line);
+                continue;
+            };
+            if !data.trim().is_empty() {
+                if !perfdata.is_empty() {
+                    perfdata.push(' ');
+                }
+                perfdata.push_str(data.trim());
+            }
+        }
+
+        Self {
+            text: text.trim().to_string(),
+            long_text: long_lines.join("\n").trim().to_string(),
+            perfdata,
+        }
+    }
+}
+
+/// 解析性能数据 `'label'=value[UOM];[warn];[crit];[min];[max]`
+///
+/// 标签可以用单引号包含空格（`''` 表示单引号）；值为 `U` 时表示无法获取，记为 null。
+/// 格式无效的项被忽略
+fn parse_perfdata(perfdata: &str) -> serde_json::Map<String, serde_json::Value> {
+    let mut parsed = serde_json::Map::new();
+    let mut rest = perfdata.trim_start();
+    while !rest.is_empty() {
+        let (label, after_label) = match rest.strip_prefix('\'') {
+            Some(quoted) => match take_quoted(quoted) {
+                Some(taken) => taken,
+                None => break,
+            },
+            None => match rest.find(|c: char| c == '=' || c.is_whitespace()) {
+                Some(pos) => (rest[..pos].to_string(), &rest[pos..]),
+                None => break,
+            },
+        };
+        let Some(after_eq) = after_label.strip_prefix('=') else {
+            // 跳过无效项
+            rest = after_label
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, rest)| rest.trim_start());
+            continue;
+        };
+        let (data, next) = after_eq
+            .split_once(char::is_whitespace)
+            .unwrap_or((after_eq, ""));
+        rest = next.trim_start();
+
+        if let Some(value) = parse_perf_value(data) {
+            if !label.is_empty() {
+                parsed.insert(label, value);
+            }
+        }
+    }
+    parsed
+}
+
+/// 读取单引号标签，返回标签和剩余内容
+fn take_quoted(input: &str) -> Option<(String, &str)> {
+    let mut label = String::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\'' {
+            if matches!(chars.peek(), Some((_, '\''))) {
+                chars.next();
+                label.push('\'');
+            } else {
+                return Some((label, &input[i + 1..]));
+            }
+        } else {
+            label.push(c);
+        }
+    }
+    None
+}
+
+/// 解析 `value[UOM];[warn];[crit];[min];[max]`
+fn parse_perf_value(data: &str) -> Option<serde_json::Value> {
+    let mut fields = data.split(';');
+    let value_uom = fields.next()?;
+    let number_len = value_uom
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+        .unwrap_or(value_uom.len());
+    let (number, uom) = value_uom.split_at(number_len);
+    let value = if value_uom == "U" {
+        serde_json::Value::Null
+    } else {
+        number.parse::<f64>().ok()?.into()
+    };
+
+    let mut entry = serde_json::Map::new();
+    entry.insert("value".to_string(), value);
+    if !uom.is_empty() && value_uom != "U" {
+        entry.insert("uom".to_string(), uom.into());
+    }
+    for (key, field) in ["warn", "crit", "min", "max"].into_iter().zip(fields) {
+        if field.is_empty() {
+            continue;
+        }
+        // 阈值可以是范围（如 `10:20`、`@~:5`），保留原始字符串；最小值和最大值为数字
+        let value = match key {
+            "min" | "max" => match field.parse::<f64>() {
+                Ok(number) => number.into(),
+                Err(_) => continue,
+            },
+            _ => field.into(),
+        };
+        entry.insert(key.to_string(), value);
+    }
+    Some(serde_json::Value::Object(entry))
+}
+
+fn truncate(text: &str, max_len: usize) -> String {
+    if text.len() <= max_len {
+        return text.to_string();
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+fn failed(
+    result: HealthResult,
+    status: HealthStatus,
+    latency: Duration,
+    message: String,
+) -> HealthResult {
+    let mut result = result
+        .with_response_time(latency)
+        .with_error(message)
+        .with_metadata(
+            "failure_kind".to_string(),
+            serde_json::Value::String("exec".to_string()),
+        );
+    result.status = status;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::effective::RequestDefaults;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_plugin_output() {
+        let output = PluginOutput::parse(
+            "DISK OK - free space: / 3326 MB (56%); | /=2643MB;5948;5958;0;5968\n\
+             / 15272 MB (77%);\n\
+             /boot 68 MB (69%); | /boot=68MB;88;93;0;98\n\
+             /home=69357MB;253404;253409;0;253414\n",
+        );
+        assert_eq!(output.text, "DISK OK - free space: / 3326 MB (56%);");
+        assert_eq!(output.long_text, "/ 15272 MB (77%);\n/boot 68 MB (69%);");
+        assert_eq!(
+            output.perfdata,
+            "/=2643MB;5948;5958;0;5968 /boot=68MB;88;93;0;98 /home=69357MB;253404;253409;0;253414"
+        );
+
+        let plain = PluginOutput::parse("PING OK");
+        assert_eq!(plain.text, "PING OK");
+        assert!(plain.perfdata.is_empty());
+    }
+
+    #[test]
+    fn test_parse_perfdata() {
+        let perfdata = parse_perfdata(
+            "junk time=0.012s;1;5;0 'in use'=85%;80;90;; 'it''s'=U rta=oops size=1024",
+        );
+        assert_eq!(
+            perfdata["time"],
+            serde_json::json!({"value": 0.012, "uom": "s", "warn": "1", "crit": "5", "min": 0.0})
+        );
+        assert_eq!(
+            perfdata["in use"],
+            serde_json::json!({"value": 85.0, "uom": "%", "warn": "80", "crit": "90"})
+        );
+        assert_eq!(perfdata["it's"], serde_json::json!({"value": null}));
+        assert!(!perfdata.contains_key("rta"));
+        assert_eq!(perfdata["size"], serde_json::json!({"value": 1024.0}));
+    }
+
+    fn service(command: &[&str], extra: &str) -> ServiceConfig {
+        toml::from_str(&format!(
+            "name = \"exec\"\nurl = \"exec://check\"\n[exec]\ncommand = {command:?}\n{extra}"
+        ))
+        .unwrap()
+    }
+
+    fn effective(service: &ServiceConfig, timeout: Duration) -> EffectiveServiceConfig {
+        let defaults = RequestDefaults {
+            headers: HashMap::new(),
+            timeout,
+            retry_attempts: 0,
+            retry_delay: Duration::from_secs(1),
+            retry_backoff: Default::default(),
+            check_interval_seconds: 60,
+        };
+        EffectiveServiceConfig::resolve(&defaults, service)
+    }
+
+    async fn run(command: &[&str], extra: &str, timeout: Duration) -> HealthResult {
+        let service = service(command, extra);
+        ExecChecker::new()
+            .check(&service, &effective(&service, timeout))
+            .await
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_exec_exit_codes_and_output() {
+        let timeout = Duration::from_secs(5);
+        let ok = run(
+            &["sh", "-c", "echo 'OK - 3 jobs | jobs=3;5;10'"],
+            "",
+            timeout,
+        )
+        .await;
+        assert_eq!(ok.status, HealthStatus::Up);
+        assert_eq!(ok.error_message, None);
+        assert_eq!(ok.metadata["exec"]["output"], "OK - 3 jobs");
+        assert_eq!(ok.metadata["perfdata"]["jobs"]["value"], 3.0);
+
+        let warning = run(
+            &["sh", "-c", "echo 'WARNING - 6 jobs'; exit 1"],
+            "",
+            timeout,
+        )
+        .await;
+        assert_eq!(warning.status, HealthStatus::Degraded);
+        assert_eq!(warning.error_message.as_deref(), Some("WARNING - 6 jobs"));
+
+        let critical = run(&["sh", "-c", "echo 'CRITICAL' >&2; exit 2"], "", timeout).await;
+        assert_eq!(critical.status, HealthStatus::Down);
+        assert_eq!(critical.error_message.as_deref(), Some("CRITICAL"));
+        assert!(!critical.metadata.contains_key("failure_kind"));
+
+        let unknown = run(&["sh", "-c", "exit 7"], "", timeout).await;
+        assert_eq!(unknown.status, HealthStatus::Unknown);
+        assert_eq!(unknown.metadata["exec"]["exit_code"], 7);
+
+        let missing = run(&["/nonexistent/check_nothing"], "", timeout).await;
+        assert_eq!(missing.status, HealthStatus::Unknown);
+        assert_eq!(missing.metadata["failure_kind"], "exec");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_exec_environment_and_timeout() {
+        std::env::set_var("SERVICE_VITALS_EXEC_TEST", "leaked");
+        let script = [
+            "sh",
+            "-c",
+            "echo \"$SERVICE_VITALS_EXEC_TEST $CHECK_MODE $(pwd)\"",
+        ];
+        let timeout = Duration::from_secs(5);
+
+        let isolated = run(
+            &script,
+            "working_dir = \"/\"\nenv = { CHECK_MODE = \"strict\" }",
+            timeout,
+        )
+        .await;
+        assert_eq!(isolated.metadata["exec"]["output"], "strict /");
+
+        let inherited = run(&script, "inherit_env = true", timeout).await;
+        assert!(inherited.metadata["exec"]["output"]
+            .as_str()
+            .unwrap()
+            .starts_with("leaked"));
+
+        let slow = run(&["sleep", "5"], "", Duration::from_millis(200)).await;
+        assert_eq!(slow.status, HealthStatus::Down);
+        assert_eq!(slow.metadata["failure_kind"], "exec");
+        assert!(slow.response_time < Duration::from_secs(2));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_exec_warning_is_not_retried() {
+        use crate::health::checker::{HealthChecker, HttpHealthChecker};
+
+        let dir = tempfile::tempdir().unwrap();
+        let runs = dir.path().join("runs");
+        let script = format!("echo run >> {}; echo 'WARNING'; exit 1", runs.display());
+        // 默认配置会重试非正常结果，插件的 WARNING 结论不应重复执行命令
+        let checker =
+            HttpHealthChecker::new(Duration::from_secs(5), 3, Duration::from_millis(10)).unwrap();
+
+        let result = checker
+            .check(&service(&["sh", "-c", &script], ""))
+            .await
+            .unwrap();
+        assert_eq!(result.status, HealthStatus::Degraded);
+        assert_eq!(std::fs::read_to_string(&runs).unwrap().lines().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_output_is_capped() {
+        let large = vec![b'a'; MAX_OUTPUT_BYTES as usize * 3];
+        let output = read_capped(Some(large.as_slice())).await.unwrap();
+        assert_eq!(output.len(), MAX_OUTPUT_BYTES as usize);
+        assert!(read_capped(None::<&[u8]>).await.unwrap().is_empty());
+
+        // 超出上限的输出不会阻塞命令
+        let noisy = run(
+            &[
+                "sh",
+                "-c",
+                "head -c 1000000 /dev/zero | tr '\\0' x; echo; echo OK",
+            ],
+            "",
+            Duration::from_secs(5),
+        )
+        .await;
+        assert_eq!(noisy.status, HealthStatus::Up);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_exec_timeout_kills_process_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let script = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+
+        let slow = run(&["sh", "-c", &script], "", Duration::from_millis(300)).await;
+        assert_eq!(slow.status, HealthStatus::Down);
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        // 后台进程被终止后可能短暂处于僵尸状态，等待其被回收
+        let mut alive = true;
+        for _ in 0..50 {
+            alive = std::fs::read_to_string(&stat).is_ok_and(|stat| !stat.contains(") Z "));
+            if !alive {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!alive, "命令派生的后台进程在超时后仍在运行");
+    }
+}
@@ -0,0 +1,349 @@
+//! gRPC健康检测
+//!
+//! 对 `grpc://` 和 `grpcs://` 服务调用 gRPC Health Checking Protocol 的
+//! `grpc.health.v1.Health/Check`，按返回的服务状态映射为 [`HealthStatus`]。
+//! `grpcs://` 使用与HTTP检测相同的TLS配置（CA证书、客户端证书、SNI等），ALPN协商 h2。
+
+use crate::common::error::HealthCheckError;
+use crate::config::effective::EffectiveServiceConfig;
+use crate::config::grpc::{grpc_target, GrpcTarget};
+use crate::config::types::TlsConfig;
+use crate::config::ServiceConfig;
+use crate::health::result::{HealthResult, HealthStatus};
+use crate::health::tls::rustls_config;
+use hyper_util::rt::TokioIo;
+use rustls::pki_types::ServerName;
+use rustls::ClientConfig;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error as StdError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tonic::metadata::{MetadataKey, MetadataValue};
+use tonic::transport::{Channel, Endpoint, Uri};
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+
+/// 检测结果中显示的请求方法
+const GRPC_METHOD: &str = "grpc.health.v1.Health/Check";
+
+/// gRPC健康检测器
+///
+/// 按TLS配置缓存 rustls 客户端配置，避免每次检测重新读取证书文件
+#[derive(Debug, Default)]
+pub struct GrpcChecker {
+    tls_configs: Mutex<HashMap<TlsConfig, Arc<ClientConfig>>>,
+}
+
+impl GrpcChecker {
+    /// 创建gRPC健康检测器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 执行一次gRPC健康检测
+    ///
+    /// 每次检测建立新连接，响应时间包含连接、TLS握手和 Check 调用的耗时
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置，使用其中的超时时间和请求头（作为gRPC元数据发送）
+    ///
+    /// # 返回
+    /// * `HealthResult` - 检测结果
+    pub async fn check(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> HealthResult {
+        let health_service = service
+            .grpc
+            .as_ref()
+            .map(|grpc| grpc.service.clone())
+            .unwrap_or_default();
+        let result = HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            HealthStatus::Up,
+            GRPC_METHOD.to_string(),
+        );
+
+        let prepared = grpc_target(&service.url).and_then(|target| {
+            check_request(&health_service, &effective.headers).map(|request| (target, request))
+        });
+        let (target, request) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => return failed(result, HealthStatus::Unknown, Duration::ZERO, e, "grpc"),
+        };
+        let tls = if target.tls {
+            match self.tls_config(service.tls.clone().unwrap_or_default()) {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    return failed(
+                        result,
+                        HealthStatus::Unknown,
+                        Duration::ZERO,
+                        e.to_string(),
+                        "tls",
+                    )
+                }
+            }
+        } else {
+            None
+        };
+        let server_name = service.tls.as_ref().and_then(|tls| tls.server_name.clone());
+
+        let start = Instant::now();
+        let outcome = tokio::time::timeout(
+            effective.timeout,
+            call(&target, tls, server_name, request, effective.timeout),
+        )
+        .await;
+        let latency = start.elapsed();
+
+        let serving_status = match outcome {
+            Ok(Ok(serving_status)) => serving_status,
+            Ok(Err(e)) => return failed(result, HealthStatus::Down, latency, e, "grpc"),
+            Err(_) => {
+                let message = format!("gRPC请求超时（{} 秒）", effective.timeout.as_secs_f64());
+                return failed(result, HealthStatus::Down, latency, message, "grpc");
+            }
+        };
+
+        let (status, error) = map_serving_status(serving_status, &health_service);
+        let mut result = result.with_response_time(latency).with_metadata(
+            "grpc".to_string(),
+            serde_json::json!({
+                "service": health_service,
+                "serving_status": serving_status.as_str_name(),
+                "tls": target.tls,
+            }),
+        );
+        result.status = status;
+        if let Some(error) = error {
+            result = result.with_error(error);
+        }
+        result
+    }
+
+    /// 获取TLS配置对应的 rustls 客户端配置，首次使用时创建
+    fn tls_config(&self, tls: TlsConfig) -> Result<Arc<ClientConfig>, HealthCheckError> {
+        if let Some(config) = self.lock_tls_configs().get(&tls) {
+            return Ok(Arc::clone(config));
+        }
+        let mut config = rustls_config(&tls)?;
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        let config = Arc::new(config);
+        Ok(Arc::clone(
+            self.lock_tls_configs().entry(tls).or_insert(config),
+        ))
+    }
+
+    fn lock_tls_configs(&self) -> std::sync::MutexGuard<'_, HashMap<TlsConfig, Arc<ClientConfig>>> {
+        self.tls_configs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 将 Check 返回的服务状态映射为健康状态
+fn map_serving_status(
+    serving_status: ServingStatus,
+    service: &str,
+) -> (HealthStatus, Option<String>) {
+    match serving_status {
+        ServingStatus::Serving => (HealthStatus::Up, None),
+        ServingStatus::NotServing => (
+            HealthStatus::Down,
+            Some(format!(
+                "gRPC服务{}状态为 NOT_SERVING",
+                display_service(service)
+            )),
+        ),
+        ServingStatus::Unknown | ServingStatus::ServiceUnknown => (
+            HealthStatus::Unknown,
+            Some(format!(
+                "gRPC服务{}状态为 {}",
+                display_service(service),
+                serving_status.as_str_name()
+            )),
+        ),
+    }
+}
+
+/// 建立连接并调用 Check
+async fn call(
+    target: &GrpcTarget,
+    tls: Option<Arc<ClientConfig>>,
+    server_name: Option<String>,
+    request: tonic::Request<HealthCheckRequest>,
+    timeout: Duration,
+) -> Result<ServingStatus, String> {
+    let endpoint = Endpoint::from_shared(target.uri())
+        .map_err(|e| format!("gRPC地址无效: {}", error_chain(&e)))?
+        .connect_timeout(timeout)
+        .timeout(timeout);
+    let channel = match tls {
+        Some(config) => connect_tls(&endpoint, target, config, server_name).await,
+        None => endpoint.connect().await,
+    }
+    .map_err(|e| format!("无法连接gRPC服务: {}", error_chain(&e)))?;
+
+    let service = request.get_ref().service.clone();
+    let response = HealthClient::new(channel)
+        .check(request)
+        .await
+        .map_err(|status| describe_status(&status, &service))?;
+    Ok(response.into_inner().status())
+}
+
+/// 通过TLS建立连接，ALPN协商 h2；配置了 `server_name` 时用于SNI和证书校验
+async fn connect_tls(
+    endpoint: &Endpoint,
+    target: &GrpcTarget,
+    config: Arc<ClientConfig>,
+    server_name: Option<String>,
+) -> Result<Channel, tonic::transport::Error> {
+    let host = target.host.clone();
+    let port = target.port;
+    let server_name = server_name.unwrap_or_else(|| host.clone());
+    let connector = tower::service_fn(move |_: Uri| {
+        let connector = TlsConnector::from(Arc::clone(&config));
+        let host = host.clone();
+        let server_name = server_name.clone();
+        async move {
+            let server_name = ServerName::try_from(server_name)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+            let stream = TcpStream::connect((host.as_str(), port)).await?;
+            let stream = connector.connect(server_name, stream).await?;
+            Ok::<_, std::io::Error>(TokioIo::new(stream))
+        }
+    });
+    endpoint.connect_with_connector(connector).await
+}
+
+/// 构造 Check 请求，请求头作为gRPC元数据发送
+fn check_request(
+    service: &str,
+    headers: &BTreeMap<String, String>,
+) -> Result<tonic::Request<HealthCheckRequest>, String> {
+    let mut request = tonic::Request::new(HealthCheckRequest {
+        service: service.to_string(),
+    });
+    for (key, value) in headers {
+        let metadata_key = MetadataKey::from_bytes(key.to_ascii_lowercase().as_bytes())
+            .map_err(|_| format!("请求头 {key} 不能作为gRPC元数据"))?;
+        let metadata_value = MetadataValue::try_from(value.as_str())
+            .map_err(|_| format!("请求头 {key} 的值不能作为gRPC元数据"))?;
+        request.metadata_mut().insert(metadata_key, metadata_value);
+    }
+    Ok(request)
+}
+
+/// 生成可读的gRPC错误
+fn describe_status(status: &tonic::Status, service: &str) -> String {
+    match status.code() {
+        tonic::Code::NotFound => format!("gRPC服务{}未注册健康状态", display_service(service)),
+        tonic::Code::Unimplemented => "服务端未实现 grpc.health.v1.Health".to_string(),
+        code => {
+            let mut message = format!("gRPC调用失败: {code:?}");
+            if !status.message().is_empty() {
+                message.push_str(&format!(": {}", status.message()));
+            }
+            if let Some(source) = status.source() {
+                message.push_str(&format!(" ({})", error_chain(source)));
+            }
+            message
+        }
+    }
+}
+
+fn display_service(service: &str) -> String {
+    if service.is_empty() {
+        String::new()
+    } else {
+        format!(" {service} ")
+    }
+}
+
+/// 拼接错误及其来源，tonic 的传输错误本身只有 "transport error"
+fn error_chain(error: &(dyn StdError + 'static)) -> String {
+    let mut messages = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(error) = source {
+        let message = error.to_string();
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+        source = error.source();
+    }
+    messages.join(": ")
+}
+
+fn failed(
+    result: HealthResult,
+    status: HealthStatus,
+    latency: Duration,
+    message: String,
+    failure_kind: &str,
+) -> HealthResult {
+    let mut result = result
+        .with_response_time(latency)
+        .with_error(message)
+        .with_metadata(
+            "failure_kind".to_string(),
+            serde_json::Value::String(failure_kind.to_string()),
+        );
+    result.status = status;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_serving_status() {
+        assert_eq!(
+            map_serving_status(ServingStatus::Serving, "pkg.Svc"),
+            (HealthStatus::Up, None)
+        );
+        let (status, error) = map_serving_status(ServingStatus::NotServing, "pkg.Svc");
+        assert_eq!(status, HealthStatus::Down);
+        assert_eq!(error.unwrap(), "gRPC服务 pkg.Svc 状态为 NOT_SERVING");
+        let (status, error) = map_serving_status(ServingStatus::Unknown, "");
+        assert_eq!(status, HealthStatus::Unknown);
+        assert_eq!(error.unwrap(), "gRPC服务状态为 UNKNOWN");
+    }
+
+    #[test]
+    fn test_request_metadata() {
+        let headers = BTreeMap::from([
+            ("Authorization".to_string(), "Bearer t".to_string()),
+            ("X-Tenant".to_string(), "a".to_string()),
+        ]);
+        let request = check_request("pkg.Svc", &headers).unwrap();
+        assert_eq!(request.get_ref().service, "pkg.Svc");
+        assert_eq!(request.metadata().get("authorization").unwrap(), "Bearer t");
+        assert_eq!(request.metadata().get("x-tenant").unwrap(), "a");
+
+        let invalid = BTreeMap::from([("X-Bad".to_string(), "a\nb".to_string())]);
+        assert!(check_request("", &invalid).unwrap_err().contains("X-Bad"));
+    }
+
+    #[test]
+    fn test_describe_status() {
+        let status = tonic::Status::not_found("unknown service");
+        assert_eq!(
+            describe_status(&status, "pkg.Svc"),
+            "gRPC服务 pkg.Svc 未注册健康状态"
+        );
+        let status = tonic::Status::unavailable("draining");
+        assert_eq!(
+            describe_status(&status, ""),
+            "gRPC调用失败: Unavailable: draining"
+        );
+    }
+}
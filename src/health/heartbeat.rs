@@ -0,0 +1,469 @@
+//! 心跳检测
+//!
+//! `heartbeat://` 服务由任务主动上报心跳，[`HeartbeatRegistry`] 在内存中记录每个服务
+//! 最近的上报，[`HeartbeatChecker`] 在定时检测时据此判断服务状态：
+//!
+//! - 最近一次结束上报之后超过周期加宽限时间没有新的结束上报：异常
+//! - 最近一次上报为失败：异常，直到下一次成功上报
+//! - 上报开始后超过最长运行时间仍未结束：异常
+//! - 启动后尚未收到心跳且未超过周期加宽限时间：未知
+//!
+//! 上报记录只保存在内存中，重启后重新从“尚未收到心跳”开始计算。
+
+use crate::config::heartbeat::HeartbeatCheckConfig;
+use crate::config::{secret, ServiceConfig};
+use crate::health::result::{HealthResult, HealthStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 检测结果中显示的请求方法
+const HEARTBEAT_METHOD: &str = "HEARTBEAT";
+/// 失败信息的最大长度
+const MAX_FAILURE_MESSAGE: usize = 500;
+
+/// 心跳上报类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeartbeatEvent {
+    /// 任务开始
+    Start,
+    /// 任务成功结束（或周期性存活上报）
+    Success,
+    /// 任务失败
+    Fail,
+}
+
+impl FromStr for HeartbeatEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(HeartbeatEvent::Start),
+            "success" | "finish" => Ok(HeartbeatEvent::Success),
+            "fail" => Ok(HeartbeatEvent::Fail),
+            other => Err(format!(
+                "未知的心跳类型: {other}，支持 start、success、fail"
+            )),
+        }
+    }
+}
+
+/// 服务的心跳记录
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeartbeatState {
+    /// 首次检测或首次上报的时间，尚未收到心跳时从该时间开始计算周期
+    pub first_seen: DateTime<Utc>,
+    /// 最近一次上报时间
+    pub last_ping: Option<DateTime<Utc>>,
+    /// 最近一次成功上报时间
+    pub last_success: Option<DateTime<Utc>>,
+    /// 正在运行的任务的开始时间
+    pub started_at: Option<DateTime<Utc>>,
+    /// 最近一次任务耗时（开始到结束上报），单位秒
+    pub last_duration_seconds: Option<f64>,
+    /// 最近一次失败上报，成功上报后清除
+    pub failure: Option<HeartbeatFailure>,
+}
+
+/// 失败上报
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeartbeatFailure {
+    /// 上报时间
+    pub at: DateTime<Utc>,
+    /// 任务上报的失败信息（请求体）
+    pub message: Option<String>,
+}
+
+impl HeartbeatState {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            first_seen: now,
+            last_ping: None,
+            last_success: None,
+            started_at: None,
+            last_duration_seconds: None,
+            failure: None,
+        }
+    }
+
+    /// 记录一次上报
+    fn record(&mut self, event: HeartbeatEvent, message: Option<String>, now: DateTime<Utc>) {
+        self.last_ping = Some(now);
+        match event {
+            HeartbeatEvent::Start => self.started_at = Some(now),
+            HeartbeatEvent::Success | HeartbeatEvent::Fail => {
+                if let Some(started_at) = self.started_at.take() {
+                    self.last_duration_seconds =
+                        Some((now - started_at).num_milliseconds().max(0) as f64 / 1000.0);
+                }
+                if event == HeartbeatEvent::Success {
+                    self.last_success = Some(now);
+                    self.failure = None;
+                } else {
+                    self.failure = Some(HeartbeatFailure {
+                        at: now,
+                        message: message
+                            .map(|message| truncate(message.trim(), MAX_FAILURE_MESSAGE))
+                            .filter(|message| !message.is_empty()),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// 心跳令牌对应的服务
+#[derive(Debug)]
+struct TokenEntry {
+    token: String,
+    service_name: String,
+}
+
+/// 心跳记录表，在检测器和Web上报接口之间共享
+#[derive(Debug, Default)]
+pub struct HeartbeatRegistry {
+    /// 按服务名称保存的心跳记录
+    states: Mutex<HashMap<String, HeartbeatState>>,
+    /// 按令牌的SHA-256摘要索引的服务，由调度器在注册和移除服务时维护
+    tokens: Mutex<HashMap<[u8; 32], TokenEntry>>,
+}
+
+impl HeartbeatRegistry {
+    /// 创建心跳记录表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次心跳上报
+    ///
+    /// # 参数
+    /// * `service_name` - 服务名称
+    /// * `event` - 上报类型
+    /// * `message` - 失败上报附带的信息
+    ///
+    /// # 返回
+    /// * `HeartbeatState` - 记录后的心跳状态
+    pub fn record(
+        &self,
+        service_name: &str,
+        event: HeartbeatEvent,
+        message: Option<String>,
+    ) -> HeartbeatState {
+        let now = Utc::now();
+        let mut states = self.lock();
+        let state = states
+            .entry(service_name.to_string())
+            .or_insert_with(|| HeartbeatState::new(now));
+        state.record(event, message, now);
+        state.clone()
+    }
+
+    /// 获取服务的心跳记录，首次获取时以当前时间作为计算周期的起点
+    pub fn state(&self, service_name: &str) -> HeartbeatState {
+        self.lock()
+            .entry(service_name.to_string())
+            .or_insert_with(|| HeartbeatState::new(Utc::now()))
+            .clone()
+    }
+
+    /// 登记服务的心跳令牌，替换该服务之前的令牌
+    pub fn register_service(&self, service: &ServiceConfig) {
+        let mut tokens = self.lock_tokens();
+        tokens.retain(|_, entry| entry.service_name != service.name);
+        if let Some(heartbeat) = &service.heartbeat {
+            tokens.insert(
+                token_digest(&heartbeat.token),
+                TokenEntry {
+                    token: heartbeat.token.clone(),
+                    service_name: service.name.clone(),
+                },
+            );
+        }
+    }
+
+    /// 移除服务的心跳记录，服务从配置中移除后调用
+    ///
+    /// # 返回
+    /// * `Option<HeartbeatState>` - 被移除的心跳记录
+    pub fn remove_state(&self, service_name: &str) -> Option<HeartbeatState> {
+        self.lock().remove(service_name)
+    }
+
+    /// 移除服务的心跳令牌，之后该令牌的上报不再被接受
+    pub fn unregister_service(&self, service_name: &str) {
+        self.lock_tokens()
+            .retain(|_, entry| entry.service_name != service_name);
+    }
+
+    /// 查找令牌对应的服务名称
+    ///
+    /// 按令牌摘要查找后再做常量时间比较，耗时与令牌内容和服务数量无关
+    pub fn service_for_token(&self, token: &str) -> Option<String> {
+        let tokens = self.lock_tokens();
+        let entry = tokens.get(&token_digest(token))?;
+        secret::constant_time_eq(&entry.token, token).then(|| entry.service_name.clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, HeartbeatState>> {
+        self.states
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_tokens(&self) -> std::sync::MutexGuard<'_, HashMap<[u8; 32], TokenEntry>> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 令牌的SHA-256摘要
+fn token_digest(token: &str) -> [u8; 32] {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+/// 心跳检测器
+#[derive(Debug, Default)]
+pub struct HeartbeatChecker {
+    registry: Arc<HeartbeatRegistry>,
+}
+
+impl HeartbeatChecker {
+    /// 创建心跳检测器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取心跳记录表，供Web上报接口记录心跳
+    pub fn registry(&self) -> Arc<HeartbeatRegistry> {
+        Arc::clone(&self.registry)
+    }
+
+    /// 根据心跳记录判断服务状态
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    ///
+    /// # 返回
+    /// * `HealthResult` - 检测结果，响应时间为最近一次任务耗时
+    pub fn check(&self, service: &ServiceConfig) -> HealthResult {
+        let mut result = HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            HealthStatus::Up,
+            HEARTBEAT_METHOD.to_string(),
+        );
+        let Some(config) = service.heartbeat.as_ref() else {
+            result.status = HealthStatus::Unknown;
+            return result.with_error("未配置 heartbeat".to_string());
+        };
+
+        let state = self.registry.state(&service.name);
+        let (status, error) = evaluate(config, &state, Utc::now());
+        if let Some(duration) = state.last_duration_seconds {
+            result = result.with_response_time(Duration::from_secs_f64(duration));
+        }
+        result = result.with_metadata(
+            "heartbeat".to_string(),
+            serde_json::to_value(&state).unwrap_or_default(),
+        );
+        result.status = status;
+        if let Some(error) = error {
+            result = result.with_error(error);
+        }
+        result
+    }
+}
+
+/// 按心跳配置判断服务状态
+fn evaluate(
+    config: &HeartbeatCheckConfig,
+    state: &HeartbeatState,
+    now: DateTime<Utc>,
+) -> (HealthStatus, Option<String>) {
+    if let Some(failure) = &state.failure {
+        let message = match &failure.message {
+            Some(message) => format!("任务上报失败: {message}"),
+            None => "任务上报失败".to_string(),
+        };
+        return (HealthStatus::Down, Some(message));
+    }
+
+    if let (Some(started_at), Some(max_duration)) = (state.started_at, config.max_duration_seconds)
+    {
+        let running = (now - started_at).num_seconds();
+        if running > max_duration as i64 {
+            return (
+                HealthStatus::Down,
+                Some(format!(
+                    "任务已运行 {running} 秒仍未结束，超过最长运行时间 {max_duration} 秒"
+                )),
+            );
+        }
+    }
+
+    let deadline = config.period_seconds + config.grace_seconds;
+    let since = state.last_success.unwrap_or(state.first_seen);
+    let elapsed = (now - since).num_seconds();
+    if elapsed > deadline as i64 {
+        let message = match state.last_success {
+            Some(_) => format!(
+                "最近一次心跳在 {elapsed} 秒前，超过周期 {} 秒与宽限 {} 秒",
+                config.period_seconds, config.grace_seconds
+            ),
+            None => format!("已等待 {elapsed} 秒仍未收到心跳"),
+        };
+        return (HealthStatus::Down, Some(message));
+    }
+
+    if state.last_success.is_none() {
+        return (HealthStatus::Unknown, Some("尚未收到心跳".to_string()));
+    }
+    (HealthStatus::Up, None)
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn config() -> HeartbeatCheckConfig {
+        HeartbeatCheckConfig {
+            token: "nightly-backup-7f3a9c".to_string(),
+            period_seconds: 3600,
+            grace_seconds: 600,
+            max_duration_seconds: Some(1800),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_period_and_grace() {
+        let start = Utc::now();
+        let mut state = HeartbeatState::new(start);
+        let at = |seconds| start + TimeDelta::seconds(seconds);
+
+        let (status, error) = evaluate(&config(), &state, at(60));
+        assert_eq!(status, HealthStatus::Unknown);
+        assert_eq!(error.as_deref(), Some("尚未收到心跳"));
+        assert_eq!(evaluate(&config(), &state, at(4201)).0, HealthStatus::Down);
+
+        state.record(HeartbeatEvent::Success, None, at(100));
+        assert_eq!(
+            evaluate(&config(), &state, at(4000)),
+            (HealthStatus::Up, None)
+        );
+        let (status, error) = evaluate(&config(), &state, at(4800));
+        assert_eq!(status, HealthStatus::Down);
+        assert_eq!(
+            error.as_deref(),
+            Some("最近一次心跳在 4700 秒前，超过周期 3600 秒与宽限 600 秒")
+        );
+    }
+
+    #[test]
+    fn test_start_finish_and_failure() {
+        let start = Utc::now();
+        let mut state = HeartbeatState::new(start);
+        let at = |seconds| start + TimeDelta::seconds(seconds);
+
+        state.record(HeartbeatEvent::Start, None, at(0));
+        assert_eq!(state.started_at, Some(at(0)));
+        let (status, error) = evaluate(&config(), &state, at(2000));
+        assert_eq!(status, HealthStatus::Down);
+        assert!(error.unwrap().contains("超过最长运行时间 1800 秒"));
+
+        state.record(HeartbeatEvent::Success, None, at(90));
+        assert_eq!(state.started_at, None);
+        assert_eq!(state.last_duration_seconds, Some(90.0));
+        assert_eq!(evaluate(&config(), &state, at(100)).0, HealthStatus::Up);
+
+        state.record(HeartbeatEvent::Start, None, at(200));
+        state.record(
+            HeartbeatEvent::Fail,
+            Some("  pg_dump: connection refused\n".to_string()),
+            at(230),
+        );
+        assert_eq!(state.last_duration_seconds, Some(30.0));
+        let (status, error) = evaluate(&config(), &state, at(240));
+        assert_eq!(status, HealthStatus::Down);
+        assert_eq!(
+            error.as_deref(),
+            Some("任务上报失败: pg_dump: connection refused")
+        );
+
+        state.record(HeartbeatEvent::Success, None, at(300));
+        assert_eq!(evaluate(&config(), &state, at(310)).0, HealthStatus::Up);
+    }
+
+    #[test]
+    fn test_checker_reads_registry() {
+        let checker = HeartbeatChecker::new();
+        let service: ServiceConfig = toml::from_str(
+            r#"
+            name = "nightly-backup"
+            url = "heartbeat://nightly-backup"
+            [heartbeat]
+            token = "nightly-backup-7f3a9c"
+            period_seconds = 3600
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(checker.check(&service).status, HealthStatus::Unknown);
+
+        checker
+            .registry()
+            .record("nightly-backup", HeartbeatEvent::Success, None);
+        let result = checker.check(&service);
+        assert_eq!(result.status, HealthStatus::Up);
+        assert_eq!(result.method, "HEARTBEAT");
+        assert!(result.metadata["heartbeat"]["last_success"].is_string());
+
+        assert_eq!("finish".parse(), Ok(HeartbeatEvent::Success));
+        assert!("stop".parse::<HeartbeatEvent>().is_err());
+    }
+
+    #[test]
+    fn test_registry_token_lookup() {
+        let registry = HeartbeatRegistry::new();
+        let mut service: ServiceConfig = toml::from_str(
+            r#"
+            name = "nightly-backup"
+            url = "heartbeat://nightly-backup"
+            [heartbeat]
+            token = "nightly-backup-7f3a9c"
+            period_seconds = 3600
+            "#,
+        )
+        .unwrap();
+
+        registry.register_service(&service);
+        assert_eq!(
+            registry
+                .service_for_token("nightly-backup-7f3a9c")
+                .as_deref(),
+            Some("nightly-backup")
+        );
+        assert_eq!(registry.service_for_token("nightly-backup-7f3a9d"), None);
+
+        service.heartbeat.as_mut().unwrap().token = "rotated-91be".to_string();
+        registry.register_service(&service);
+        assert_eq!(registry.service_for_token("nightly-backup-7f3a9c"), None);
+        assert!(registry.service_for_token("rotated-91be").is_some());
+
+        registry.unregister_service("nightly-backup");
+        assert_eq!(registry.service_for_token("rotated-91be"), None);
+    }
+}
@@ -0,0 +1,749 @@
+//! 本机资源检测
+//!
+//! 对 `host://` 服务读取 `/proc` 和 `statvfs` 检测运行 service-vitals 的主机：
+//! 磁盘与inode使用率、内存与交换分区使用率、平均负载、进程存活以及文件更新时间。
+//! 达到降级阈值标记为降级，达到异常阈值标记为异常；无法读取系统信息时标记为未知。
+
+use crate::config::effective::EffectiveServiceConfig;
+use crate::config::host::{
+    DiskCheckConfig, FileCheckConfig, HostCheckConfig, LoadCheckConfig, MemoryCheckConfig,
+    ProcessCheckConfig,
+};
+use crate::config::ServiceConfig;
+use crate::health::result::{HealthResult, HealthStatus};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// 检测结果中显示的请求方法
+const HOST_METHOD: &str = "HOST";
+/// 元数据中最多记录的进程ID数量
+const MAX_LISTED_PIDS: usize = 20;
+
+/// 本机资源检测器
+#[derive(Debug, Default)]
+pub struct HostChecker;
+
+/// 一次检测的测量结果
+#[derive(Debug)]
+struct Measurement {
+    status: HealthStatus,
+    /// 超过阈值的指标说明
+    problems: Vec<String>,
+    /// 写入元数据 `host` 的测量值
+    details: serde_json::Value,
+}
+
+impl HostChecker {
+    /// 创建本机资源检测器
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 执行一次本机资源检测
+    ///
+    /// 读取系统信息在阻塞线程中进行，超过服务的超时时间（例如网络文件系统无响应）标记为异常
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置，使用其中的超时时间
+    ///
+    /// # 返回
+    /// * `HealthResult` - 检测结果，无法读取系统信息时标记失败类型 `host`
+    pub async fn check(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> HealthResult {
+        let result = HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            HealthStatus::Up,
+            HOST_METHOD.to_string(),
+        );
+        let Some(config) = service.host.clone() else {
+            let message = "未配置 host.check".to_string();
+            return failed(result, HealthStatus::Unknown, Duration::ZERO, message);
+        };
+
+        let start = Instant::now();
+        let outcome = tokio::time::timeout(
+            effective.timeout,
+            tokio::task::spawn_blocking(move || measure(&config)),
+        )
+        .await;
+        let latency = start.elapsed();
+
+        let measurement = match outcome {
+            Ok(Ok(Ok(measurement))) => measurement,
+            Ok(Ok(Err(message))) => {
+                return failed(result, HealthStatus::Unknown, latency, message);
+            }
+            Ok(Err(e)) => {
+                let message = format!("本机资源检测任务异常退出: {e}");
+                return failed(result, HealthStatus::Unknown, latency, message);
+            }
+            Err(_) => {
+                let message = format!("读取系统信息超时（{} 秒）", effective.timeout.as_secs_f64());
+                return failed(result, HealthStatus::Down, latency, message);
+            }
+        };
+
+        let mut result = result
+            .with_response_time(latency)
+            .with_metadata("host".to_string(), measurement.details);
+        result.status = measurement.status;
+        if !measurement.problems.is_empty() {
+            result = result.with_error(measurement.problems.join("；"));
+        }
+        result
+    }
+}
+
+/// 按检测项读取系统信息并与阈值比较
+fn measure(config: &HostCheckConfig) -> Result<Measurement, String> {
+    let mut measurement = match config {
+        HostCheckConfig::Disk(disk) => measure_disk(disk)?,
+        HostCheckConfig::Memory(memory) => {
+            let meminfo = std::fs::read_to_string("/proc/meminfo")
+                .map_err(|e| format!("读取 /proc/meminfo 失败: {e}"))?;
+            evaluate_memory(&parse_meminfo(&meminfo), memory)?
+        }
+        HostCheckConfig::Load(load) => {
+            let loadavg = std::fs::read_to_string("/proc/loadavg")
+                .map_err(|e| format!("读取 /proc/loadavg 失败: {e}"))?;
+            let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+            evaluate_load(&parse_loadavg(&loadavg)?, cpus, load)
+        }
+        HostCheckConfig::Process(process) => measure_process(process)?,
+        HostCheckConfig::File(file) => measure_file(file)?,
+    };
+    measurement.details["check"] = config.kind().into();
+    Ok(measurement)
+}
+
+/// 按阈值评估一项指标，达到异常阈值为异常，达到降级阈值为降级
+///
+/// # 返回
+/// * `(HealthStatus, Option<String>)` - 状态以及超过阈值时的说明
+fn grade(
+    label: &str,
+    value: f64,
+    unit: &str,
+    warning: Option<f64>,
+    critical: Option<f64>,
+) -> (HealthStatus, Option<String>) {
+    if let Some(critical) = critical.filter(|critical| value >= *critical) {
+        let message = format!("{label} {value:.1}{unit}，达到异常阈值 {critical}{unit}");
+        return (HealthStatus::Down, Some(message));
+    }
+    if let Some(warning) = warning.filter(|warning| value >= *warning) {
+        let message = format!("{label} {value:.1}{unit}，达到降级阈值 {warning}{unit}");
+        return (HealthStatus::Degraded, Some(message));
+    }
+    (HealthStatus::Up, None)
+}
+
+impl Measurement {
+    fn new(details: serde_json::Value) -> Self {
+        Self {
+            status: HealthStatus::Up,
+            problems: Vec::new(),
+            details,
+        }
+    }
+
+    /// 合并一项指标的评估结果，保留最严重的状态
+    fn add(&mut self, (status, problem): (HealthStatus, Option<String>)) {
+        if severity(status) > severity(self.status) {
+            self.status = status;
+        }
+        self.problems.extend(problem);
+    }
+}
+
+/// 状态的严重程度，用于合并多项指标
+fn severity(status: HealthStatus) -> u8 {
+    match status {
+        HealthStatus::Up => 0,
+        HealthStatus::Degraded => 1,
+        HealthStatus::Unknown => 2,
+        HealthStatus::Down => 3,
+    }
+}
+
+/// 百分比，总量为0时为0
+fn percent(used: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f64 * 100.0 / total as f64
+    }
+}
+
+/// 文件系统容量
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FsStats {
+    /// 总容量（字节）
+    total_bytes: u64,
+    /// 已使用（字节）
+    used_bytes: u64,
+    /// 非特权用户可用（字节）
+    available_bytes: u64,
+    /// inode总数
+    inodes_total: u64,
+    /// 空闲inode数
+    inodes_free: u64,
+}
+
+/// 读取路径所在文件系统的容量
+#[cfg(unix)]
+// statvfs 各字段的整数类型随平台不同，统一转换为 u64
+#[allow(clippy::unnecessary_cast)]
+fn statvfs(path: &Path) -> Result<FsStats, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| format!("路径 {} 包含空字符", path.display()))?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: c_path 是以空字符结尾的有效路径，stat 在调用成功后才读取
+    let ret = unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) };
+    if ret != 0 {
+        return Err(format!(
+            "读取 {} 的文件系统信息失败: {}",
+            path.display(),
+            std::io::Error::last_os_error()
+        ));
+    }
+    // SAFETY: statvfs 返回0时已填充结构体
+    let stat = unsafe { stat.assume_init() };
+    let fragment = stat.f_frsize as u64;
+    let blocks = stat.f_blocks as u64;
+    let free = stat.f_bfree as u64;
+    Ok(FsStats {
+        total_bytes: blocks * fragment,
+        used_bytes: blocks.saturating_sub(free) * fragment,
+        available_bytes: stat.f_bavail as u64 * fragment,
+        inodes_total: stat.f_files as u64,
+        inodes_free: stat.f_ffree as u64,
+    })
+}
+
+#[cfg(not(unix))]
+fn statvfs(_path: &Path) -> Result<FsStats, String> {
+    Err("当前平台不支持磁盘检测".to_string())
+}
+
+/// 检测磁盘空间和inode使用率
+fn measure_disk(config: &DiskCheckConfig) -> Result<Measurement, String> {
+    let stats = statvfs(&config.path)?;
+    Ok(evaluate_disk(&stats, config))
+}
+
+/// 按阈值评估磁盘使用率
+///
+/// 与 `df` 一致，使用率为已使用空间占（已使用 + 非特权用户可用）空间的比例
+fn evaluate_disk(stats: &FsStats, config: &DiskCheckConfig) -> Measurement {
+    let path = config.path.display().to_string();
+    let usage = percent(stats.used_bytes, stats.used_bytes + stats.available_bytes);
+    let inodes_used = stats.inodes_total.saturating_sub(stats.inodes_free);
+    let inode_usage = percent(inodes_used, stats.inodes_total);
+
+    let mut measurement = Measurement::new(json!({
+        "path": path,
+        "total_bytes": stats.total_bytes,
+        "used_bytes": stats.used_bytes,
+        "available_bytes": stats.available_bytes,
+        "used_percent": usage,
+        "inodes_total": stats.inodes_total,
+        "inodes_used": inodes_used,
+        "inodes_used_percent": inode_usage,
+    }));
+    measurement.add(grade(
+        &format!("{path} 磁盘使用率"),
+        usage,
+        "%",
+        Some(config.warning_percent),
+        Some(config.critical_percent),
+    ));
+    // 部分文件系统（如btrfs）不报告inode数量
+    if stats.inodes_total > 0 {
+        measurement.add(grade(
+            &format!("{path} inode使用率"),
+            inode_usage,
+            "%",
+            config.inode_warning_percent,
+            config.inode_critical_percent,
+        ));
+    }
+    measurement
+}
+
+/// 解析 `/proc/meminfo`，返回以kB为单位的各项数值
+fn parse_meminfo(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// 按阈值评估内存和交换分区使用率
+///
+/// 内存使用率按 `MemTotal - MemAvailable` 计算，不把可回收的页缓存算作已使用
+fn evaluate_memory(
+    meminfo: &HashMap<String, u64>,
+    config: &MemoryCheckConfig,
+) -> Result<Measurement, String> {
+    let field = |name: &str| {
+        meminfo
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("/proc/meminfo 中缺少 {name}"))
+    };
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+    let swap_total = field("SwapTotal").unwrap_or(0);
+    let swap_free = field("SwapFree").unwrap_or(0);
+    let used = total.saturating_sub(available);
+    let swap_used = swap_total.saturating_sub(swap_free);
+    let usage = percent(used, total);
+    let swap_usage = percent(swap_used, swap_total);
+
+    let mut measurement = Measurement::new(json!({
+        "total_bytes": total * 1024,
+        "available_bytes": available * 1024,
+        "used_percent": usage,
+        "swap_total_bytes": swap_total * 1024,
+        "swap_used_bytes": swap_used * 1024,
+        "swap_used_percent": swap_usage,
+    }));
+    measurement.add(grade(
+        "内存使用率",
+        usage,
+        "%",
+        Some(config.warning_percent),
+        Some(config.critical_percent),
+    ));
+    if swap_total > 0 {
+        measurement.add(grade(
+            "交换分区使用率",
+            swap_usage,
+            "%",
+            config.swap_warning_percent,
+            config.swap_critical_percent,
+        ));
+    }
+    Ok(measurement)
+}
+
+/// 解析 `/proc/loadavg` 中的1、5、15分钟平均负载
+fn parse_loadavg(content: &str) -> Result<[f64; 3], String> {
+    let mut fields = content.split_whitespace().map(str::parse::<f64>);
+    let mut loads = [0.0; 3];
+    for load in &mut loads {
+        *load = match fields.next() {
+            Some(Ok(value)) => value,
+            _ => return Err(format!("无法解析 /proc/loadavg: {}", content.trim())),
+        };
+    }
+    Ok(loads)
+}
+
+/// 按阈值评估平均负载
+fn evaluate_load(loads: &[f64; 3], cpus: usize, config: &LoadCheckConfig) -> Measurement {
+    let load = match config.minutes {
+        1 => loads[0],
+        15 => loads[2],
+        _ => loads[1],
+    };
+    let value = if config.per_cpu {
+        load / cpus.max(1) as f64
+    } else {
+        load
+    };
+    let label = if config.per_cpu {
+        format!("{} 分钟每核平均负载", config.minutes)
+    } else {
+        format!("{} 分钟平均负载", config.minutes)
+    };
+
+    let mut measurement = Measurement::new(json!({
+        "load1": loads[0],
+        "load5": loads[1],
+        "load15": loads[2],
+        "cpus": cpus,
+        "value": value,
+    }));
+    measurement.add(grade(
+        &label,
+        value,
+        "",
+        Some(config.warning),
+        Some(config.critical),
+    ));
+    measurement
+}
+
+/// 检测进程存活
+fn measure_process(config: &ProcessCheckConfig) -> Result<Measurement, String> {
+    if let Some(pidfile) = &config.pidfile {
+        return Ok(measure_pidfile(pidfile));
+    }
+    let name = config.name.as_deref().unwrap_or_default();
+    let pids = find_processes(name)?;
+    Ok(evaluate_process_count(name, &pids, config))
+}
+
+/// 按PID文件检测进程，PID文件不存在、无法解析或进程不存在时为异常
+fn measure_pidfile(pidfile: &Path) -> Measurement {
+    let path = pidfile.display().to_string();
+    let mut measurement = Measurement::new(json!({ "pidfile": path }));
+    let problem = match std::fs::read_to_string(pidfile) {
+        Err(e) => Some(format!("无法读取PID文件 {path}: {e}")),
+        Ok(content) => match content.trim().parse::<u32>() {
+            Err(_) => Some(format!("PID文件 {path} 的内容无效: {}", content.trim())),
+            Ok(pid) => {
+                measurement.details["pid"] = pid.into();
+                let running = process_exists(pid);
+                measurement.details["running"] = running.into();
+                (!running).then(|| format!("PID文件 {path} 中的进程 {pid} 不存在"))
+            }
+        },
+    };
+    measurement.add((
+        if problem.is_some() {
+            HealthStatus::Down
+        } else {
+            HealthStatus::Up
+        },
+        problem,
+    ));
+    measurement
+}
+
+/// 判断进程是否存在
+fn process_exists(pid: u32) -> bool {
+    if Path::new("/proc").join(pid.to_string()).exists() {
+        return true;
+    }
+    // 没有 /proc 的平台使用0号信号探测，EPERM 说明进程存在但属于其他用户
+    #[cfg(unix)]
+    {
+        let Ok(pid) = libc::pid_t::try_from(pid) else {
+            return false;
+        };
+        // SAFETY: 0号信号不会发送给进程，只检查进程是否存在
+        if unsafe { libc::kill(pid, 0) } == 0 {
+            return true;
+        }
+        std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    }
+    #[cfg(not(unix))]
+    false
+}
+
+/// 遍历 `/proc` 查找名称匹配的进程
+///
+/// 名称与 `/proc/<pid>/comm`（内核截断为15个字符）或命令行第一项的文件名相同即匹配
+fn find_processes(name: &str) -> Result<Vec<u32>, String> {
+    let entries = std::fs::read_dir("/proc").map_err(|e| format!("读取 /proc 失败: {e}"))?;
+    let comm_name: String = name.chars().take(15).collect();
+    let mut pids: Vec<u32> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            // 进程可能在遍历期间退出，读取失败时跳过
+            let comm = std::fs::read_to_string(entry.path().join("comm")).ok()?;
+            if comm.trim_end_matches('\n') == comm_name && name.len() <= 15 {
+                return Some(pid);
+            }
+            let cmdline = std::fs::read(entry.path().join("cmdline")).ok()?;
+            let argv0 = cmdline.split(|byte| *byte == 0).next()?;
+            let argv0 = Path::new(std::str::from_utf8(argv0).ok()?).file_name()?;
+            (argv0 == name).then_some(pid)
+        })
+        .collect();
+    pids.sort_unstable();
+    Ok(pids)
+}
+
+/// 按阈值评估进程数量，少于最少进程数为异常，超过最多进程数为降级
+fn evaluate_process_count(name: &str, pids: &[u32], config: &ProcessCheckConfig) -> Measurement {
+    let count = pids.len();
+    let mut measurement = Measurement::new(json!({
+        "name": name,
+        "count": count,
+        "pids": pids.iter().take(MAX_LISTED_PIDS).collect::<Vec<_>>(),
+    }));
+    if count < config.min_count {
+        measurement.add((
+            HealthStatus::Down,
+            Some(format!(
+                "进程 {name} 数量为 {count}，少于 {}",
+                config.min_count
+            )),
+        ));
+    } else if let Some(max) = config.max_count.filter(|max| count > *max) {
+        measurement.add((
+            HealthStatus::Degraded,
+            Some(format!("进程 {name} 数量为 {count}，多于 {max}")),
+        ));
+    }
+    measurement
+}
+
+/// 检测文件更新时间，文件不存在时为异常
+fn measure_file(config: &FileCheckConfig) -> Result<Measurement, String> {
+    let path = config.path.display().to_string();
+    let modified = match std::fs::metadata(&config.path) {
+        Ok(metadata) => metadata
+            .modified()
+            .map_err(|e| format!("读取文件 {path} 的修改时间失败: {e}"))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let mut measurement = Measurement::new(json!({ "path": path, "exists": false }));
+            measurement.add((HealthStatus::Down, Some(format!("文件 {path} 不存在"))));
+            return Ok(measurement);
+        }
+        Err(e) => return Err(format!("读取文件 {path} 失败: {e}")),
+    };
+    // 修改时间晚于当前时间（时钟回拨）时按0处理
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    Ok(evaluate_file_age(&path, age, config))
+}
+
+/// 按阈值评估文件距离最后修改的时间
+fn evaluate_file_age(path: &str, age: Duration, config: &FileCheckConfig) -> Measurement {
+    let age_seconds = age.as_secs();
+    let mut measurement = Measurement::new(json!({
+        "path": path,
+        "exists": true,
+        "age_seconds": age_seconds,
+    }));
+    measurement.add(grade(
+        &format!("文件 {path} 距最后修改"),
+        age_seconds as f64,
+        " 秒",
+        config.warning_age_seconds.map(|seconds| seconds as f64),
+        Some(config.critical_age_seconds as f64),
+    ));
+    measurement
+}
+
+/// 构造无法完成检测时的结果
+fn failed(
+    result: HealthResult,
+    status: HealthStatus,
+    latency: Duration,
+    message: String,
+) -> HealthResult {
+    let mut result = result
+        .with_response_time(latency)
+        .with_error(message)
+        .with_metadata(
+            "failure_kind".to_string(),
+            serde_json::Value::String("host".to_string()),
+        );
+    result.status = status;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::effective::RequestDefaults;
+    use std::path::PathBuf;
+
+    fn effective(service: &ServiceConfig) -> EffectiveServiceConfig {
+        let defaults = RequestDefaults {
+            headers: HashMap::new(),
+            timeout: Duration::from_secs(5),
+            retry_attempts: 0,
+            retry_delay: Duration::from_secs(1),
+            retry_backoff: Default::default(),
+            check_interval_seconds: 60,
+        };
+        EffectiveServiceConfig::resolve(&defaults, service)
+    }
+
+    fn disk_config() -> DiskCheckConfig {
+        DiskCheckConfig {
+            path: PathBuf::from("/data"),
+            warning_percent: 80.0,
+            critical_percent: 90.0,
+            inode_warning_percent: None,
+            inode_critical_percent: Some(95.0),
+        }
+    }
+
+    #[test]
+    fn test_disk_and_memory_thresholds() {
+        let gib = 1024 * 1024 * 1024;
+        let mut stats = FsStats {
+            total_bytes: 100 * gib,
+            used_bytes: 50 * gib,
+            available_bytes: 45 * gib,
+            inodes_total: 1000,
+            inodes_free: 500,
+        };
+        let measurement = evaluate_disk(&stats, &disk_config());
+        assert_eq!(measurement.status, HealthStatus::Up);
+        assert!(measurement.problems.is_empty());
+
+        stats.used_bytes = 85 * gib;
+        stats.available_bytes = 10 * gib;
+        let measurement = evaluate_disk(&stats, &disk_config());
+        assert_eq!(measurement.status, HealthStatus::Degraded);
+
+        // inode耗尽时即使空间充足也标记为异常
+        stats.used_bytes = 10 * gib;
+        stats.available_bytes = 85 * gib;
+        stats.inodes_free = 10;
+        let measurement = evaluate_disk(&stats, &disk_config());
+        assert_eq!(measurement.status, HealthStatus::Down);
+        assert!(measurement.problems[0].contains("inode使用率"));
+
+        let meminfo = parse_meminfo(
+            "MemTotal:       16000000 kB\nMemFree:          500000 kB\nMemAvailable:    1200000 kB\nSwapTotal:       2000000 kB\nSwapFree:        2000000 kB\n",
+        );
+        let config = MemoryCheckConfig {
+            warning_percent: 90.0,
+            critical_percent: 95.0,
+            swap_warning_percent: Some(50.0),
+            swap_critical_percent: None,
+        };
+        let measurement = evaluate_memory(&meminfo, &config).unwrap();
+        assert_eq!(measurement.status, HealthStatus::Degraded);
+        assert_eq!(measurement.details["total_bytes"], 16_000_000_u64 * 1024);
+        assert!(evaluate_memory(&HashMap::new(), &config).is_err());
+    }
+
+    #[test]
+    fn test_load_and_process_count() {
+        let loads = parse_loadavg("3.20 6.40 0.50 2/345 6789\n").unwrap();
+        assert_eq!(loads, [3.2, 6.4, 0.5]);
+        assert!(parse_loadavg("").is_err());
+
+        let mut config = LoadCheckConfig {
+            warning: 1.0,
+            critical: 2.0,
+            minutes: 5,
+            per_cpu: true,
+        };
+        assert_eq!(
+            evaluate_load(&loads, 4, &config).status,
+            HealthStatus::Degraded
+        );
+        config.per_cpu = false;
+        assert_eq!(evaluate_load(&loads, 4, &config).status, HealthStatus::Down);
+        config.minutes = 15;
+        assert_eq!(evaluate_load(&loads, 4, &config).status, HealthStatus::Up);
+
+        let config = ProcessCheckConfig {
+            name: Some("nginx".to_string()),
+            pidfile: None,
+            min_count: 1,
+            max_count: Some(2),
+        };
+        assert_eq!(
+            evaluate_process_count("nginx", &[], &config).status,
+            HealthStatus::Down
+        );
+        assert_eq!(
+            evaluate_process_count("nginx", &[10, 11], &config).status,
+            HealthStatus::Up
+        );
+        assert_eq!(
+            evaluate_process_count("nginx", &[10, 11, 12], &config).status,
+            HealthStatus::Degraded
+        );
+    }
+
+    #[tokio::test]
+    async fn test_host_checker_file_and_pidfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("backup.tar");
+        std::fs::write(&path, b"data").unwrap();
+        let mut service: ServiceConfig = toml::from_str(&format!(
+            r#"
+            name = "备份文件"
+            url = "host://backup-file"
+            [host]
+            check = "file"
+            path = "{}"
+            critical_age_seconds = 3600
+            "#,
+            path.display()
+        ))
+        .unwrap();
+        let effective = effective(&service);
+        let checker = HostChecker::new();
+
+        let result = checker.check(&service, &effective).await;
+        assert_eq!(result.status, HealthStatus::Up);
+        assert_eq!(result.method, "HOST");
+        assert_eq!(result.metadata["host"]["check"], "file");
+
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(7200))
+            .unwrap();
+        let result = checker.check(&service, &effective).await;
+        assert_eq!(result.status, HealthStatus::Down);
+        assert!(result.error_message.unwrap().contains("异常阈值"));
+
+        std::fs::remove_file(&path).unwrap();
+        let result = checker.check(&service, &effective).await;
+        assert_eq!(result.status, HealthStatus::Down);
+        assert!(result.error_message.unwrap().contains("不存在"));
+
+        let pidfile = dir.path().join("service.pid");
+        std::fs::write(&pidfile, format!("{}\n", std::process::id())).unwrap();
+        service.host = Some(HostCheckConfig::Process(ProcessCheckConfig {
+            name: None,
+            pidfile: Some(pidfile.clone()),
+            min_count: 1,
+            max_count: None,
+        }));
+        let result = checker.check(&service, &effective).await;
+        assert_eq!(result.status, HealthStatus::Up);
+        assert_eq!(result.metadata["host"]["pid"], std::process::id());
+
+        std::fs::write(&pidfile, "not-a-pid").unwrap();
+        let result = checker.check(&service, &effective).await;
+        assert_eq!(result.status, HealthStatus::Down);
+
+        service.host = None;
+        let result = checker.check(&service, &effective).await;
+        assert_eq!(result.status, HealthStatus::Unknown);
+        assert_eq!(result.metadata["failure_kind"], "host");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_host_checker_reads_local_system() {
+        let checker = HostChecker::new();
+        for check in [
+            "check = \"disk\"\npath = \"/\"\nwarning_percent = 100\ncritical_percent = 100",
+            "check = \"memory\"\nwarning_percent = 100\ncritical_percent = 100",
+            "check = \"load\"\nwarning = 100000\ncritical = 100000",
+        ] {
+            let service: ServiceConfig = toml::from_str(&format!(
+                "name = \"本机\"\nurl = \"host://local\"\n[host]\n{check}"
+            ))
+            .unwrap();
+            let result = checker.check(&service, &effective(&service)).await;
+            assert_ne!(result.status, HealthStatus::Unknown, "{check}: {result:?}");
+        }
+
+        // 测试进程自身的命令名一定存在
+        let exe = std::env::current_exe().unwrap();
+        let name = exe.file_name().unwrap().to_str().unwrap();
+        assert!(find_processes(name).unwrap().contains(&std::process::id()));
+    }
+}
@@ -1,12 +1,34 @@
 //! 健康检测模块
 //!
-//! 提供HTTP健康检测、结果处理和任务调度功能
+//! 提供HTTP、DNS、gRPC、WebSocket、数据库、命令、心跳与本机资源健康检测、结果处理和任务调度功能
 
+pub mod auth;
 pub mod checker;
+pub mod database;
+pub mod dispatcher;
+pub mod dns;
+pub mod exec;
+pub mod grpc;
+pub mod heartbeat;
+pub mod host;
 pub mod result;
+pub mod schedule;
 pub mod scheduler;
+pub mod steps;
+pub mod timing;
+pub mod tls;
+pub mod websocket;
 
 // 重新导出主要类型
+pub use auth::TokenCache;
 pub use checker::{HealthChecker, HttpHealthChecker};
-pub use result::{HealthResult, HealthStatus};
-pub use scheduler::{Scheduler, TaskScheduler};
+pub use database::{MysqlHealthChecker, PostgresHealthChecker, RedisHealthChecker};
+pub use dns::DnsChecker;
+pub use exec::ExecChecker;
+pub use grpc::GrpcChecker;
+pub use heartbeat::{HeartbeatChecker, HeartbeatEvent, HeartbeatRegistry};
+pub use host::HostChecker;
+pub use result::{HealthResult, HealthStatus, RequestTimings};
+pub use schedule::ServiceSchedule;
+pub use scheduler::{PauseInfo, Scheduler, SilenceInfo, TaskScheduler};
+pub use websocket::WebSocketChecker;
@@ -73,6 +73,49 @@ pub struct HealthResult {
     /// 额外的元数据
     #[serde(default)]
     pub metadata: std::collections::HashMap<String, serde_json::Value>,
+    /// 请求各阶段耗时（仅在收到响应时记录）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<RequestTimings>,
+}
+
+/// 请求各阶段耗时（毫秒）
+///
+/// 复用已有连接时没有DNS、连接和TLS阶段；URL主机为IP地址时没有DNS阶段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestTimings {
+    /// DNS解析耗时
+    pub dns_ms: Option<f64>,
+    /// TCP连接耗时
+    pub connect_ms: Option<f64>,
+    /// TLS握手耗时
+    pub tls_ms: Option<f64>,
+    /// 首字节时间：从连接就绪（或复用连接时从发起请求）到收到响应头
+    pub ttfb_ms: f64,
+    /// 响应体下载耗时，响应体未读取完成时为空
+    pub download_ms: Option<f64>,
+    /// 是否复用了已有连接
+    pub connection_reused: bool,
+}
+
+impl RequestTimings {
+    /// 按顺序列出已记录的阶段及耗时（毫秒）
+    pub fn phases(&self) -> Vec<(&'static str, f64)> {
+        [
+            ("dns", self.dns_ms),
+            ("connect", self.connect_ms),
+            ("tls", self.tls_ms),
+            ("ttfb", Some(self.ttfb_ms)),
+            ("download", self.download_ms),
+        ]
+        .into_iter()
+        .filter_map(|(phase, ms)| ms.map(|ms| (phase, ms)))
+        .collect()
+    }
+
+    /// 各阶段耗时之和（毫秒）
+    pub fn total_ms(&self) -> f64 {
+        self.phases().iter().map(|(_, ms)| ms).sum()
+    }
 }
 
 impl HealthResult {
@@ -105,6 +148,7 @@ impl HealthResult {
             method,
             response_size: None,
             metadata: std::collections::HashMap::new(),
+            timings: None,
         }
     }
 
@@ -138,6 +182,12 @@ impl HealthResult {
         self
     }
 
+    /// 设置请求各阶段耗时
+    pub fn with_timings(mut self, timings: RequestTimings) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
     /// 添加元数据
     pub fn with_metadata(mut self, key: String, value: serde_json::Value) -> Self {
         self.metadata.insert(key, value);
@@ -357,6 +407,39 @@ mod tests {
         assert_eq!(deserialized.status, result.status);
         assert_eq!(deserialized.status_code, result.status_code);
         assert_eq!(deserialized.response_time_ms(), result.response_time_ms());
+        assert!(!json.contains("timings"));
+    }
+
+    #[test]
+    fn test_request_timings() {
+        let timings = RequestTimings {
+            dns_ms: None,
+            connect_ms: Some(2.0),
+            tls_ms: Some(5.5),
+            ttfb_ms: 10.0,
+            download_ms: Some(1.5),
+            connection_reused: false,
+        };
+        assert_eq!(
+            timings.phases(),
+            vec![
+                ("connect", 2.0),
+                ("tls", 5.5),
+                ("ttfb", 10.0),
+                ("download", 1.5)
+            ]
+        );
+        assert_eq!(timings.total_ms(), 19.0);
+
+        let result = HealthResult::new(
+            "Test Service".to_string(),
+            "https://example.com".to_string(),
+            HealthStatus::Up,
+            "GET".to_string(),
+        )
+        .with_timings(timings);
+        let deserialized = HealthResult::from_json(&result.to_json().unwrap()).unwrap();
+        assert_eq!(deserialized.timings, Some(timings));
     }
 
     #[test]
@@ -0,0 +1,545 @@
+//! 服务检测调度计算
+//!
+//! 根据服务的调度配置计算下一次检测时间，支持：
+//! - 固定间隔或cron表达式触发
+//! - 启动相位分散与随机抖动，避免所有服务同时检测
+//! - 检测超时错过调度点时的补偿策略（跳过或补齐）
+//! - 仅在指定时区的工作时间窗口内检测
+//! - 根据检测结果自适应调整检测间隔（失败时加快、持续健康时指数退避）
+
+use crate::config::types::{BackoffConfig, CatchUpPolicy, GlobalConfig, ServiceConfig};
+use crate::health::HealthStatus;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use rand::Rng;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+/// 查找下一个工作时间窗口时最多向后搜索的次数
+const MAX_WINDOW_SEARCH: usize = 400;
+
+/// 检测触发方式
+#[derive(Debug, Clone)]
+enum Trigger {
+    /// 固定间隔
+    Interval(Duration),
+    /// cron表达式
+    Cron(Box<cron::Schedule>),
+}
+
+/// 工作时间窗口
+#[derive(Debug, Clone)]
+struct BusinessWindow {
+    days: Vec<Weekday>,
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+/// 自适应检测间隔
+///
+/// 仅对固定间隔触发的服务生效
+#[derive(Debug, Clone)]
+struct AdaptiveInterval {
+    /// 正常检测间隔
+    base: Duration,
+    /// 服务异常时的检测间隔
+    failure: Option<Duration>,
+    /// 持续健康时的退避配置
+    backoff: Option<BackoffConfig>,
+    /// 连续健康次数
+    healthy_streak: u32,
+}
+
+impl AdaptiveInterval {
+    /// 根据最新检测状态计算下一个检测间隔
+    fn next_period(&mut self, status: HealthStatus) -> Duration {
+        if !status.is_healthy() {
+            self.healthy_streak = 0;
+            return self.failure.unwrap_or(self.base);
+        }
+
+        self.healthy_streak = self.healthy_streak.saturating_add(1);
+        let Some(ref backoff) = self.backoff else {
+            return self.base;
+        };
+        if self.healthy_streak <= backoff.after_healthy_checks {
+            return self.base;
+        }
+
+        let exponent = (self.healthy_streak - backoff.after_healthy_checks).min(64) as i32;
+        let max_seconds = backoff.max_interval_seconds as f64;
+        let seconds =
+            (self.base.as_secs_f64() * backoff.multiplier.powi(exponent)).min(max_seconds);
+        Duration::from_secs_f64(seconds.max(self.base.as_secs_f64()))
+    }
+}
+
+/// 单个服务的调度状态
+#[derive(Debug, Clone)]
+pub struct ServiceSchedule {
+    /// 触发方式
+    trigger: Trigger,
+    /// 计算cron和工作时间所用的时区
+    timezone: Tz,
+    /// 随机抖动上限
+    jitter: Duration,
+    /// 补偿策略
+    catch_up: CatchUpPolicy,
+    /// 工作时间窗口
+    window: Option<BusinessWindow>,
+    /// 下一次计划检测时间，None表示没有后续调度
+    next_due: Option<DateTime<Utc>>,
+    /// 自适应检测间隔，仅固定间隔触发时存在
+    adaptive: Option<AdaptiveInterval>,
+}
+
+impl ServiceSchedule {
+    /// 根据服务配置和全局配置创建调度状态
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `global` - 全局配置
+    /// * `now` - 当前时间
+    pub fn new(
+        service: &ServiceConfig,
+        global: &GlobalConfig,
+        now: DateTime<Utc>,
+    ) -> Result<Self, String> {
+        let schedule_config = service.schedule.clone().unwrap_or_default();
+
+        let trigger = match schedule_config.parsed_cron()? {
+            Some(schedule) => Trigger::Cron(Box::new(schedule)),
+            None => Trigger::Interval(Duration::from_secs(
+                service
+                    .check_interval_seconds
+                    .unwrap_or(global.check_interval_seconds)
+                    .max(1),
+            )),
+        };
+
+        let window = match schedule_config.business_hours {
+            Some(ref hours) => {
+                let (days, start, end) = hours.parsed()?;
+                Some(BusinessWindow { days, start, end })
+            }
+            None => None,
+        };
+
+        let jitter_seconds = schedule_config
+            .jitter_seconds
+            .or(global.jitter_seconds)
+            .unwrap_or(0);
+
+        let adaptive = match trigger {
+            Trigger::Interval(base)
+                if service.failure_check_interval_seconds.is_some()
+                    || service.backoff.is_some() =>
+            {
+                Some(AdaptiveInterval {
+                    base,
+                    failure: service
+                        .failure_check_interval_seconds
+                        .map(|seconds| Duration::from_secs(seconds.max(1))),
+                    backoff: service.backoff.clone(),
+                    healthy_streak: 0,
+                })
+            }
+            _ => None,
+        };
+
+        let mut schedule = Self {
+            trigger,
+            timezone: schedule_config.parsed_timezone()?,
+            jitter: Duration::from_secs(jitter_seconds),
+            catch_up: schedule_config.catch_up,
+            window,
+            next_due: None,
+            adaptive,
+        };
+
+        let first = match schedule.trigger {
+            Trigger::Interval(period) => {
+                let offset = global
+                    .startup_spread_seconds
+                    .map(|spread| phase_offset(&service.name, spread.min(period.as_secs())))
+                    .unwrap_or_default();
+                Some(now + offset)
+            }
+            Trigger::Cron(_) => schedule.cron_after(now),
+        };
+        schedule.next_due = first.and_then(|due| schedule.align_to_window(due));
+
+        Ok(schedule)
+    }
+
+    /// 下一次计划检测时间
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.next_due
+    }
+
+    /// 当前检测间隔，cron触发时返回None
+    pub fn current_interval(&self) -> Option<Duration> {
+        match self.trigger {
+            Trigger::Interval(period) => Some(period),
+            Trigger::Cron(_) => None,
+        }
+    }
+
+    /// 记录最新的检测状态并按需切换检测间隔
+    ///
+    /// 应在 [`advance`](Self::advance) 之前调用，使新的间隔作用于下一次调度。
+    /// 间隔发生变化时返回新的间隔。
+    pub fn record_status(&mut self, status: HealthStatus) -> Option<Duration> {
+        let adaptive = self.adaptive.as_mut()?;
+        let next_period = adaptive.next_period(status);
+        match self.trigger {
+            Trigger::Interval(ref mut period) if *period != next_period => {
+                *period = next_period;
+                Some(next_period)
+            }
+            _ => None,
+        }
+    }
+
+    /// 计算距离下一次检测需要等待的时间（包含随机抖动）
+    ///
+    /// 返回None表示没有后续调度
+    pub fn delay_until_next(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let due = self.next_due?;
+        let delay = (due - now).to_std().unwrap_or_default();
+        Some(delay + self.random_jitter())
+    }
+
+    /// 一次检测完成（或被跳过）后推进到下一个调度点
+    ///
+    /// # 参数
+    /// * `now` - 当前时间，用于判断是否错过了调度点
+    pub fn advance(&mut self, now: DateTime<Utc>) {
+        let Some(previous) = self.next_due else {
+            return;
+        };
+
+        let candidate = match (&self.trigger, self.catch_up) {
+            (Trigger::Interval(period), CatchUpPolicy::Burst) => Some(previous + *period),
+            (Trigger::Interval(period), CatchUpPolicy::Skip) => {
+                let period_ms = (period.as_millis() as i64).max(1);
+                let elapsed_ms = (now - previous).num_milliseconds().max(0);
+                let steps = elapsed_ms / period_ms + 1;
+                Some(previous + ChronoDuration::milliseconds(steps * period_ms))
+            }
+            (Trigger::Cron(_), CatchUpPolicy::Burst) => self.cron_after(previous),
+            (Trigger::Cron(_), CatchUpPolicy::Skip) => self.cron_after(previous.max(now)),
+        };
+
+        self.next_due = candidate.and_then(|due| self.align_to_window(due));
+    }
+
+    /// 生成 0~jitter 之间的随机延迟
+    fn random_jitter(&self) -> Duration {
+        let max_ms = self.jitter.as_millis() as u64;
+        if max_ms == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::rng().random_range(0..=max_ms))
+    }
+
+    /// cron表达式在指定时间之后的下一个触发时间
+    fn cron_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match &self.trigger {
+            Trigger::Cron(schedule) => schedule
+                .after(&after.with_timezone(&self.timezone))
+                .next()
+                .map(|due| due.with_timezone(&Utc)),
+            Trigger::Interval(_) => None,
+        }
+    }
+
+    /// 将调度时间调整到工作时间窗口内
+    fn align_to_window(&self, mut due: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let Some(ref window) = self.window else {
+            return Some(due);
+        };
+
+        for _ in 0..MAX_WINDOW_SEARCH {
+            if self.in_window(window, due) {
+                return Some(due);
+            }
+            let window_start = self.next_window_start(window, due)?;
+            due = match self.trigger {
+                Trigger::Interval(_) => window_start,
+                Trigger::Cron(_) => self.cron_after(window_start - ChronoDuration::seconds(1))?,
+            };
+        }
+
+        None
+    }
+
+    /// 判断时间是否位于工作时间窗口内
+    fn in_window(&self, window: &BusinessWindow, time: DateTime<Utc>) -> bool {
+        let local = time.with_timezone(&self.timezone);
+        let local_time = local.time();
+        window.days.contains(&local.weekday())
+            && local_time >= window.start
+            && local_time < window.end
+    }
+
+    /// 指定时间之后最近的工作时间窗口开始时间
+    fn next_window_start(
+        &self,
+        window: &BusinessWindow,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let local_date = after.with_timezone(&self.timezone).date_naive();
+        (0..=7)
+            .filter_map(|offset| local_date.checked_add_days(chrono::Days::new(offset)))
+            .filter(|date| window.days.contains(&date.weekday()))
+            .filter_map(|date| {
+                let naive = date.and_time(window.start);
+                // 夏令时跳变导致本地时间不存在时顺延一小时
+                self.timezone
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .or_else(|| {
+                        self.timezone
+                            .from_local_datetime(&(naive + ChronoDuration::hours(1)))
+                            .earliest()
+                    })
+            })
+            .map(|start| start.with_timezone(&Utc))
+            .find(|start| *start > after)
+    }
+}
+
+/// 根据服务名称计算稳定的启动相位偏移
+fn phase_offset(service_name: &str, spread_seconds: u64) -> ChronoDuration {
+    if spread_seconds == 0 {
+        return ChronoDuration::zero();
+    }
+    let mut hasher = DefaultHasher::new();
+    service_name.hash(&mut hasher);
+    let spread_ms = spread_seconds * 1000;
+    ChronoDuration::milliseconds((hasher.finish() % spread_ms) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::{BusinessHours, ScheduleConfig};
+
+    fn global_config() -> GlobalConfig {
+        toml::from_str("check_interval_seconds = 60").unwrap()
+    }
+
+    fn service_with(schedule: Option<ScheduleConfig>, interval: Option<u64>) -> ServiceConfig {
+        let mut service: ServiceConfig = toml::from_str(
+            r#"
+            name = "svc"
+            url = "http://localhost"
+            expected_status_codes = [200]
+            "#,
+        )
+        .unwrap();
+        service.schedule = schedule;
+        service.check_interval_seconds = interval;
+        service
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_interval_first_check_is_immediate() {
+        let now = utc("2024-01-01T00:00:00Z");
+        let schedule =
+            ServiceSchedule::new(&service_with(None, Some(30)), &global_config(), now).unwrap();
+        assert_eq!(schedule.next_due(), Some(now));
+        assert_eq!(schedule.delay_until_next(now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_startup_spread_is_stable_and_bounded() {
+        let now = utc("2024-01-01T00:00:00Z");
+        let mut global = global_config();
+        global.startup_spread_seconds = Some(600);
+
+        let service = service_with(None, Some(20));
+        let first = ServiceSchedule::new(&service, &global, now).unwrap();
+        let second = ServiceSchedule::new(&service, &global, now).unwrap();
+        let due = first.next_due().unwrap();
+
+        assert_eq!(first.next_due(), second.next_due());
+        // 分散窗口不超过检测间隔
+        assert!(due >= now && due < now + ChronoDuration::seconds(20));
+    }
+
+    #[test]
+    fn test_interval_skip_and_burst_after_overrun() {
+        let now = utc("2024-01-01T00:00:00Z");
+        let overrun = utc("2024-01-01T00:02:30Z");
+
+        let mut skip =
+            ServiceSchedule::new(&service_with(None, Some(60)), &global_config(), now).unwrap();
+        skip.advance(overrun);
+        assert_eq!(skip.next_due(), Some(utc("2024-01-01T00:03:00Z")));
+
+        let burst_config = ScheduleConfig {
+            catch_up: CatchUpPolicy::Burst,
+            ..Default::default()
+        };
+        let mut burst = ServiceSchedule::new(
+            &service_with(Some(burst_config), Some(60)),
+            &global_config(),
+            now,
+        )
+        .unwrap();
+        burst.advance(overrun);
+        assert_eq!(burst.next_due(), Some(utc("2024-01-01T00:01:00Z")));
+        assert_eq!(burst.delay_until_next(overrun), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_cron_with_timezone() {
+        let config = ScheduleConfig {
+            cron: Some("30 9 * * *".to_string()),
+            timezone: Some("Asia/Shanghai".to_string()),
+            ..Default::default()
+        };
+        let now = utc("2024-01-01T00:00:00Z");
+        let mut schedule =
+            ServiceSchedule::new(&service_with(Some(config), None), &global_config(), now).unwrap();
+
+        // 上海时间 09:30 即 UTC 01:30
+        assert_eq!(schedule.next_due(), Some(utc("2024-01-01T01:30:00Z")));
+
+        // 跳过策略：超时两天后从当前时间之后的下一个触发点继续
+        schedule.advance(utc("2024-01-03T02:00:00Z"));
+        assert_eq!(schedule.next_due(), Some(utc("2024-01-04T01:30:00Z")));
+    }
+
+    #[test]
+    fn test_business_hours_window() {
+        let config = ScheduleConfig {
+            timezone: Some("Europe/Berlin".to_string()),
+            business_hours: Some(BusinessHours {
+                days: vec!["Mon".to_string(), "Tue".to_string()],
+                start: "09:00".to_string(),
+                end: "17:00".to_string(),
+            }),
+            ..Default::default()
+        };
+        // 2024-01-06 是周六
+        let now = utc("2024-01-06T12:00:00Z");
+        let mut schedule = ServiceSchedule::new(
+            &service_with(Some(config), Some(3600)),
+            &global_config(),
+            now,
+        )
+        .unwrap();
+
+        // 顺延到周一柏林时间 09:00（UTC 08:00）
+        assert_eq!(schedule.next_due(), Some(utc("2024-01-08T08:00:00Z")));
+
+        // 窗口结束后顺延到周二
+        schedule.advance(utc("2024-01-08T15:30:00Z"));
+        assert_eq!(schedule.next_due(), Some(utc("2024-01-09T08:00:00Z")));
+    }
+
+    #[test]
+    fn test_jitter_is_bounded() {
+        let config = ScheduleConfig {
+            jitter_seconds: Some(2),
+            ..Default::default()
+        };
+        let now = utc("2024-01-01T00:00:00Z");
+        let schedule =
+            ServiceSchedule::new(&service_with(Some(config), Some(60)), &global_config(), now)
+                .unwrap();
+
+        for _ in 0..100 {
+            let delay = schedule.delay_until_next(now).unwrap();
+            assert!(delay <= Duration::from_secs(2));
+        }
+    }
+
+    #[test]
+    fn test_adaptive_interval_on_failure_and_recovery() {
+        let now = utc("2024-01-01T00:00:00Z");
+        let mut service = service_with(None, Some(60));
+        service.failure_check_interval_seconds = Some(10);
+        let mut schedule = ServiceSchedule::new(&service, &global_config(), now).unwrap();
+
+        // 失败时切换到失败间隔
+        assert_eq!(
+            schedule.record_status(HealthStatus::Down),
+            Some(Duration::from_secs(10))
+        );
+        schedule.advance(now + ChronoDuration::seconds(1));
+        assert_eq!(schedule.next_due(), Some(utc("2024-01-01T00:00:10Z")));
+
+        // 持续失败不再切换
+        assert_eq!(schedule.record_status(HealthStatus::Down), None);
+
+        // 恢复后回到正常间隔
+        assert_eq!(
+            schedule.record_status(HealthStatus::Up),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(schedule.current_interval(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_adaptive_interval_backoff_bounds() {
+        let now = utc("2024-01-01T00:00:00Z");
+        let mut service = service_with(None, Some(60));
+        service.backoff = Some(BackoffConfig {
+            max_interval_seconds: 300,
+            multiplier: 2.0,
+            after_healthy_checks: 2,
+        });
+        let mut schedule = ServiceSchedule::new(&service, &global_config(), now).unwrap();
+
+        let periods: Vec<u64> = (0..6)
+            .map(|_| {
+                schedule.record_status(HealthStatus::Up);
+                schedule.current_interval().unwrap().as_secs()
+            })
+            .collect();
+        assert_eq!(periods, vec![60, 60, 120, 240, 300, 300]);
+
+        // 一次失败立即回到正常间隔
+        assert_eq!(
+            schedule.record_status(HealthStatus::Down),
+            Some(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn test_adaptive_interval_ignored_for_cron() {
+        let config = ScheduleConfig {
+            cron: Some("*/5 * * * *".to_string()),
+            ..Default::default()
+        };
+        let mut service = service_with(Some(config), None);
+        service.failure_check_interval_seconds = Some(10);
+        let mut schedule = ServiceSchedule::new(&service, &global_config(), Utc::now()).unwrap();
+
+        assert_eq!(schedule.record_status(HealthStatus::Down), None);
+        assert_eq!(schedule.current_interval(), None);
+    }
+
+    #[test]
+    fn test_invalid_cron_is_rejected() {
+        let config = ScheduleConfig {
+            cron: Some("not a cron".to_string()),
+            ..Default::default()
+        };
+        let result = ServiceSchedule::new(
+            &service_with(Some(config), None),
+            &global_config(),
+            Utc::now(),
+        );
+        assert!(result.is_err());
+    }
+}
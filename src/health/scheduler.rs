@@ -2,6 +2,13 @@
 //!
 //! 提供健康检测任务的调度、管理和并发控制功能
 //!
+//! # 调度模型
+//!
+//! 所有服务共享一个定时器（按到期时间排序的最小堆）和固定大小的工作池，
+//! 工作任务数量等于 `max_concurrent_checks`。服务配置、通知状态和暂停状态
+//! 保存在分片映射中，每个服务的通知状态独立加锁，检测与通知处理不会在
+//! 全局锁上串行化，因此可以支撑数千个服务同时监控。
+//!
 //! # 通知系统
 //!
 //! 通知系统负责在服务健康状态发生变化时发送通知。系统支持以下通知场景：
@@ -44,7 +51,7 @@
 //!
 //! ## 示例
 //!
-//! ```rust
+//! ```rust,ignore
 //! use service_vitals::health::scheduler::{TaskScheduler, ServiceNotificationState};
 //! use service_vitals::config::types::ServiceConfig;
 //! use std::sync::Arc;
@@ -60,16 +67,23 @@
 //! scheduler.start(services).await?;
 //! ```
 
+use crate::common::glob::glob_match_any;
+use crate::common::ShardedMap;
 use crate::config::types::{GlobalConfig, ServiceConfig};
-use crate::config::{ConfigDiff, ConfigUpdateNotification};
+use crate::config::{ConfigDiff, ConfigUpdateNotification, EffectiveServiceConfig};
+use crate::health::dispatcher::{self, JobQueue, ScheduledCheck};
+use crate::health::schedule::ServiceSchedule;
 use crate::health::{HealthChecker, HealthResult, HealthStatus};
 use crate::notification::NotificationSender;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::Duration;
-use tokio::sync::{broadcast, RwLock, Semaphore};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock, Semaphore};
 use tokio::task::JoinHandle;
 use tokio::time::{interval, Instant};
 use tracing::{debug, error, info, warn};
@@ -112,10 +126,63 @@ pub struct ServiceNotificationState {
     pub notification_state: NotificationState,
 }
 
+/// 持有通知状态锁时决定发送、释放锁后再发送的通知
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlannedNotification {
+    /// 服务恢复通知
+    Recovery,
+    /// 告警通知
+    Alert {
+        /// 决定发送前的冷却时间，发送失败时恢复
+        previous_cooldown: Option<Instant>,
+    },
+}
+
+/// 服务暂停信息
+///
+/// 暂停期间定时检测会被跳过，但服务的通知状态保持不变，
+/// 恢复后不会因暂停产生误报的恢复或告警通知
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PauseInfo {
+    /// 暂停时间
+    pub paused_at: DateTime<Utc>,
+    /// 自动恢复时间（为空表示需要手动恢复）
+    pub until: Option<DateTime<Utc>>,
+    /// 暂停原因
+    pub reason: Option<String>,
+}
+
+impl PauseInfo {
+    /// 暂停是否已到期
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.until.is_some_and(|until| now >= until)
+    }
+}
+
+/// 服务静默信息
+///
+/// 静默期间照常检测并记录失败状态，只是不发送告警和恢复通知
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SilenceInfo {
+    /// 静默开始时间
+    pub silenced_at: DateTime<Utc>,
+    /// 静默结束时间（为空表示需要手动取消）
+    pub until: Option<DateTime<Utc>>,
+    /// 静默原因
+    pub reason: Option<String>,
+}
+
+impl SilenceInfo {
+    /// 静默是否已到期
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.until.is_some_and(|until| now >= until)
+    }
+}
+
 /// 调度器状态
 #[derive(Debug, Clone)]
 pub struct SchedulerStatus {
-    /// 运行中的任务数量
+    /// 已进入调度的服务数量
     pub running_tasks: usize,
     /// 总服务数量
     pub total_services: usize,
@@ -178,26 +245,73 @@ pub trait Scheduler: Send + Sync {
     async fn get_status(&self) -> SchedulerStatus;
 }
 
-/// 任务调度器实现
-pub struct TaskScheduler {
+/// 检测执行上下文
+///
+/// 汇总执行检测所需的共享组件。定时任务与手动触发都通过它执行检测，
+/// 保证检测结果经过同一套通知和状态回调流程。
+#[derive(Clone)]
+struct CheckContext {
     /// 健康检测器
     checker: Arc<dyn HealthChecker>,
     /// 通知发送器
     notifier: Option<Arc<dyn NotificationSender>>,
-    /// 运行中的任务
-    tasks: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
+    /// 已注册的服务及其调度状态
+    services: Arc<ShardedMap<Arc<RegisteredService>>>,
     /// 全局配置
     config: Arc<RwLock<GlobalConfig>>,
     /// 并发控制信号量
     semaphore: Arc<Semaphore>,
+    /// 工作池大小
+    worker_count: usize,
     /// 调度器状态
     status: Arc<RwLock<SchedulerStatus>>,
-    /// 服务通知状态
-    notification_states: Arc<RwLock<HashMap<String, ServiceNotificationState>>>,
-    /// 配置更新接收器
-    config_update_receiver: Option<broadcast::Receiver<ConfigUpdateNotification>>,
+    /// 服务通知状态，每个服务独立加锁
+    notification_states: Arc<ShardedMap<Arc<Mutex<ServiceNotificationState>>>>,
     /// 健康检测结果回调
     health_result_callback: Arc<RwLock<Option<HealthResultCallback>>>,
+    /// 已暂停的服务
+    paused: Arc<ShardedMap<PauseInfo>>,
+    /// 已静默通知的服务
+    silenced: Arc<ShardedMap<SilenceInfo>>,
+    /// 共享定时器和工作池，首次注册服务时启动
+    dispatcher: Arc<StdMutex<Option<Dispatcher>>>,
+    /// 服务注册代数计数器
+    generation: Arc<AtomicU64>,
+}
+
+/// 已注册的服务
+struct RegisteredService {
+    /// 注册代数，用于识别服务重新注册后残留的旧调度
+    generation: u64,
+    /// 服务配置
+    service: ServiceConfig,
+    /// 调度计划
+    schedule: StdMutex<ServiceSchedule>,
+}
+
+/// 运行中的共享定时器和工作池
+struct Dispatcher {
+    /// 调度请求发送端
+    schedules: mpsc::UnboundedSender<ScheduledCheck>,
+    /// 定时器和工作任务句柄
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Dispatcher {
+    /// 停止定时器和所有工作任务
+    fn shutdown(self) {
+        for handle in self.handles {
+            handle.abort();
+        }
+    }
+}
+
+/// 任务调度器实现
+pub struct TaskScheduler {
+    /// 检测执行上下文
+    ctx: CheckContext,
+    /// 配置更新接收器
+    config_update_receiver: Option<broadcast::Receiver<ConfigUpdateNotification>>,
 }
 
 impl TaskScheduler {
@@ -225,15 +339,22 @@ impl TaskScheduler {
         };
 
         Self {
-            checker,
-            notifier,
-            tasks: Arc::new(RwLock::new(HashMap::new())),
-            config: Arc::new(RwLock::new(config)),
-            semaphore: Arc::new(Semaphore::new(max_concurrent)),
-            status: Arc::new(RwLock::new(status)),
-            notification_states: Arc::new(RwLock::new(HashMap::new())),
+            ctx: CheckContext {
+                checker,
+                notifier,
+                services: Arc::new(ShardedMap::default()),
+                config: Arc::new(RwLock::new(config)),
+                semaphore: Arc::new(Semaphore::new(max_concurrent)),
+                worker_count: max_concurrent.max(1),
+                status: Arc::new(RwLock::new(status)),
+                notification_states: Arc::new(ShardedMap::default()),
+                health_result_callback: Arc::new(RwLock::new(None)),
+                paused: Arc::new(ShardedMap::default()),
+                silenced: Arc::new(ShardedMap::default()),
+                dispatcher: Arc::new(StdMutex::new(None)),
+                generation: Arc::new(AtomicU64::new(0)),
+            },
             config_update_receiver: None,
-            health_result_callback: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -247,29 +368,26 @@ impl TaskScheduler {
     /// * `service` - 服务配置
     /// * `result` - 健康检测结果
     /// * `status_arc` - 调度器状态，用于更新通知统计信息
-    /// * `notification_state` - 通知状态，用于更新失败计数
     ///
     /// # 返回
-    /// * `Result<bool>` - 是否发送成功
+    /// * `bool` - 是否发送成功
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let success = TaskScheduler::send_with_retry(
     ///     &notifier,
     ///     &service,
     ///     &result,
     ///     &status_arc,
-    ///     &mut notification_state
-    /// ).await?;
+    /// ).await;
     /// ```
     async fn send_with_retry(
         notifier: &Arc<dyn NotificationSender>,
         service: &ServiceConfig,
         result: &HealthResult,
         status_arc: &Arc<RwLock<SchedulerStatus>>,
-        notification_state: &mut NotificationState,
-    ) -> Result<bool> {
+    ) -> bool {
         let mut retry_count = 0;
         let max_retries = 3;
         let mut send_result = notifier.send_health_alert(service, result).await;
@@ -291,8 +409,7 @@ impl TaskScheduler {
                 info!("发送通知成功: {}", service.name);
                 Self::update_notification_stats_static(status_arc, true, retry_count > 0, false)
                     .await;
-                notification_state.notification_failures = 0;
-                Ok(true)
+                true
             }
             Err(e) => {
                 error!(
@@ -301,8 +418,7 @@ impl TaskScheduler {
                 );
                 Self::update_notification_stats_static(status_arc, false, retry_count > 0, false)
                     .await;
-                notification_state.notification_failures += 1;
-                Ok(false)
+                false
             }
         }
     }
@@ -321,7 +437,7 @@ impl TaskScheduler {
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let (changed, recovered) = TaskScheduler::check_status_change(
     ///     current_status,
     ///     &notification_state
@@ -340,12 +456,12 @@ impl TaskScheduler {
     ) -> (bool, bool) {
         let status_changed = notification_state
             .last_health_status
-            .map_or(true, |last| last != current_status);
+            .is_none_or(|last| last != current_status);
         let recovered_from_unhealthy = status_changed
             && current_status.is_healthy()
             && notification_state
                 .last_health_status
-                .map_or(false, |s| !s.is_healthy());
+                .is_some_and(|s| !s.is_healthy());
 
         (status_changed, recovered_from_unhealthy)
     }
@@ -369,7 +485,7 @@ impl TaskScheduler {
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// let should_alert = TaskScheduler::should_send_alert(
     ///     &notification_state,
     ///     &service,
@@ -418,7 +534,7 @@ impl TaskScheduler {
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// // 服务恢复健康时，重置失败状态
     /// TaskScheduler::reset_failure_state(&mut notification_state);
     /// ```
@@ -442,7 +558,7 @@ impl TaskScheduler {
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// // 每次检测失败时，更新失败状态
     /// TaskScheduler::update_failure_state(&mut notification_state, Instant::now());
     /// ```
@@ -471,7 +587,7 @@ impl TaskScheduler {
     ///
     /// # 示例
     ///
-    /// ```rust
+    /// ```rust,ignore
     /// // 发送告警后，更新冷却时间
     /// TaskScheduler::update_alert_cooldown(
     ///     &mut notification_state,
@@ -499,98 +615,342 @@ impl TaskScheduler {
     /// # 参数
     /// * `callback` - 健康检测结果回调函数
     pub async fn set_health_result_callback(&self, callback: HealthResultCallback) {
-        let mut cb = self.health_result_callback.write().await;
+        let mut cb = self.ctx.health_result_callback.write().await;
         *cb = Some(callback);
     }
 
+    /// 立即触发指定服务的健康检测
+    ///
+    /// 检测结果与定时检测一样经过通知和状态回调流程，不影响服务原有的检测周期。
+    ///
+    /// # 参数
+    /// * `service_name` - 服务名称
+    ///
+    /// # 返回
+    /// * `Result<Option<HealthResult>>` - 检测结果，服务未注册时返回 `None`
+    pub async fn trigger_check(&self, service_name: &str) -> Result<Option<HealthResult>> {
+        let service = self
+            .ctx
+            .services
+            .with(service_name, |entry| entry.service.clone());
+        match service {
+            Some(service) => {
+                info!("手动触发服务检测: {}", service_name);
+                self.ctx.execute_check(&service).await.map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 立即触发带有指定标签的所有服务的健康检测
+    ///
+    /// # 参数
+    /// * `tag` - 服务标签，支持通配符（例如 `team:*`）
+    ///
+    /// # 返回
+    /// * `Vec<(String, Result<HealthResult>)>` - 按服务名称排序的检测结果，没有匹配服务时为空
+    pub async fn trigger_checks_by_tag(&self, tag: &str) -> Vec<(String, Result<HealthResult>)> {
+        let services: Vec<ServiceConfig> = self
+            .ctx
+            .services
+            .snapshot()
+            .into_iter()
+            .map(|(_, entry)| entry.service.clone())
+            .filter(|s| glob_match_any(&[tag], &s.tags))
+            .collect();
+
+        info!("手动触发标签检测: {}，匹配服务数: {}", tag, services.len());
+
+        let checks = services.iter().map(|service| async move {
+            (service.name.clone(), self.ctx.execute_check(service).await)
+        });
+        let mut results = futures::future::join_all(checks).await;
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+        results
+    }
+
+    /// 暂停服务的定时检测
+    ///
+    /// 重复暂停会覆盖之前的暂停信息。
+    ///
+    /// # 参数
+    /// * `service_name` - 服务名称
+    /// * `reason` - 暂停原因
+    /// * `until` - 自动恢复时间，为空表示需要手动恢复
+    ///
+    /// # 返回
+    /// * `Option<PauseInfo>` - 暂停信息，服务未注册时返回 `None`
+    pub async fn pause_service(
+        &self,
+        service_name: &str,
+        reason: Option<String>,
+        until: Option<DateTime<Utc>>,
+    ) -> Option<PauseInfo> {
+        if !self.ctx.services.contains_key(service_name) {
+            return None;
+        }
+
+        let info = PauseInfo {
+            paused_at: Utc::now(),
+            until,
+            reason,
+        };
+        info!(
+            "暂停服务检测: {}，原因: {}，自动恢复时间: {}",
+            service_name,
+            info.reason.as_deref().unwrap_or("未说明"),
+            info.until
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_else(|| "无".to_string())
+        );
+
+        self.ctx
+            .paused
+            .insert(service_name.to_string(), info.clone());
+        Some(info)
+    }
+
+    /// 恢复服务的定时检测
+    ///
+    /// # 参数
+    /// * `service_name` - 服务名称
+    ///
+    /// # 返回
+    /// * `Option<Option<PauseInfo>>` - 服务未注册时返回 `None`，否则返回恢复前的暂停信息
+    pub async fn resume_service(&self, service_name: &str) -> Option<Option<PauseInfo>> {
+        if !self.ctx.services.contains_key(service_name) {
+            return None;
+        }
+
+        let previous = self.ctx.paused.remove(service_name);
+        if previous.is_some() {
+            info!("恢复服务检测: {}", service_name);
+        }
+        Some(previous)
+    }
+
+    /// 获取服务的暂停信息（已到期的暂停视为未暂停）
+    pub async fn pause_info(&self, service_name: &str) -> Option<PauseInfo> {
+        self.ctx
+            .paused
+            .get(service_name)
+            .filter(|info| !info.is_expired(Utc::now()))
+    }
+
+    /// 获取所有仍在暂停中的服务
+    pub async fn paused_services(&self) -> HashMap<String, PauseInfo> {
+        let now = Utc::now();
+        self.ctx
+            .paused
+            .snapshot()
+            .into_iter()
+            .filter(|(_, info)| !info.is_expired(now))
+            .collect()
+    }
+
+    /// 静默服务的告警和恢复通知
+    ///
+    /// 重复静默会覆盖之前的静默信息。
+    ///
+    /// # 参数
+    /// * `service_name` - 服务名称
+    /// * `reason` - 静默原因
+    /// * `until` - 静默结束时间，为空表示需要手动取消
+    ///
+    /// # 返回
+    /// * `Option<SilenceInfo>` - 静默信息，服务未注册时返回 `None`
+    pub async fn silence_service(
+        &self,
+        service_name: &str,
+        reason: Option<String>,
+        until: Option<DateTime<Utc>>,
+    ) -> Option<SilenceInfo> {
+        if !self.ctx.services.contains_key(service_name) {
+            return None;
+        }
+
+        let info = SilenceInfo {
+            silenced_at: Utc::now(),
+            until,
+            reason,
+        };
+        info!(
+            "静默服务通知: {}，原因: {}，结束时间: {}",
+            service_name,
+            info.reason.as_deref().unwrap_or("未说明"),
+            info.until
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_else(|| "无".to_string())
+        );
+
+        self.ctx
+            .silenced
+            .insert(service_name.to_string(), info.clone());
+        Some(info)
+    }
+
+    /// 取消服务的通知静默
+    ///
+    /// # 参数
+    /// * `service_name` - 服务名称
+    ///
+    /// # 返回
+    /// * `Option<Option<SilenceInfo>>` - 服务未注册时返回 `None`，否则返回取消前的静默信息
+    pub async fn unsilence_service(&self, service_name: &str) -> Option<Option<SilenceInfo>> {
+        if !self.ctx.services.contains_key(service_name) {
+            return None;
+        }
+
+        let previous = self.ctx.silenced.remove(service_name);
+        if previous.is_some() {
+            info!("取消服务通知静默: {}", service_name);
+        }
+        Some(previous)
+    }
+
+    /// 获取所有仍在静默中的服务
+    pub async fn silenced_services(&self) -> HashMap<String, SilenceInfo> {
+        let now = Utc::now();
+        self.ctx
+            .silenced
+            .snapshot()
+            .into_iter()
+            .filter(|(_, info)| !info.is_expired(now))
+            .collect()
+    }
+
+    /// 获取服务当前的通知状态
+    pub async fn notification_state(&self, service_name: &str) -> Option<ServiceNotificationState> {
+        let state = self.ctx.notification_states.get(service_name)?;
+        let state = state.lock().await;
+        Some(state.clone())
+    }
+
+    /// 获取已注册的服务名称列表（按名称排序）
+    pub async fn service_names(&self) -> Vec<String> {
+        let mut names = self.ctx.services.keys();
+        names.sort();
+        names
+    }
+
+    /// 获取已注册服务的当前配置（按名称排序）
+    pub async fn service_configs(&self) -> Vec<ServiceConfig> {
+        let mut services: Vec<ServiceConfig> = self
+            .ctx
+            .services
+            .snapshot()
+            .into_iter()
+            .map(|(_, entry)| entry.service.clone())
+            .collect();
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+        services
+    }
+
     /// 静态方法处理通知逻辑
     ///
     /// 此方法是通知系统的核心逻辑，负责处理健康检测结果并决定是否发送通知。
     /// 处理流程如下：
-    /// 1. 检查健康状态是否发生变化
-    /// 2. 如果是从不健康状态恢复，发送恢复通知并重置失败状态
-    /// 3. 如果服务不健康，更新失败状态并判断是否需要发送告警
-    /// 4. 如果需要发送告警，调用通知发送器并更新冷却时间
-    /// 5. 如果在冷却期内，增加错过通知计数
+    /// 1. 持有通知状态锁，更新失败状态并决定是否发送恢复通知或告警（见 [`Self::plan_notification`]）
+    /// 2. 释放锁后发送通知；发送失败时会重试并等待十几秒，期间不阻塞该服务的后续检测
+    /// 3. 再次获取锁，记录发送结果（见 [`Self::record_delivery`]）
     ///
     /// # 参数
     /// * `service` - 服务配置
     /// * `result` - 健康检测结果
-    /// * `notification_state` - 通知状态，将被更新
+    /// * `notification_state` - 服务的通知状态
     /// * `notifier` - 通知发送器（可选）
     /// * `status_arc` - 调度器状态，用于更新统计信息
     ///
     /// # 返回
-    /// * `Result<()>` - 处理结果
+    /// * `u32` - 处理本次结果后的连续失败次数
     async fn handle_notification_static(
         service: &ServiceConfig,
         result: &crate::health::HealthResult,
-        notification_state: &mut ServiceNotificationState,
+        notification_state: &Mutex<ServiceNotificationState>,
         notifier: &Option<Arc<dyn NotificationSender>>,
         status_arc: &Arc<RwLock<SchedulerStatus>>,
-    ) -> Result<()> {
-        let current_status = result.status;
+    ) -> u32 {
         let now = Instant::now();
-        let is_healthy = current_status.is_healthy();
+        let (planned, missed, consecutive_failures) = {
+            let mut state = notification_state.lock().await;
+            let (planned, missed) =
+                Self::plan_notification(service, result, &mut state, notifier.is_some(), now);
+            (planned, missed, state.failure_state.consecutive_failures)
+        };
+
+        if missed {
+            Self::update_notification_stats_static(status_arc, false, false, true).await;
+        }
+        if let (Some(planned), Some(notifier)) = (planned, notifier) {
+            let success = Self::send_with_retry(notifier, service, result, status_arc).await;
+            let mut state = notification_state.lock().await;
+            Self::record_delivery(&mut state, &planned, success, now);
+        }
+
+        consecutive_failures
+    }
+
+    /// 根据检测结果更新通知状态，并决定需要发送的通知
+    ///
+    /// 告警在决定发送时即设置冷却时间，避免发送期间的后续检测重复告警；
+    /// 冷却期间不发送的告警计入错过通知
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `result` - 健康检测结果
+    /// * `notification_state` - 通知状态，将被更新
+    /// * `can_notify` - 是否可以发送通知（配置了通知发送器且未静默）
+    /// * `now` - 当前时间
+    ///
+    /// # 返回
+    /// * `(Option<PlannedNotification>, bool)` - (需要发送的通知, 是否在冷却期间错过了告警)
+    fn plan_notification(
+        service: &ServiceConfig,
+        result: &HealthResult,
+        notification_state: &mut ServiceNotificationState,
+        can_notify: bool,
+        now: Instant,
+    ) -> (Option<PlannedNotification>, bool) {
+        let current_status = result.status;
+        let mut planned = None;
+        let mut missed = false;
 
         // 检查状态变化
         let (_status_changed, recovered_from_unhealthy) =
             Self::check_status_change(current_status, notification_state);
 
-        // 处理恢复通知
         if recovered_from_unhealthy {
-            if let Some(ref notifier) = notifier {
-                Self::send_with_retry(
-                    notifier,
-                    service,
-                    result,
-                    status_arc,
-                    &mut notification_state.notification_state,
-                )
-                .await?;
+            // 处理恢复通知
+            if can_notify {
+                planned = Some(PlannedNotification::Recovery);
             }
             Self::reset_failure_state(notification_state);
-        }
-
-        // 处理告警通知
-        if !is_healthy {
+        } else if !current_status.is_healthy() {
+            // 处理告警通知
             Self::update_failure_state(notification_state, now);
 
             if Self::should_send_alert(notification_state, service, now) {
-                if let Some(ref notifier) = notifier {
-                    let success = Self::send_with_retry(
-                        notifier,
+                if can_notify {
+                    // 判断是否需要设置冷却时间
+                    let first_threshold_failure =
+                        notification_state.failure_state.consecutive_failures
+                            == service.failure_threshold;
+                    let previous_cooldown =
+                        notification_state.notification_state.alert_cooldown_until;
+                    Self::update_alert_cooldown(
+                        notification_state,
                         service,
-                        result,
-                        status_arc,
-                        &mut notification_state.notification_state,
-                    )
-                    .await?;
-
-                    if success {
-                        notification_state.notification_state.notification_count += 1;
-                        notification_state.notification_state.last_notification_time = Some(now);
-
-                        // 判断是否需要设置冷却时间
-                        let is_first_threshold_failure =
-                            notification_state.failure_state.consecutive_failures
-                                == service.failure_threshold;
-
-                        Self::update_alert_cooldown(
-                            notification_state,
-                            service,
-                            now,
-                            is_first_threshold_failure,
-                        );
-                    }
+                        now,
+                        first_threshold_failure,
+                    );
+                    planned = Some(PlannedNotification::Alert { previous_cooldown });
                 }
             } else {
                 // 在冷却期间错过了通知，增加计数
                 notification_state
                     .notification_state
                     .missed_notifications_during_cooldown += 1;
-                // 更新统计信息
-                Self::update_notification_stats_static(status_arc, false, false, true).await;
+                missed = true;
             }
         } else {
             // 服务健康时重置失败状态，表示当前故障周期结束
@@ -600,7 +960,32 @@ impl TaskScheduler {
         // 更新最后健康状态
         notification_state.last_health_status = Some(current_status);
 
-        Ok(())
+        (planned, missed)
+    }
+
+    /// 记录通知的发送结果
+    ///
+    /// 告警发送失败时恢复发送前的冷却时间，下次检测可以再次尝试告警
+    fn record_delivery(
+        notification_state: &mut ServiceNotificationState,
+        planned: &PlannedNotification,
+        success: bool,
+        now: Instant,
+    ) {
+        let state = &mut notification_state.notification_state;
+        if !success {
+            state.notification_failures += 1;
+            if let PlannedNotification::Alert { previous_cooldown } = planned {
+                state.alert_cooldown_until = *previous_cooldown;
+            }
+            return;
+        }
+
+        state.notification_failures = 0;
+        if matches!(planned, PlannedNotification::Alert { .. }) {
+            state.notification_count += 1;
+            state.last_notification_time = Some(now);
+        }
     }
 
     /// 静态方法更新通知统计
@@ -626,124 +1011,9 @@ impl TaskScheduler {
         status.notification_stats.last_notification_time = Some(Instant::now());
     }
 
-    /// 启动单个服务的检测任务
-    async fn start_service_task(&self, service: ServiceConfig) -> Result<()> {
-        let service_name = service.name.clone();
-        let service_name_for_task = service_name.clone();
-        let checker = Arc::clone(&self.checker);
-        let config = Arc::clone(&self.config);
-        let semaphore = Arc::clone(&self.semaphore);
-        let notification_states = Arc::clone(&self.notification_states);
-
-        // 计算检测间隔
-        let check_interval = service.check_interval_seconds.unwrap_or_else(|| {
-            let config = config.try_read().unwrap();
-            config.check_interval_seconds
-        });
-
-        // 初始化通知状态
-        {
-            let mut states = notification_states.write().await;
-            states.insert(service_name.clone(), ServiceNotificationState::default());
-        }
-
-        // 创建检测任务
-        let notifier = self.notifier.clone();
-        let status_arc = Arc::clone(&self.status);
-        let health_callback = self.health_result_callback.clone();
-        let task = tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(check_interval));
-
-            info!("启动服务检测任务: {}", service_name_for_task);
-
-            loop {
-                interval.tick().await;
-
-                // 获取信号量许可
-                let _permit = match semaphore.acquire().await {
-                    Ok(permit) => permit,
-                    Err(_) => {
-                        warn!("获取并发许可失败，跳过本次检测: {}", service_name_for_task);
-                        continue;
-                    }
-                };
-
-                debug!("开始检测服务: {}", service_name_for_task);
-
-                // 执行健康检测
-                let result = match checker.check(&service).await {
-                    Ok(result) => result,
-                    Err(e) => {
-                        error!("检测服务失败 {}: {}", service_name_for_task, e);
-                        continue;
-                    }
-                };
-
-                // 处理通知逻辑
-                {
-                    let mut states = notification_states.write().await;
-                    if let Some(notification_state) = states.get_mut(&service_name_for_task) {
-                        if let Err(e) = Self::handle_notification_static(
-                            &service,
-                            &result,
-                            notification_state,
-                            &notifier,
-                            &status_arc,
-                        )
-                        .await
-                        {
-                            error!("处理通知失败: {}", e);
-                        }
-                    }
-                }
-
-                // 调用健康检测结果回调
-
-                // 调用健康检测结果回调
-                {
-                    let callback_guard = health_callback.read().await;
-                    if let Some(ref callback) = *callback_guard {
-                        callback(&result);
-                    }
-                }
-
-                // 记录检测结果
-                if result.status.is_healthy() {
-                    debug!("服务检测正常: {}", service_name_for_task);
-                } else {
-                    warn!(
-                        "服务检测失败: {},{}",
-                        service_name_for_task,
-                        result.error_message.unwrap_or_else(|| "N/A".to_string())
-                    );
-                }
-            }
-        });
-
-        // 保存任务句柄
-        let mut tasks = self.tasks.write().await;
-        tasks.insert(service_name, task);
-
-        Ok(())
-    }
-
-    /// 停止单个服务的检测任务
-    async fn stop_service_task(&self, service_name: &str) -> Result<()> {
-        let mut tasks = self.tasks.write().await;
-        if let Some(task) = tasks.remove(service_name) {
-            task.abort();
-            info!("停止服务检测任务: {}", service_name);
-        }
-        Ok(())
-    }
-
     /// 更新调度器状态
     async fn update_status(&self) {
-        let tasks = self.tasks.read().await;
-        let mut status = self.status.write().await;
-
-        status.running_tasks = tasks.len();
-        status.last_update = Instant::now();
+        self.ctx.refresh_status().await;
     }
 
     /// 启用配置热重载
@@ -761,31 +1031,14 @@ impl TaskScheduler {
     /// 启动配置更新监听器
     pub async fn start_config_update_listener(&mut self) {
         if let Some(mut receiver) = self.config_update_receiver.take() {
-            let tasks = Arc::clone(&self.tasks);
-            let config = Arc::clone(&self.config);
-            let status = Arc::clone(&self.status);
-            let checker = Arc::clone(&self.checker);
-            let notifier = self.notifier.clone();
-            let semaphore = Arc::clone(&self.semaphore);
-            let notification_states = Arc::clone(&self.notification_states);
+            let ctx = self.ctx.clone();
 
             tokio::spawn(async move {
                 info!("配置更新监听器已启动");
                 while let Ok(update) = receiver.recv().await {
                     info!("收到配置更新通知，版本: {}", update.version);
 
-                    if let Err(e) = TaskScheduler::handle_config_update(
-                        update,
-                        &tasks,
-                        &config,
-                        &status,
-                        &checker,
-                        &notifier,
-                        &semaphore,
-                        &notification_states,
-                    )
-                    .await
-                    {
+                    if let Err(e) = TaskScheduler::handle_config_update(update, &ctx).await {
                         error!("处理配置更新失败: {}", e);
                     }
                 }
@@ -794,10 +1047,9 @@ impl TaskScheduler {
         }
 
         // 启动定期检查错过通知的任务
-        let notification_states = Arc::clone(&self.notification_states);
-        let notifier = self.notifier.clone();
-        let status = Arc::clone(&self.status);
-        let _config = Arc::clone(&self.config);
+        let ctx = self.ctx.clone();
+        let notifier = self.ctx.notifier.clone();
+        let status = Arc::clone(&self.ctx.status);
 
         tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(60)); // 每分钟检查一次
@@ -805,34 +1057,10 @@ impl TaskScheduler {
                 interval.tick().await;
 
                 // 检查是否有错过通知需要发送
-                let states_clone = notification_states.read().await;
                 let now = Instant::now();
 
                 // 收集需要处理的服务
-                let services_to_check: Vec<_> = states_clone
-                    .iter()
-                    .filter(|(_, state)| {
-                        state
-                            .notification_state
-                            .missed_notifications_during_cooldown
-                            > 0
-                            && state
-                                .notification_state
-                                .alert_cooldown_until
-                                .map(|until| now >= until)
-                                .unwrap_or(true)
-                    })
-                    .map(|(name, state)| {
-                        (
-                            name.clone(),
-                            state
-                                .notification_state
-                                .missed_notifications_during_cooldown,
-                        )
-                    })
-                    .collect();
-
-                drop(states_clone);
+                let services_to_check = ctx.missed_notification_summaries(now).await;
 
                 // 处理这些服务
                 if !services_to_check.is_empty() {
@@ -876,9 +1104,10 @@ impl TaskScheduler {
                             }
 
                             // 重置错过通知计数
-                            let mut states = notification_states.write().await;
-                            if let Some(state) = states.get_mut(&service_name) {
+                            if let Some(state) = ctx.notification_states.get(&service_name) {
                                 state
+                                    .lock()
+                                    .await
                                     .notification_state
                                     .missed_notifications_during_cooldown = 0;
                             }
@@ -892,9 +1121,10 @@ impl TaskScheduler {
                             );
 
                             // 重置错过通知计数
-                            let mut states = notification_states.write().await;
-                            if let Some(state) = states.get_mut(&service_name) {
+                            if let Some(state) = ctx.notification_states.get(&service_name) {
                                 state
+                                    .lock()
+                                    .await
                                     .notification_state
                                     .missed_notifications_during_cooldown = 0;
                             }
@@ -906,16 +1136,9 @@ impl TaskScheduler {
     }
 
     /// 处理配置更新
-    #[allow(clippy::too_many_arguments)]
     async fn handle_config_update(
         update: ConfigUpdateNotification,
-        tasks: &Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
-        config: &Arc<RwLock<GlobalConfig>>,
-        status: &Arc<RwLock<SchedulerStatus>>,
-        checker: &Arc<dyn HealthChecker>,
-        notifier: &Option<Arc<dyn NotificationSender>>,
-        semaphore: &Arc<Semaphore>,
-        notification_states: &Arc<RwLock<HashMap<String, ServiceNotificationState>>>,
+        ctx: &CheckContext,
     ) -> Result<()> {
         info!(
             "处理配置更新，版本: {}, 变更数量: {}",
@@ -932,190 +1155,391 @@ impl TaskScheduler {
                 }
                 ConfigDiff::ServiceAdded(service) => {
                     info!("添加新服务: {}", service.name);
-                    if let Err(e) = TaskScheduler::start_new_service_task(
-                        (**service).clone(),
-                        tasks,
-                        checker,
-                        notifier,
-                        config,
-                        semaphore,
-                        notification_states,
-                        status,
-                    )
-                    .await
-                    {
-                        error!("启动新服务任务失败: {}", e);
-                    }
+                    ctx.register_service((**service).clone()).await;
                 }
                 ConfigDiff::ServiceRemoved(service_name) => {
                     info!("移除服务: {}", service_name);
-                    TaskScheduler::stop_service_task_by_name(
-                        service_name,
-                        tasks,
-                        notification_states,
-                    )
-                    .await;
+                    ctx.unregister_service(service_name).await;
+                    ctx.remove_heartbeat_state(service_name);
                 }
                 ConfigDiff::ServiceModified { old: _, new } => {
-                    info!("修改服务: {}", new.name);
-                    // 先停止旧任务
-                    TaskScheduler::stop_service_task_by_name(&new.name, tasks, notification_states)
-                        .await;
-                    // 启动新任务
-                    if let Err(e) = TaskScheduler::start_new_service_task(
-                        (**new).clone(),
-                        tasks,
-                        checker,
-                        notifier,
-                        config,
-                        semaphore,
-                        notification_states,
-                        status,
-                    )
-                    .await
-                    {
-                        error!("重启修改的服务任务失败: {}", e);
+                    info!("{}", diff);
+                    // 先停止旧任务，再按新配置启动，运行时的通知、暂停和静默状态保持不变
+                    let notification = ctx.notification_states.get(&new.name);
+                    let pause = ctx.paused.get(&new.name);
+                    let silence = ctx.silenced.get(&new.name);
+                    ctx.unregister_service(&new.name).await;
+                    if new.enabled {
+                        if let Some(notification) = notification {
+                            ctx.notification_states
+                                .insert(new.name.clone(), notification);
+                        }
+                        ctx.register_service((**new).clone()).await;
+                        if let Some(pause) = pause {
+                            ctx.paused.insert(new.name.clone(), pause);
+                        }
+                        if let Some(silence) = silence {
+                            ctx.silenced.insert(new.name.clone(), silence);
+                        }
+                    }
+                    if !new.enabled || new.heartbeat.is_none() {
+                        ctx.remove_heartbeat_state(&new.name);
                     }
                 }
             }
         }
 
         // 更新状态统计
-        {
-            let mut status_guard = status.write().await;
-            let tasks_guard = tasks.read().await;
-            status_guard.running_tasks = tasks_guard.len();
-            status_guard.last_update = Instant::now();
-        }
+        ctx.refresh_status().await;
 
         info!("配置更新处理完成");
         Ok(())
     }
+}
 
-    /// 启动新服务任务
-    #[allow(clippy::too_many_arguments)]
-    async fn start_new_service_task(
-        service: ServiceConfig,
-        tasks: &Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
-        checker: &Arc<dyn HealthChecker>,
-        notifier: &Option<Arc<dyn NotificationSender>>,
-        _config: &Arc<RwLock<GlobalConfig>>,
-        semaphore: &Arc<Semaphore>,
-        notification_states: &Arc<RwLock<HashMap<String, ServiceNotificationState>>>,
-        status: &Arc<RwLock<SchedulerStatus>>,
-    ) -> Result<()> {
-        let service_name = service.name.clone();
-        let service_name_for_task = service_name.clone();
-        let checker = Arc::clone(checker);
-        let notifier = notifier.clone();
-        let semaphore = Arc::clone(semaphore);
-        let notification_states = Arc::clone(notification_states);
-        let status_arc = Arc::clone(status);
-
-        // 计算检测间隔
-        let check_interval = service.check_interval_seconds.unwrap_or({
-            // 从全局配置获取默认值
-            60 // 默认60秒，实际应该从config中读取
-        });
+impl CheckContext {
+    /// 执行一次健康检测，并将结果交给通知逻辑和状态回调处理
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    ///
+    /// # 返回
+    /// * `Result<HealthResult>` - 检测结果
+    async fn execute_check(&self, service: &ServiceConfig) -> Result<HealthResult> {
+        let mut result = {
+            // 获取信号量许可
+            let _permit = self
+                .semaphore
+                .acquire()
+                .await
+                .with_context(|| format!("获取并发许可失败: {}", service.name))?;
+
+            debug!("开始检测服务: {}", service.name);
+
+            // 按当前全局配置解析服务的有效配置，配置热重载后立即生效
+            let effective = {
+                let config = self.config.read().await;
+                EffectiveServiceConfig::from_global(&config, service)
+            };
+
+            self.checker
+                .check_with_config(service, &effective)
+                .await
+                .with_context(|| format!("检测服务失败: {}", service.name))?
+        };
+
+        // 处理通知逻辑，只锁定当前服务的通知状态；静默期间照常记录失败状态但不发送通知
+        if let Some(state) = self.notification_states.get(&service.name) {
+            let notifier = if self.is_silenced(&service.name) {
+                debug!("服务通知已静默，跳过通知: {}", service.name);
+                None
+            } else {
+                self.notifier.clone()
+            };
+            result.consecutive_failures = TaskScheduler::handle_notification_static(
+                service,
+                &result,
+                &state,
+                &notifier,
+                &self.status,
+            )
+            .await;
+        }
 
-        // 初始化通知状态
+        // 调用健康检测结果回调
         {
-            let mut states = notification_states.write().await;
-            states.insert(service_name.clone(), ServiceNotificationState::default());
+            let callback_guard = self.health_result_callback.read().await;
+            if let Some(ref callback) = *callback_guard {
+                callback(&result);
+            }
         }
 
-        // 创建检测任务
-        let task = tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(check_interval));
-            info!("启动服务检测任务: {}", service_name_for_task);
+        // 记录检测结果
+        if result.status.is_healthy() {
+            debug!("服务检测正常: {}", service.name);
+        } else {
+            warn!(
+                "服务检测失败: {},{}",
+                service.name,
+                result.error_message.as_deref().unwrap_or("N/A")
+            );
+        }
 
-            loop {
-                interval.tick().await;
+        Ok(result)
+    }
 
-                // 获取信号量许可
-                let _permit = match semaphore.acquire().await {
-                    Ok(permit) => permit,
-                    Err(_) => {
-                        warn!("获取并发许可失败，跳过本次检测: {}", service_name_for_task);
-                        continue;
-                    }
+    /// 注册服务并加入共享定时器调度
+    ///
+    /// 同名服务已存在时会替换旧的注册，旧的调度会在到期时被丢弃，避免重复检测；
+    /// 已有的通知状态保持不变。
+    async fn register_service(&self, service: ServiceConfig) {
+        let service_name = service.name.clone();
+
+        // 计算调度计划
+        let schedule = {
+            let config = self.config.read().await;
+            match ServiceSchedule::new(&service, &config, Utc::now()) {
+                Ok(schedule) => schedule,
+                Err(e) => {
+                    error!(
+                        "服务 {} 的调度配置无效，未启动检测任务: {}",
+                        service_name, e
+                    );
+                    return;
+                }
+            }
+        };
+        let first_delay = schedule.delay_until_next(Utc::now());
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+
+        // 初始化通知状态，重新注册时沿用已有状态
+        if !self.notification_states.contains_key(&service_name) {
+            self.notification_states.insert(
+                service_name.clone(),
+                Arc::new(Mutex::new(ServiceNotificationState::default())),
+            );
+        }
+        if let Some(heartbeats) = self.checker.heartbeat_registry() {
+            heartbeats.register_service(&service);
+        }
+        self.services.insert(
+            service_name.clone(),
+            Arc::new(RegisteredService {
+                generation,
+                service,
+                schedule: StdMutex::new(schedule),
+            }),
+        );
+
+        match first_delay {
+            Some(delay) => {
+                let check = ScheduledCheck {
+                    due: Instant::now() + delay,
+                    generation,
+                    service_name: service_name.clone(),
                 };
+                if self.schedule_sender().send(check).is_err() {
+                    error!("调度器定时器已停止，无法调度服务: {}", service_name);
+                    return;
+                }
+                info!("服务任务已启动: {}", service_name);
+            }
+            None => info!("服务没有后续调度时间，未加入调度: {}", service_name),
+        }
+    }
 
-                debug!("开始检测服务: {}", service_name_for_task);
+    /// 获取调度请求发送端，定时器和工作池未运行时启动它们
+    fn schedule_sender(&self) -> mpsc::UnboundedSender<ScheduledCheck> {
+        let mut dispatcher = lock(&self.dispatcher);
+        if let Some(ref running) = *dispatcher {
+            return running.schedules.clone();
+        }
+
+        let (schedule_tx, schedule_rx) = mpsc::unbounded_channel();
+        let (job_tx, job_rx) = mpsc::unbounded_channel();
+        let jobs: JobQueue = Arc::new(Mutex::new(job_rx));
+
+        let mut handles = Vec::with_capacity(self.worker_count + 1);
+        handles.push(tokio::spawn(dispatcher::run_timer(schedule_rx, job_tx)));
+        for _ in 0..self.worker_count {
+            let ctx = self.clone();
+            handles.push(tokio::spawn(
+                ctx.run_worker(Arc::clone(&jobs), schedule_tx.clone()),
+            ));
+        }
+        info!("调度器定时器已启动，工作任务数: {}", self.worker_count);
 
-                // 执行健康检测
-                let result = match checker.check(&service).await {
-                    Ok(result) => result,
+        *dispatcher = Some(Dispatcher {
+            schedules: schedule_tx.clone(),
+            handles,
+        });
+        schedule_tx
+    }
+
+    /// 停止共享定时器和工作池
+    fn shutdown_dispatcher(&self) {
+        if let Some(dispatcher) = lock(&self.dispatcher).take() {
+            dispatcher.shutdown();
+        }
+    }
+
+    /// 工作任务：执行到期的检测并把下一次调度交回定时器
+    async fn run_worker(self, jobs: JobQueue, schedules: mpsc::UnboundedSender<ScheduledCheck>) {
+        while let Some(job) = dispatcher::next_job(&jobs).await {
+            let Some(entry) = self
+                .services
+                .get(&job.service_name)
+                .filter(|entry| entry.generation == job.generation)
+            else {
+                debug!("丢弃已失效的调度: {}", job.service_name);
+                continue;
+            };
+            let service = &entry.service;
+
+            let status = if self.is_paused(&service.name).await {
+                debug!("服务已暂停，跳过本次检测: {}", service.name);
+                None
+            } else {
+                match self.execute_check(service).await {
+                    Ok(result) => Some(result.status),
                     Err(e) => {
-                        error!("检测服务失败 {}: {}", service_name_for_task, e);
-                        continue;
+                        error!("{:#}", e);
+                        None
                     }
-                };
-
-                // 处理通知逻辑
-                {
-                    let mut states = notification_states.write().await;
-                    if let Some(notification_state) = states.get_mut(&service_name_for_task) {
-                        if let Err(e) = TaskScheduler::handle_notification_static(
-                            &service,
-                            &result,
-                            notification_state,
-                            &notifier,
-                            &status_arc,
-                        )
-                        .await
-                        {
-                            error!("处理通知失败: {}", e);
-                        }
+                }
+            };
+
+            let delay = {
+                let mut schedule = lock(&entry.schedule);
+                // 根据检测结果自适应切换检测间隔
+                if let Some(status) = status {
+                    if let Some(period) = schedule.record_status(status) {
+                        info!(
+                            "服务 {} 状态为 {}，检测间隔调整为 {} 秒",
+                            service.name,
+                            status,
+                            period.as_secs()
+                        );
                     }
                 }
+                schedule.advance(Utc::now());
+                schedule.delay_until_next(Utc::now())
+            };
+
+            let Some(delay) = delay else {
+                info!("服务没有后续调度时间，检测任务结束: {}", service.name);
+                continue;
+            };
+
+            // 检测期间服务可能已被移除或重新注册
+            if self
+                .services
+                .with(&service.name, |current| current.generation)
+                != Some(job.generation)
+            {
+                continue;
+            }
 
-                // 记录检测结果
-                if result.status.is_healthy() {
-                    debug!("服务检测正常: {}", service_name_for_task);
-                } else {
-                    warn!(
-                        "服务检测失败: {},{}",
-                        service_name_for_task,
-                        result.error_message.unwrap_or_else(|| "N/A".to_string())
-                    );
-                }
+            let next = ScheduledCheck {
+                due: Instant::now() + delay,
+                ..job
+            };
+            if schedules.send(next).is_err() {
+                break;
             }
-        });
+        }
+    }
 
-        // 将任务添加到tasks映射中
-        {
-            let mut task_map = tasks.write().await;
-            task_map.insert(service_name.clone(), task);
+    /// 移除服务的调度并清理相关状态
+    async fn unregister_service(&self, service_name: &str) {
+        // 移除注册后，队列中的旧调度会在到期时被丢弃
+        if self.services.remove(service_name).is_some() {
+            info!("已停止服务任务: {}", service_name);
         }
 
-        info!("服务任务已启动: {}", service_name);
-        Ok(())
+        if let Some(heartbeats) = self.checker.heartbeat_registry() {
+            heartbeats.unregister_service(service_name);
+        }
+
+        // 清理通知状态、暂停状态和静默状态
+        self.notification_states.remove(service_name);
+        self.paused.remove(service_name);
+        self.silenced.remove(service_name);
     }
 
-    /// 按名称停止服务任务
-    async fn stop_service_task_by_name(
-        service_name: &str,
-        tasks: &Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
-        notification_states: &Arc<RwLock<HashMap<String, ServiceNotificationState>>>,
-    ) {
-        // 停止任务
+    /// 清理已移除或不再使用心跳检测的服务的心跳记录
+    fn remove_heartbeat_state(&self, service_name: &str) {
+        if let Some(heartbeats) = self.checker.heartbeat_registry() {
+            heartbeats.remove_state(service_name);
+        }
+    }
+
+    /// 判断服务通知当前是否处于静默状态，静默到期时自动取消
+    fn is_silenced(&self, service_name: &str) -> bool {
+        let now = Utc::now();
+        match self
+            .silenced
+            .with(service_name, |info| info.is_expired(now))
         {
-            let mut task_map = tasks.write().await;
-            if let Some(task) = task_map.remove(service_name) {
-                task.abort();
-                info!("已停止服务任务: {}", service_name);
+            None => false,
+            Some(false) => true,
+            Some(true) => {
+                if self
+                    .silenced
+                    .remove_if(service_name, |info| info.is_expired(now))
+                    .is_some()
+                {
+                    info!("服务通知静默已到期: {}", service_name);
+                }
+                false
             }
         }
+    }
 
-        // 清理通知状态
-        {
-            let mut states = notification_states.write().await;
-            states.remove(service_name);
+    /// 判断服务当前是否处于暂停状态，暂停到期时自动恢复
+    async fn is_paused(&self, service_name: &str) -> bool {
+        let now = Utc::now();
+        let expired = match self.paused.with(service_name, |info| info.is_expired(now)) {
+            None => return false,
+            Some(expired) => expired,
+        };
+
+        if expired {
+            if self
+                .paused
+                .remove_if(service_name, |info| info.is_expired(now))
+                .is_some()
+            {
+                info!("服务暂停已到期，自动恢复检测: {}", service_name);
+            }
+            return false;
+        }
+
+        true
+    }
+
+    /// 收集冷却结束后需要发送错过通知汇总的服务及错过次数
+    ///
+    /// 静默或暂停中的服务不发送汇总，错过次数保留到静默或暂停结束
+    async fn missed_notification_summaries(&self, now: Instant) -> Vec<(String, u32)> {
+        let mut summaries = Vec::new();
+        for (name, state) in self.notification_states.snapshot() {
+            if self.is_silenced(&name) || self.is_paused(&name).await {
+                continue;
+            }
+            let state = state.lock().await;
+            let missed_count = state
+                .notification_state
+                .missed_notifications_during_cooldown;
+            let cooldown_ended = state
+                .notification_state
+                .alert_cooldown_until
+                .map(|until| now >= until)
+                .unwrap_or(true);
+            if missed_count > 0 && cooldown_ended {
+                summaries.push((name, missed_count));
+            }
         }
+        summaries
+    }
+
+    /// 刷新调度中的服务统计
+    async fn refresh_status(&self) {
+        let running = lock(&self.dispatcher).is_some();
+        let mut status = self.status.write().await;
+
+        status.running_tasks = if running { self.services.len() } else { 0 };
+        status.last_update = Instant::now();
     }
 }
 
+/// 获取同步互斥锁，锁被污染时继续使用内部数据
+fn lock<T>(mutex: &StdMutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 #[async_trait]
 impl Scheduler for TaskScheduler {
     async fn start(&self, services: Vec<ServiceConfig>) -> Result<()> {
@@ -1123,7 +1547,7 @@ impl Scheduler for TaskScheduler {
 
         // 更新状态
         {
-            let mut status = self.status.write().await;
+            let mut status = self.ctx.status.write().await;
             status.total_services = services.len();
             status.is_running = true;
         }
@@ -1131,10 +1555,7 @@ impl Scheduler for TaskScheduler {
         // 启动所有服务的检测任务
         for service in services {
             if service.enabled {
-                let service_name = service.name.clone();
-                self.start_service_task(service)
-                    .await
-                    .with_context(|| format!("启动服务任务失败: {service_name}"))?;
+                self.ctx.register_service(service).await;
             } else {
                 debug!("跳过已禁用的服务: {}", service.name);
             }
@@ -1148,16 +1569,12 @@ impl Scheduler for TaskScheduler {
     async fn stop(&self) -> Result<()> {
         info!("停止任务调度器");
 
-        // 停止所有任务
-        let mut tasks = self.tasks.write().await;
-        for (service_name, task) in tasks.drain() {
-            task.abort();
-            debug!("停止任务: {}", service_name);
-        }
+        // 停止共享定时器和工作池
+        self.ctx.shutdown_dispatcher();
 
         // 更新状态
         {
-            let mut status = self.status.write().await;
+            let mut status = self.ctx.status.write().await;
             status.is_running = false;
             status.running_tasks = 0;
         }
@@ -1169,11 +1586,8 @@ impl Scheduler for TaskScheduler {
     async fn reload_config(&self, services: Vec<ServiceConfig>) -> Result<()> {
         info!("重新加载配置，服务数量: {}", services.len());
 
-        // 获取当前运行的任务列表
-        let current_tasks: Vec<String> = {
-            let tasks = self.tasks.read().await;
-            tasks.keys().cloned().collect()
-        };
+        // 获取当前已注册的服务列表
+        let current_tasks = self.ctx.services.keys();
 
         // 获取新配置中的服务列表
         let new_services: HashMap<String, ServiceConfig> = services
@@ -1185,17 +1599,20 @@ impl Scheduler for TaskScheduler {
         // 停止不再需要的任务
         for service_name in &current_tasks {
             if !new_services.contains_key(service_name) {
-                self.stop_service_task(service_name).await?;
+                self.ctx.unregister_service(service_name).await;
             }
         }
 
-        // 启动新的或更新的任务
-        for (service_name, service) in new_services {
-            if current_tasks.contains(&service_name) {
-                // 重启已存在的任务（配置可能已更改）
-                self.stop_service_task(&service_name).await?;
+        // 启动新的或配置变更的任务，配置未变的任务保持原调度，通知状态保持不变
+        for (name, service) in new_services {
+            let unchanged = self
+                .ctx
+                .services
+                .with(&name, |registered| registered.service == service)
+                .unwrap_or(false);
+            if !unchanged {
+                self.ctx.register_service(service).await;
             }
-            self.start_service_task(service).await?;
         }
 
         self.update_status().await;
@@ -1204,20 +1621,93 @@ impl Scheduler for TaskScheduler {
     }
 
     async fn get_status(&self) -> SchedulerStatus {
-        self.status.read().await.clone()
+        self.ctx.status.read().await.clone()
     }
 }
 
 impl Drop for TaskScheduler {
     fn drop(&mut self) {
-        // 确保在调度器被销毁时停止所有任务
-        // 注意：这里不能使用async，所以只能发出停止信号
-        let tasks = self.tasks.clone();
-        tokio::spawn(async move {
-            let mut tasks = tasks.write().await;
-            for (_, task) in tasks.drain() {
-                task.abort();
-            }
-        });
+        // 确保在调度器被销毁时停止定时器和工作池
+        self.ctx.shutdown_dispatcher();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::HttpHealthChecker;
+
+    #[tokio::test]
+    async fn test_missed_notification_summaries_skip_silenced_and_paused() {
+        let checker = Arc::new(
+            HttpHealthChecker::new(Duration::from_secs(1), 0, Duration::from_millis(10)).unwrap(),
+        );
+        let scheduler = TaskScheduler::new(checker, None, toml::from_str("").unwrap());
+        for name in ["api", "db", "cache"] {
+            let service: ServiceConfig = toml::from_str(&format!(
+                "name = \"{name}\"\nurl = \"http://127.0.0.1:1/{name}\"\ncheck_interval_seconds = 3600"
+            ))
+            .unwrap();
+            scheduler.ctx.register_service(service).await;
+            let state = scheduler.ctx.notification_states.get(name).unwrap();
+            state
+                .lock()
+                .await
+                .notification_state
+                .missed_notifications_during_cooldown = 2;
+        }
+
+        scheduler.silence_service("db", None, None).await.unwrap();
+        scheduler.pause_service("cache", None, None).await.unwrap();
+
+        let summaries = scheduler
+            .ctx
+            .missed_notification_summaries(Instant::now())
+            .await;
+        assert_eq!(summaries, vec![("api".to_string(), 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_config_update_prunes_removed_heartbeat_services() {
+        let checker = Arc::new(
+            HttpHealthChecker::new(Duration::from_secs(1), 0, Duration::from_millis(10)).unwrap(),
+        );
+        let heartbeats = checker.heartbeats();
+        let scheduler = TaskScheduler::new(checker, None, toml::from_str("").unwrap());
+        let service: ServiceConfig = toml::from_str(
+            r#"
+            name = "nightly-backup"
+            url = "heartbeat://nightly-backup"
+            check_interval_seconds = 3600
+            [heartbeat]
+            token = "nightly-backup-7f3a9c"
+            period_seconds = 3600
+            "#,
+        )
+        .unwrap();
+        scheduler.ctx.register_service(service).await;
+        assert!(heartbeats
+            .service_for_token("nightly-backup-7f3a9c")
+            .is_some());
+        heartbeats.record(
+            "nightly-backup",
+            crate::health::HeartbeatEvent::Success,
+            None,
+        );
+
+        let update = ConfigUpdateNotification {
+            version: 2,
+            diffs: vec![ConfigDiff::ServiceRemoved("nightly-backup".to_string())],
+            timestamp: std::time::Instant::now(),
+            requires_restart: false,
+        };
+        TaskScheduler::handle_config_update(update, &scheduler.ctx)
+            .await
+            .unwrap();
+
+        assert!(heartbeats
+            .service_for_token("nightly-backup-7f3a9c")
+            .is_none());
+        assert!(heartbeats.remove_state("nightly-backup").is_none());
     }
 }
@@ -0,0 +1,329 @@
+//! 多步骤检测的执行辅助
+//!
+//! 为每个步骤生成请求使用的服务配置，校验步骤响应并按提取规则更新变量。
+//! 步骤请求本身由 [`HttpHealthChecker`](crate::health::HttpHealthChecker) 发送，
+//! 与单请求检测共享客户端、认证和超时处理。
+
+use crate::config::effective::EffectiveServiceConfig;
+use crate::config::steps::{
+    substitute_json_variables, substitute_variables, Extractor, JsonPath, StepConfig,
+};
+use crate::config::ServiceConfig;
+use crate::health::result::HealthStatus;
+use regex::Regex;
+use reqwest::header::HeaderMap;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// 步骤之间传递的变量
+pub type StepVariables = HashMap<String, String>;
+
+/// 单个步骤的执行结果，记录在检测结果元数据的 `steps` 中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepOutcome {
+    /// 步骤名称
+    pub name: String,
+    /// HTTP方法
+    pub method: String,
+    /// 替换变量后的请求URL
+    pub url: String,
+    /// 步骤状态
+    pub status: HealthStatus,
+    /// HTTP状态码
+    pub status_code: Option<u16>,
+    /// 步骤耗时（毫秒）
+    pub response_time_ms: u64,
+    /// 失败原因
+    pub error: Option<String>,
+    /// 本步骤提取的变量名（不记录变量值）
+    #[serde(default)]
+    pub extracted: Vec<String>,
+}
+
+/// 生成步骤请求使用的服务配置和有效配置
+///
+/// 替换URL、请求头和请求体中的变量，相对URL基于服务URL解析；
+/// 步骤自带 Authorization 请求头时不再使用服务的认证配置
+///
+/// # 参数
+/// * `service` - 服务配置
+/// * `effective` - 服务的有效配置
+/// * `step` - 步骤配置
+/// * `variables` - 前面步骤提取的变量
+///
+/// # 返回
+/// * `Result<(ServiceConfig, EffectiveServiceConfig), String>` - 步骤请求配置，URL无效时返回错误
+pub fn step_request(
+    service: &ServiceConfig,
+    effective: &EffectiveServiceConfig,
+    step: &StepConfig,
+    variables: &StepVariables,
+) -> Result<(ServiceConfig, EffectiveServiceConfig), String> {
+    let url = substitute_variables(&step.url, variables);
+    let url = if url.starts_with('/') {
+        Url::parse(&service.url)
+            .and_then(|base| base.join(&url))
+            .map_err(|e| format!("无法解析步骤URL {url}: {e}"))?
+            .to_string()
+    } else {
+        url
+    };
+
+    let mut headers = effective.headers.clone();
+    for (key, value) in &step.headers {
+        let key = substitute_variables(key, variables);
+        headers.retain(|existing, _| !existing.eq_ignore_ascii_case(&key));
+        headers.insert(key, substitute_variables(value, variables));
+    }
+    let overrides_auth = step
+        .headers
+        .keys()
+        .any(|key| key.eq_ignore_ascii_case("authorization"));
+
+    let expected_status_codes = if step.expected_status_codes.is_empty() {
+        service.expected_status_codes.clone()
+    } else {
+        step.expected_status_codes.clone()
+    };
+
+    let step_service = ServiceConfig {
+        url: url.clone(),
+        method: step.method.clone(),
+        expected_status_codes,
+        headers: HashMap::new(),
+        body: step
+            .body
+            .as_ref()
+            .map(|body| substitute_json_variables(body, variables)),
+        auth: if overrides_auth {
+            None
+        } else {
+            service.auth.clone()
+        },
+        steps: Vec::new(),
+        dns: None,
+        grpc: None,
+        database: None,
+        exec: None,
+        heartbeat: None,
+        host: None,
+        websocket: None,
+        ..service.clone()
+    };
+    let step_effective = EffectiveServiceConfig {
+        url,
+        method: step.method.clone(),
+        headers,
+        ..effective.clone()
+    };
+
+    Ok((step_service, step_effective))
+}
+
+/// 校验步骤响应并提取变量
+///
+/// # 参数
+/// * `step` - 步骤配置
+/// * `headers` - 响应头
+/// * `body` - 响应体
+/// * `variables` - 变量表，提取到的变量写入其中
+///
+/// # 返回
+/// * `Result<Vec<String>, String>` - 提取到的变量名，断言失败或无法提取时返回原因
+pub fn evaluate_response(
+    step: &StepConfig,
+    headers: &HeaderMap,
+    body: &[u8],
+    variables: &mut StepVariables,
+) -> Result<Vec<String>, String> {
+    let text = String::from_utf8_lossy(body);
+
+    if let Some(expected) = &step.body_contains {
+        if !text.contains(expected.as_str()) {
+            return Err(format!("响应体不包含 {expected:?}"));
+        }
+    }
+
+    let mut extracted = Vec::with_capacity(step.extract.len());
+    for (variable, extractor) in &step.extract {
+        let value = extract(extractor, headers, &text)
+            .map_err(|reason| format!("无法提取变量 {variable}: {reason}"))?;
+        variables.insert(variable.clone(), value);
+        extracted.push(variable.clone());
+    }
+
+    Ok(extracted)
+}
+
+/// 按提取方式从响应中取值
+fn extract(extractor: &Extractor, headers: &HeaderMap, body: &str) -> Result<String, String> {
+    match extractor {
+        Extractor::Json(path) => {
+            let path = JsonPath::from_str(path)?;
+            let json: serde_json::Value =
+                serde_json::from_str(body).map_err(|e| format!("响应体不是有效的JSON: {e}"))?;
+            match path.select(&json) {
+                Some(serde_json::Value::String(value)) => Ok(value.clone()),
+                Some(serde_json::Value::Null) | None => Err(format!("{extractor} 没有匹配的值")),
+                Some(value) => Ok(value.to_string()),
+            }
+        }
+        Extractor::Header(name) => headers
+            .get(name.as_str())
+            .ok_or_else(|| format!("{extractor} 不存在"))?
+            .to_str()
+            .map(str::to_string)
+            .map_err(|_| format!("{extractor} 不是有效的文本")),
+        Extractor::Regex(pattern) => {
+            let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+            let captures = regex
+                .captures(body)
+                .ok_or_else(|| format!("{extractor} 没有匹配的内容"))?;
+            let matched = captures.get(1).or_else(|| captures.get(0));
+            Ok(matched.map(|m| m.as_str().to_string()).unwrap_or_default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::effective::RequestDefaults;
+    use crate::config::types::AuthConfig;
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    fn service() -> ServiceConfig {
+        toml::from_str(
+            r#"
+            name = "flow"
+            url = "https://api.example.com/v1/health"
+            expected_status_codes = [200]
+            headers = { "X-Trace" = "1", "Accept" = "text/plain" }
+            auth = { type = "bearer", token = "service-token" }
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn step(toml_str: &str) -> StepConfig {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    fn effective(service: &ServiceConfig) -> EffectiveServiceConfig {
+        let defaults = RequestDefaults {
+            headers: HashMap::new(),
+            timeout: Duration::from_secs(5),
+            retry_attempts: 0,
+            retry_delay: Duration::from_secs(1),
+            retry_backoff: Default::default(),
+            check_interval_seconds: 60,
+        };
+        EffectiveServiceConfig::resolve(&defaults, service)
+    }
+
+    #[test]
+    fn test_step_request_substitutes_variables() {
+        let service = service();
+        let effective = effective(&service);
+        let step = step(
+            r#"
+            name = "profile"
+            url = "/users/${user}"
+            method = "POST"
+            headers = { accept = "application/json" }
+            body = { session = "${session}" }
+            "#,
+        );
+        let variables = StepVariables::from([
+            ("user".to_string(), "42".to_string()),
+            ("session".to_string(), "s-1".to_string()),
+        ]);
+
+        let (step_service, step_effective) =
+            step_request(&service, &effective, &step, &variables).unwrap();
+        assert_eq!(step_service.url, "https://api.example.com/users/42");
+        assert_eq!(step_service.method, "POST");
+        assert_eq!(step_service.expected_status_codes, vec![200]);
+        assert_eq!(
+            step_service.body,
+            Some(serde_json::json!({ "session": "s-1" }))
+        );
+        assert!(step_service.auth.is_some());
+        assert_eq!(
+            step_effective.headers,
+            BTreeMap::from([
+                ("X-Trace".to_string(), "1".to_string()),
+                ("accept".to_string(), "application/json".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_step_authorization_header_overrides_auth() {
+        let service = service();
+        let effective = effective(&service);
+        let step = step(
+            r#"
+            name = "me"
+            url = "https://api.example.com/me"
+            headers = { Authorization = "Bearer ${token}" }
+            "#,
+        );
+        let variables = StepVariables::from([("token".to_string(), "t".to_string())]);
+
+        let (step_service, step_effective) =
+            step_request(&service, &effective, &step, &variables).unwrap();
+        assert!(step_service.auth.is_none());
+        assert_eq!(step_effective.headers["Authorization"], "Bearer t");
+        assert!(matches!(service.auth, Some(AuthConfig::Bearer { .. })));
+    }
+
+    #[test]
+    fn test_evaluate_response_extracts_variables() {
+        let step = step(
+            r#"
+            name = "login"
+            url = "/login"
+            body_contains = "access_token"
+            [extract]
+            token = { json = "$.access_token" }
+            expires = { json = "$.expires_in" }
+            session = { header = "X-Session" }
+            csrf = { regex = "csrf=([a-z0-9]+)" }
+            "#,
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert("x-session", "abc".parse().unwrap());
+        let body = br#"{"access_token":"tok","expires_in":3600,"note":"csrf=f00"}"#;
+
+        let mut variables = StepVariables::new();
+        let extracted = evaluate_response(&step, &headers, body, &mut variables).unwrap();
+        assert_eq!(extracted, vec!["csrf", "expires", "session", "token"]);
+        assert_eq!(variables["token"], "tok");
+        assert_eq!(variables["expires"], "3600");
+        assert_eq!(variables["session"], "abc");
+        assert_eq!(variables["csrf"], "f00");
+    }
+
+    #[test]
+    fn test_evaluate_response_failures() {
+        let mut variables = StepVariables::new();
+        let headers = HeaderMap::new();
+
+        let contains = step("name = \"a\"\nurl = \"/\"\nbody_contains = \"ok\"");
+        let error = evaluate_response(&contains, &headers, b"error", &mut variables).unwrap_err();
+        assert!(error.contains("不包含"));
+
+        let missing =
+            step("name = \"b\"\nurl = \"/\"\nextract = { token = { json = \"$.token\" } }");
+        let error = evaluate_response(&missing, &headers, b"{}", &mut variables).unwrap_err();
+        assert!(error.contains("token"));
+
+        let not_json = evaluate_response(&missing, &headers, b"<html>", &mut variables);
+        assert!(not_json.unwrap_err().contains("JSON"));
+        assert!(variables.is_empty());
+    }
+}
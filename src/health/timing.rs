@@ -0,0 +1,378 @@
+//! 检测请求的阶段耗时记录
+//!
+//! 每次发送请求时通过 task-local 的 [`PhaseRecorder`] 收集时间点：
+//! 连接器层记录建立连接的起止，解析器记录DNS解析的起止，
+//! TLS会话缓存在客户端发送ClientHello前被查询，以此记录TLS握手开始。
+//! 复用连接池中的连接时不会建立新连接，只记录首字节时间和下载时间。
+
+use crate::health::result::RequestTimings;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use rustls::client::{ClientSessionStore, Tls12ClientSessionValue, Tls13ClientSessionValue};
+use rustls::pki_types::ServerName;
+use rustls::NamedGroup;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+tokio::task_local! {
+    static RECORDER: PhaseRecorder;
+}
+
+/// 一次请求中记录到的时间点
+#[derive(Debug, Clone, Copy, Default)]
+struct PhaseMarks {
+    connect_start: Option<Instant>,
+    dns_start: Option<Instant>,
+    dns_end: Option<Instant>,
+    tls_start: Option<Instant>,
+    connected: Option<Instant>,
+}
+
+/// 请求阶段记录器
+#[derive(Debug, Clone, Default)]
+pub struct PhaseRecorder {
+    marks: Arc<StdMutex<PhaseMarks>>,
+}
+
+impl PhaseRecorder {
+    /// 在记录器作用域内执行请求
+    ///
+    /// 只有在该future所在任务中建立的连接会被记录；
+    /// 连接池中已有连接先就绪时，后台继续建立的连接不计入本次请求
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        RECORDER.scope(self.clone(), future).await
+    }
+
+    /// 计算各阶段耗时
+    ///
+    /// # 参数
+    /// * `request_start` - 开始发送请求的时间
+    /// * `headers_at` - 收到响应头的时间
+    /// * `body_end` - 响应体读取完成的时间
+    pub fn timings(
+        &self,
+        request_start: Instant,
+        headers_at: Instant,
+        body_end: Option<Instant>,
+    ) -> RequestTimings {
+        let marks = *self
+            .marks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        compute_timings(&marks, request_start, headers_at, body_end)
+    }
+}
+
+fn compute_timings(
+    marks: &PhaseMarks,
+    request_start: Instant,
+    headers_at: Instant,
+    body_end: Option<Instant>,
+) -> RequestTimings {
+    let download_ms = body_end.map(|end| elapsed_ms(headers_at, end));
+
+    let Some(connected) = marks.connected else {
+        return RequestTimings {
+            ttfb_ms: elapsed_ms(request_start, headers_at),
+            download_ms,
+            connection_reused: true,
+            ..Default::default()
+        };
+    };
+
+    let connect_start = marks.connect_start.unwrap_or(request_start);
+    let dns_ms = marks
+        .dns_start
+        .zip(marks.dns_end)
+        .map(|(start, end)| elapsed_ms(start, end));
+    let tcp_start = marks.dns_end.unwrap_or(connect_start);
+    let tcp_end = marks.tls_start.unwrap_or(connected);
+
+    RequestTimings {
+        dns_ms,
+        connect_ms: Some(elapsed_ms(tcp_start, tcp_end)),
+        tls_ms: marks.tls_start.map(|start| elapsed_ms(start, connected)),
+        ttfb_ms: elapsed_ms(connected, headers_at),
+        download_ms,
+        connection_reused: false,
+    }
+}
+
+fn elapsed_ms(from: Instant, to: Instant) -> f64 {
+    to.saturating_duration_since(from).as_secs_f64() * 1000.0
+}
+
+/// 在当前任务的记录器上记录时间点，不在记录器作用域内时忽略
+fn mark(update: impl FnOnce(&mut PhaseMarks, Instant)) {
+    let _ = RECORDER.try_with(|recorder| {
+        let mut marks = recorder
+            .marks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        update(&mut marks, Instant::now());
+    });
+}
+
+/// 为HTTP客户端的连接器记录建立连接的起止时间
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimingLayer;
+
+impl<S> tower::Layer<S> for TimingLayer {
+    type Service = TimingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimingService { inner }
+    }
+}
+
+/// [`TimingLayer`] 包装后的连接器
+#[derive(Debug, Clone)]
+pub struct TimingService<S> {
+    inner: S,
+}
+
+impl<S, R> tower::Service<R> for TimingService<S>
+where
+    S: tower::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        // 新的连接覆盖之前的记录
+        mark(|marks, now| {
+            *marks = PhaseMarks {
+                connect_start: Some(now),
+                ..Default::default()
+            }
+        });
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let connection = connecting.await?;
+            mark(|marks, now| marks.connected = Some(now));
+            Ok(connection)
+        })
+    }
+}
+
+/// 记录解析耗时的DNS解析器
+///
+/// 覆盖SNI时把 `server_name` 解析为原URL主机的地址
+#[derive(Debug, Default)]
+pub struct TimedResolver {
+    server_name_override: Option<(String, String)>,
+}
+
+impl TimedResolver {
+    /// 创建把 `server_name` 解析为 `target_host` 地址的解析器
+    pub fn with_server_name(server_name: String, target_host: String) -> Self {
+        Self {
+            server_name_override: Some((server_name, target_host)),
+        }
+    }
+}
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = match &self.server_name_override {
+            Some((server_name, target_host)) if name.as_str().eq_ignore_ascii_case(server_name) => {
+                target_host.clone()
+            }
+            _ => name.as_str().to_string(),
+        };
+
+        mark(|marks, now| marks.dns_start = Some(now));
+        Box::pin(async move {
+            let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            mark(|marks, now| marks.dns_end = Some(now));
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// 记录TLS握手开始时间的会话缓存
+///
+/// rustls 在生成ClientHello时查询会话缓存，第一次查询即视为握手开始
+#[derive(Debug)]
+pub struct TimedSessionStore {
+    inner: Arc<dyn ClientSessionStore>,
+}
+
+impl TimedSessionStore {
+    /// 包装会话缓存
+    pub fn new(inner: Arc<dyn ClientSessionStore>) -> Self {
+        Self { inner }
+    }
+
+    fn mark_handshake_start() {
+        mark(|marks, now| {
+            marks.tls_start.get_or_insert(now);
+        });
+    }
+}
+
+impl ClientSessionStore for TimedSessionStore {
+    fn set_kx_hint(&self, server_name: ServerName<'static>, group: NamedGroup) {
+        self.inner.set_kx_hint(server_name, group);
+    }
+
+    fn kx_hint(&self, server_name: &ServerName<'_>) -> Option<NamedGroup> {
+        Self::mark_handshake_start();
+        self.inner.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: ServerName<'static>, value: Tls12ClientSessionValue) {
+        self.inner.set_tls12_session(server_name, value);
+    }
+
+    fn tls12_session(&self, server_name: &ServerName<'_>) -> Option<Tls12ClientSessionValue> {
+        Self::mark_handshake_start();
+        self.inner.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName<'static>) {
+        self.inner.remove_tls12_session(server_name);
+    }
+
+    fn insert_tls13_ticket(
+        &self,
+        server_name: ServerName<'static>,
+        value: Tls13ClientSessionValue,
+    ) {
+        self.inner.insert_tls13_ticket(server_name, value);
+    }
+
+    fn take_tls13_ticket(
+        &self,
+        server_name: &ServerName<'static>,
+    ) -> Option<Tls13ClientSessionValue> {
+        Self::mark_handshake_start();
+        self.inner.take_tls13_ticket(server_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn test_compute_timings_new_connection() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let marks = PhaseMarks {
+            connect_start: Some(at(1)),
+            dns_start: Some(at(1)),
+            dns_end: Some(at(6)),
+            tls_start: Some(at(16)),
+            connected: Some(at(36)),
+        };
+
+        let timings = compute_timings(&marks, start, at(86), Some(at(90)));
+        assert_eq!(timings.dns_ms, Some(5.0));
+        assert_eq!(timings.connect_ms, Some(10.0));
+        assert_eq!(timings.tls_ms, Some(20.0));
+        assert_eq!(timings.ttfb_ms, 50.0);
+        assert_eq!(timings.download_ms, Some(4.0));
+        assert!(!timings.connection_reused);
+
+        // 明文HTTP且主机为IP地址：连接阶段从发起连接算起
+        let plain = PhaseMarks {
+            connect_start: Some(at(1)),
+            connected: Some(at(3)),
+            ..Default::default()
+        };
+        let timings = compute_timings(&plain, start, at(10), None);
+        assert_eq!(timings.dns_ms, None);
+        assert_eq!(timings.connect_ms, Some(2.0));
+        assert_eq!(timings.tls_ms, None);
+        assert_eq!(timings.ttfb_ms, 7.0);
+        assert_eq!(timings.download_ms, None);
+    }
+
+    #[test]
+    fn test_compute_timings_reused_connection() {
+        let start = Instant::now();
+        let timings = compute_timings(
+            &PhaseMarks::default(),
+            start,
+            start + Duration::from_millis(8),
+            Some(start + Duration::from_millis(9)),
+        );
+        assert!(timings.connection_reused);
+        assert_eq!(timings.connect_ms, None);
+        assert_eq!(timings.ttfb_ms, 8.0);
+        assert_eq!(timings.download_ms, Some(1.0));
+    }
+
+    /// 启动支持keep-alive的HTTP服务器，返回端口和已接受的连接数
+    async fn start_keep_alive_server() -> (u16, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&accepted);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    while matches!(socket.read(&mut buf).await, Ok(n) if n > 0) {
+                        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+                        if socket.write_all(response).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        (port, accepted)
+    }
+
+    #[tokio::test]
+    async fn test_recorder_marks_connection_phases() {
+        let (port, accepted) = start_keep_alive_server().await;
+        let client = reqwest::Client::builder()
+            .dns_resolver(Arc::new(TimedResolver::default()))
+            .connector_layer(TimingLayer)
+            .build()
+            .unwrap();
+        let url = format!("http://localhost:{port}/");
+
+        let send = || {
+            let recorder = PhaseRecorder::default();
+            let request = client.get(&url);
+            async move {
+                let start = Instant::now();
+                let response = recorder.scope(request.send()).await.unwrap();
+                let headers_at = Instant::now();
+                response.bytes().await.unwrap();
+                recorder.timings(start, headers_at, Some(Instant::now()))
+            }
+        };
+
+        let first = send().await;
+        assert!(!first.connection_reused);
+        assert!(first.dns_ms.is_some());
+        assert!(first.connect_ms.is_some());
+        assert!(first.tls_ms.is_none());
+        assert!(first.download_ms.is_some());
+
+        // 等待连接归还连接池
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let second = send().await;
+        assert!(second.connection_reused);
+        assert!(second.dns_ms.is_none());
+        assert!(second.connect_ms.is_none());
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+}
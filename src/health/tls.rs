@@ -0,0 +1,360 @@
+//! 检测请求的TLS客户端管理
+//!
+//! 每种不同的TLS配置（客户端证书、CA证书、校验选项、SNI、最低版本）对应一个HTTP客户端，
+//! 配置相同的服务共享同一个客户端及其连接池。未配置TLS的服务使用默认客户端。
+//! 所有客户端都使用自行构建的 rustls 配置，以便记录请求各阶段耗时。
+
+use crate::common::error::HealthCheckError;
+use crate::config::types::{ServiceConfig, TlsConfig, TlsVersion};
+use crate::health::timing::{TimedResolver, TimedSessionStore, TimingLayer};
+use reqwest::{Client, Url};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::{ClientSessionMemoryCache, Resumption};
+use rustls::crypto::WebPkiSupportedAlgorithms;
+use rustls::pki_types::pem::{self, PemObject};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex as StdMutex};
+use tracing::debug;
+
+/// 每个客户端缓存的TLS会话数量（与 rustls 默认值一致）
+const SESSION_CACHE_SIZE: usize = 256;
+
+/// 客户端缓存键
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct TlsProfile {
+    tls: TlsConfig,
+    /// 覆盖SNI时实际连接的主机，不同主机需要不同的解析器
+    target_host: Option<String>,
+}
+
+/// 按TLS配置复用的HTTP客户端池
+#[derive(Debug)]
+pub struct ClientPool {
+    default_client: Client,
+    user_agent: String,
+    clients: StdMutex<HashMap<TlsProfile, Client>>,
+}
+
+impl ClientPool {
+    /// 创建客户端池
+    ///
+    /// # 参数
+    /// * `user_agent` - 所有客户端使用的User-Agent
+    pub fn new(user_agent: String) -> Result<Self, HealthCheckError> {
+        let default_client = build_client(&user_agent, &TlsProfile::default())?;
+
+        Ok(Self {
+            default_client,
+            user_agent,
+            clients: StdMutex::new(HashMap::new()),
+        })
+    }
+
+    /// 默认客户端（不带自定义TLS配置）
+    pub fn default_client(&self) -> &Client {
+        &self.default_client
+    }
+
+    /// 已创建的自定义TLS客户端数量
+    pub fn profile_count(&self) -> usize {
+        self.lock_clients().len()
+    }
+
+    /// 获取服务使用的客户端，首次使用某种TLS配置时创建
+    pub fn client_for(&self, service: &ServiceConfig) -> Result<Client, HealthCheckError> {
+        let Some(tls) = &service.tls else {
+            return Ok(self.default_client.clone());
+        };
+
+        let target_host = match &tls.server_name {
+            Some(_) => Some(target_host(&service.url)?),
+            None => None,
+        };
+        let profile = TlsProfile {
+            tls: tls.clone(),
+            target_host,
+        };
+
+        if let Some(client) = self.lock_clients().get(&profile) {
+            return Ok(client.clone());
+        }
+
+        // 构建客户端涉及读取证书文件，不持有锁；并发创建时保留先插入的客户端
+        let client = build_client(&self.user_agent, &profile)?;
+        debug!("创建TLS客户端: {}", service.name);
+        Ok(self.lock_clients().entry(profile).or_insert(client).clone())
+    }
+
+    fn lock_clients(&self) -> std::sync::MutexGuard<'_, HashMap<TlsProfile, Client>> {
+        self.clients
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 按TLS配置构建客户端
+fn build_client(user_agent: &str, profile: &TlsProfile) -> Result<Client, HealthCheckError> {
+    let resolver = match (&profile.tls.server_name, &profile.target_host) {
+        (Some(server_name), Some(target_host)) => {
+            TimedResolver::with_server_name(server_name.clone(), target_host.clone())
+        }
+        _ => TimedResolver::default(),
+    };
+
+    Client::builder()
+        .user_agent(user_agent)
+        .use_preconfigured_tls(rustls_config(&profile.tls)?)
+        .dns_resolver(Arc::new(resolver))
+        .connector_layer(TimingLayer)
+        .build()
+        .map_err(|e| tls_error(format!("无法创建TLS客户端: {e}")))
+}
+
+/// 按TLS配置构建 rustls 客户端配置
+///
+/// HTTP客户端与gRPC检测共用，gRPC检测在此基础上设置ALPN
+pub fn rustls_config(tls: &TlsConfig) -> Result<ClientConfig, HealthCheckError> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let versions: &[&rustls::SupportedProtocolVersion] = match tls.min_version {
+        Some(TlsVersion::Tls13) => &[&rustls::version::TLS13],
+        Some(TlsVersion::Tls12) | None => rustls::ALL_VERSIONS,
+    };
+
+    let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_protocol_versions(versions)
+        .map_err(|e| tls_error(format!("不支持的TLS版本: {e}")))?;
+
+    let builder = if tls.insecure_skip_verify {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification {
+                algorithms: provider.signature_verification_algorithms,
+            }))
+    } else {
+        let mut roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        for path in &tls.ca_certs {
+            let certs = parse_certificates(&read_file(path)?)
+                .map_err(|e| tls_error(format!("无法解析CA证书 {path}: {e}")))?;
+            if certs.is_empty() {
+                return Err(tls_error(format!("CA证书文件中没有证书: {path}")));
+            }
+            for cert in certs {
+                roots
+                    .add(cert)
+                    .map_err(|e| tls_error(format!("无法解析CA证书 {path}: {e}")))?;
+            }
+        }
+        builder.with_root_certificates(roots)
+    };
+
+    let mut config = match (&tls.client_cert, &tls.client_key) {
+        (Some(cert_path), Some(key_path)) => {
+            let load_error = |e: String| tls_error(format!("无法加载客户端证书 {cert_path}: {e}"));
+            let certs = parse_certificates(&read_file(cert_path)?)
+                .map_err(|e| load_error(e.to_string()))?;
+            let key = PrivateKeyDer::from_pem_slice(&read_file(key_path)?)
+                .map_err(|e| load_error(e.to_string()))?;
+            builder
+                .with_client_auth_cert(certs, key)
+                .map_err(|e| load_error(e.to_string()))?
+        }
+        _ => builder.with_no_client_auth(),
+    };
+
+    config.resumption = Resumption::store(Arc::new(TimedSessionStore::new(Arc::new(
+        ClientSessionMemoryCache::new(SESSION_CACHE_SIZE),
+    ))));
+    Ok(config)
+}
+
+/// 解析PEM内容中的所有证书
+fn parse_certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, pem::Error> {
+    CertificateDer::pem_slice_iter(pem).collect()
+}
+
+/// 跳过证书校验（`insecure_skip_verify`），仍校验握手签名
+#[derive(Debug)]
+struct NoCertificateVerification {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// 覆盖SNI时计算实际请求的URL和需要显式设置的Host请求头
+///
+/// 把URL的主机替换为 `server_name`，使TLS握手和证书校验使用该名称，
+/// 同时通过解析器仍然连接原主机，并保留原始的Host请求头。
+/// 未配置 `server_name` 时返回None
+pub fn server_name_target(
+    service: &ServiceConfig,
+) -> Result<Option<(Url, String)>, HealthCheckError> {
+    let Some(server_name) = service
+        .tls
+        .as_ref()
+        .and_then(|tls| tls.server_name.as_ref())
+    else {
+        return Ok(None);
+    };
+
+    let mut url = Url::parse(&service.url).map_err(|e| HealthCheckError::ConnectionError {
+        url: format!("{}: {e}", service.url),
+    })?;
+
+    let host_header = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(tls_error(format!("URL缺少主机: {}", service.url))),
+    };
+    url.set_host(Some(server_name))
+        .map_err(|e| tls_error(format!("无效的 server_name {server_name}: {e}")))?;
+
+    Ok(Some((url, host_header)))
+}
+
+/// 解析URL中需要实际连接的主机
+fn target_host(url: &str) -> Result<String, HealthCheckError> {
+    let url = Url::parse(url).map_err(|e| HealthCheckError::ConnectionError {
+        url: format!("{url}: {e}"),
+    })?;
+    // IPv6地址在URL中带方括号，解析时需要去掉
+    url.host_str()
+        .map(|host| {
+            host.trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string()
+        })
+        .ok_or_else(|| tls_error(format!("URL缺少主机: {url}")))
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, HealthCheckError> {
+    std::fs::read(path).map_err(|e| tls_error(format!("无法读取证书文件 {path}: {e}")))
+}
+
+fn tls_error(reason: String) -> HealthCheckError {
+    HealthCheckError::TlsConfig { reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TLS_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/tls");
+
+    fn service_with_tls(url: &str, tls: Option<TlsConfig>) -> ServiceConfig {
+        let mut service: ServiceConfig = toml::from_str(&format!(
+            r#"
+            name = "api"
+            url = "{url}"
+            expected_status_codes = [200]
+            "#
+        ))
+        .unwrap();
+        service.tls = tls;
+        service
+    }
+
+    fn ca_tls() -> TlsConfig {
+        TlsConfig {
+            ca_certs: vec![format!("{TLS_DATA_DIR}/ca.pem")],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_clients_shared_per_profile() {
+        let pool = ClientPool::new("test".to_string()).unwrap();
+
+        pool.client_for(&service_with_tls("https://a.example.com", None))
+            .unwrap();
+        assert_eq!(pool.profile_count(), 0);
+
+        pool.client_for(&service_with_tls("https://a.example.com", Some(ca_tls())))
+            .unwrap();
+        pool.client_for(&service_with_tls("https://b.example.com", Some(ca_tls())))
+            .unwrap();
+        assert_eq!(pool.profile_count(), 1);
+
+        let mut strict = ca_tls();
+        strict.min_version = Some(TlsVersion::Tls13);
+        pool.client_for(&service_with_tls("https://a.example.com", Some(strict)))
+            .unwrap();
+        assert_eq!(pool.profile_count(), 2);
+    }
+
+    #[test]
+    fn test_invalid_certificate_files() {
+        let pool = ClientPool::new("test".to_string()).unwrap();
+
+        let missing = TlsConfig {
+            ca_certs: vec![format!("{TLS_DATA_DIR}/missing.pem")],
+            ..Default::default()
+        };
+        let error = pool
+            .client_for(&service_with_tls("https://a.example.com", Some(missing)))
+            .unwrap_err();
+        assert!(error.to_string().contains("无法读取证书文件"));
+
+        // 私钥文件中没有证书
+        let not_a_cert = TlsConfig {
+            ca_certs: vec![format!("{TLS_DATA_DIR}/client.key")],
+            ..Default::default()
+        };
+        assert!(pool
+            .client_for(&service_with_tls("https://a.example.com", Some(not_a_cert)))
+            .is_err());
+        assert_eq!(pool.profile_count(), 0);
+    }
+
+    #[test]
+    fn test_request_target_with_server_name() {
+        let tls = TlsConfig {
+            server_name: Some("api.internal".to_string()),
+            ..Default::default()
+        };
+        let service = service_with_tls("https://10.0.0.5:8443/health?x=1", Some(tls));
+
+        let (url, host) = server_name_target(&service).unwrap().unwrap();
+        assert_eq!(url.as_str(), "https://api.internal:8443/health?x=1");
+        assert_eq!(host, "10.0.0.5:8443");
+
+        let plain = service_with_tls("https://10.0.0.5/health", Some(TlsConfig::default()));
+        assert!(server_name_target(&plain).unwrap().is_none());
+    }
+}
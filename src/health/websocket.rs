@@ -0,0 +1,744 @@
+//! WebSocket检测
+//!
+//! 对 `ws://` 和 `wss://` 服务完成 RFC 6455 升级握手，校验 `Sec-WebSocket-Accept`；
+//! 配置了 `send`、`expect` 或 `expect_regex` 时发送消息并在超时时间内等待期望的应答。
+//! 握手耗时记录为首字节阶段，消息往返耗时写入元数据 `websocket.round_trip_ms`。
+
+use crate::common::error::HealthCheckError;
+use crate::config::effective::EffectiveServiceConfig;
+use crate::config::types::TlsConfig;
+use crate::config::websocket::{websocket_target, WebSocketCheckConfig, WebSocketTarget};
+use crate::config::ServiceConfig;
+use crate::health::result::{HealthResult, HealthStatus, RequestTimings};
+use crate::health::tls::rustls_config;
+use base64::Engine;
+use regex::Regex;
+use rustls::pki_types::ServerName;
+use rustls::ClientConfig;
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+/// 检测结果中显示的请求方法
+const WEBSOCKET_METHOD: &str = "WEBSOCKET";
+/// 计算 `Sec-WebSocket-Accept` 使用的固定GUID
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// 握手响应头的最大长度
+const MAX_HANDSHAKE_RESPONSE: usize = 16 * 1024;
+/// 单条消息的最大长度
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+/// 元数据中保留的消息的最大长度
+const MAX_RECORDED_MESSAGE: usize = 1024;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// WebSocket检测器
+///
+/// 按TLS配置缓存 rustls 客户端配置，避免每次检测重新读取证书文件
+#[derive(Debug, Default)]
+pub struct WebSocketChecker {
+    tls_configs: Mutex<HashMap<TlsConfig, Arc<ClientConfig>>>,
+}
+
+/// 检测进行到的阶段，用于生成超时错误
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    #[default]
+    Connect,
+    Handshake,
+    Reply,
+}
+
+/// 检测过程中记录的数据，超时后仍可读取
+#[derive(Debug, Default)]
+struct Progress {
+    stage: Stage,
+    timings: RequestTimings,
+    /// 升级握手耗时（毫秒），从发送请求到收到101响应
+    handshake_ms: Option<f64>,
+    /// 消息往返耗时（毫秒），从发送消息到收到期望的应答
+    round_trip_ms: Option<f64>,
+    /// 服务端选择的子协议
+    subprotocol: Option<String>,
+    /// 收到的数据消息数量
+    messages_received: usize,
+    /// 最后收到的数据消息
+    last_message: Option<String>,
+}
+
+/// 期望的应答
+#[derive(Debug)]
+enum Expectation {
+    /// 任意数据消息
+    Any,
+    /// 与文本完全相同
+    Exact(String),
+    /// 匹配正则表达式
+    Pattern(Regex),
+}
+
+impl Expectation {
+    fn from_config(config: &WebSocketCheckConfig) -> Result<Option<Self>, String> {
+        if let Some(expect) = &config.expect {
+            return Ok(Some(Expectation::Exact(expect.clone())));
+        }
+        if let Some(pattern) = &config.expect_regex {
+            let regex = Regex::new(pattern).map_err(|e| format!("expect_regex 无效: {e}"))?;
+            return Ok(Some(Expectation::Pattern(regex)));
+        }
+        Ok(config.awaits_reply().then_some(Expectation::Any))
+    }
+
+    fn matches(&self, message: &str) -> bool {
+        match self {
+            Expectation::Any => true,
+            Expectation::Exact(expected) => message == expected,
+            Expectation::Pattern(regex) => regex.is_match(message),
+        }
+    }
+}
+
+/// 可读写的连接（明文或TLS）
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+impl WebSocketChecker {
+    /// 创建WebSocket检测器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 执行一次WebSocket检测
+    ///
+    /// 每次检测建立新连接，响应时间包含连接、TLS握手、升级握手和消息往返的耗时；
+    /// 检测结束时发送关闭帧，不等待服务端确认
+    ///
+    /// # 参数
+    /// * `service` - 服务配置
+    /// * `effective` - 服务的有效配置，使用其中的超时时间和请求头（随升级请求发送）
+    ///
+    /// # 返回
+    /// * `HealthResult` - 检测结果
+    pub async fn check(
+        &self,
+        service: &ServiceConfig,
+        effective: &EffectiveServiceConfig,
+    ) -> HealthResult {
+        let config = service.websocket.clone().unwrap_or_default();
+        let result = HealthResult::new(
+            service.name.clone(),
+            service.url.clone(),
+            HealthStatus::Up,
+            WEBSOCKET_METHOD.to_string(),
+        );
+
+        let prepared = websocket_target(&service.url)
+            .and_then(|target| Expectation::from_config(&config).map(|expect| (target, expect)));
+        let (target, expectation) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                return failed(
+                    result,
+                    HealthStatus::Unknown,
+                    Duration::ZERO,
+                    e,
+                    "websocket",
+                )
+            }
+        };
+        let tls = if target.tls {
+            match self.tls_config(service.tls.clone().unwrap_or_default()) {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    return failed(
+                        result,
+                        HealthStatus::Unknown,
+                        Duration::ZERO,
+                        e.to_string(),
+                        "tls",
+                    )
+                }
+            }
+        } else {
+            None
+        };
+        let server_name = service.tls.as_ref().and_then(|tls| tls.server_name.clone());
+
+        let mut progress = Progress::default();
+        let start = Instant::now();
+        let outcome = tokio::time::timeout(
+            effective.timeout,
+            run(
+                &target,
+                tls,
+                server_name,
+                &config,
+                expectation.as_ref(),
+                &effective.headers,
+                &mut progress,
+            ),
+        )
+        .await;
+        let latency = start.elapsed();
+
+        let error = match outcome {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e),
+            Err(_) => Some(timeout_message(&progress, effective.timeout)),
+        };
+
+        let mut metadata = serde_json::json!({
+            "tls": target.tls,
+            "handshake_ms": progress.handshake_ms,
+            "round_trip_ms": progress.round_trip_ms,
+            "subprotocol": progress.subprotocol,
+            "messages_received": progress.messages_received,
+        });
+        if let Some(message) = &progress.last_message {
+            metadata["last_message"] = truncate(message, MAX_RECORDED_MESSAGE).into();
+        }
+        let mut result = result
+            .with_response_time(latency)
+            .with_metadata("websocket".to_string(), metadata);
+        if progress.handshake_ms.is_some() {
+            result = result.with_timings(progress.timings);
+        }
+        if let Some(error) = error {
+            return failed(result, HealthStatus::Down, latency, error, "websocket");
+        }
+        result
+    }
+
+    /// 获取TLS配置对应的 rustls 客户端配置，首次使用时创建
+    fn tls_config(&self, tls: TlsConfig) -> Result<Arc<ClientConfig>, HealthCheckError> {
+        if let Some(config) = self.lock_tls_configs().get(&tls) {
+            return Ok(Arc::clone(config));
+        }
+        let mut config = rustls_config(&tls)?;
+        // 升级握手只能在 HTTP/1.1 上进行
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        let config = Arc::new(config);
+        Ok(Arc::clone(
+            self.lock_tls_configs().entry(tls).or_insert(config),
+        ))
+    }
+
+    fn lock_tls_configs(&self) -> std::sync::MutexGuard<'_, HashMap<TlsConfig, Arc<ClientConfig>>> {
+        self.tls_configs
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 按检测阶段生成超时错误
+fn timeout_message(progress: &Progress, timeout: Duration) -> String {
+    let seconds = timeout.as_secs_f64();
+    match progress.stage {
+        Stage::Connect => format!("连接WebSocket服务超时（{seconds} 秒）"),
+        Stage::Handshake => format!("WebSocket升级握手超时（{seconds} 秒）"),
+        Stage::Reply => match &progress.last_message {
+            Some(message) => format!(
+                "{seconds} 秒内未收到期望的应答，最后收到: {}",
+                truncate(message, 200)
+            ),
+            None => format!("{seconds} 秒内未收到应答"),
+        },
+    }
+}
+
+/// 建立连接、完成升级握手并按配置收发消息
+async fn run(
+    target: &WebSocketTarget,
+    tls: Option<Arc<ClientConfig>>,
+    server_name: Option<String>,
+    config: &WebSocketCheckConfig,
+    expectation: Option<&Expectation>,
+    headers: &BTreeMap<String, String>,
+    progress: &mut Progress,
+) -> Result<(), String> {
+    let mut stream = connect(target, tls, server_name, progress).await?;
+
+    progress.stage = Stage::Handshake;
+    let key = base64::engine::general_purpose::STANDARD.encode(rand::random::<[u8; 16]>());
+    let request = handshake_request(target, &key, &config.subprotocols, headers);
+    let handshake_start = Instant::now();
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("发送升级请求失败: {e}"))?;
+    let mut buffer = Vec::new();
+    let response = read_handshake_response(&mut stream, &mut buffer).await?;
+    progress.subprotocol = validate_handshake(&response, &key, &config.subprotocols)?;
+    let handshake_ms = elapsed_ms(handshake_start);
+    progress.handshake_ms = Some(handshake_ms);
+    progress.timings.ttfb_ms = handshake_ms;
+
+    if let Some(expectation) = expectation {
+        progress.stage = Stage::Reply;
+        let round_trip_start = Instant::now();
+        if let Some(message) = &config.send {
+            write_frame(&mut stream, OPCODE_TEXT, message.as_bytes()).await?;
+        }
+        loop {
+            let message = read_message(&mut stream, &mut buffer).await?;
+            progress.messages_received += 1;
+            let matched = expectation.matches(&message);
+            progress.last_message = Some(message);
+            if matched {
+                progress.round_trip_ms = Some(elapsed_ms(round_trip_start));
+                break;
+            }
+        }
+    }
+
+    // 正常关闭连接（1000），服务端的关闭确认不影响检测结果
+    let _ = write_frame(&mut stream, OPCODE_CLOSE, &1000u16.to_be_bytes()).await;
+    let _ = stream.shutdown().await;
+    Ok(())
+}
+
+/// 解析地址并建立TCP连接，`wss://` 继续完成TLS握手
+async fn connect(
+    target: &WebSocketTarget,
+    tls: Option<Arc<ClientConfig>>,
+    server_name: Option<String>,
+    progress: &mut Progress,
+) -> Result<Box<dyn Connection>, String> {
+    let dns_start = Instant::now();
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((target.host.as_str(), target.port))
+        .await
+        .map_err(|e| format!("无法解析主机 {}: {e}", target.host))?
+        .collect();
+    // 主机为IP地址时没有DNS阶段
+    if target.host.parse::<IpAddr>().is_err() {
+        progress.timings.dns_ms = Some(elapsed_ms(dns_start));
+    }
+
+    let connect_start = Instant::now();
+    let mut last_error = None;
+    let mut tcp = None;
+    for addr in addrs {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => {
+                tcp = Some(stream);
+                break;
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    let tcp = tcp.ok_or_else(|| match last_error {
+        Some(e) => format!("无法连接WebSocket服务: {e}"),
+        None => format!("主机 {} 没有可用地址", target.host),
+    })?;
+    let _ = tcp.set_nodelay(true);
+    progress.timings.connect_ms = Some(elapsed_ms(connect_start));
+
+    let Some(config) = tls else {
+        return Ok(Box::new(tcp));
+    };
+    let tls_start = Instant::now();
+    let server_name = server_name.unwrap_or_else(|| target.host.clone());
+    let server_name =
+        ServerName::try_from(server_name).map_err(|e| format!("无效的TLS服务器名称: {e}"))?;
+    let stream = TlsConnector::from(config)
+        .connect(server_name, tcp)
+        .await
+        .map_err(|e| format!("TLS握手失败: {e}"))?;
+    progress.timings.tls_ms = Some(elapsed_ms(tls_start));
+    Ok(Box::new(stream))
+}
+
+/// 构造升级请求，附加服务配置的请求头
+fn handshake_request(
+    target: &WebSocketTarget,
+    key: &str,
+    subprotocols: &[String],
+    headers: &BTreeMap<String, String>,
+) -> String {
+    let mut request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n",
+        target.resource, target.host_header
+    );
+    if !subprotocols.is_empty() {
+        request.push_str(&format!(
+            "Sec-WebSocket-Protocol: {}\r\n",
+            subprotocols.join(", ")
+        ));
+    }
+    for (name, value) in headers {
+        // 握手相关的请求头由检测器生成
+        let lower = name.to_ascii_lowercase();
+        if matches!(lower.as_str(), "host" | "upgrade" | "connection")
+            || lower.starts_with("sec-websocket-")
+        {
+            continue;
+        }
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str(&format!(
+        "User-Agent: {}/{}\r\n\r\n",
+        crate::APP_NAME,
+        crate::VERSION
+    ));
+    request
+}
+
+/// 升级握手的响应
+#[derive(Debug)]
+struct HandshakeResponse {
+    status: u16,
+    /// 响应头，名称为小写
+    headers: HashMap<String, String>,
+}
+
+/// 读取握手响应头，之后已读取的数据保留在 `buffer` 中作为WebSocket帧
+async fn read_handshake_response(
+    stream: &mut (impl AsyncRead + Unpin),
+    buffer: &mut Vec<u8>,
+) -> Result<HandshakeResponse, String> {
+    loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&buffer[..end]).to_string();
+            buffer.drain(..end + 4);
+            return parse_handshake_response(&head);
+        }
+        if buffer.len() > MAX_HANDSHAKE_RESPONSE {
+            return Err("握手响应头过长".to_string());
+        }
+        read_more(stream, buffer, "握手响应").await?;
+    }
+}
+
+/// 解析握手响应的状态行和响应头
+fn parse_handshake_response(head: &str) -> Result<HandshakeResponse, String> {
+    let mut lines = head.split("\r\n");
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line
+        .strip_prefix("HTTP/1.")
+        .and_then(|rest| rest.get(2..5))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("握手响应的状态行无效: {status_line}"))?;
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    Ok(HandshakeResponse { status, headers })
+}
+
+/// 校验握手响应，返回服务端选择的子协议
+fn validate_handshake(
+    response: &HandshakeResponse,
+    key: &str,
+    subprotocols: &[String],
+) -> Result<Option<String>, String> {
+    if response.status != 101 {
+        return Err(format!(
+            "WebSocket升级失败: 服务端返回 HTTP {}",
+            response.status
+        ));
+    }
+    let header = |name: &str| response.headers.get(name).map(String::as_str);
+    if !header("upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket")) {
+        return Err("握手响应缺少 Upgrade: websocket".to_string());
+    }
+    if !header("connection").is_some_and(|value| {
+        value
+            .split(',')
+            .any(|token| token.trim().eq_ignore_ascii_case("upgrade"))
+    }) {
+        return Err("握手响应缺少 Connection: Upgrade".to_string());
+    }
+    if header("sec-websocket-accept") != Some(accept_key(key).as_str()) {
+        return Err("握手响应的 Sec-WebSocket-Accept 不正确".to_string());
+    }
+    match header("sec-websocket-protocol") {
+        Some(protocol) if !subprotocols.iter().any(|p| p == protocol) => {
+            Err(format!("服务端选择了未请求的子协议 {protocol}"))
+        }
+        Some(protocol) => Ok(Some(protocol.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// 按 RFC 6455 计算 `Sec-WebSocket-Accept`
+fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+}
+
+/// 从连接读取更多数据追加到 `buffer`
+async fn read_more(
+    stream: &mut (impl AsyncRead + Unpin),
+    buffer: &mut Vec<u8>,
+    what: &str,
+) -> Result<(), String> {
+    let mut chunk = [0u8; 4096];
+    let n = stream
+        .read(&mut chunk)
+        .await
+        .map_err(|e| format!("读取{what}失败: {e}"))?;
+    if n == 0 {
+        return Err(format!("读取{what}时连接被关闭"));
+    }
+    buffer.extend_from_slice(&chunk[..n]);
+    Ok(())
+}
+
+/// WebSocket帧
+#[derive(Debug, PartialEq)]
+struct Frame {
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+/// 从 `buffer` 中解析一个完整的帧，数据不足时返回None
+fn parse_frame(buffer: &mut Vec<u8>) -> Result<Option<Frame>, String> {
+    if buffer.len() < 2 {
+        return Ok(None);
+    }
+    let fin = buffer[0] & 0x80 != 0;
+    let opcode = buffer[0] & 0x0F;
+    let masked = buffer[1] & 0x80 != 0;
+    let (length, mut offset) = match buffer[1] & 0x7F {
+        126 => {
+            let Some(bytes) = buffer.get(2..4) else {
+                return Ok(None);
+            };
+            (u16::from_be_bytes([bytes[0], bytes[1]]) as u64, 4)
+        }
+        127 => {
+            let Some(bytes) = buffer.get(2..10) else {
+                return Ok(None);
+            };
+            (u64::from_be_bytes(bytes.try_into().unwrap_or_default()), 10)
+        }
+        length => (u64::from(length), 2),
+    };
+    if length > MAX_MESSAGE_SIZE as u64 {
+        return Err(format!("消息长度 {length} 超过限制"));
+    }
+    let length = length as usize;
+    let mask = if masked {
+        let Some(mask) = buffer.get(offset..offset + 4) else {
+            return Ok(None);
+        };
+        let mask = [mask[0], mask[1], mask[2], mask[3]];
+        offset += 4;
+        Some(mask)
+    } else {
+        None
+    };
+    if buffer.len() < offset + length {
+        return Ok(None);
+    }
+    let mut payload: Vec<u8> = buffer.drain(..offset + length).skip(offset).collect();
+    if let Some(mask) = mask {
+        apply_mask(&mut payload, mask);
+    }
+    Ok(Some(Frame {
+        fin,
+        opcode,
+        payload,
+    }))
+}
+
+/// 按掩码异或负载
+fn apply_mask(payload: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+/// 编码客户端帧，客户端发送的帧必须使用掩码
+fn encode_frame(opcode: u8, payload: &[u8], mask: [u8; 4]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(0x80 | len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(&mask);
+    let start = frame.len();
+    frame.extend_from_slice(payload);
+    apply_mask(&mut frame[start..], mask);
+    frame
+}
+
+/// 发送一帧
+async fn write_frame(
+    stream: &mut (impl AsyncWrite + Unpin),
+    opcode: u8,
+    payload: &[u8],
+) -> Result<(), String> {
+    let frame = encode_frame(opcode, payload, rand::random());
+    stream
+        .write_all(&frame)
+        .await
+        .map_err(|e| format!("发送WebSocket消息失败: {e}"))
+}
+
+/// 读取下一条数据消息，合并分片并自动回复Ping
+///
+/// 二进制消息按UTF-8（无效字节替换）转换为文本；服务端关闭连接时返回错误
+async fn read_message(
+    stream: &mut (impl AsyncRead + AsyncWrite + Unpin),
+    buffer: &mut Vec<u8>,
+) -> Result<String, String> {
+    let mut message = Vec::new();
+    loop {
+        let frame = loop {
+            if let Some(frame) = parse_frame(buffer)? {
+                break frame;
+            }
+            read_more(stream, buffer, "WebSocket消息").await?;
+        };
+        match frame.opcode {
+            OPCODE_PING => write_frame(stream, OPCODE_PONG, &frame.payload).await?,
+            OPCODE_PONG => {}
+            OPCODE_CLOSE => return Err(close_message(&frame.payload)),
+            OPCODE_TEXT | OPCODE_BINARY | OPCODE_CONTINUATION => {
+                if message.len() + frame.payload.len() > MAX_MESSAGE_SIZE {
+                    return Err("消息长度超过限制".to_string());
+                }
+                message.extend_from_slice(&frame.payload);
+                if frame.fin {
+                    return Ok(String::from_utf8_lossy(&message).into_owned());
+                }
+            }
+            opcode => return Err(format!("收到未知的WebSocket帧类型 {opcode:#x}")),
+        }
+    }
+}
+
+/// 描述服务端的关闭帧
+fn close_message(payload: &[u8]) -> String {
+    match payload {
+        [high, low, reason @ ..] => {
+            let code = u16::from_be_bytes([*high, *low]);
+            let reason = String::from_utf8_lossy(reason);
+            if reason.is_empty() {
+                format!("服务端关闭了WebSocket连接（{code}）")
+            } else {
+                format!("服务端关闭了WebSocket连接（{code}）: {reason}")
+            }
+        }
+        _ => "服务端关闭了WebSocket连接".to_string(),
+    }
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// 按字符截断文本
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((index, _)) => format!("{}...", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+fn failed(
+    result: HealthResult,
+    status: HealthStatus,
+    latency: Duration,
+    message: String,
+    failure_kind: &str,
+) -> HealthResult {
+    let mut result = result
+        .with_response_time(latency)
+        .with_error(message)
+        .with_metadata(
+            "failure_kind".to_string(),
+            serde_json::Value::String(failure_kind.to_string()),
+        );
+    result.status = status;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key() {
+        // RFC 6455 第1.3节的示例
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let payload = "x".repeat(300);
+        let mut buffer = encode_frame(OPCODE_TEXT, payload.as_bytes(), [1, 2, 3, 4]);
+        buffer.extend_from_slice(&[0x81, 0x02, b'h']);
+        let frame = parse_frame(&mut buffer).unwrap().unwrap();
+        assert!(frame.fin);
+        assert_eq!(frame.opcode, OPCODE_TEXT);
+        assert_eq!(frame.payload, payload.as_bytes());
+        // 不完整的帧保留在缓冲区等待更多数据
+        assert_eq!(parse_frame(&mut buffer).unwrap(), None);
+        buffer.push(b'i');
+        assert_eq!(parse_frame(&mut buffer).unwrap().unwrap().payload, b"hi");
+        assert!(buffer.is_empty());
+
+        let mut oversized = vec![0x82, 127];
+        oversized.extend_from_slice(&u64::MAX.to_be_bytes());
+        assert!(parse_frame(&mut oversized).is_err());
+
+        assert_eq!(
+            close_message(&[0x03, 0xE9, b'b', b'y', b'e']),
+            "服务端关闭了WebSocket连接（1001）: bye"
+        );
+    }
+
+    #[test]
+    fn test_validate_handshake() {
+        let key = "dGhlIHNhbXBsZSBub25jZQ==";
+        let response = parse_handshake_response(
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\nSec-WebSocket-Protocol: chat",
+        )
+        .unwrap();
+        assert_eq!(
+            validate_handshake(&response, key, &["chat".to_string()]).unwrap(),
+            Some("chat".to_string())
+        );
+        assert!(validate_handshake(&response, key, &[])
+            .unwrap_err()
+            .contains("未请求的子协议"));
+        assert!(
+            validate_handshake(&response, "other", &["chat".to_string()])
+                .unwrap_err()
+                .contains("Sec-WebSocket-Accept")
+        );
+
+        let response = parse_handshake_response("HTTP/1.1 200 OK\r\nContent-Length: 2").unwrap();
+        assert_eq!(
+            validate_handshake(&response, key, &[]).unwrap_err(),
+            "WebSocket升级失败: 服务端返回 HTTP 200"
+        );
+        assert!(parse_handshake_response("garbage").is_err());
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! 实现飞书webhook通知功能，支持多种消息格式和重试机制
 
+use crate::config::secret;
 use crate::config::types::ServiceConfig;
 use crate::health::HealthResult;
 use crate::notification::sender::{MessageType, NotificationMessage, NotificationSender};
@@ -408,11 +409,12 @@ impl FeishuSender {
 
         TemplateContext {
             service_name: service.name.clone(),
-            service_url: service.url.clone(),
+            service_url: secret::redact_url(&service.url).into_owned(),
             status_code: result.status_code,
             response_time: result.response_time_ms(),
             timestamp: result.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
             error_message: result.error_message.clone(),
+            tags: service.tags.clone(),
             custom_fields,
         }
     }
@@ -454,7 +456,7 @@ impl NotificationSender for FeishuSender {
             title: format!("{} - {}", title_prefix, service.name),
             content,
             service_name: service.name.clone(),
-            service_url: service.url.clone(),
+            service_url: secret::redact_url(&service.url).into_owned(),
             message_type,
         };
 
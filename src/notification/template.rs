@@ -21,6 +21,8 @@ pub struct TemplateContext {
     pub timestamp: String,
     /// 错误信息
     pub error_message: Option<String>,
+    /// 服务标签
+    pub tags: Vec<String>,
     /// 自定义字段
     pub custom_fields: HashMap<String, Value>,
 }
@@ -84,6 +86,10 @@ impl SimpleTemplate {
             result = result.replace("{{error_message}}", "");
         }
 
+        let tags_text = context.tags.join(", ");
+        result = result.replace("{{tags}}", &tags_text);
+        result = result.replace("{{tags_text}}", &tags_text);
+
         // 替换自定义字段
         for (key, value) in &context.custom_fields {
             let placeholder = format!("{{{{{key}}}}}");
@@ -182,6 +188,26 @@ impl MessageTemplate for HandlebarsTemplate {
             );
         }
 
+        // 添加服务标签：tags 为数组，tags_text 为逗号分隔的文本，
+        // key:value 形式的标签同时放入 labels，例如 {{labels.team}}
+        data.insert("tags".to_string(), serde_json::json!(context.tags));
+        data.insert(
+            "tags_text".to_string(),
+            serde_json::Value::String(context.tags.join(", ")),
+        );
+        let labels: serde_json::Map<String, serde_json::Value> = context
+            .tags
+            .iter()
+            .filter_map(|tag| tag.split_once(':'))
+            .map(|(key, value)| {
+                (
+                    key.to_string(),
+                    serde_json::Value::String(value.to_string()),
+                )
+            })
+            .collect();
+        data.insert("labels".to_string(), serde_json::Value::Object(labels));
+
         // 添加自定义字段
         for (key, value) in &context.custom_fields {
             data.insert(key.clone(), value.clone());
@@ -247,6 +273,9 @@ pub fn default_alert_template() -> String {
 {{#if service_description}}
 - **服务描述**: {{service_description}}
 {{/if}}
+{{#if tags}}
+- **服务标签**: {{tags_text}}
+{{/if}}
 
 **检测结果**
 - **状态码**: {{#if status_code}}{{status_code}}{{else}}N/A{{/if}}
@@ -276,6 +305,9 @@ pub fn default_recovery_template() -> String {
 {{#if service_description}}
 - **服务描述**: {{service_description}}
 {{/if}}
+{{#if tags}}
+- **服务标签**: {{tags_text}}
+{{/if}}
 
 **恢复详情**
 - **状态码**: {{status_code}}
@@ -315,6 +347,7 @@ mod tests {
             response_time: 100,
             timestamp: "2023-01-01 12:00:00".to_string(),
             error_message: None,
+            tags: Vec::new(),
             custom_fields: HashMap::new(),
         };
 
@@ -332,10 +365,40 @@ mod tests {
             response_time: 100,
             timestamp: "2023-01-01 12:00:00".to_string(),
             error_message: None,
+            tags: Vec::new(),
             custom_fields: HashMap::new(),
         };
 
         let result = template.render(&context).unwrap();
         assert_eq!(result, "Status: N/A");
     }
+
+    #[test]
+    fn test_template_tags() {
+        let context = TemplateContext {
+            service_name: "orders".to_string(),
+            service_url: "http://example.com".to_string(),
+            status_code: None,
+            response_time: 100,
+            timestamp: "2023-01-01 12:00:00".to_string(),
+            error_message: None,
+            tags: vec!["core".to_string(), "team:orders".to_string()],
+            custom_fields: HashMap::new(),
+        };
+
+        let simple = SimpleTemplate::new("[{{tags}}] {{service_name}}".to_string());
+        assert_eq!(
+            simple.render(&context).unwrap(),
+            "[core, team:orders] orders"
+        );
+
+        let handlebars = HandlebarsTemplate::new(
+            "{{labels.team}}|{{#each tags}}<{{this}}>{{/each}}|{{tags_text}}".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            handlebars.render(&context).unwrap(),
+            "orders|<core><team:orders>|core, team:orders"
+        );
+    }
 }
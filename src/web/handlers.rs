@@ -3,13 +3,20 @@
 //! 实现 Web 服务器的路由处理逻辑
 
 use super::{WebAppState, WebServiceStatus};
+use crate::config::secret;
+use crate::health::{
+    HealthResult, HealthStatus, HeartbeatEvent, PauseInfo, RequestTimings, SilenceInfo,
+};
 use askama::Template;
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
-    response::{Html, IntoResponse, Json},
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Json, Response},
 };
-use tracing::error;
+use std::collections::{BTreeSet, HashMap};
+use std::net::SocketAddr;
+use tracing::{error, info};
 
 /// 仪表板模板
 #[derive(Template)]
@@ -22,37 +29,299 @@ struct DashboardTemplate {
     unknown_count: usize,
     refresh_interval: u32,
     show_problems_only: bool,
+    /// 当前的标签过滤条件（逗号分隔）
+    tag_filter: String,
+    /// 标签过滤选项：所有服务的标签，以及不在其中的当前过滤条件
+    tag_options: Vec<String>,
+}
+
+/// 状态查询参数
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct StatusQuery {
+    /// 按标签过滤，多个标签用逗号分隔，服务带有任一标签即被选中，支持通配符
+    pub tag: Option<String>,
+}
+
+impl StatusQuery {
+    /// 解析出的标签列表
+    fn tags(&self) -> Vec<&str> {
+        self.tag
+            .as_deref()
+            .map(|tag| {
+                tag.split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 /// API 状态响应结构
-#[derive(serde::Serialize)]
-struct ApiStatusResponse {
-    services: Vec<ApiServiceStatus>,
-    last_updated: String,
-    total_services: usize,
-    online_services: usize,
-    offline_services: usize,
-    unknown_services: usize,
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiStatusResponse {
+    /// 服务状态列表（按名称排序）
+    pub services: Vec<ApiServiceStatus>,
+    /// 响应生成时间（RFC3339）
+    pub last_updated: String,
+    /// 总服务数
+    pub total_services: usize,
+    /// 在线服务数
+    pub online_services: usize,
+    /// 离线服务数
+    pub offline_services: usize,
+    /// 状态未知的服务数
+    pub unknown_services: usize,
 }
 
 /// API 服务状态结构
-#[derive(serde::Serialize)]
-struct ApiServiceStatus {
-    name: String,
-    url: String,
-    status: String,
-    response_time_ms: Option<u64>,
-    last_check: Option<String>,
-    error_message: Option<String>,
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiServiceStatus {
+    /// 服务名称
+    pub name: String,
+    /// 服务URL
+    pub url: String,
+    /// 当前状态（Online、Offline 或 Unknown）
+    pub status: String,
+    /// 响应时间（毫秒）
+    pub response_time_ms: Option<u64>,
+    /// 请求各阶段耗时
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<RequestTimings>,
+    /// 最后检测时间（RFC3339）
+    pub last_check: Option<String>,
+    /// 错误信息
+    pub error_message: Option<String>,
+    /// 连续失败次数
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// 暂停信息
+    pub paused: Option<PauseInfo>,
+    /// 通知静默信息
+    #[serde(default)]
+    pub silenced: Option<SilenceInfo>,
+    /// 服务标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 手动触发检测的单项结果
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiCheckResult {
+    /// 服务名称
+    pub name: String,
+    /// 检测状态
+    pub status: HealthStatus,
+    /// HTTP状态码
+    pub status_code: Option<u16>,
+    /// 响应时间（毫秒）
+    pub response_time_ms: Option<u64>,
+    /// 请求各阶段耗时
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<RequestTimings>,
+    /// 错误信息
+    pub error_message: Option<String>,
+    /// 检测时间（RFC3339）
+    pub checked_at: String,
+}
+
+impl From<&HealthResult> for ApiCheckResult {
+    fn from(result: &HealthResult) -> Self {
+        Self {
+            name: result.service_name.clone(),
+            status: result.status,
+            status_code: result.status_code,
+            response_time_ms: Some(result.response_time_ms()),
+            timings: result.timings,
+            error_message: result.error_message.clone(),
+            checked_at: result.timestamp.to_rfc3339(),
+        }
+    }
+}
+
+/// 手动触发检测响应结构
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiCheckResponse {
+    /// 检测结果列表
+    pub results: Vec<ApiCheckResult>,
+}
+
+/// 心跳上报响应结构
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiHeartbeatResponse {
+    /// 服务名称
+    pub name: String,
+    /// 上报类型
+    pub event: HeartbeatEvent,
+    /// 最近一次任务耗时（秒），由开始与结束上报计算
+    pub duration_seconds: Option<f64>,
+    /// 上报后立即检测的结果，开始上报不触发检测
+    pub result: Option<ApiCheckResult>,
+}
+
+/// API 错误响应结构
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiErrorResponse {
+    /// 错误信息
+    pub error: String,
+}
+
+/// 暂停服务请求结构
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ApiPauseRequest {
+    /// 暂停原因
+    pub reason: Option<String>,
+    /// 自动恢复时间
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// 暂停时长（秒），与 `until` 二选一
+    pub duration_seconds: Option<u64>,
+}
+
+/// 暂停/恢复服务响应结构
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiPauseResponse {
+    /// 服务名称
+    pub name: String,
+    /// 当前是否处于暂停状态
+    pub paused: bool,
+    /// 暂停信息（恢复时为恢复前的暂停信息）
+    pub pause: Option<PauseInfo>,
+}
+
+/// 静默/取消静默服务通知响应结构
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ApiSilenceResponse {
+    /// 服务名称
+    pub name: String,
+    /// 当前是否处于静默状态
+    pub silenced: bool,
+    /// 静默信息（取消静默时为取消前的静默信息）
+    pub silence: Option<SilenceInfo>,
+}
+
+/// 构造 JSON 错误响应
+fn api_error(status: StatusCode, message: impl Into<String>) -> Response {
+    (
+        status,
+        Json(ApiErrorResponse {
+            error: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+/// 管理接口（触发检测、暂停、静默）的访问控制
+///
+/// 配置了 `api_token` 时要求请求携带 `Authorization: Bearer <令牌>`；
+/// 未配置时只接受来自本机回环地址的请求，并拒绝浏览器发起的跨域请求，
+/// 避免网络中的其他主机或任意网页暂停、静默监控
+pub async fn require_api_token(
+    State(app_state): State<WebAppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+    match app_state.config.api_token.as_deref() {
+        Some(token) => {
+            let authorized = headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .is_some_and(|provided| secret::constant_time_eq(provided.trim(), token));
+            if !authorized {
+                return api_error(StatusCode::UNAUTHORIZED, "缺少或无效的API令牌");
+            }
+        }
+        None if !is_loopback_peer(&request) => {
+            return api_error(
+                StatusCode::FORBIDDEN,
+                "未配置API令牌时只允许本机调用管理接口",
+            );
+        }
+        None if is_cross_origin(headers) => {
+            return api_error(
+                StatusCode::FORBIDDEN,
+                "未配置API令牌时不允许跨域调用管理接口",
+            );
+        }
+        None => {}
+    }
+    next.run(request).await
+}
+
+/// 请求是否来自本机回环地址，无法获取对端地址时视为否
+fn is_loopback_peer(request: &Request) -> bool {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .is_some_and(|ConnectInfo(peer)| peer.ip().is_loopback())
+}
+
+/// 请求是否来自其他站点的页面（`Origin` 与 `Host` 不一致）
+fn is_cross_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return false;
+    };
+    let origin_host = origin
+        .to_str()
+        .ok()
+        .and_then(|origin| origin.split_once("://"))
+        .map(|(_, host)| host.trim_end_matches('/'));
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok());
+    match (origin_host, host) {
+        (Some(origin_host), Some(host)) => !origin_host.eq_ignore_ascii_case(host),
+        _ => true,
+    }
+}
+
+/// 获取服务状态快照，并附加调度器中的暂停、静默信息和服务标签
+async fn snapshot_services(app_state: &WebAppState) -> HashMap<String, WebServiceStatus> {
+    let mut services = app_state.services.read().await.clone();
+
+    if let Some(scheduler) = &app_state.scheduler {
+        let paused = scheduler.paused_services().await;
+        let silenced = scheduler.silenced_services().await;
+        let mut tags: HashMap<String, Vec<String>> = scheduler
+            .service_configs()
+            .await
+            .into_iter()
+            .map(|service| (service.name, service.tags))
+            .collect();
+        for (name, service) in services.iter_mut() {
+            service.paused = paused.get(name).cloned();
+            service.silenced = silenced.get(name).cloned();
+            service.tags = tags.remove(name).unwrap_or_default();
+        }
+    }
+
+    services
 }
 
 /// 仪表板页面处理函数
-pub async fn dashboard(State(app_state): State<WebAppState>) -> impl IntoResponse {
-    let state_guard = app_state.services.read().await;
-    let all_services: Vec<WebServiceStatus> = state_guard.values().cloned().collect();
-    drop(state_guard);
+///
+/// `?tag=` 只显示带有指定标签的服务，统计数据也只包含这些服务
+pub async fn dashboard(
+    State(app_state): State<WebAppState>,
+    Query(query): Query<StatusQuery>,
+) -> impl IntoResponse {
+    let snapshot: Vec<WebServiceStatus> =
+        snapshot_services(&app_state).await.into_values().collect();
+    let tags = query.tags();
+    let mut tag_options: BTreeSet<String> = snapshot
+        .iter()
+        .flat_map(|service| service.tags.iter().cloned())
+        .collect();
+    if !tags.is_empty() {
+        tag_options.insert(tags.join(","));
+    }
+    let all_services: Vec<WebServiceStatus> = snapshot
+        .into_iter()
+        .filter(|service| service.has_any_tag(&tags))
+        .collect();
 
-    // 先计算所有服务的统计数据（不受过滤影响）
+    // 先计算所有服务的统计数据（不受“只显示离线”过滤影响）
     let online_count = all_services.iter().filter(|s| s.status == "Online").count();
     let offline_count = all_services
         .iter()
@@ -79,6 +348,8 @@ pub async fn dashboard(State(app_state): State<WebAppState>) -> impl IntoRespons
         unknown_count,
         refresh_interval: app_state.config.refresh_interval_seconds,
         show_problems_only: app_state.config.show_problems_only,
+        tag_filter: tags.join(","),
+        tag_options: tag_options.into_iter().collect(),
     };
 
     match template.render() {
@@ -91,10 +362,15 @@ pub async fn dashboard(State(app_state): State<WebAppState>) -> impl IntoRespons
 }
 
 /// API 状态端点处理函数
-pub async fn api_status(State(app_state): State<WebAppState>) -> impl IntoResponse {
-    let state_guard = app_state.services.read().await;
-    let services_map = state_guard.clone();
-    drop(state_guard);
+///
+/// `?tag=` 只返回带有指定标签的服务，统计数据也只包含这些服务
+pub async fn api_status(
+    State(app_state): State<WebAppState>,
+    Query(query): Query<StatusQuery>,
+) -> impl IntoResponse {
+    let tags = query.tags();
+    let mut services_map = snapshot_services(&app_state).await;
+    services_map.retain(|_, service| service.has_any_tag(&tags));
 
     let mut services = Vec::new();
     let mut online_count = 0;
@@ -124,13 +400,18 @@ pub async fn api_status(State(app_state): State<WebAppState>) -> impl IntoRespon
             url: service.url.clone(),
             status: service.status.clone(),
             response_time_ms: service.response_time_ms,
+            timings: service.timings,
             last_check: service.last_check.map(|dt| dt.to_rfc3339()),
             error_message: service.error_message.clone(),
+            consecutive_failures: service.consecutive_failures,
+            paused: service.paused.clone(),
+            silenced: service.silenced.clone(),
+            tags: service.tags.clone(),
         });
     }
 
     let response = ApiStatusResponse {
-        total_services: services_map.len(), // 总服务数（未过滤）
+        total_services: services_map.len(), // 总服务数（只受标签过滤影响）
         online_services: online_count,
         offline_services: offline_count,
         unknown_services: unknown_count,
@@ -147,12 +428,410 @@ pub async fn api_status(State(app_state): State<WebAppState>) -> impl IntoRespon
     (headers, Json(response)).into_response()
 }
 
+/// Prometheus 指标端点处理函数
+pub async fn metrics(State(app_state): State<WebAppState>) -> impl IntoResponse {
+    let mut services: Vec<_> = snapshot_services(&app_state).await.into_values().collect();
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "text/plain; version=0.0.4; charset=utf-8".parse().unwrap(),
+    );
+
+    (headers, render_metrics(&services)).into_response()
+}
+
+/// 以 Prometheus 文本格式输出服务状态及最近一次检测的各阶段耗时
+///
+/// 尚未完成检测的服务不输出指标
+fn render_metrics(services: &[WebServiceStatus]) -> String {
+    use std::fmt::Write;
+
+    let checked: Vec<_> = services.iter().filter(|s| s.last_check.is_some()).collect();
+    let mut out = String::new();
+
+    out.push_str("# HELP service_vitals_up 服务是否正常（1 正常，0 异常或未知）\n");
+    out.push_str("# TYPE service_vitals_up gauge\n");
+    for service in &checked {
+        let up = u8::from(service.status == "Online");
+        let _ = writeln!(
+            out,
+            "service_vitals_up{{service=\"{}\",url=\"{}\"}} {up}",
+            escape_label(&service.name),
+            escape_label(&service.url)
+        );
+    }
+
+    out.push_str("# HELP service_vitals_service_info 服务标签，tags 为逗号包围的标签列表，key:value 形式的标签同时输出为 tag_<key> 标签\n");
+    out.push_str("# TYPE service_vitals_service_info gauge\n");
+    for service in &checked {
+        let _ = writeln!(
+            out,
+            "service_vitals_service_info{{service=\"{}\"{}}} 1",
+            escape_label(&service.name),
+            tag_labels(&service.tags)
+        );
+    }
+
+    out.push_str(
+        "# HELP service_vitals_response_time_seconds 最近一次检测的响应时间（到收到响应头）\n",
+    );
+    out.push_str("# TYPE service_vitals_response_time_seconds gauge\n");
+    for service in &checked {
+        if let Some(ms) = service.response_time_ms {
+            let _ = writeln!(
+                out,
+                "service_vitals_response_time_seconds{{service=\"{}\"}} {}",
+                escape_label(&service.name),
+                ms as f64 / 1000.0
+            );
+        }
+    }
+
+    out.push_str("# HELP service_vitals_request_phase_seconds 最近一次检测各阶段耗时\n");
+    out.push_str("# TYPE service_vitals_request_phase_seconds gauge\n");
+    for service in &checked {
+        let Some(timings) = service.timings else {
+            continue;
+        };
+        for (phase, ms) in timings.phases() {
+            let _ = writeln!(
+                out,
+                "service_vitals_request_phase_seconds{{service=\"{}\",phase=\"{phase}\"}} {}",
+                escape_label(&service.name),
+                ms / 1000.0
+            );
+        }
+    }
+
+    out.push_str("# HELP service_vitals_connection_reused 最近一次检测是否复用了已有连接\n");
+    out.push_str("# TYPE service_vitals_connection_reused gauge\n");
+    for service in &checked {
+        if let Some(timings) = service.timings {
+            let _ = writeln!(
+                out,
+                "service_vitals_connection_reused{{service=\"{}\"}} {}",
+                escape_label(&service.name),
+                u8::from(timings.connection_reused)
+            );
+        }
+    }
+
+    out
+}
+
+/// 服务标签对应的 Prometheus 标签，例如 `,tags=",core,team:orders,",tag_team="orders"`
+///
+/// `tags` 用逗号包围，便于用 `=~".*,core,.*"` 匹配；同名的 key:value 标签只输出第一个
+fn tag_labels(tags: &[String]) -> String {
+    let mut labels = if tags.is_empty() {
+        ",tags=\"\"".to_string()
+    } else {
+        format!(",tags=\",{},\"", escape_label(&tags.join(",")))
+    };
+
+    let mut keys = BTreeSet::new();
+    for (key, value) in tags.iter().filter_map(|tag| tag.split_once(':')) {
+        let name: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if name.is_empty() || !keys.insert(name.clone()) {
+            continue;
+        }
+        labels.push_str(&format!(",tag_{name}=\"{}\"", escape_label(value)));
+    }
+    labels
+}
+
+/// 转义 Prometheus 标签值中的反斜杠、双引号和换行
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 手动触发单个服务检测的处理函数
+pub async fn api_trigger_service_check(
+    State(app_state): State<WebAppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let Some(scheduler) = app_state.scheduler.as_ref() else {
+        return api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "调度器未运行，无法触发检测",
+        );
+    };
+
+    match scheduler.trigger_check(&name).await {
+        Ok(Some(result)) => Json(ApiCheckResponse {
+            results: vec![ApiCheckResult::from(&result)],
+        })
+        .into_response(),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, format!("服务不存在或未启用: {name}")),
+        Err(e) => {
+            error!("手动触发检测失败: {:#}", e);
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}"))
+        }
+    }
+}
+
+/// 手动触发标签组内所有服务检测的处理函数
+pub async fn api_trigger_tag_check(
+    State(app_state): State<WebAppState>,
+    Path(tag): Path<String>,
+) -> Response {
+    let Some(scheduler) = app_state.scheduler.as_ref() else {
+        return api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "调度器未运行，无法触发检测",
+        );
+    };
+
+    let results = scheduler.trigger_checks_by_tag(&tag).await;
+    if results.is_empty() {
+        return api_error(
+            StatusCode::NOT_FOUND,
+            format!("没有带标签 '{tag}' 的启用服务"),
+        );
+    }
+
+    let results = results
+        .into_iter()
+        .map(|(name, result)| match result {
+            Ok(result) => ApiCheckResult::from(&result),
+            Err(e) => ApiCheckResult {
+                name,
+                status: HealthStatus::Unknown,
+                status_code: None,
+                response_time_ms: None,
+                timings: None,
+                error_message: Some(format!("{e:#}")),
+                checked_at: chrono::Utc::now().to_rfc3339(),
+            },
+        })
+        .collect();
+
+    Json(ApiCheckResponse { results }).into_response()
+}
+
+/// 暂停服务定时检测的处理函数
+///
+/// 请求体可省略；`until` 与 `duration_seconds` 均未指定时需要手动恢复
+pub async fn api_pause_service(
+    State(app_state): State<WebAppState>,
+    Path(name): Path<String>,
+    request: Option<Json<ApiPauseRequest>>,
+) -> Response {
+    let Some(scheduler) = app_state.scheduler.as_ref() else {
+        return api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "调度器未运行，无法暂停服务",
+        );
+    };
+
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let until = match resolve_until(&request, "自动恢复时间") {
+        Ok(until) => until,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+
+    match scheduler.pause_service(&name, request.reason, until).await {
+        Some(pause) => Json(ApiPauseResponse {
+            name,
+            paused: true,
+            pause: Some(pause),
+        })
+        .into_response(),
+        None => api_error(StatusCode::NOT_FOUND, format!("服务不存在或未启用: {name}")),
+    }
+}
+
+/// 恢复服务定时检测的处理函数
+pub async fn api_resume_service(
+    State(app_state): State<WebAppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let Some(scheduler) = app_state.scheduler.as_ref() else {
+        return api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "调度器未运行，无法恢复服务",
+        );
+    };
+
+    match scheduler.resume_service(&name).await {
+        Some(previous) => Json(ApiPauseResponse {
+            name,
+            paused: false,
+            pause: previous,
+        })
+        .into_response(),
+        None => api_error(StatusCode::NOT_FOUND, format!("服务不存在或未启用: {name}")),
+    }
+}
+
+/// 静默服务通知的处理函数
+///
+/// 请求体与暂停接口相同，可指定原因以及结束时间或时长
+pub async fn api_silence_service(
+    State(app_state): State<WebAppState>,
+    Path(name): Path<String>,
+    request: Option<Json<ApiPauseRequest>>,
+) -> Response {
+    let Some(scheduler) = app_state.scheduler.as_ref() else {
+        return api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "调度器未运行，无法静默服务通知",
+        );
+    };
+
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let until = match resolve_until(&request, "静默结束时间") {
+        Ok(until) => until,
+        Err(message) => return api_error(StatusCode::BAD_REQUEST, message),
+    };
+
+    match scheduler
+        .silence_service(&name, request.reason, until)
+        .await
+    {
+        Some(silence) => Json(ApiSilenceResponse {
+            name,
+            silenced: true,
+            silence: Some(silence),
+        })
+        .into_response(),
+        None => api_error(StatusCode::NOT_FOUND, format!("服务不存在或未启用: {name}")),
+    }
+}
+
+/// 取消服务通知静默的处理函数
+pub async fn api_unsilence_service(
+    State(app_state): State<WebAppState>,
+    Path(name): Path<String>,
+) -> Response {
+    let Some(scheduler) = app_state.scheduler.as_ref() else {
+        return api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "调度器未运行，无法取消静默",
+        );
+    };
+
+    match scheduler.unsilence_service(&name).await {
+        Some(previous) => Json(ApiSilenceResponse {
+            name,
+            silenced: false,
+            silence: previous,
+        })
+        .into_response(),
+        None => api_error(StatusCode::NOT_FOUND, format!("服务不存在或未启用: {name}")),
+    }
+}
+
+/// 根据 `until` 或 `duration_seconds` 计算结束时间，两者不能同时指定且必须晚于当前时间
+fn resolve_until(
+    request: &ApiPauseRequest,
+    label: &str,
+) -> std::result::Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+    let until = match (request.until, request.duration_seconds) {
+        (Some(_), Some(_)) => {
+            return Err("until 与 duration_seconds 不能同时指定".to_string());
+        }
+        (Some(until), None) => Some(until),
+        (None, Some(seconds)) => {
+            Some(chrono::Utc::now() + chrono::Duration::seconds(seconds as i64))
+        }
+        (None, None) => None,
+    };
+
+    if until.is_some_and(|until| until <= chrono::Utc::now()) {
+        return Err(format!("{label}必须晚于当前时间"));
+    }
+    Ok(until)
+}
+
+/// 记录心跳上报，结束和失败上报后立即检测服务，使状态和通知及时更新
+///
+/// 暂停的服务只记录上报，不立即检测
+async fn record_heartbeat(
+    app_state: WebAppState,
+    token: &str,
+    event: HeartbeatEvent,
+    body: String,
+) -> Response {
+    let (Some(scheduler), Some(heartbeats)) =
+        (app_state.scheduler.as_ref(), app_state.heartbeats.as_ref())
+    else {
+        return api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "调度器未运行，无法接收心跳",
+        );
+    };
+
+    let Some(service_name) = heartbeats.service_for_token(token) else {
+        return api_error(StatusCode::NOT_FOUND, "心跳令牌不存在");
+    };
+
+    let message = (event == HeartbeatEvent::Fail).then_some(body);
+    let state = heartbeats.record(&service_name, event, message);
+    info!("收到服务 {} 的心跳上报: {:?}", service_name, event);
+
+    let result = match event {
+        HeartbeatEvent::Start => None,
+        HeartbeatEvent::Success | HeartbeatEvent::Fail
+            if scheduler.pause_info(&service_name).await.is_some() =>
+        {
+            None
+        }
+        HeartbeatEvent::Success | HeartbeatEvent::Fail => {
+            match scheduler.trigger_check(&service_name).await {
+                Ok(result) => result.as_ref().map(ApiCheckResult::from),
+                Err(e) => {
+                    error!("心跳上报后检测服务 {} 失败: {:#}", service_name, e);
+                    None
+                }
+            }
+        }
+    };
+
+    Json(ApiHeartbeatResponse {
+        name: service_name,
+        event,
+        duration_seconds: state.last_duration_seconds,
+        result,
+    })
+    .into_response()
+}
+
+/// 心跳上报处理函数，表示任务成功结束（或周期性存活上报）
+pub async fn api_heartbeat(
+    State(app_state): State<WebAppState>,
+    Path(token): Path<String>,
+) -> Response {
+    record_heartbeat(app_state, &token, HeartbeatEvent::Success, String::new()).await
+}
+
+/// 带类型的心跳上报处理函数：`start`、`success`（或 `finish`）、`fail`
+///
+/// 失败上报的请求体作为失败信息
+pub async fn api_heartbeat_event(
+    State(app_state): State<WebAppState>,
+    Path((token, event)): Path<(String, String)>,
+    body: String,
+) -> Response {
+    match event.parse::<HeartbeatEvent>() {
+        Ok(event) => record_heartbeat(app_state, &token, event, body).await,
+        Err(e) => api_error(StatusCode::NOT_FOUND, e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::types::WebConfig;
     use crate::web::WebServiceStatus;
-    use std::collections::HashMap;
     use std::sync::Arc;
     use tokio::sync::RwLock;
 
@@ -167,24 +846,102 @@ mod tests {
                 url: "https://example.com".to_string(),
                 status: "Online".to_string(),
                 response_time_ms: Some(150),
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
 
         let app_state = WebAppState {
             config: WebConfig::default(),
             services: Arc::new(RwLock::new(test_data)),
+            scheduler: None,
+            heartbeats: None,
         };
 
         // 调用处理函数
-        let response = api_status(State(app_state)).await;
+        let response = api_status(State(app_state), Query(StatusQuery::default())).await;
 
         // 验证响应
         // 注意：这里只是基本的结构测试，实际的 JSON 内容验证需要更复杂的测试
         assert!(response.into_response().status().is_success());
     }
 
+    #[test]
+    fn test_render_metrics() {
+        let timings = RequestTimings {
+            dns_ms: Some(2.0),
+            connect_ms: Some(1.0),
+            tls_ms: None,
+            ttfb_ms: 20.0,
+            download_ms: Some(0.5),
+            connection_reused: false,
+        };
+        let services = vec![
+            WebServiceStatus {
+                name: "api \"v2\"".to_string(),
+                url: "http://example.com".to_string(),
+                status: "Online".to_string(),
+                response_time_ms: Some(23),
+                timings: Some(timings),
+                last_check: Some(chrono::Utc::now()),
+                error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
+            },
+            WebServiceStatus {
+                name: "pending".to_string(),
+                url: "http://pending.example.com".to_string(),
+                status: "Unknown".to_string(),
+                response_time_ms: None,
+                timings: None,
+                last_check: None,
+                error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
+            },
+        ];
+
+        let metrics = render_metrics(&services);
+        assert!(metrics.contains(
+            "service_vitals_up{service=\"api \\\"v2\\\"\",url=\"http://example.com\"} 1"
+        ));
+        assert!(metrics
+            .contains("service_vitals_response_time_seconds{service=\"api \\\"v2\\\"\"} 0.023"));
+        assert!(metrics.contains(
+            "service_vitals_request_phase_seconds{service=\"api \\\"v2\\\"\",phase=\"dns\"} 0.002"
+        ));
+        assert!(metrics.contains("phase=\"ttfb\"} 0.02"));
+        assert!(!metrics.contains("phase=\"tls\""));
+        assert!(!metrics.contains("pending"));
+        assert!(
+            metrics.contains("service_vitals_service_info{service=\"api \\\"v2\\\"\",tags=\"\"} 1")
+        );
+    }
+
+    #[test]
+    fn test_tag_labels() {
+        let tags = vec![
+            "core".to_string(),
+            "team:orders".to_string(),
+            "team:billing".to_string(),
+            "env-name:prod".to_string(),
+        ];
+        assert_eq!(
+            tag_labels(&tags),
+            ",tags=\",core,team:orders,team:billing,env-name:prod,\",tag_team=\"orders\",tag_env_name=\"prod\""
+        );
+        assert_eq!(tag_labels(&[]), ",tags=\"\"");
+    }
+
     #[tokio::test]
     async fn test_dashboard_handler() {
         // 创建测试状态数据
@@ -196,18 +953,25 @@ mod tests {
                 url: "https://example.com".to_string(),
                 status: "Online".to_string(),
                 response_time_ms: Some(150),
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
 
         let app_state = WebAppState {
             config: WebConfig::default(),
             services: Arc::new(RwLock::new(test_data)),
+            scheduler: None,
+            heartbeats: None,
         };
 
         // 调用处理函数
-        let response = dashboard(State(app_state)).await;
+        let response = dashboard(State(app_state), Query(StatusQuery::default())).await;
 
         // 验证响应状态
         assert!(response.into_response().status().is_success());
@@ -224,8 +988,13 @@ mod tests {
                 url: "https://example.com".to_string(),
                 status: "Online".to_string(),
                 response_time_ms: Some(150),
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
         test_data.insert(
@@ -235,8 +1004,13 @@ mod tests {
                 url: "https://example2.com".to_string(),
                 status: "Offline".to_string(),
                 response_time_ms: None,
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: Some("Connection refused".to_string()),
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
 
@@ -249,9 +1023,11 @@ mod tests {
         let app_state = WebAppState {
             config,
             services: Arc::new(RwLock::new(test_data)),
+            scheduler: None,
+            heartbeats: None,
         };
 
-        let response = api_status(State(app_state)).await;
+        let response = api_status(State(app_state), Query(StatusQuery::default())).await;
         assert!(response.into_response().status().is_success());
     }
 
@@ -266,8 +1042,13 @@ mod tests {
                 url: "https://example.com".to_string(),
                 status: "Online".to_string(),
                 response_time_ms: Some(150),
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
         test_data.insert(
@@ -277,8 +1058,13 @@ mod tests {
                 url: "https://example2.com".to_string(),
                 status: "Offline".to_string(),
                 response_time_ms: None,
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: Some("Service unavailable".to_string()),
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
 
@@ -291,9 +1077,11 @@ mod tests {
         let app_state = WebAppState {
             config,
             services: Arc::new(RwLock::new(test_data)),
+            scheduler: None,
+            heartbeats: None,
         };
 
-        let response = dashboard(State(app_state)).await;
+        let response = dashboard(State(app_state), Query(StatusQuery::default())).await;
         assert!(response.into_response().status().is_success());
     }
 
@@ -310,9 +1098,11 @@ mod tests {
         let app_state = WebAppState {
             config,
             services: Arc::new(RwLock::new(test_data)),
+            scheduler: None,
+            heartbeats: None,
         };
 
-        let response = dashboard(State(app_state)).await;
+        let response = dashboard(State(app_state), Query(StatusQuery::default())).await;
         assert!(response.into_response().status().is_success());
     }
 
@@ -329,8 +1119,13 @@ mod tests {
                 url: "https://example1.com".to_string(),
                 status: "Online".to_string(),
                 response_time_ms: Some(150),
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
         test_data.insert(
@@ -340,8 +1135,13 @@ mod tests {
                 url: "https://example2.com".to_string(),
                 status: "Online".to_string(),
                 response_time_ms: Some(200),
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
         test_data.insert(
@@ -351,8 +1151,13 @@ mod tests {
                 url: "https://example3.com".to_string(),
                 status: "Offline".to_string(),
                 response_time_ms: None,
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: Some("HTTP 500 Internal Server Error".to_string()),
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
         test_data.insert(
@@ -362,8 +1167,13 @@ mod tests {
                 url: "https://example4.com".to_string(),
                 status: "Unknown".to_string(),
                 response_time_ms: None,
+                timings: None,
                 last_check: Some(chrono::Utc::now()),
                 error_message: Some("DNS resolution failed".to_string()),
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
             },
         );
 
@@ -372,9 +1182,11 @@ mod tests {
         let app_state_no_filter = WebAppState {
             config: config_no_filter,
             services: Arc::new(RwLock::new(test_data.clone())),
+            scheduler: None,
+            heartbeats: None,
         };
 
-        let response = api_status(State(app_state_no_filter)).await;
+        let response = api_status(State(app_state_no_filter), Query(StatusQuery::default())).await;
         let response = response.into_response();
         assert!(response.status().is_success());
 
@@ -387,9 +1199,12 @@ mod tests {
         let app_state_with_filter = WebAppState {
             config: config_with_filter,
             services: Arc::new(RwLock::new(test_data)),
+            scheduler: None,
+            heartbeats: None,
         };
 
-        let response = api_status(State(app_state_with_filter)).await;
+        let response =
+            api_status(State(app_state_with_filter), Query(StatusQuery::default())).await;
         let response = response.into_response();
         assert!(response.status().is_success());
 
@@ -400,4 +1215,446 @@ mod tests {
         // - offline_services: 1
         // - unknown_services: 1
     }
+
+    /// 创建指向测试服务器的服务配置
+    fn test_service(name: &str, url: String, tags: &[&str]) -> crate::config::ServiceConfig {
+        crate::config::ServiceConfig {
+            name: name.to_string(),
+            url,
+            expected_status_codes: vec![200],
+            check_interval_seconds: Some(3600),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// 创建带调度器的应用状态
+    async fn app_state_with_scheduler(
+        services: Vec<crate::config::ServiceConfig>,
+    ) -> (WebAppState, Arc<std::sync::atomic::AtomicUsize>) {
+        use crate::health::{HttpHealthChecker, Scheduler, TaskScheduler};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        let checker = Arc::new(
+            HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap(),
+        );
+        let heartbeats = checker.heartbeats();
+        let global: crate::config::GlobalConfig =
+            toml::from_str("check_interval_seconds = 3600").unwrap();
+        let scheduler = Arc::new(TaskScheduler::new(checker, None, global));
+
+        let callback_count = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&callback_count);
+        scheduler
+            .set_health_result_callback(Arc::new(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }))
+            .await;
+        scheduler.start(services).await.unwrap();
+
+        let app_state = WebAppState {
+            config: WebConfig::default(),
+            services: Arc::new(RwLock::new(HashMap::new())),
+            scheduler: Some(scheduler),
+            heartbeats: Some(heartbeats),
+        };
+        (app_state, callback_count)
+    }
+
+    async fn response_json(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_trigger_check_without_scheduler() {
+        let app_state = WebAppState {
+            config: WebConfig::default(),
+            services: Arc::new(RwLock::new(HashMap::new())),
+            scheduler: None,
+            heartbeats: None,
+        };
+
+        let response = api_trigger_service_check(State(app_state), Path("any".to_string())).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_check_unknown_service() {
+        let (app_state, _) = app_state_with_scheduler(Vec::new()).await;
+
+        let response =
+            api_trigger_service_check(State(app_state), Path("missing".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_service_check_runs_pipeline() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/health")
+            .with_status(200)
+            .expect_at_least(2)
+            .create_async()
+            .await;
+
+        let service = test_service("api", format!("{}/health", server.url()), &[]);
+        let (app_state, callback_count) = app_state_with_scheduler(vec![service]).await;
+
+        let response =
+            api_trigger_service_check(State(app_state.clone()), Path("api".to_string())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["results"][0]["name"], "api");
+        assert_eq!(body["results"][0]["status"], "up");
+        assert_eq!(body["results"][0]["status_code"], 200);
+
+        // 定时任务启动时的首次检测与手动触发都应经过结果回调
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(callback_count.load(std::sync::atomic::Ordering::SeqCst) >= 2);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_trigger_tag_check() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/ok")
+            .with_status(200)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/fail")
+            .with_status(503)
+            .create_async()
+            .await;
+
+        let services = vec![
+            test_service("web-a", format!("{}/ok", server.url()), &["web"]),
+            test_service("web-b", format!("{}/fail", server.url()), &["web"]),
+            test_service("db", format!("{}/ok", server.url()), &["db"]),
+        ];
+        let (app_state, _) = app_state_with_scheduler(services).await;
+
+        let response =
+            api_trigger_tag_check(State(app_state.clone()), Path("web".to_string())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["name"], "web-a");
+        assert_eq!(results[0]["status"], "up");
+        assert_eq!(results[1]["name"], "web-b");
+        assert_eq!(results[1]["status"], "down");
+
+        let response = api_trigger_tag_check(State(app_state), Path("none".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_status_tag_filter() {
+        let services = vec![
+            test_service(
+                "orders",
+                "http://orders".to_string(),
+                &["team:orders", "core"],
+            ),
+            test_service("billing", "http://billing".to_string(), &["team:billing"]),
+            test_service("db", "http://db".to_string(), &[]),
+        ];
+        let (app_state, _) = app_state_with_scheduler(services).await;
+        for name in ["orders", "billing", "db"] {
+            app_state.services.write().await.insert(
+                name.to_string(),
+                WebServiceStatus {
+                    name: name.to_string(),
+                    url: format!("http://{name}"),
+                    status: "Online".to_string(),
+                    response_time_ms: Some(5),
+                    timings: None,
+                    last_check: Some(chrono::Utc::now()),
+                    error_message: None,
+                    paused: None,
+                    silenced: None,
+                    consecutive_failures: 0,
+                    tags: Vec::new(),
+                },
+            );
+        }
+
+        let query = StatusQuery {
+            tag: Some("team:*".to_string()),
+        };
+        let response = api_status(State(app_state.clone()), Query(query))
+            .await
+            .into_response();
+        let body = response_json(response).await;
+        let services = body["services"].as_array().unwrap();
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[0]["name"], "billing");
+        assert_eq!(
+            services[1]["tags"],
+            serde_json::json!(["team:orders", "core"])
+        );
+        assert_eq!(body["total_services"], 2);
+
+        let query = StatusQuery {
+            tag: Some("core, missing".to_string()),
+        };
+        assert_eq!(query.tags(), ["core", "missing"]);
+        let response = dashboard(State(app_state), Query(query))
+            .await
+            .into_response();
+        let html = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let html = String::from_utf8(html.to_vec()).unwrap();
+        assert!(html.contains("orders"));
+        assert!(!html.contains("http://billing"));
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_service() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/health")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let service = test_service("api", format!("{}/health", server.url()), &[]);
+        let (app_state, _) = app_state_with_scheduler(vec![service]).await;
+        app_state.services.write().await.insert(
+            "api".to_string(),
+            WebServiceStatus {
+                name: "api".to_string(),
+                url: server.url(),
+                status: "Online".to_string(),
+                response_time_ms: Some(5),
+                timings: None,
+                last_check: Some(chrono::Utc::now()),
+                error_message: None,
+                paused: None,
+                silenced: None,
+                consecutive_failures: 0,
+                tags: Vec::new(),
+            },
+        );
+
+        let request = ApiPauseRequest {
+            reason: Some("数据库维护".to_string()),
+            until: None,
+            duration_seconds: Some(600),
+        };
+        let response = api_pause_service(
+            State(app_state.clone()),
+            Path("api".to_string()),
+            Some(Json(request)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["paused"], true);
+        assert_eq!(body["pause"]["reason"], "数据库维护");
+        assert!(body["pause"]["until"].is_string());
+
+        // 暂停信息应出现在状态接口中
+        let response = api_status(State(app_state.clone()), Query(StatusQuery::default()))
+            .await
+            .into_response();
+        let body = response_json(response).await;
+        assert_eq!(body["services"][0]["paused"]["reason"], "数据库维护");
+
+        let response = dashboard(State(app_state.clone()), Query(StatusQuery::default()))
+            .await
+            .into_response();
+        let html = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let html = String::from_utf8(html.to_vec()).unwrap();
+        assert!(html.contains("已暂停"));
+        assert!(html.contains("数据库维护"));
+
+        let response = api_resume_service(State(app_state.clone()), Path("api".to_string())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["paused"], false);
+        assert_eq!(body["pause"]["reason"], "数据库维护");
+
+        let scheduler = app_state.scheduler.as_ref().unwrap();
+        assert!(scheduler.pause_info("api").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_silence_and_unsilence_service() {
+        let service = test_service("api", "http://127.0.0.1:9/health".to_string(), &[]);
+        let (app_state, _) = app_state_with_scheduler(vec![service]).await;
+        app_state.services.write().await.insert(
+            "api".to_string(),
+            WebServiceStatus {
+                name: "api".to_string(),
+                url: "http://127.0.0.1:9/health".to_string(),
+                status: "Offline".to_string(),
+                response_time_ms: Some(1),
+                timings: None,
+                last_check: Some(chrono::Utc::now()),
+                error_message: Some("Connection refused".to_string()),
+                paused: None,
+                silenced: None,
+                consecutive_failures: 3,
+                tags: Vec::new(),
+            },
+        );
+
+        let request = ApiPauseRequest {
+            reason: Some("已知故障".to_string()),
+            until: None,
+            duration_seconds: Some(600),
+        };
+        let response = api_silence_service(
+            State(app_state.clone()),
+            Path("api".to_string()),
+            Some(Json(request)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["silenced"], true);
+        assert_eq!(body["silence"]["reason"], "已知故障");
+
+        let response = api_status(State(app_state.clone()), Query(StatusQuery::default()))
+            .await
+            .into_response();
+        let body: ApiStatusResponse =
+            serde_json::from_value(response_json(response).await).unwrap();
+        assert_eq!(body.services[0].consecutive_failures, 3);
+        assert_eq!(
+            body.services[0]
+                .silenced
+                .as_ref()
+                .unwrap()
+                .reason
+                .as_deref(),
+            Some("已知故障")
+        );
+
+        let response =
+            api_unsilence_service(State(app_state.clone()), Path("api".to_string())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["silenced"], false);
+
+        let response = api_unsilence_service(State(app_state), Path("missing".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_pause_service_validation() {
+        let (app_state, _) = app_state_with_scheduler(Vec::new()).await;
+
+        let response =
+            api_pause_service(State(app_state.clone()), Path("missing".to_string()), None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let request = ApiPauseRequest {
+            reason: None,
+            until: Some(chrono::Utc::now() + chrono::Duration::seconds(60)),
+            duration_seconds: Some(60),
+        };
+        let response = api_pause_service(
+            State(app_state.clone()),
+            Path("missing".to_string()),
+            Some(Json(request)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = api_resume_service(State(app_state), Path("missing".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_start_finish_and_fail() {
+        let mut service = test_service(
+            "nightly-backup",
+            "heartbeat://nightly-backup".to_string(),
+            &[],
+        );
+        service.expected_status_codes = Vec::new();
+        service.heartbeat = Some(crate::config::heartbeat::HeartbeatCheckConfig {
+            token: "nightly-backup-7f3a9c".to_string(),
+            period_seconds: 3600,
+            grace_seconds: 600,
+            max_duration_seconds: None,
+        });
+        let (app_state, _) = app_state_with_scheduler(vec![service]).await;
+        let token = || "nightly-backup-7f3a9c".to_string();
+
+        let response =
+            api_heartbeat(State(app_state.clone()), Path("wrong-token".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = api_heartbeat_event(
+            State(app_state.clone()),
+            Path((token(), "start".to_string())),
+            String::new(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["name"], "nightly-backup");
+        assert_eq!(body["event"], "start");
+        assert!(body["result"].is_null());
+
+        let response = api_heartbeat(State(app_state.clone()), Path(token())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["event"], "success");
+        assert!(body["duration_seconds"].is_number());
+        assert_eq!(body["result"]["status"], "up");
+
+        let response = api_heartbeat_event(
+            State(app_state.clone()),
+            Path((token(), "fail".to_string())),
+            "pg_dump: connection refused".to_string(),
+        )
+        .await;
+        let body = response_json(response).await;
+        assert_eq!(body["result"]["status"], "down");
+        assert_eq!(
+            body["result"]["error_message"],
+            "任务上报失败: pg_dump: connection refused"
+        );
+
+        // 暂停的服务只记录上报，不立即检测
+        app_state
+            .scheduler
+            .as_ref()
+            .unwrap()
+            .pause_service("nightly-backup", None, None)
+            .await
+            .unwrap();
+        let response = api_heartbeat(State(app_state.clone()), Path(token())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response_json(response).await["result"].is_null());
+        let state = app_state
+            .heartbeats
+            .as_ref()
+            .unwrap()
+            .state("nightly-backup");
+        assert!(state.failure.is_none());
+
+        let response = api_heartbeat_event(
+            State(app_state),
+            Path((token(), "stop".to_string())),
+            String::new(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
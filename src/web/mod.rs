@@ -5,10 +5,17 @@
 pub mod handlers;
 
 use crate::common::error::{Result, ServiceVitalsError};
+use crate::common::glob::glob_match_any;
 use crate::common::status::ServiceStatus;
+use crate::config::secret;
 use crate::config::types::WebConfig;
 use crate::health::result::HealthStatus;
-use axum::{routing::get, Router};
+use crate::health::{HeartbeatRegistry, PauseInfo, RequestTimings, SilenceInfo, TaskScheduler};
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -16,7 +23,7 @@ use tokio::sync::{mpsc, RwLock};
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
-use tracing::{error, info};
+use tracing::info;
 
 /// Web 服务器错误类型
 #[derive(Debug, thiserror::Error)]
@@ -40,10 +47,53 @@ pub struct WebServiceStatus {
     pub status: String,
     /// 响应延迟（毫秒）
     pub response_time_ms: Option<u64>,
+    /// 最近一次检测的请求各阶段耗时
+    pub timings: Option<RequestTimings>,
     /// 最后检查时间
     pub last_check: Option<chrono::DateTime<chrono::Utc>>,
     /// 错误信息（当状态为 Offline 或 Unknown 时）
     pub error_message: Option<String>,
+    /// 暂停信息（服务被暂停时）
+    pub paused: Option<PauseInfo>,
+    /// 静默信息（服务通知被静默时）
+    pub silenced: Option<SilenceInfo>,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 服务标签（来自调度器中的服务配置）
+    pub tags: Vec<String>,
+}
+
+impl WebServiceStatus {
+    /// 是否带有任一指定标签（支持通配符），未指定标签时总是返回 `true`
+    pub fn has_any_tag(&self, tags: &[&str]) -> bool {
+        tags.is_empty() || glob_match_any(tags, &self.tags)
+    }
+
+    /// 请求各阶段耗时的摘要，例如 `DNS 1.2ms · 连接 0.3ms · 首字节 20.5ms · 下载 0.4ms`
+    pub fn timing_breakdown(&self) -> Option<String> {
+        let timings = self.timings?;
+        let summary = timings
+            .phases()
+            .into_iter()
+            .map(|(phase, ms)| {
+                let label = match phase {
+                    "dns" => "DNS",
+                    "connect" => "连接",
+                    "tls" => "TLS",
+                    "ttfb" => "首字节",
+                    _ => "下载",
+                };
+                format!("{label} {ms:.1}ms")
+            })
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        Some(if timings.connection_reused {
+            format!("{summary}（复用连接）")
+        } else {
+            summary
+        })
+    }
 }
 
 /// 共享的 Web 状态数据
@@ -56,6 +106,10 @@ pub struct WebAppState {
     pub config: WebConfig,
     /// 服务状态数据
     pub services: SharedWebState,
+    /// 任务调度器，用于手动触发检测（未关联时相关接口返回 503）
+    pub scheduler: Option<Arc<TaskScheduler>>,
+    /// 心跳记录表，用于接收心跳上报（未关联时上报接口返回 503）
+    pub heartbeats: Option<Arc<HeartbeatRegistry>>,
 }
 
 /// Web 服务器结构
@@ -66,6 +120,10 @@ pub struct WebServer {
     state: SharedWebState,
     /// 状态更新接收器
     status_receiver: Option<mpsc::Receiver<ServiceStatus>>,
+    /// 任务调度器
+    scheduler: Option<Arc<TaskScheduler>>,
+    /// 心跳记录表
+    heartbeats: Option<Arc<HeartbeatRegistry>>,
 }
 
 impl WebServer {
@@ -79,11 +137,25 @@ impl WebServer {
                 config,
                 state,
                 status_receiver: Some(rx),
+                scheduler: None,
+                heartbeats: None,
             },
             tx,
         )
     }
 
+    /// 关联任务调度器，启用手动触发检测等控制接口
+    pub fn with_scheduler(mut self, scheduler: Arc<TaskScheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// 关联心跳记录表，启用心跳上报接口
+    pub fn with_heartbeats(mut self, heartbeats: Arc<HeartbeatRegistry>) -> Self {
+        self.heartbeats = Some(heartbeats);
+        self
+    }
+
     /// 启动 Web 服务器
     pub async fn start(mut self) -> Result<()> {
         if !self.config.enabled {
@@ -129,7 +201,13 @@ impl WebServer {
 
         info!("Web 服务器已启动，访问地址: http://{}", addr);
 
-        axum::serve(listener, app).await.map_err(|e| {
+        // 管理接口未配置令牌时按对端地址判断是否为本机请求
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .map_err(|e| {
             ServiceVitalsError::WebError(WebError::ServerStartError {
                 message: e.to_string(),
             })
@@ -143,16 +221,53 @@ impl WebServer {
         let app_state = WebAppState {
             config: self.config.clone(),
             services: Arc::clone(&self.state),
+            scheduler: self.scheduler.clone(),
+            heartbeats: self.heartbeats.clone(),
         };
 
+        // 管理接口会改变检测和通知行为，需要API令牌且不允许跨域访问
+        let control = Router::new()
+            .route(
+                "/api/v1/services/{name}/check",
+                post(handlers::api_trigger_service_check),
+            )
+            .route(
+                "/api/v1/tags/{tag}/check",
+                post(handlers::api_trigger_tag_check),
+            )
+            .route(
+                "/api/v1/services/{name}/pause",
+                post(handlers::api_pause_service),
+            )
+            .route(
+                "/api/v1/services/{name}/resume",
+                post(handlers::api_resume_service),
+            )
+            .route(
+                "/api/v1/services/{name}/silence",
+                post(handlers::api_silence_service),
+            )
+            .route(
+                "/api/v1/services/{name}/unsilence",
+                post(handlers::api_unsilence_service),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                handlers::require_api_token,
+            ));
+
         Router::new()
             .route("/dashboard", get(handlers::dashboard))
             .route("/api/v1/status", get(handlers::api_status))
-            .layer(
-                ServiceBuilder::new()
-                    .layer(TraceLayer::new_for_http())
-                    .layer(CorsLayer::permissive()),
+            .route("/metrics", get(handlers::metrics))
+            .route("/api/v1/heartbeat/{token}", post(handlers::api_heartbeat))
+            .route(
+                "/api/v1/heartbeat/{token}/{event}",
+                post(handlers::api_heartbeat_event),
             )
+            .layer(CorsLayer::permissive())
+            .merge(control)
+            .layer(ServiceBuilder::new().layer(TraceLayer::new_for_http()))
             .with_state(app_state)
     }
 
@@ -165,11 +280,16 @@ impl WebServer {
                 .entry(status.name.clone())
                 .or_insert_with(|| WebServiceStatus {
                     name: status.name.clone(),
-                    url: status.url.clone(),
+                    url: secret::redact_url(&status.url).into_owned(),
                     status: "Unknown".to_string(),
                     response_time_ms: None,
+                    timings: None,
                     last_check: None,
                     error_message: None,
+                    paused: None,
+                    silenced: None,
+                    consecutive_failures: 0,
+                    tags: Vec::new(),
                 });
 
         // 更新当前状态
@@ -182,6 +302,8 @@ impl WebServer {
 
         web_status.status = new_status.to_string();
         web_status.response_time_ms = status.response_time_ms;
+        web_status.timings = status.timings;
+        web_status.consecutive_failures = status.consecutive_failures;
         web_status.last_check = Some(chrono::Utc::now());
 
         // 更新错误信息：只有在服务离线或未知状态时才保留错误信息
@@ -192,7 +314,7 @@ impl WebServer {
         };
 
         if !status.url.is_empty() {
-            web_status.url = status.url;
+            web_status.url = secret::redact_url(&status.url).into_owned();
         }
     }
 }
@@ -213,6 +335,152 @@ mod tests {
         assert_eq!(web_server.config.bind_address, config.bind_address);
     }
 
+    /// 从指定对端地址向路由发送请求，返回状态码和是否带有CORS响应头
+    async fn send(
+        router: &Router,
+        method: &str,
+        uri: &str,
+        peer: &str,
+        headers: &[(&str, &str)],
+    ) -> (axum::http::StatusCode, bool) {
+        use tower::ServiceExt;
+
+        let peer: SocketAddr = peer.parse().unwrap();
+        let mut request = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .extension(axum::extract::ConnectInfo(peer));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let response = router
+            .clone()
+            .oneshot(request.body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let cors = response
+            .headers()
+            .contains_key("access-control-allow-origin");
+        (response.status(), cors)
+    }
+
+    #[tokio::test]
+    async fn test_control_routes_require_token_and_same_origin() {
+        use axum::http::StatusCode;
+
+        const LOCAL: &str = "127.0.0.1:50000";
+        const REMOTE: &str = "192.0.2.10:50000";
+
+        let (web_server, _sender) = WebServer::new(WebConfig::default());
+        let router = web_server.create_router();
+        let evil = [
+            ("origin", "https://evil.example"),
+            ("host", "127.0.0.1:8080"),
+        ];
+
+        // 只读接口允许跨域
+        let (status, cors) = send(&router, "GET", "/api/v1/status", REMOTE, &evil).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(cors);
+
+        // 未配置令牌时拒绝其他主机的管理请求，即使不带 Origin（如 curl）
+        let (status, _) = send(&router, "POST", "/api/v1/services/api/pause", REMOTE, &[]).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&router, "POST", "/api/v1/tags/core/check", REMOTE, &[]).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // 本机的跨域请求同样拒绝，本机命令行和同源页面可以调用
+        let (status, cors) =
+            send(&router, "POST", "/api/v1/services/api/pause", LOCAL, &evil).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(!cors);
+        let (status, _) = send(&router, "POST", "/api/v1/tags/core/check", LOCAL, &[]).await;
+        assert_ne!(status, StatusCode::FORBIDDEN);
+        let same_origin = [
+            ("origin", "http://127.0.0.1:8080"),
+            ("host", "127.0.0.1:8080"),
+        ];
+        let (status, _) = send(
+            &router,
+            "POST",
+            "/api/v1/tags/core/check",
+            LOCAL,
+            &same_origin,
+        )
+        .await;
+        assert_ne!(status, StatusCode::FORBIDDEN);
+
+        let config = WebConfig {
+            api_token: Some("s3cret".to_string()),
+            ..Default::default()
+        };
+        let (web_server, _sender) = WebServer::new(config);
+        let router = web_server.create_router();
+        let (status, _) = send(&router, "POST", "/api/v1/services/api/silence", LOCAL, &[]).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(
+            &router,
+            "POST",
+            "/api/v1/services/api/silence",
+            REMOTE,
+            &[("authorization", "Bearer wrong")],
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        // 配置了令牌后其他主机可以凭令牌调用
+        let (status, _) = send(
+            &router,
+            "POST",
+            "/api/v1/services/api/silence",
+            REMOTE,
+            &[("authorization", "Bearer s3cret")],
+        )
+        .await;
+        assert_ne!(status, StatusCode::UNAUTHORIZED);
+        assert_ne!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_timing_breakdown() {
+        let mut status = WebServiceStatus {
+            name: "api".to_string(),
+            url: "https://example.com".to_string(),
+            status: "Online".to_string(),
+            response_time_ms: Some(30),
+            timings: None,
+            last_check: Some(chrono::Utc::now()),
+            error_message: None,
+            paused: None,
+            silenced: None,
+            consecutive_failures: 0,
+            tags: Vec::new(),
+        };
+        assert_eq!(status.timing_breakdown(), None);
+
+        status.timings = Some(RequestTimings {
+            dns_ms: Some(1.25),
+            connect_ms: Some(0.5),
+            tls_ms: Some(8.0),
+            ttfb_ms: 20.0,
+            download_ms: Some(0.4),
+            connection_reused: false,
+        });
+        assert_eq!(
+            status.timing_breakdown().unwrap(),
+            "DNS 1.2ms · 连接 0.5ms · TLS 8.0ms · 首字节 20.0ms · 下载 0.4ms"
+        );
+
+        status.timings = Some(RequestTimings {
+            ttfb_ms: 5.0,
+            connection_reused: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            status.timing_breakdown().unwrap(),
+            "首字节 5.0ms（复用连接）"
+        );
+    }
+
     #[tokio::test]
     async fn test_status_update() {
         let config = WebConfig::default();
@@ -225,6 +493,7 @@ mod tests {
             last_check: Some(chrono::Utc::now()),
             status_code: Some(200),
             response_time_ms: Some(150),
+            timings: None,
             consecutive_failures: 0,
             error_message: None,
             enabled: true,
@@ -254,6 +523,7 @@ mod tests {
             last_check: Some(chrono::Utc::now()),
             status_code: Some(200),
             response_time_ms: Some(150),
+            timings: None,
             consecutive_failures: 0,
             error_message: None,
             enabled: true,
@@ -269,6 +539,7 @@ mod tests {
             last_check: Some(chrono::Utc::now()),
             status_code: Some(500),
             response_time_ms: Some(5000),
+            timings: None,
             consecutive_failures: 1,
             error_message: Some("Internal Server Error".to_string()),
             enabled: true,
@@ -301,6 +572,7 @@ mod tests {
             last_check: Some(chrono::Utc::now()),
             status_code: Some(500),
             response_time_ms: Some(1000),
+            timings: None,
             consecutive_failures: 1,
             error_message: Some("HTTP 500 Internal Server Error".to_string()),
             enabled: true,
@@ -330,6 +602,7 @@ mod tests {
             last_check: Some(chrono::Utc::now()),
             status_code: None,
             response_time_ms: None,
+            timings: None,
             consecutive_failures: 0,
             error_message: Some("DNS resolution failed".to_string()),
             enabled: true,
@@ -357,6 +630,7 @@ mod tests {
             last_check: Some(chrono::Utc::now()),
             status_code: Some(200),
             response_time_ms: Some(150),
+            timings: None,
             consecutive_failures: 0,
             error_message: None,
             enabled: true,
@@ -379,6 +653,8 @@ mod tests {
         let app_state = WebAppState {
             config: config.clone(),
             services: services.clone(),
+            scheduler: None,
+            heartbeats: None,
         };
 
         let cloned_state = app_state.clone();
@@ -0,0 +1,210 @@
+//! gRPC健康检测测试
+//!
+//! 启动进程内的 tonic 健康检查服务（h2c 与 TLS），
+//! 测试服务状态映射、未注册服务、连接失败以及 `grpcs://` 的TLS配置
+
+use service_vitals::config::types::ServiceConfig;
+use service_vitals::health::{HealthChecker, HealthResult, HealthStatus, HttpHealthChecker};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{self, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+const TLS_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/tls");
+
+/// 启动h2c健康检查服务，`pkg.Orders` 为 SERVING，`pkg.Billing` 为 NOT_SERVING
+async fn start_server() -> (SocketAddr, HealthReporter) {
+    let (reporter, health_service) = tonic_health::server::health_reporter();
+    reporter
+        .set_service_status("pkg.Orders", ServingStatus::Serving)
+        .await;
+    reporter
+        .set_service_status("pkg.Billing", ServingStatus::NotServing)
+        .await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(health_service)
+            .serve_with_incoming(TcpIncoming::from(listener)),
+    );
+    (addr, reporter)
+}
+
+/// 启动使用测试证书的TLS健康检查服务，ALPN 为 h2
+async fn start_tls_server() -> SocketAddr {
+    let (reporter, health_service) = tonic_health::server::health_reporter();
+    reporter
+        .set_service_status("pkg.Orders", ServingStatus::Serving)
+        .await;
+
+    let certs = CertificateDer::pem_file_iter(format!("{TLS_DATA_DIR}/server.pem"))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let key = PrivateKeyDer::from_pem_file(format!("{TLS_DATA_DIR}/server.key")).unwrap();
+    let mut config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .unwrap();
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming =
+        futures::stream::unfold((listener, acceptor), |(listener, acceptor)| async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return None;
+                };
+                // 握手失败的连接直接丢弃，继续接受下一个连接
+                if let Ok(stream) = acceptor.accept(stream).await {
+                    return Some((Ok::<_, std::io::Error>(stream), (listener, acceptor)));
+                }
+            }
+        });
+    tokio::spawn(async move {
+        // 保持 reporter 存活，避免服务状态被清理
+        let _reporter = reporter;
+        Server::builder()
+            .add_service(health_service)
+            .serve_with_incoming(incoming)
+            .await
+    });
+    addr
+}
+
+fn create_service(url: &str, extra: &str) -> ServiceConfig {
+    toml::from_str(&format!(
+        r#"
+        name = "grpc-service"
+        url = "{url}"
+
+        {extra}
+        "#
+    ))
+    .unwrap()
+}
+
+async fn check(service: &ServiceConfig) -> HealthResult {
+    let checker =
+        HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap();
+    checker.check(service).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_grpc_serving_status_mapping() {
+    let (addr, reporter) = start_server().await;
+    let url = format!("grpc://{addr}");
+
+    let serving = check(&create_service(&url, "grpc = { service = \"pkg.Orders\" }")).await;
+    assert_eq!(
+        serving.status,
+        HealthStatus::Up,
+        "{:?}",
+        serving.error_message
+    );
+    assert_eq!(serving.method, "grpc.health.v1.Health/Check");
+    assert_eq!(serving.metadata["grpc"]["serving_status"], "SERVING");
+    assert!(serving.response_time > Duration::ZERO);
+
+    let not_serving = check(&create_service(
+        &url,
+        "grpc = { service = \"pkg.Billing\" }",
+    ))
+    .await;
+    assert_eq!(not_serving.status, HealthStatus::Down);
+    assert_eq!(
+        not_serving.error_message.as_deref(),
+        Some("gRPC服务 pkg.Billing 状态为 NOT_SERVING")
+    );
+
+    reporter
+        .set_service_status("pkg.Billing", ServingStatus::Unknown)
+        .await;
+    let unknown = check(&create_service(
+        &url,
+        "grpc = { service = \"pkg.Billing\" }",
+    ))
+    .await;
+    assert_eq!(unknown.status, HealthStatus::Unknown);
+
+    // 未配置服务名时检测服务端整体状态
+    let overall = check(&create_service(&url, "")).await;
+    assert_eq!(
+        overall.status,
+        HealthStatus::Up,
+        "{:?}",
+        overall.error_message
+    );
+}
+
+#[tokio::test]
+async fn test_grpc_unregistered_service_and_connection_failure() {
+    let (addr, _reporter) = start_server().await;
+
+    let missing = check(&create_service(
+        &format!("grpc://{addr}"),
+        "grpc = { service = \"pkg.Missing\" }",
+    ))
+    .await;
+    assert_eq!(missing.status, HealthStatus::Down);
+    assert_eq!(missing.metadata["failure_kind"], "grpc");
+    assert!(missing.error_message.unwrap().contains("未注册"));
+
+    let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_addr = closed.local_addr().unwrap();
+    drop(closed);
+    let refused = check(&create_service(&format!("grpc://{closed_addr}"), "")).await;
+    assert_eq!(refused.status, HealthStatus::Down);
+    assert_eq!(refused.metadata["failure_kind"], "grpc");
+    assert!(refused.error_message.unwrap().contains("无法连接gRPC服务"));
+}
+
+#[tokio::test]
+async fn test_grpcs_with_custom_ca_and_server_name() {
+    let addr = start_tls_server().await;
+    let url = format!("grpcs://{addr}");
+    let tls = format!(
+        r#"
+        grpc = {{ service = "pkg.Orders" }}
+        [tls]
+        ca_certs = ["{TLS_DATA_DIR}/ca.pem"]
+        server_name = "localhost"
+        "#
+    );
+
+    let result = check(&create_service(&url, &tls)).await;
+    assert_eq!(
+        result.status,
+        HealthStatus::Up,
+        "{:?}",
+        result.error_message
+    );
+    assert_eq!(result.metadata["grpc"]["tls"], true);
+
+    // 不信任测试CA时握手失败
+    let untrusted = check(&create_service(
+        &url,
+        "grpc = { service = \"pkg.Orders\" }\n[tls]\nserver_name = \"localhost\"",
+    ))
+    .await;
+    assert_eq!(untrusted.status, HealthStatus::Down);
+    assert!(untrusted
+        .error_message
+        .unwrap()
+        .contains("无法连接gRPC服务"));
+}
@@ -52,8 +52,10 @@ fn test_check_status_change_first_time() {
 
 #[test]
 fn test_check_status_change_no_change() {
-    let mut state = ServiceNotificationState::default();
-    state.last_health_status = Some(HealthStatus::Up);
+    let state = ServiceNotificationState {
+        last_health_status: Some(HealthStatus::Up),
+        ..Default::default()
+    };
 
     let current_status = HealthStatus::Up;
 
@@ -70,8 +72,10 @@ fn test_check_status_change_no_change() {
 
 #[test]
 fn test_check_status_change_recovered() {
-    let mut state = ServiceNotificationState::default();
-    state.last_health_status = Some(HealthStatus::Down);
+    let state = ServiceNotificationState {
+        last_health_status: Some(HealthStatus::Down),
+        ..Default::default()
+    };
 
     let current_status = HealthStatus::Up;
 
@@ -98,6 +102,24 @@ fn test_should_send_alert_below_threshold() {
         expected_status_codes: vec![200],
         failure_threshold: 3,
         alert_cooldown_secs: Some(60),
+        tags: Vec::new(),
+        schedule: None,
+        failure_check_interval_seconds: None,
+        backoff: None,
+        request_timeout_seconds: None,
+        retry_attempts: None,
+        retry_delay_seconds: None,
+        retry_backoff: None,
+        auth: None,
+        tls: None,
+        steps: Vec::new(),
+        dns: None,
+        grpc: None,
+        database: None,
+        exec: None,
+        heartbeat: None,
+        host: None,
+        websocket: None,
         enabled: true,
         description: None,
         feishu_webhook_url: None,
@@ -128,6 +150,24 @@ fn test_should_send_alert_at_threshold() {
         expected_status_codes: vec![200],
         failure_threshold: 3,
         alert_cooldown_secs: Some(60),
+        tags: Vec::new(),
+        schedule: None,
+        failure_check_interval_seconds: None,
+        backoff: None,
+        request_timeout_seconds: None,
+        retry_attempts: None,
+        retry_delay_seconds: None,
+        retry_backoff: None,
+        auth: None,
+        tls: None,
+        steps: Vec::new(),
+        dns: None,
+        grpc: None,
+        database: None,
+        exec: None,
+        heartbeat: None,
+        host: None,
+        websocket: None,
         enabled: true,
         description: None,
         feishu_webhook_url: None,
@@ -159,6 +199,24 @@ fn test_should_send_alert_in_cooldown() {
         expected_status_codes: vec![200],
         failure_threshold: 3,
         alert_cooldown_secs: Some(60),
+        tags: Vec::new(),
+        schedule: None,
+        failure_check_interval_seconds: None,
+        backoff: None,
+        request_timeout_seconds: None,
+        retry_attempts: None,
+        retry_delay_seconds: None,
+        retry_backoff: None,
+        auth: None,
+        tls: None,
+        steps: Vec::new(),
+        dns: None,
+        grpc: None,
+        database: None,
+        exec: None,
+        heartbeat: None,
+        host: None,
+        websocket: None,
         enabled: true,
         description: None,
         feishu_webhook_url: None,
@@ -186,6 +244,24 @@ fn test_should_send_alert_simplified_logic() {
         expected_status_codes: vec![200],
         failure_threshold: 3,
         alert_cooldown_secs: Some(60),
+        tags: Vec::new(),
+        schedule: None,
+        failure_check_interval_seconds: None,
+        backoff: None,
+        request_timeout_seconds: None,
+        retry_attempts: None,
+        retry_delay_seconds: None,
+        retry_backoff: None,
+        auth: None,
+        tls: None,
+        steps: Vec::new(),
+        dns: None,
+        grpc: None,
+        database: None,
+        exec: None,
+        heartbeat: None,
+        host: None,
+        websocket: None,
         enabled: true,
         description: None,
         feishu_webhook_url: None,
@@ -232,6 +308,24 @@ fn test_update_alert_cooldown_simplified() {
         expected_status_codes: vec![200],
         failure_threshold: 3,
         alert_cooldown_secs: Some(60),
+        tags: Vec::new(),
+        schedule: None,
+        failure_check_interval_seconds: None,
+        backoff: None,
+        request_timeout_seconds: None,
+        retry_attempts: None,
+        retry_delay_seconds: None,
+        retry_backoff: None,
+        auth: None,
+        tls: None,
+        steps: Vec::new(),
+        dns: None,
+        grpc: None,
+        database: None,
+        exec: None,
+        heartbeat: None,
+        host: None,
+        websocket: None,
         enabled: true,
         description: None,
         feishu_webhook_url: None,
@@ -310,6 +404,24 @@ fn test_update_alert_cooldown() {
         expected_status_codes: vec![200],
         failure_threshold: 3,
         alert_cooldown_secs: Some(60),
+        tags: Vec::new(),
+        schedule: None,
+        failure_check_interval_seconds: None,
+        backoff: None,
+        request_timeout_seconds: None,
+        retry_attempts: None,
+        retry_delay_seconds: None,
+        retry_backoff: None,
+        auth: None,
+        tls: None,
+        steps: Vec::new(),
+        dns: None,
+        grpc: None,
+        database: None,
+        exec: None,
+        heartbeat: None,
+        host: None,
+        websocket: None,
         enabled: true,
         description: None,
         feishu_webhook_url: None,
@@ -322,14 +434,14 @@ fn test_update_alert_cooldown() {
 
     // 第一次达到阈值，不应该设置冷却时间
     service_vitals::health::scheduler::TaskScheduler::update_alert_cooldown(
-        &mut state, &service, now, true, false,
+        &mut state, &service, now, true,
     );
 
     assert!(state.notification_state.alert_cooldown_until.is_none());
 
     // 非第一次达到阈值，应该设置冷却时间
     service_vitals::health::scheduler::TaskScheduler::update_alert_cooldown(
-        &mut state, &service, now, false, false,
+        &mut state, &service, now, false,
     );
 
     assert_eq!(
@@ -0,0 +1,305 @@
+//! 调度器运行时控制测试
+//!
+//! 测试手动触发检测、暂停与恢复、通知静默等运行时控制功能
+
+use async_trait::async_trait;
+use service_vitals::config::types::{GlobalConfig, ServiceConfig};
+use service_vitals::health::{
+    HealthResult, HealthStatus, HttpHealthChecker, Scheduler, TaskScheduler,
+};
+use service_vitals::notification::sender::NotificationMessage;
+use service_vitals::notification::NotificationSender;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn create_service(name: &str, url: String, check_interval_seconds: u64) -> ServiceConfig {
+    ServiceConfig {
+        name: name.to_string(),
+        url,
+        expected_status_codes: vec![200],
+        check_interval_seconds: Some(check_interval_seconds),
+        ..Default::default()
+    }
+}
+
+async fn create_scheduler() -> (Arc<TaskScheduler>, Arc<AtomicUsize>) {
+    let checker = Arc::new(
+        HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap(),
+    );
+    // 调度器按全局配置解析重试策略，关闭重试以便精确统计检测次数
+    let global: GlobalConfig = toml::from_str("retry_attempts = 0").unwrap();
+    let scheduler = Arc::new(TaskScheduler::new(checker, None, global));
+
+    let checks = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&checks);
+    scheduler
+        .set_health_result_callback(Arc::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }))
+        .await;
+
+    (scheduler, checks)
+}
+
+#[tokio::test]
+async fn test_pause_skips_checks_and_keeps_notification_state() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/health")
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let (scheduler, checks) = create_scheduler().await;
+    let service = create_service("api", format!("{}/health", server.url()), 1);
+    scheduler.start(vec![service]).await.unwrap();
+
+    // 等待首次检测完成
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let state = scheduler.notification_state("api").await.unwrap();
+    assert_eq!(state.last_health_status, Some(HealthStatus::Down));
+    assert_eq!(state.failure_state.consecutive_failures, 1);
+
+    let pause = scheduler
+        .pause_service("api", Some("维护".to_string()), None)
+        .await
+        .unwrap();
+    assert_eq!(pause.reason.as_deref(), Some("维护"));
+    let checks_before = checks.load(Ordering::SeqCst);
+
+    // 暂停期间不执行定时检测，通知状态保持不变
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(checks.load(Ordering::SeqCst), checks_before);
+    let state = scheduler.notification_state("api").await.unwrap();
+    assert_eq!(state.last_health_status, Some(HealthStatus::Down));
+    assert_eq!(state.failure_state.consecutive_failures, 1);
+
+    let previous = scheduler.resume_service("api").await.unwrap();
+    assert_eq!(previous, Some(pause));
+    assert!(scheduler.pause_info("api").await.is_none());
+
+    tokio::time::sleep(Duration::from_millis(1300)).await;
+    assert!(checks.load(Ordering::SeqCst) > checks_before);
+
+    scheduler.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_config_keeps_notification_state() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/health")
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let (scheduler, _checks) = create_scheduler().await;
+    let service = create_service("api", format!("{}/health", server.url()), 60);
+    scheduler.start(vec![service.clone()]).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let state = scheduler.notification_state("api").await.unwrap();
+    assert_eq!(state.failure_state.consecutive_failures, 1);
+
+    // 配置未变的服务不重新注册
+    scheduler
+        .reload_config(vec![service.clone()])
+        .await
+        .unwrap();
+    let state = scheduler.notification_state("api").await.unwrap();
+    assert_eq!(state.last_health_status, Some(HealthStatus::Down));
+    assert_eq!(state.failure_state.consecutive_failures, 1);
+
+    // 配置变更的服务按新配置重新注册，通知状态保持不变
+    let changed = ServiceConfig {
+        check_interval_seconds: Some(120),
+        ..service
+    };
+    scheduler.reload_config(vec![changed]).await.unwrap();
+    let state = scheduler.notification_state("api").await.unwrap();
+    assert_eq!(state.last_health_status, Some(HealthStatus::Down));
+    assert_eq!(state.failure_state.consecutive_failures, 1);
+
+    scheduler.reload_config(Vec::new()).await.unwrap();
+    assert!(scheduler.notification_state("api").await.is_none());
+
+    scheduler.stop().await.unwrap();
+}
+
+/// 统计告警次数的通知发送器
+struct CountingSender(Arc<AtomicUsize>);
+
+#[async_trait]
+impl NotificationSender for CountingSender {
+    async fn send_health_alert(
+        &self,
+        _service: &ServiceConfig,
+        _result: &HealthResult,
+    ) -> anyhow::Result<()> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn send_message(&self, _message: &NotificationMessage) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_silence_suppresses_notifications() {
+    let mut server = mockito::Server::new_async().await;
+    let failing = server
+        .mock("GET", "/health")
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let checker = Arc::new(
+        HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap(),
+    );
+    let alerts = Arc::new(AtomicUsize::new(0));
+    let notifier = Arc::new(CountingSender(Arc::clone(&alerts)));
+    let global: GlobalConfig = toml::from_str("retry_attempts = 0").unwrap();
+    let scheduler = TaskScheduler::new(checker, Some(notifier), global);
+    let service = create_service("api", format!("{}/health", server.url()), 3600);
+    scheduler.start(vec![service]).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(alerts.load(Ordering::SeqCst), 1);
+
+    let silence = scheduler
+        .silence_service("api", Some("已知故障".to_string()), None)
+        .await
+        .unwrap();
+    assert_eq!(scheduler.silenced_services().await["api"], silence);
+
+    // 静默期间恢复不发送通知，但失败状态照常重置
+    failing.remove_async().await;
+    server
+        .mock("GET", "/health")
+        .with_status(200)
+        .create_async()
+        .await;
+    let result = scheduler.trigger_check("api").await.unwrap().unwrap();
+    assert_eq!(result.status, HealthStatus::Up);
+    assert_eq!(alerts.load(Ordering::SeqCst), 1);
+    let state = scheduler.notification_state("api").await.unwrap();
+    assert_eq!(state.failure_state.consecutive_failures, 0);
+
+    assert_eq!(
+        scheduler.unsilence_service("api").await.unwrap(),
+        Some(silence)
+    );
+    assert!(scheduler.silenced_services().await.is_empty());
+    assert!(scheduler
+        .silence_service("missing", None, None)
+        .await
+        .is_none());
+
+    scheduler.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pause_auto_resume() {
+    let (scheduler, _) = create_scheduler().await;
+    let service = create_service("api", "http://127.0.0.1:9/health".to_string(), 3600);
+    scheduler.start(vec![service]).await.unwrap();
+
+    let until = chrono::Utc::now() + chrono::Duration::milliseconds(200);
+    scheduler
+        .pause_service("api", None, Some(until))
+        .await
+        .unwrap();
+    assert!(scheduler.pause_info("api").await.is_some());
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(scheduler.pause_info("api").await.is_none());
+    assert!(scheduler.paused_services().await.is_empty());
+
+    scheduler.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_pause_unknown_service() {
+    let (scheduler, _) = create_scheduler().await;
+
+    assert!(scheduler
+        .pause_service("missing", None, None)
+        .await
+        .is_none());
+    assert!(scheduler.resume_service("missing").await.is_none());
+}
+
+#[tokio::test]
+async fn test_trigger_check_unknown_service() {
+    let (scheduler, checks) = create_scheduler().await;
+
+    assert!(scheduler.trigger_check("missing").await.unwrap().is_none());
+    assert!(scheduler.trigger_checks_by_tag("none").await.is_empty());
+    assert_eq!(checks.load(Ordering::SeqCst), 0);
+}
+
+/// 收到放行信号前一直阻塞的通知发送器，模拟缓慢的Webhook
+struct BlockingSender(Arc<tokio::sync::Semaphore>);
+
+#[async_trait]
+impl NotificationSender for BlockingSender {
+    async fn send_health_alert(
+        &self,
+        _service: &ServiceConfig,
+        _result: &HealthResult,
+    ) -> anyhow::Result<()> {
+        self.0.acquire().await?.forget();
+        Ok(())
+    }
+
+    async fn send_message(&self, _message: &NotificationMessage) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_notification_state_unlocked_while_sending() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/health")
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let checker = Arc::new(
+        HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap(),
+    );
+    let release = Arc::new(tokio::sync::Semaphore::new(0));
+    let notifier = Arc::new(BlockingSender(Arc::clone(&release)));
+    let global: GlobalConfig = toml::from_str("retry_attempts = 0").unwrap();
+    let scheduler = TaskScheduler::new(checker, Some(notifier), global);
+    let service = create_service("api", format!("{}/health", server.url()), 3600);
+    scheduler.start(vec![service]).await.unwrap();
+
+    // 告警发送期间通知状态不被锁定，状态查询不会等待通知发送完成
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let state = tokio::time::timeout(Duration::from_secs(1), scheduler.notification_state("api"))
+        .await
+        .expect("发送通知期间通知状态被锁定")
+        .unwrap();
+    assert_eq!(state.failure_state.consecutive_failures, 1);
+    assert_eq!(state.notification_state.notification_count, 0);
+
+    release.add_permits(1);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let state = scheduler.notification_state("api").await.unwrap();
+    assert_eq!(state.notification_state.notification_count, 1);
+    assert!(state.notification_state.last_notification_time.is_some());
+
+    scheduler.stop().await.unwrap();
+}
@@ -0,0 +1,137 @@
+//! 调度器分发测试
+//!
+//! 测试共享定时器与工作池在多服务场景下的调度行为
+
+use service_vitals::config::types::{GlobalConfig, ServiceConfig};
+use service_vitals::health::{HttpHealthChecker, Scheduler, TaskScheduler};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn create_service(name: &str, url: String, check_interval_seconds: u64) -> ServiceConfig {
+    ServiceConfig {
+        name: name.to_string(),
+        url,
+        expected_status_codes: vec![200],
+        check_interval_seconds: Some(check_interval_seconds),
+        ..Default::default()
+    }
+}
+
+/// 创建调度器，并记录每个服务的检测次数
+async fn create_scheduler(
+    max_concurrent_checks: usize,
+) -> (Arc<TaskScheduler>, Arc<Mutex<HashMap<String, usize>>>) {
+    let checker = Arc::new(
+        HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap(),
+    );
+    let global: GlobalConfig =
+        toml::from_str(&format!("max_concurrent_checks = {max_concurrent_checks}")).unwrap();
+    let scheduler = Arc::new(TaskScheduler::new(checker, None, global));
+
+    let checks = Arc::new(Mutex::new(HashMap::new()));
+    let counter = Arc::clone(&checks);
+    scheduler
+        .set_health_result_callback(Arc::new(move |result| {
+            *counter
+                .lock()
+                .unwrap()
+                .entry(result.service_name.clone())
+                .or_insert(0) += 1;
+        }))
+        .await;
+
+    (scheduler, checks)
+}
+
+#[tokio::test]
+async fn test_many_services_share_small_worker_pool() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/health")
+        .with_status(200)
+        .expect_at_least(200)
+        .create_async()
+        .await;
+
+    let (scheduler, checks) = create_scheduler(4).await;
+    let services: Vec<ServiceConfig> = (0..200)
+        .map(|i| {
+            create_service(
+                &format!("svc-{i:03}"),
+                format!("{}/health", server.url()),
+                3600,
+            )
+        })
+        .collect();
+    scheduler.start(services).await.unwrap();
+
+    let status = scheduler.get_status().await;
+    assert_eq!(status.running_tasks, 200);
+
+    // 所有服务都应完成首次检测
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while checks.lock().unwrap().len() < 200 && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let checks = checks.lock().unwrap().clone();
+    assert_eq!(checks.len(), 200);
+    assert!(checks.values().all(|&count| count == 1));
+
+    scheduler.stop().await.unwrap();
+    assert_eq!(scheduler.get_status().await.running_tasks, 0);
+}
+
+#[tokio::test]
+async fn test_reload_does_not_duplicate_checks() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/health")
+        .with_status(200)
+        .create_async()
+        .await;
+
+    let (scheduler, checks) = create_scheduler(8).await;
+    let service = create_service("api", format!("{}/health", server.url()), 1);
+    scheduler.start(vec![service.clone()]).await.unwrap();
+
+    // 重复加载同一服务，旧的调度应被丢弃
+    for _ in 0..3 {
+        scheduler
+            .reload_config(vec![service.clone()])
+            .await
+            .unwrap();
+    }
+
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    let count = checks.lock().unwrap().get("api").copied().unwrap_or(0);
+    // 每次重新注册会立即检测一次，之后每秒一次；重复调度会使次数明显增加
+    assert!((3..=8).contains(&count), "检测次数异常: {count}");
+
+    scheduler.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_stop_halts_scheduled_checks() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/health")
+        .with_status(200)
+        .create_async()
+        .await;
+
+    let (scheduler, checks) = create_scheduler(2).await;
+    let service = create_service("api", format!("{}/health", server.url()), 1);
+    scheduler.start(vec![service]).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    scheduler.stop().await.unwrap();
+    let stopped_at = checks.lock().unwrap().get("api").copied().unwrap_or(0);
+    assert_eq!(stopped_at, 1);
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(checks.lock().unwrap().get("api").copied(), Some(stopped_at));
+
+    // 停止后仍可手动触发检测
+    assert!(scheduler.trigger_check("api").await.unwrap().is_some());
+}
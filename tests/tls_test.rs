@@ -0,0 +1,278 @@
+//! TLS配置测试
+//!
+//! 使用 `tests/data/tls` 下的测试证书启动本地TLS服务器，
+//! 测试自定义CA、跳过校验、双向TLS、SNI覆盖与最低TLS版本
+
+use service_vitals::config::types::ServiceConfig;
+use service_vitals::health::{HealthChecker, HealthStatus, HttpHealthChecker};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{self, RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+const TLS_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/tls");
+
+/// 服务器收到的请求：SNI 与请求头
+#[derive(Debug, Clone)]
+struct ReceivedRequest {
+    server_name: Option<String>,
+    head: String,
+}
+
+struct TlsServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+fn data_path(name: &str) -> String {
+    format!("{TLS_DATA_DIR}/{name}")
+}
+
+/// 启动本地TLS服务器
+///
+/// # 参数
+/// * `require_client_cert` - 是否要求客户端证书
+/// * `tls12_only` - 是否只支持TLS 1.2
+async fn start_server(require_client_cert: bool, tls12_only: bool) -> TlsServer {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let versions: &[&rustls::SupportedProtocolVersion] = if tls12_only {
+        &[&rustls::version::TLS12]
+    } else {
+        rustls::ALL_VERSIONS
+    };
+
+    let certs = CertificateDer::pem_file_iter(data_path("server.pem"))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let key = PrivateKeyDer::from_pem_file(data_path("server.key")).unwrap();
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(versions)
+        .unwrap();
+    let builder = if require_client_cert {
+        let mut roots = RootCertStore::empty();
+        roots
+            .add(CertificateDer::from_pem_file(data_path("ca.pem")).unwrap())
+            .unwrap();
+        let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .unwrap();
+        builder.with_client_cert_verifier(verifier)
+    } else {
+        builder.with_no_client_auth()
+    };
+    let config = builder.with_single_cert(certs, key).unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&requests);
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            let received = Arc::clone(&received);
+            tokio::spawn(async move {
+                let Ok(mut stream) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let server_name = stream.get_ref().1.server_name().map(str::to_string);
+
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                received.lock().unwrap().push(ReceivedRequest {
+                    server_name,
+                    head: String::from_utf8_lossy(&head).to_string(),
+                });
+
+                let _ = stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                    )
+                    .await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+
+    TlsServer { addr, requests }
+}
+
+fn create_service(url: String, tls: &str) -> ServiceConfig {
+    toml::from_str(&format!(
+        r#"
+        name = "tls-service"
+        url = "{url}"
+        expected_status_codes = [200]
+
+        {tls}
+        "#
+    ))
+    .unwrap()
+}
+
+async fn check(service: &ServiceConfig) -> service_vitals::health::HealthResult {
+    let checker =
+        HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap();
+    checker.check(service).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_custom_ca_roots() {
+    let server = start_server(false, false).await;
+    let url = format!("https://localhost:{}/health", server.addr.port());
+
+    // 内部CA签发的证书默认不受信任
+    let result = check(&create_service(url.clone(), "")).await;
+    assert_eq!(result.status, HealthStatus::Down);
+    assert!(result.error_message.unwrap().contains("SSL/TLS"));
+
+    let tls = format!("[tls]\nca_certs = [\"{}\"]", data_path("ca.pem"));
+    let result = check(&create_service(url, &tls)).await;
+    assert_eq!(
+        result.status,
+        HealthStatus::Up,
+        "{:?}",
+        result.error_message
+    );
+}
+
+#[tokio::test]
+async fn test_insecure_skip_verify() {
+    let server = start_server(false, false).await;
+    let url = format!("https://localhost:{}/health", server.addr.port());
+
+    let result = check(&create_service(url, "[tls]\ninsecure_skip_verify = true")).await;
+    assert_eq!(
+        result.status,
+        HealthStatus::Up,
+        "{:?}",
+        result.error_message
+    );
+}
+
+#[tokio::test]
+async fn test_mutual_tls() {
+    let server = start_server(true, false).await;
+    let url = format!("https://localhost:{}/health", server.addr.port());
+
+    let ca_only = format!("[tls]\nca_certs = [\"{}\"]", data_path("ca.pem"));
+    let result = check(&create_service(url.clone(), &ca_only)).await;
+    assert_eq!(result.status, HealthStatus::Down);
+
+    let mtls = format!(
+        "[tls]\nca_certs = [\"{}\"]\nclient_cert = \"{}\"\nclient_key = \"{}\"",
+        data_path("ca.pem"),
+        data_path("client.pem"),
+        data_path("client.key")
+    );
+    let result = check(&create_service(url, &mtls)).await;
+    assert_eq!(
+        result.status,
+        HealthStatus::Up,
+        "{:?}",
+        result.error_message
+    );
+}
+
+#[tokio::test]
+async fn test_server_name_override() {
+    let server = start_server(false, false).await;
+    let url = format!("https://127.0.0.1:{}/health", server.addr.port());
+
+    // 服务端证书不包含IP地址，直接按IP校验会失败
+    let ca_only = format!("[tls]\nca_certs = [\"{}\"]", data_path("ca.pem"));
+    let result = check(&create_service(url.clone(), &ca_only)).await;
+    assert_eq!(result.status, HealthStatus::Down);
+
+    let with_sni = format!(
+        "[tls]\nca_certs = [\"{}\"]\nserver_name = \"api.internal\"",
+        data_path("ca.pem")
+    );
+    let result = check(&create_service(url, &with_sni)).await;
+    assert_eq!(
+        result.status,
+        HealthStatus::Up,
+        "{:?}",
+        result.error_message
+    );
+
+    let requests = server.requests.lock().unwrap().clone();
+    let request = requests.last().unwrap();
+    assert_eq!(request.server_name.as_deref(), Some("api.internal"));
+    assert!(request
+        .head
+        .to_lowercase()
+        .contains(&format!("host: 127.0.0.1:{}", server.addr.port())));
+}
+
+#[tokio::test]
+async fn test_min_tls_version() {
+    let server = start_server(false, true).await;
+    let url = format!("https://localhost:{}/health", server.addr.port());
+
+    let tls13 = format!(
+        "[tls]\nca_certs = [\"{}\"]\nmin_version = \"1.3\"",
+        data_path("ca.pem")
+    );
+    let result = check(&create_service(url.clone(), &tls13)).await;
+    assert_eq!(result.status, HealthStatus::Down);
+
+    let tls12 = format!(
+        "[tls]\nca_certs = [\"{}\"]\nmin_version = \"1.2\"",
+        data_path("ca.pem")
+    );
+    let result = check(&create_service(url, &tls12)).await;
+    assert_eq!(
+        result.status,
+        HealthStatus::Up,
+        "{:?}",
+        result.error_message
+    );
+}
+
+#[tokio::test]
+async fn test_unreadable_certificate_reported_separately() {
+    let tls = "[tls]\nca_certs = [\"/nonexistent/ca.pem\"]";
+    let result = check(&create_service(
+        "https://localhost:1/health".to_string(),
+        tls,
+    ))
+    .await;
+
+    assert_eq!(result.status, HealthStatus::Unknown);
+    assert!(result.error_message.unwrap().contains("TLS配置错误"));
+    assert_eq!(result.metadata["failure_kind"], "tls");
+}
+
+#[tokio::test]
+async fn test_tls_handshake_timing_recorded() {
+    let server = start_server(false, false).await;
+    let url = format!("https://localhost:{}/health", server.addr.port());
+
+    let tls = format!("[tls]\nca_certs = [\"{}\"]", data_path("ca.pem"));
+    let result = check(&create_service(url, &tls)).await;
+    assert_eq!(result.status, HealthStatus::Up);
+
+    let timings = result.timings.unwrap();
+    assert!(!timings.connection_reused);
+    assert!(timings.dns_ms.is_some());
+    assert!(timings.connect_ms.is_some());
+    assert!(timings.tls_ms.is_some());
+    assert!(timings.download_ms.is_some());
+    assert_eq!(result.response_size, Some(2));
+}
@@ -0,0 +1,298 @@
+//! WebSocket检测测试
+//!
+//! 启动进程内的最小 WebSocket 服务（明文与TLS），
+//! 测试升级握手、消息往返、期望应答超时、非101响应以及服务端关闭连接
+
+use base64::Engine;
+use service_vitals::config::types::ServiceConfig;
+use service_vitals::health::{HealthChecker, HealthResult, HealthStatus, HttpHealthChecker};
+use sha1::{Digest, Sha1};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{self, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+const TLS_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/data/tls");
+
+/// 按请求路径模拟不同的服务端行为：
+/// `/echo` 先发送Ping和一条欢迎消息再回显，`/silent` 不回复，
+/// `/http` 返回200，`/close` 收到消息后以1011关闭连接
+async fn serve<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S) {
+    let mut request = Vec::new();
+    while !request.ends_with(b"\r\n\r\n") {
+        let mut byte = [0u8; 1];
+        if stream.read_exact(&mut byte).await.is_err() {
+            return;
+        }
+        request.push(byte[0]);
+    }
+    let request = String::from_utf8_lossy(&request).to_string();
+    let path = request
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    if path == "/http" {
+        let _ = stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+            .await;
+        return;
+    }
+    let key = request
+        .lines()
+        .find_map(|line| line.strip_prefix("Sec-WebSocket-Key: "))
+        .unwrap_or_default();
+    let mut hasher = Sha1::new();
+    hasher.update(key.trim().as_bytes());
+    hasher.update(b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11");
+    let accept = base64::engine::general_purpose::STANDARD.encode(hasher.finalize());
+    let mut response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n"
+    );
+    if request.contains("Sec-WebSocket-Protocol: chat") {
+        response.push_str("Sec-WebSocket-Protocol: chat\r\n");
+    }
+    response.push_str("\r\n");
+    if stream.write_all(response.as_bytes()).await.is_err() {
+        return;
+    }
+
+    if path == "/echo" {
+        let _ = stream.write_all(&[0x89, 0x00]).await;
+        let _ = stream.write_all(&server_frame(0x1, b"welcome")).await;
+    }
+    loop {
+        let Some((opcode, payload)) = read_client_frame(&mut stream).await else {
+            return;
+        };
+        match (path.as_str(), opcode) {
+            (_, 0x8) => return,
+            ("/echo", 0x1) => {
+                // 分两片回显，验证客户端合并分片
+                let (first, second) = payload.split_at(payload.len() / 2);
+                let mut frame = server_frame(0x1, first);
+                frame[0] &= 0x7F;
+                let _ = stream.write_all(&frame).await;
+                let _ = stream.write_all(&server_frame(0x0, second)).await;
+            }
+            ("/close", 0x1) => {
+                let mut payload = 1011u16.to_be_bytes().to_vec();
+                payload.extend_from_slice(b"overloaded");
+                let _ = stream.write_all(&server_frame(0x8, &payload)).await;
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 编码服务端帧（不使用掩码）
+fn server_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode, payload.len() as u8];
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// 读取客户端帧，客户端帧必须带掩码
+async fn read_client_frame<S: AsyncRead + Unpin>(stream: &mut S) -> Option<(u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await.ok()?;
+    assert!(header[1] & 0x80 != 0, "客户端帧必须使用掩码");
+    let length = (header[1] & 0x7F) as usize;
+    let mut mask = [0u8; 4];
+    stream.read_exact(&mut mask).await.ok()?;
+    let mut payload = vec![0u8; length];
+    stream.read_exact(&mut payload).await.ok()?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Some((header[0] & 0x0F, payload))
+}
+
+async fn start_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream));
+        }
+    });
+    addr
+}
+
+async fn start_tls_server() -> SocketAddr {
+    let certs = CertificateDer::pem_file_iter(format!("{TLS_DATA_DIR}/server.pem"))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let key = PrivateKeyDer::from_pem_file(format!("{TLS_DATA_DIR}/server.key")).unwrap();
+    let config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                if let Ok(stream) = acceptor.accept(stream).await {
+                    serve(stream).await;
+                }
+            });
+        }
+    });
+    addr
+}
+
+fn create_service(url: &str, extra: &str) -> ServiceConfig {
+    toml::from_str(&format!(
+        r#"
+        name = "realtime-gateway"
+        url = "{url}"
+        request_timeout_seconds = 1
+
+        {extra}
+        "#
+    ))
+    .unwrap()
+}
+
+async fn check(service: &ServiceConfig) -> HealthResult {
+    let checker =
+        HttpHealthChecker::new(Duration::from_secs(5), 0, Duration::from_millis(10)).unwrap();
+    checker.check(service).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_websocket_handshake_and_round_trip() {
+    let addr = start_server().await;
+
+    let handshake = check(&create_service(&format!("ws://{addr}/echo"), "")).await;
+    assert_eq!(
+        handshake.status,
+        HealthStatus::Up,
+        "{:?}",
+        handshake.error_message
+    );
+    assert_eq!(handshake.method, "WEBSOCKET");
+    assert!(handshake.metadata["websocket"]["handshake_ms"].is_number());
+    assert!(handshake.metadata["websocket"]["round_trip_ms"].is_null());
+    let timings = handshake.timings.unwrap();
+    assert!(timings.connect_ms.is_some());
+    assert!(timings.dns_ms.is_none());
+
+    // 欢迎消息不匹配时继续等待，Ping 自动回复，分片的回显合并后匹配
+    let echo = check(&create_service(
+        &format!("ws://{addr}/echo"),
+        r#"
+        [websocket]
+        send = "ping 42"
+        expect = "ping 42"
+        subprotocols = ["chat"]
+        "#,
+    ))
+    .await;
+    assert_eq!(echo.status, HealthStatus::Up, "{:?}", echo.error_message);
+    let websocket = &echo.metadata["websocket"];
+    assert!(websocket["round_trip_ms"].is_number());
+    assert_eq!(websocket["subprotocol"], "chat");
+    assert_eq!(websocket["messages_received"], 2);
+    assert_eq!(websocket["last_message"], "ping 42");
+
+    let regex = check(&create_service(
+        &format!("ws://{addr}/echo"),
+        "websocket = { expect_regex = \"^wel\" }",
+    ))
+    .await;
+    assert_eq!(regex.status, HealthStatus::Up, "{:?}", regex.error_message);
+}
+
+#[tokio::test]
+async fn test_websocket_failures() {
+    let addr = start_server().await;
+
+    let silent = check(&create_service(
+        &format!("ws://{addr}/silent"),
+        "websocket = { send = \"ping\", expect = \"pong\" }",
+    ))
+    .await;
+    assert_eq!(silent.status, HealthStatus::Down);
+    assert_eq!(silent.metadata["failure_kind"], "websocket");
+    assert!(silent.error_message.unwrap().contains("未收到应答"));
+    assert!(silent.metadata["websocket"]["handshake_ms"].is_number());
+
+    let http = check(&create_service(&format!("ws://{addr}/http"), "")).await;
+    assert_eq!(http.status, HealthStatus::Down);
+    assert_eq!(
+        http.error_message.as_deref(),
+        Some("WebSocket升级失败: 服务端返回 HTTP 200")
+    );
+
+    let closed = check(&create_service(
+        &format!("ws://{addr}/close"),
+        "websocket = { send = \"ping\" }",
+    ))
+    .await;
+    assert_eq!(closed.status, HealthStatus::Down);
+    assert_eq!(
+        closed.error_message.as_deref(),
+        Some("服务端关闭了WebSocket连接（1011）: overloaded")
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let refused_addr = listener.local_addr().unwrap();
+    drop(listener);
+    let refused = check(&create_service(&format!("ws://{refused_addr}/"), "")).await;
+    assert_eq!(refused.status, HealthStatus::Down);
+    assert!(refused
+        .error_message
+        .unwrap()
+        .contains("无法连接WebSocket服务"));
+}
+
+#[tokio::test]
+async fn test_wss_with_custom_ca_and_server_name() {
+    let addr = start_tls_server().await;
+    let service = create_service(
+        &format!("wss://{addr}/echo"),
+        &format!(
+            r#"
+            [websocket]
+            send = "hello"
+            expect = "hello"
+            [tls]
+            ca_certs = ["{TLS_DATA_DIR}/ca.pem"]
+            server_name = "localhost"
+            "#
+        ),
+    );
+    let result = check(&service).await;
+    assert_eq!(
+        result.status,
+        HealthStatus::Up,
+        "{:?}",
+        result.error_message
+    );
+    assert_eq!(result.metadata["websocket"]["tls"], true);
+    assert!(result.timings.unwrap().tls_ms.is_some());
+
+    // 不信任测试CA时握手失败
+    let untrusted = check(&create_service(
+        &format!("wss://{addr}/echo"),
+        "[tls]\nserver_name = \"localhost\"",
+    ))
+    .await;
+    assert_eq!(untrusted.status, HealthStatus::Down);
+    assert!(untrusted.error_message.unwrap().contains("TLS握手失败"));
+}